@@ -0,0 +1,164 @@
+// Asset commands - encrypted images embedded in notes
+// Stored as {workspace}/.assets/{noteId}/{assetId}.{ext}.asset in the encrypted format,
+// referenced from markdown as claudia-asset://{noteId}/{assetId}.{ext}
+
+use std::fs;
+use tauri::State;
+
+use crate::error::ClaudiaError;
+use crate::storage::{StorageState, foldersDir, trashNotesDir, noteAssetsDir, writeFileAtomic};
+use crate::encrypted_storage::{self, fileIdOf};
+use super::common::{newId, validateByteSize};
+use super::note::{scanAllNotes, scanNotesInFolder};
+
+const ASSET_SCHEME: &str = "claudia-asset://";
+const ASSET_EXTENSION: &str = "asset";
+
+/// Image types accepted for pasting, with the MIME type used to display them
+const IMAGE_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("bmp", "image/bmp"),
+];
+
+/// Asset metadata (encrypted like item frontmatter)
+#[derive(serde::Serialize, serde::Deserialize)]
+struct AssetFrontmatter {
+    name: String,
+    mime: String,
+    size: u64,
+    created: i64,
+}
+
+#[derive(serde::Serialize)]
+pub struct NoteAssetInfo {
+    pub name: String,
+    pub reference: String,
+    pub size: u64,
+}
+
+fn mimeForExtension(extension: &str) -> Option<&'static str> {
+    IMAGE_TYPES.iter().find(|(ext, _)| *ext == extension).map(|(_, mime)| *mime)
+}
+
+/// Validate an asset name of the form {uuid}.{ext} (rejects path separators and traversal)
+fn validateAssetName(name: &str) -> Result<(), String> {
+    let (id, ext) = name.split_once('.').ok_or("Invalid asset name")?;
+    if uuid::Uuid::parse_str(id).is_err() || mimeForExtension(ext).is_none() {
+        return Err("Invalid asset name".to_string());
+    }
+    Ok(())
+}
+
+/// Check that a note exists (including the trash) before touching its assets
+fn noteExists(wsPath: &str, id: &str, masterPassword: &str) -> bool {
+    scanAllNotes(&foldersDir(wsPath), Some(masterPassword)).iter().any(|n| n.frontmatter.id == id)
+        || scanNotesInFolder(&trashNotesDir(wsPath), Some(masterPassword)).iter().any(|n| n.frontmatter.id == id)
+}
+
+/// Encrypt pasted image bytes into the note's assets and return the markdown reference
+#[tauri::command]
+pub fn saveNoteImageFromBytes(storage: State<'_, StorageState>, id: String, bytes: Vec<u8>, extension: String) -> Result<NoteAssetInfo, ClaudiaError> {
+    println!("[saveNoteImageFromBytes] Called with id: {}, {} bytes, extension: {}", id, bytes.len(), extension);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let extension = extension.trim_start_matches('.').to_lowercase();
+    let mime = mimeForExtension(&extension)
+        .ok_or_else(|| format!("Unsupported image type: {}", extension))?;
+    validateByteSize(bytes.len() as u64, storage.effectiveSettings().maxItemBytes)?;
+
+    if !noteExists(&wsPath, &id, &masterPassword) {
+        return Err(ClaudiaError::NotFound("Note not found".to_string()));
+    }
+
+    let dir = noteAssetsDir(&wsPath, &id);
+    fs::create_dir_all(&dir)?;
+
+    let name = format!("{}.{}", newId(), extension);
+    let fm = AssetFrontmatter {
+        name: name.clone(),
+        mime: mime.to_string(),
+        size: bytes.len() as u64,
+        created: chrono::Utc::now().timestamp_millis(),
+    };
+    let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bytes);
+    let assetPath = dir.join(format!("{}.{}", name, ASSET_EXTENSION));
+    let fileContent = encrypted_storage::serializeAndEncrypt(&fm, &encoded, &masterPassword, &fileIdOf(&assetPath))?;
+
+    writeFileAtomic(&assetPath, fileContent).map_err(|e| {
+        println!("[saveNoteImageFromBytes] ERROR writing asset: {}", e);
+        e.to_string()
+    })?;
+
+    println!("[saveNoteImageFromBytes] SUCCESS - saved {}", assetPath.display());
+    storage.updateActivity();
+    Ok(NoteAssetInfo {
+        reference: format!("{}{}/{}", ASSET_SCHEME, id, name),
+        name,
+        size: fm.size,
+    })
+}
+
+/// Decrypt a note asset and return it as a data: URL the webview can display
+#[tauri::command]
+pub fn readNoteAsset(storage: State<'_, StorageState>, id: String, name: String) -> Result<String, ClaudiaError> {
+    println!("[readNoteAsset] Called with id: {}, name: {}", id, name);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    if uuid::Uuid::parse_str(&id).is_err() {
+        return Err(ClaudiaError::InvalidInput("Invalid note id".to_string()));
+    }
+    validateAssetName(&name)?;
+
+    let assetPath = noteAssetsDir(&wsPath, &id).join(format!("{}.{}", name, ASSET_EXTENSION));
+    let fileContent = fs::read_to_string(&assetPath)
+        .map_err(|_| ClaudiaError::NotFound("Asset not found".to_string()))?;
+
+    let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&assetPath))?;
+    let yaml = encrypted.decryptMetadata(&masterPassword)?;
+    let fm: AssetFrontmatter = serde_yaml::from_str(&yaml)
+        .map_err(|e| format!("Failed to parse asset metadata: {}", e))?;
+    let encoded = encrypted.decryptContent(&masterPassword)?;
+
+    storage.updateActivity();
+    Ok(format!("data:{};base64,{}", fm.mime, encoded))
+}
+
+/// Give a copied note its own copy of the original's assets
+/// Asset files are encrypted under their own id, not the note's, so they copy byte for byte
+pub(crate) fn copyNoteAssets(wsPath: &str, fromId: &str, toId: &str) -> Result<(), String> {
+    let source = noteAssetsDir(wsPath, fromId);
+    if !source.exists() {
+        return Ok(());
+    }
+    let target = noteAssetsDir(wsPath, toId);
+    fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+    for entry in fs::read_dir(&source).map_err(|e| e.to_string())?.filter_map(|e| e.ok()) {
+        if entry.path().is_file() {
+            fs::copy(entry.path(), target.join(entry.file_name())).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Point a body's asset references at another note's assets
+pub(crate) fn retargetAssetReferences(body: &str, fromId: &str, toId: &str) -> String {
+    body.replace(&format!("{}{}/", ASSET_SCHEME, fromId), &format!("{}{}/", ASSET_SCHEME, toId))
+}
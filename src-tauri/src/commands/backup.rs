@@ -0,0 +1,921 @@
+// Backup commands - workspace export archives, vault archives, plaintext export/import and scheduled automatic backups
+// Archives are plain zips of the workspace directory; item payloads stay encrypted
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::error::ClaudiaError;
+use crate::encrypted_storage;
+use crate::mcp::api;
+use crate::models::{Folder, ItemKind, NoteFrontmatter, Task};
+use crate::storage::{StorageState, globalConfigDir, foldersDir, assetsDir, trashDir, isItemPathOfKind, itemsDir, slugify, toMarkdown, parseFrontmatter, validateMoveTarget, writeFileAtomic};
+use super::common::now;
+use super::folder::{readFolderFrontmatter, scanFolders};
+use super::note::scanNotesInFolder;
+use super::password::scanPasswordsInFolder;
+use super::task::scanTasksInFolder;
+use super::vault::checkReauth;
+
+/// Timestamp of the last automatic backup (hidden file in workspace root)
+const LAST_BACKUP_FILE: &str = ".last-backup";
+const BACKUP_PREFIX: &str = "claudia-backup-";
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Zip the whole workspace directory into `dest`, returning the archive size
+pub(crate) fn exportWorkspaceArchive(wsPath: &str, dest: &Path) -> Result<u64, String> {
+    let root = PathBuf::from(wsPath);
+    if dest.starts_with(&root) {
+        return Err("Invalid input: archive cannot be written inside the workspace".to_string());
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let file = fs::File::create(dest).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    for entry in walkdir::WalkDir::new(&root).min_depth(1).into_iter().filter_map(|e| e.ok()) {
+        let relative = entry.path().strip_prefix(&root).map_err(|e| e.to_string())?;
+        let name = relative.to_string_lossy().replace('\\', "/");
+
+        if entry.file_type().is_dir() {
+            zip.add_directory(name, options).map_err(|e| e.to_string())?;
+        } else if entry.file_type().is_file() {
+            let bytes = fs::read(entry.path()).map_err(|e| e.to_string())?;
+            zip.start_file(name, options).map_err(|e| e.to_string())?;
+            zip.write_all(&bytes).map_err(|e| e.to_string())?;
+        }
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+    fs::metadata(dest).map(|m| m.len()).map_err(|e| e.to_string())
+}
+
+/// Export the current workspace to a zip archive
+/// With reauthForSensitive on, `reauthToken` must come from verifyMasterPasswordFresh
+#[tauri::command]
+pub fn exportWorkspace(
+    storage: State<'_, StorageState>,
+    destPath: String,
+    reauthToken: Option<String>,
+) -> Result<u64, ClaudiaError> {
+    println!("[exportWorkspace] Called with destPath: {}", destPath);
+    checkReauth(&storage, "exportWorkspace", reauthToken.as_deref())?;
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+    let size = exportWorkspaceArchive(&wsPath, Path::new(&destPath))?;
+
+    println!("[exportWorkspace] SUCCESS - wrote {} bytes", size);
+    Ok(size)
+}
+
+/// Last automatic backup time in milliseconds, if any
+#[tauri::command]
+pub fn getLastBackupTime(storage: State<'_, StorageState>) -> Result<Option<i64>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+    Ok(readLastBackupTime(&wsPath))
+}
+
+fn readLastBackupTime(wsPath: &str) -> Option<i64> {
+    fs::read_to_string(PathBuf::from(wsPath).join(LAST_BACKUP_FILE))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+}
+
+fn scheduleInterval(schedule: &str) -> Option<i64> {
+    match schedule {
+        "daily" => Some(DAY_MS),
+        "weekly" => Some(7 * DAY_MS),
+        _ => None,
+    }
+}
+
+/// Run a backup of the current workspace if one is due under the backupSchedule setting.
+/// Skips silently when backups are off, no workspace is open or it is unavailable.
+pub(crate) fn runScheduledBackup(storage: &StorageState) -> Result<Option<PathBuf>, String> {
+    let settings = storage.effectiveSettings();
+    let Some(interval) = scheduleInterval(&settings.backupSchedule) else {
+        return Ok(None);
+    };
+
+    let wsPath = match storage.getWorkspacePath() {
+        Some(p) if Path::new(&p).is_dir() => p,
+        _ => return Ok(None),
+    };
+
+    let timestamp = now();
+    if readLastBackupTime(&wsPath).is_some_and(|last| timestamp - last < interval) {
+        return Ok(None);
+    }
+
+    let backupDir = if settings.backupDir.is_empty() {
+        globalConfigDir().join("backups")
+    } else {
+        PathBuf::from(&settings.backupDir)
+    };
+    let wsName = Path::new(&wsPath).file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "workspace".to_string());
+    let prefix = format!("{}{}-", BACKUP_PREFIX, wsName);
+    let archive = backupDir.join(format!("{}{}.zip", prefix, chrono::Local::now().format("%Y%m%d-%H%M%S")));
+
+    exportWorkspaceArchive(&wsPath, &archive)?;
+    fs::write(PathBuf::from(&wsPath).join(LAST_BACKUP_FILE), timestamp.to_string())
+        .map_err(|e| e.to_string())?;
+    println!("[backup] Wrote {}", archive.display());
+
+    rotateBackups(&backupDir, &prefix, settings.backupRetention as usize);
+    Ok(Some(archive))
+}
+
+/// Keep only the newest `retention` archives for one workspace (names sort by timestamp)
+fn rotateBackups(backupDir: &Path, prefix: &str, retention: usize) {
+    let mut archives: Vec<PathBuf> = fs::read_dir(backupDir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name().and_then(|n| n.to_str())
+                .map(|n| n.starts_with(prefix) && n.ends_with(".zip"))
+                .unwrap_or(false)
+        })
+        .collect();
+    archives.sort();
+
+    let excess = archives.len().saturating_sub(retention.max(1));
+    for old in archives.into_iter().take(excess) {
+        println!("[backup] Removing old backup {}", old.display());
+        let _ = fs::remove_file(old);
+    }
+}
+
+// ============================================
+// VAULT ARCHIVES (.claudia-backup)
+// ============================================
+// A zip of the encrypted item files, note assets, the vault key files and a
+// manifest.json. Nothing is decrypted: restoring needs the same master password.
+
+const VAULT_ARCHIVE_FORMAT: &str = "claudia-backup";
+const VAULT_ARCHIVE_VERSION: u32 = 1;
+const VAULT_MANIFEST: &str = "manifest.json";
+
+/// Workspace files holding the vault key material; items can't be unlocked without them
+const VAULT_KEY_FILES: &[&str] = &[".vault", ".vault-salt"];
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct VaultItemCounts {
+    pub folders: usize,
+    pub notes: usize,
+    pub tasks: usize,
+    pub passwords: usize,
+}
+
+impl VaultItemCounts {
+    fn add(&mut self, archiveName: &str) {
+        let path = Path::new(archiveName);
+        if path.file_name().is_some_and(|n| n == ".folder.md") {
+            self.folders += 1;
+        } else if path.extension().is_some_and(|e| e == "md") {
+            if isItemPathOfKind(path, ItemKind::Note) {
+                self.notes += 1;
+            } else if isItemPathOfKind(path, ItemKind::Task) {
+                self.tasks += 1;
+            } else if isItemPathOfKind(path, ItemKind::Password) {
+                self.passwords += 1;
+            }
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct VaultManifest {
+    pub format: String,
+    pub version: u32,
+    pub created: i64,
+    pub includesTrash: bool,
+    pub counts: VaultItemCounts,
+    /// blake3 over every archived file name and content, in name order
+    pub checksum: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct VaultImportResult {
+    pub restored: usize,
+    pub replaced: usize,
+    pub counts: VaultItemCounts,
+}
+
+/// Incremental archive checksum; files must be fed in name order
+struct ArchiveChecksum(blake3::Hasher);
+
+impl ArchiveChecksum {
+    fn new() -> Self {
+        Self(blake3::Hasher::new())
+    }
+
+    fn add(&mut self, name: &str, bytes: &[u8]) {
+        self.0.update(name.as_bytes());
+        self.0.update(&[0]);
+        self.0.update(blake3::hash(bytes).as_bytes());
+    }
+
+    fn finish(&self) -> String {
+        self.0.finalize().to_hex().to_string()
+    }
+}
+
+/// Whether an archive entry name belongs in a vault archive
+fn isVaultArchiveName(name: &str) -> bool {
+    VAULT_KEY_FILES.contains(&name)
+        || ["folders/", ".assets/", ".trash/"].iter().any(|root| name.starts_with(root))
+}
+
+/// Files to bundle from a workspace as (archive name, path), sorted by name
+fn collectVaultFiles(wsPath: &str, includeTrash: bool) -> Vec<(String, PathBuf)> {
+    let root = PathBuf::from(wsPath);
+    let mut dirs = vec![foldersDir(wsPath), assetsDir(wsPath)];
+    if includeTrash {
+        dirs.push(trashDir(wsPath));
+    }
+
+    let mut files: Vec<(String, PathBuf)> = VAULT_KEY_FILES.iter()
+        .map(|name| root.join(name))
+        .chain(dirs.iter().flat_map(|dir| {
+            walkdir::WalkDir::new(dir).into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+                .map(|e| e.into_path())
+        }))
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let name = path.strip_prefix(&root).ok()?.to_string_lossy().replace('\\', "/");
+            Some((name, path))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Export the encrypted items of the current workspace to a .claudia-backup archive
+/// Returns the manifest; with reauthForSensitive on, `reauthToken` must come from verifyMasterPasswordFresh
+#[tauri::command]
+pub fn exportVault(
+    storage: State<'_, StorageState>,
+    destPath: String,
+    includeTrash: bool,
+    reauthToken: Option<String>,
+) -> Result<VaultManifest, ClaudiaError> {
+    println!("[exportVault] Called with destPath: {}, includeTrash: {}", destPath, includeTrash);
+    checkReauth(&storage, "exportVault", reauthToken.as_deref())?;
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+    if !storage.isVaultSetup() {
+        return Err("Vault is not set up".into());
+    }
+
+    let dest = Path::new(&destPath);
+    if dest.starts_with(&wsPath) {
+        return Err(ClaudiaError::InvalidInput("Invalid input: archive cannot be written inside the workspace".to_string()));
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let file = fs::File::create(dest).map_err(|e| ClaudiaError::Io(format!("Failed to create archive: {}", e)))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let mut checksum = ArchiveChecksum::new();
+    let mut counts = VaultItemCounts::default();
+    for (name, path) in collectVaultFiles(&wsPath, includeTrash) {
+        let bytes = fs::read(&path)?;
+        checksum.add(&name, &bytes);
+        counts.add(&name);
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(&bytes).map_err(|e| e.to_string())?;
+    }
+
+    let manifest = VaultManifest {
+        format: VAULT_ARCHIVE_FORMAT.to_string(),
+        version: VAULT_ARCHIVE_VERSION,
+        created: now(),
+        includesTrash: includeTrash,
+        counts,
+        checksum: checksum.finish(),
+    };
+    zip.start_file(VAULT_MANIFEST, options).map_err(|e| e.to_string())?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?)
+        .map_err(|e| e.to_string())?;
+    zip.finish().map_err(|e| e.to_string())?;
+
+    println!("[exportVault] SUCCESS - {:?}", manifest.counts);
+    Ok(manifest)
+}
+
+/// Archive entries as (relative path, bytes)
+type ArchiveFiles = Vec<(String, Vec<u8>)>;
+
+/// Read and verify a .claudia-backup archive, returning its manifest and files sorted by name
+fn readVaultArchive(srcPath: &Path) -> Result<(VaultManifest, ArchiveFiles), String> {
+    let file = fs::File::open(srcPath).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Invalid archive: {}", e))?;
+
+    let manifest: VaultManifest = {
+        let entry = zip.by_name(VAULT_MANIFEST).map_err(|_| "Invalid archive: missing manifest".to_string())?;
+        serde_json::from_reader(entry).map_err(|e| format!("Invalid archive manifest: {}", e))?
+    };
+    if manifest.format != VAULT_ARCHIVE_FORMAT {
+        return Err("Invalid archive: not a Claudia vault backup".to_string());
+    }
+    if manifest.version > VAULT_ARCHIVE_VERSION {
+        return Err(format!("Archive version {} is newer than this app supports", manifest.version));
+    }
+
+    let mut files = Vec::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| e.to_string())?;
+        if entry.is_dir() || entry.name() == VAULT_MANIFEST {
+            continue;
+        }
+        // enclosed_name rejects absolute paths and ".." components
+        let name = entry.enclosed_name()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .filter(|n| isVaultArchiveName(n))
+            .ok_or_else(|| format!("Invalid archive: unexpected entry {}", entry.name()))?;
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+        files.push((name, bytes));
+    }
+    files.sort();
+
+    let mut checksum = ArchiveChecksum::new();
+    for (name, bytes) in &files {
+        checksum.add(name, bytes);
+    }
+    if checksum.finish() != manifest.checksum {
+        return Err("Archive checksum mismatch: the backup is corrupted or was modified".to_string());
+    }
+
+    Ok((manifest, files))
+}
+
+/// Restore a .claudia-backup archive into a workspace directory
+/// Existing items are only overwritten with `merge`; a workspace with a different vault is always refused
+#[tauri::command]
+pub fn importVault(storage: State<'_, StorageState>, srcPath: String, targetWorkspacePath: String, merge: Option<bool>) -> Result<VaultImportResult, ClaudiaError> {
+    println!("[importVault] Called with srcPath: {}, target: {}", srcPath, targetWorkspacePath);
+    let merge = merge.unwrap_or(false);
+
+    let (manifest, files) = readVaultArchive(Path::new(&srcPath))?;
+    let target = PathBuf::from(&targetWorkspacePath);
+
+    // Check everything before writing anything
+    let mut existing = 0;
+    for (name, bytes) in &files {
+        let dest = target.join(name);
+        if !dest.exists() {
+            continue;
+        }
+        if VAULT_KEY_FILES.contains(&name.as_str()) {
+            if fs::read(&dest)? != *bytes {
+                return Err("Target workspace belongs to a different vault".into());
+            }
+        } else {
+            existing += 1;
+        }
+    }
+    if existing > 0 && !merge {
+        return Err(format!("{} items already exist in the target workspace; import with merge to overwrite them", existing).into());
+    }
+
+    let mut restored = 0;
+    let mut replaced = 0;
+    for (name, bytes) in &files {
+        let dest = target.join(name);
+        if VAULT_KEY_FILES.contains(&name.as_str()) && dest.exists() {
+            continue;
+        }
+        if dest.exists() {
+            replaced += 1;
+        } else {
+            restored += 1;
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        writeFileAtomic(&dest, bytes).map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    }
+
+    // Replaced note bodies may carry different links
+    storage.clearLinks();
+    println!("[importVault] SUCCESS - restored {}, replaced {}", restored, replaced);
+    Ok(VaultImportResult { restored, replaced, counts: manifest.counts })
+}
+
+// ============================================
+// PLAINTEXT EXPORT
+// ============================================
+// Decrypted notes and tasks as ordinary `---` frontmatter markdown, one directory per
+// folder, with filenames slugged from titles so other markdown tools can read them.
+
+#[derive(serde::Serialize, Default, Debug)]
+pub struct PlaintextExportResult {
+    pub folders: usize,
+    pub notes: usize,
+    pub tasks: usize,
+    pub passwords: usize,
+}
+
+/// Slugged file or directory name not yet used in one output directory
+/// Collisions get a numeric suffix: "todo", "todo-2", "todo-3"...
+fn uniqueSlug(used: &mut HashSet<String>, title: &str, extension: &str) -> String {
+    let base = match slugify(title) {
+        s if s.is_empty() => "untitled".to_string(),
+        s => s,
+    };
+    let mut n = 1;
+    loop {
+        let name = if n == 1 { format!("{}{}", base, extension) } else { format!("{}-{}{}", base, n, extension) };
+        if used.insert(name.clone()) {
+            return name;
+        }
+        n += 1;
+    }
+}
+
+/// Task frontmatter with its status added
+/// Status lives in the directory layout, so carry it in the exported frontmatter
+fn taskExportFrontmatter(task: &Task) -> Result<serde_yaml::Value, String> {
+    let mut frontmatter = serde_yaml::to_value(&task.frontmatter).map_err(|e| e.to_string())?;
+    if let serde_yaml::Value::Mapping(map) = &mut frontmatter {
+        map.insert("status".into(), task.status.folderName().into());
+    }
+    Ok(frontmatter)
+}
+
+/// Write the items of one folder into `outDir`, then recurse into its children
+fn exportFolderPlaintextTo(
+    folderDir: &Path,
+    children: &[Folder],
+    outDir: &Path,
+    masterPassword: &str,
+    includePasswords: bool,
+    result: &mut PlaintextExportResult,
+) -> Result<(), String> {
+    fs::create_dir_all(outDir).map_err(|e| format!("Failed to create {}: {}", outDir.display(), e))?;
+    let mut used = HashSet::new();
+
+    for note in scanNotesInFolder(&itemsDir(folderDir, ItemKind::Note), Some(masterPassword)) {
+        let body = encrypted_storage::readDecryptedBody(&note.path, masterPassword)?
+            .unwrap_or(note.content);
+        let name = uniqueSlug(&mut used, &note.frontmatter.title, ".md");
+        fs::write(outDir.join(name), toMarkdown(&note.frontmatter, &body)?).map_err(|e| e.to_string())?;
+        result.notes += 1;
+    }
+
+    for task in scanTasksInFolder(&itemsDir(folderDir, ItemKind::Task), Some(masterPassword)) {
+        let frontmatter = taskExportFrontmatter(&task)?;
+        let body = encrypted_storage::readDecryptedBody(&task.path, masterPassword)?
+            .unwrap_or(task.content);
+        let name = uniqueSlug(&mut used, &task.frontmatter.title, ".md");
+        fs::write(outDir.join(name), toMarkdown(&frontmatter, &body)?).map_err(|e| e.to_string())?;
+        result.tasks += 1;
+    }
+
+    if includePasswords {
+        // Frontmatter only: credentials stay in the encrypted content and are never written out
+        for password in scanPasswordsInFolder(&itemsDir(folderDir, ItemKind::Password), Some(masterPassword)) {
+            let name = uniqueSlug(&mut used, &password.frontmatter.title, ".md");
+            fs::write(outDir.join(name), toMarkdown(&password.frontmatter, "")?).map_err(|e| e.to_string())?;
+            result.passwords += 1;
+        }
+    }
+
+    for child in children {
+        let dirName = uniqueSlug(&mut used, &child.frontmatter.name, "");
+        result.folders += 1;
+        exportFolderPlaintextTo(&child.path, &child.children, &outDir.join(dirName), masterPassword, includePasswords, result)?;
+    }
+
+    Ok(())
+}
+
+/// Export a folder subtree as decrypted markdown files under `destDir`
+/// Passwords are skipped unless `includePasswordMetadata`, which writes their frontmatter only.
+/// With reauthForSensitive on, `reauthToken` must come from verifyMasterPasswordFresh
+#[tauri::command]
+pub fn exportFolderPlaintext(
+    storage: State<'_, StorageState>,
+    folderPath: String,
+    destDir: String,
+    includePasswordMetadata: Option<bool>,
+    reauthToken: Option<String>,
+) -> Result<PlaintextExportResult, ClaudiaError> {
+    println!("[exportFolderPlaintext] Called with folderPath: {}, destDir: {}", folderPath, destDir);
+    checkReauth(&storage, "exportFolderPlaintext", reauthToken.as_deref())?;
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let folderDir = validateMoveTarget(&wsPath, &folderPath)?.to_path_buf();
+    let dest = PathBuf::from(&destDir);
+    if dest.starts_with(&wsPath) {
+        return Err(ClaudiaError::InvalidInput("Invalid input: export cannot be written inside the workspace".to_string()));
+    }
+
+    let children = scanFolders(&folderDir, Some(folderDir.clone()), Some(&masterPassword));
+    let mut result = PlaintextExportResult::default();
+    exportFolderPlaintextTo(&folderDir, &children, &dest, &masterPassword, includePasswordMetadata.unwrap_or(false), &mut result)?;
+
+    println!("[exportFolderPlaintext] SUCCESS - {:?}", result);
+    storage.updateActivity();
+    Ok(result)
+}
+
+// ============================================
+// FOLDER ZIP EXPORT
+// ============================================
+// One folder subtree as a zip. Encrypted exports copy the files verbatim so the archive
+// can be dropped back into a Claudia workspace; decrypted exports use the plaintext
+// layout above, plus each folder's metadata as a plain `.folder.md`.
+
+#[derive(serde::Serialize, Debug)]
+pub struct FolderZipExport {
+    pub files: usize,
+    pub path: String,
+}
+
+type ZipOut = zip::ZipWriter<fs::File>;
+
+fn zipOptions() -> zip::write::SimpleFileOptions {
+    zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated)
+}
+
+fn addZipFile(zip: &mut ZipOut, name: &str, bytes: &[u8]) -> Result<(), String> {
+    zip.start_file(name, zipOptions()).map_err(|e| e.to_string())?;
+    zip.write_all(bytes).map_err(|e| e.to_string())
+}
+
+/// Copy every file under `folderDir` as-is, rooted at the folder's own directory name
+fn zipFolderEncrypted(zip: &mut ZipOut, folderDir: &Path) -> Result<usize, String> {
+    let base = folderDir.parent().ok_or("Invalid folder path")?;
+    let mut files = 0;
+
+    for entry in walkdir::WalkDir::new(folderDir).into_iter().filter_map(|e| e.ok()) {
+        let relative = entry.path().strip_prefix(base).map_err(|e| e.to_string())?;
+        let name = relative.to_string_lossy().replace('\\', "/");
+
+        if entry.file_type().is_dir() {
+            zip.add_directory(name, zipOptions()).map_err(|e| e.to_string())?;
+        } else if entry.file_type().is_file() {
+            let bytes = fs::read(entry.path()).map_err(|e| e.to_string())?;
+            addZipFile(zip, &name, &bytes)?;
+            files += 1;
+        }
+    }
+
+    Ok(files)
+}
+
+/// Write one folder's metadata, notes and tasks as plaintext under `prefix`, then recurse
+/// Passwords are left out entirely.
+fn zipFolderDecrypted(
+    zip: &mut ZipOut,
+    folderDir: &Path,
+    children: &[Folder],
+    prefix: &str,
+    masterPassword: &str,
+) -> Result<usize, String> {
+    zip.add_directory(prefix, zipOptions()).map_err(|e| e.to_string())?;
+    let mut used = HashSet::new();
+    used.insert(".folder.md".to_string());
+
+    let metadata = readFolderFrontmatter(&folderDir.join(".folder.md"), masterPassword)?;
+    addZipFile(zip, &format!("{}/.folder.md", prefix), toMarkdown(&metadata, "")?.as_bytes())?;
+    let mut files = 1;
+
+    for note in scanNotesInFolder(&itemsDir(folderDir, ItemKind::Note), Some(masterPassword)) {
+        let body = encrypted_storage::readDecryptedBody(&note.path, masterPassword)?
+            .unwrap_or(note.content);
+        let name = uniqueSlug(&mut used, &note.frontmatter.title, ".md");
+        addZipFile(zip, &format!("{}/{}", prefix, name), toMarkdown(&note.frontmatter, &body)?.as_bytes())?;
+        files += 1;
+    }
+
+    for task in scanTasksInFolder(&itemsDir(folderDir, ItemKind::Task), Some(masterPassword)) {
+        let body = encrypted_storage::readDecryptedBody(&task.path, masterPassword)?
+            .unwrap_or_default();
+        let frontmatter = taskExportFrontmatter(&task)?;
+        let name = uniqueSlug(&mut used, &task.frontmatter.title, ".md");
+        addZipFile(zip, &format!("{}/{}", prefix, name), toMarkdown(&frontmatter, &body)?.as_bytes())?;
+        files += 1;
+    }
+
+    for child in children {
+        let dirName = uniqueSlug(&mut used, &child.frontmatter.name, "");
+        files += zipFolderDecrypted(zip, &child.path, &child.children, &format!("{}/{}", prefix, dirName), masterPassword)?;
+    }
+
+    Ok(files)
+}
+
+/// Export a folder subtree to a zip archive at `destPath`
+/// `decrypt=false` bundles the encrypted files untouched; `decrypt=true` writes plaintext
+/// markdown and requires an unlocked vault.
+/// With reauthForSensitive on, `reauthToken` must come from verifyMasterPasswordFresh
+#[tauri::command]
+pub fn exportFolderZip(
+    storage: State<'_, StorageState>,
+    folderPath: String,
+    destPath: String,
+    decrypt: bool,
+    reauthToken: Option<String>,
+) -> Result<FolderZipExport, ClaudiaError> {
+    println!("[exportFolderZip] Called with folderPath: {}, destPath: {}, decrypt: {}", folderPath, destPath, decrypt);
+    checkReauth(&storage, "exportFolderZip", reauthToken.as_deref())?;
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+    let folderDir = validateMoveTarget(&wsPath, &folderPath)?.to_path_buf();
+    if folderDir == foldersDir(&wsPath) {
+        return Err(ClaudiaError::InvalidInput("Invalid input: choose a folder to export".to_string()));
+    }
+
+    let dest = PathBuf::from(&destPath);
+    if dest.starts_with(&wsPath) {
+        return Err(ClaudiaError::InvalidInput("Invalid input: archive cannot be written inside the workspace".to_string()));
+    }
+
+    let masterPassword = if decrypt {
+        if !storage.isUnlocked() {
+            return Err(ClaudiaError::VaultLocked);
+        }
+        Some(storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?)
+    } else {
+        None
+    };
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = fs::File::create(&dest).map_err(|e| ClaudiaError::Io(format!("Failed to create archive: {}", e)))?;
+    let mut zip = zip::ZipWriter::new(file);
+
+    let written = match masterPassword.as_deref() {
+        Some(mp) => {
+            let metadata = readFolderFrontmatter(&folderDir.join(".folder.md"), mp)?;
+            let root = uniqueSlug(&mut HashSet::new(), &metadata.name, "");
+            let children = scanFolders(&folderDir, Some(folderDir.clone()), Some(mp));
+            zipFolderDecrypted(&mut zip, &folderDir, &children, &root, mp)
+        }
+        None => zipFolderEncrypted(&mut zip, &folderDir),
+    };
+    let files = match written.and_then(|n| zip.finish().map(|_| n).map_err(|e| e.to_string())) {
+        Ok(n) => n,
+        Err(e) => {
+            // Don't leave a truncated archive behind
+            let _ = fs::remove_file(&dest);
+            return Err(e.into());
+        }
+    };
+
+    println!("[exportFolderZip] SUCCESS - wrote {} files to {}", files, dest.display());
+    if decrypt {
+        storage.updateActivity();
+    }
+    Ok(FolderZipExport { files, path: dest.to_string_lossy().to_string() })
+}
+
+// ============================================
+// MARKDOWN IMPORT
+// ============================================
+
+#[derive(serde::Serialize, Debug)]
+pub struct SkippedImportFile {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(serde::Serialize, Default, Debug)]
+pub struct MarkdownImportResult {
+    pub notesCreated: usize,
+    pub foldersCreated: usize,
+    pub skipped: Vec<SkippedImportFile>,
+}
+
+/// Title, color, tags and body of a markdown file
+/// Our own frontmatter is used as-is; other tools' frontmatter only contributes a `title`,
+/// and files without frontmatter are titled after their filename
+fn parseImportedNote(raw: &str, fileStem: &str) -> (String, Option<String>, Option<Vec<String>>, String) {
+    if let Some((fm, body)) = parseFrontmatter::<NoteFrontmatter>(raw) {
+        return (fm.title, Some(fm.color).filter(|c| !c.is_empty()), Some(fm.tags), body);
+    }
+    if let Some((fm, body)) = parseFrontmatter::<serde_yaml::Value>(raw) {
+        let title = fm.get("title").and_then(|t| t.as_str()).unwrap_or(fileStem).to_string();
+        return (title, None, None, body);
+    }
+    (fileStem.to_string(), None, None, raw.to_string())
+}
+
+/// Import the .md files of `srcDir` into `folderDir`, mirroring subdirectories as folders
+fn importMarkdownDirInto(storage: &StorageState, wsPath: &str, srcDir: &Path, folderDir: &Path, result: &mut MarkdownImportResult) {
+    let mut entries: Vec<PathBuf> = fs::read_dir(srcDir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+        .collect();
+    entries.sort();
+
+    // create_note takes folder paths relative to the folders root
+    let relativeFolder = folderDir.strip_prefix(foldersDir(wsPath)).ok()
+        .map(|p| p.to_string_lossy().to_string())
+        .filter(|p| !p.is_empty());
+
+    for path in entries {
+        let skip = |result: &mut MarkdownImportResult, error: String| {
+            result.skipped.push(SkippedImportFile { path: path.to_string_lossy().to_string(), error });
+        };
+
+        if path.is_dir() {
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let parent = folderDir.to_string_lossy();
+            match api::create_folder(storage, &name, Some(parent.as_ref())) {
+                Ok(folder) => {
+                    result.foldersCreated += 1;
+                    importMarkdownDirInto(storage, wsPath, &path, Path::new(&folder.path), result);
+                }
+                Err(e) => skip(result, e),
+            }
+            continue;
+        }
+
+        if !path.extension().is_some_and(|e| e.eq_ignore_ascii_case("md")) {
+            continue;
+        }
+
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) => {
+                skip(result, format!("Failed to read file: {}", e));
+                continue;
+            }
+        };
+        let stem = path.file_stem().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let (title, color, tags, body) = parseImportedNote(&raw, &stem);
+
+        match api::create_note(storage, &title, Some(&body), relativeFolder.as_deref(), color.as_deref(), tags.as_deref()) {
+            Ok(_) => result.notesCreated += 1,
+            Err(e) => skip(result, e),
+        }
+    }
+}
+
+/// Import a directory of markdown files as encrypted notes under `targetFolderPath`
+/// Subdirectories become folders; unreadable files are reported in `skipped` instead of aborting
+#[tauri::command]
+pub fn importMarkdownDir(
+    storage: State<'_, StorageState>,
+    srcDir: String,
+    targetFolderPath: String,
+) -> Result<MarkdownImportResult, ClaudiaError> {
+    println!("[importMarkdownDir] Called with srcDir: {}, target: {}", srcDir, targetFolderPath);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let src = Path::new(&srcDir);
+    if !src.is_dir() {
+        return Err(ClaudiaError::InvalidInput(format!("Invalid input: {} is not a directory", srcDir)));
+    }
+    if src.starts_with(&wsPath) {
+        return Err(ClaudiaError::InvalidInput("Invalid input: cannot import from inside the workspace".to_string()));
+    }
+    let target = validateMoveTarget(&wsPath, &targetFolderPath)?;
+
+    let mut result = MarkdownImportResult::default();
+    importMarkdownDirInto(&storage, &wsPath, src, target, &mut result);
+
+    println!("[importMarkdownDir] SUCCESS - {} notes, {} folders, {} skipped",
+        result.notesCreated, result.foldersCreated, result.skipped.len());
+    storage.updateActivity();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_slug() {
+        let mut used = HashSet::new();
+        assert_eq!(uniqueSlug(&mut used, "Meeting Notes", ".md"), "meeting-notes.md");
+        assert_eq!(uniqueSlug(&mut used, "meeting notes!", ".md"), "meeting-notes-2.md");
+        assert_eq!(uniqueSlug(&mut used, "Meeting Notes", ".md"), "meeting-notes-3.md");
+        assert_eq!(uniqueSlug(&mut used, "Meeting Notes", ""), "meeting-notes");
+        assert_eq!(uniqueSlug(&mut used, "???", ".md"), "untitled.md");
+    }
+
+    #[test]
+    fn test_parse_imported_note() {
+        let (title, _, tags, body) = parseImportedNote("---\ntitle: From Obsidian\naliases: [x]\n---\n\nHello", "file");
+        assert_eq!(title, "From Obsidian");
+        assert!(tags.is_none());
+        assert_eq!(body, "Hello");
+
+        let (title, _, _, body) = parseImportedNote("# Just markdown", "my-note");
+        assert_eq!(title, "my-note");
+        assert_eq!(body, "# Just markdown");
+
+        let raw = "---\nid: x\ntitle: Ours\ntags: [a]\ncreated: 1\nupdated: 2\n---\n\nBody";
+        let (title, _, tags, body) = parseImportedNote(raw, "file");
+        assert_eq!(title, "Ours");
+        assert_eq!(tags, Some(vec!["a".to_string()]));
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn test_item_counts_by_layout() {
+        let mut counts = VaultItemCounts::default();
+        for name in [
+            "folders/a/.folder.md",
+            "folders/a/notes/1.md",
+            "folders/notes/2.md",
+            "folders/a/tasks/todo/3.md",
+            "folders/a/passwords/4.md",
+            ".trash/notes/5.md",
+            ".assets/1/image.png",
+            ".vault",
+        ] {
+            counts.add(name);
+        }
+        assert_eq!(counts, VaultItemCounts { folders: 1, notes: 3, tasks: 1, passwords: 1 });
+    }
+
+    #[test]
+    fn test_archive_names() {
+        assert!(isVaultArchiveName(".vault"));
+        assert!(isVaultArchiveName("folders/a/notes/1.md"));
+        assert!(isVaultArchiveName(".trash/tasks/done/1.md"));
+        assert!(!isVaultArchiveName("config.md"));
+        assert!(!isVaultArchiveName(".vaultx"));
+    }
+
+    #[test]
+    fn test_checksum_covers_names_and_content() {
+        let sum = |files: &[(&str, &[u8])]| {
+            let mut checksum = ArchiveChecksum::new();
+            for (name, bytes) in files {
+                checksum.add(name, bytes);
+            }
+            checksum.finish()
+        };
+        let base = sum(&[("a", b"1"), ("b", b"2")]);
+        assert_eq!(base, sum(&[("a", b"1"), ("b", b"2")]));
+        assert_ne!(base, sum(&[("a", b"1"), ("b", b"3")]));
+        assert_ne!(base, sum(&[("a", b"1"), ("c", b"2")]));
+    }
+
+    #[test]
+    fn test_encrypted_folder_zip_keeps_layout() {
+        let root = std::env::temp_dir().join(format!("claudia-test-{}", super::super::common::newId()));
+        let folder = root.join("abc");
+        fs::create_dir_all(folder.join("notes")).unwrap();
+        fs::create_dir_all(folder.join("tasks/todo")).unwrap();
+        fs::write(folder.join(".folder.md"), "meta").unwrap();
+        fs::write(folder.join("notes/1.md"), "note").unwrap();
+        fs::write(folder.join("tasks/todo/2.md"), "task").unwrap();
+        fs::write(root.join("sibling.md"), "outside").unwrap();
+
+        let dest = root.join("out.zip");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&dest).unwrap());
+        assert_eq!(zipFolderEncrypted(&mut zip, &folder).unwrap(), 3);
+        zip.finish().unwrap();
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&dest).unwrap()).unwrap();
+        let mut note = String::new();
+        archive.by_name("abc/notes/1.md").unwrap().read_to_string(&mut note).unwrap();
+        assert_eq!(note, "note");
+        assert!(archive.by_name("abc/.folder.md").is_ok());
+        assert!(archive.by_name("abc/tasks/todo/2.md").is_ok());
+        assert!(archive.by_name("sibling.md").is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
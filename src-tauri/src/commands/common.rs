@@ -4,6 +4,8 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
+use crate::error::ClaudiaError;
+
 /// Get current timestamp in milliseconds
 pub fn now() -> i64 {
     SystemTime::now()
@@ -16,3 +18,141 @@ pub fn now() -> i64 {
 pub fn newId() -> String {
     Uuid::new_v4().to_string()
 }
+
+/// Outcome for one id of a batch command, so partial failures stay visible
+#[derive(serde::Serialize)]
+pub struct BatchResult {
+    pub id: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl BatchResult {
+    pub fn new(id: String, result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Self { id, success: true, error: None },
+            Err(e) => Self { id, success: false, error: Some(e) },
+        }
+    }
+}
+
+/// One page of a list; `total` counts every item matching the filters, not just this page
+#[derive(serde::Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    /// Offset of the next page, None on the last one
+    pub nextOffset: Option<usize>,
+}
+
+impl<T> Page<T> {
+    /// Cut a page out of the full, already sorted list; no limit means "everything from offset"
+    pub fn of(items: Vec<T>, offset: usize, limit: Option<usize>) -> Self {
+        let total = items.len();
+        let items: Vec<T> = items.into_iter().skip(offset).take(limit.unwrap_or(usize::MAX)).collect();
+        let end = offset.saturating_add(items.len());
+        Self { items, total, nextOffset: (end < total).then_some(end) }
+    }
+}
+
+/// Reject item bodies larger than the configured limit (0 = unlimited)
+/// Checked before encryption so oversized pastes never reach the cipher
+pub fn validateItemSize(body: &str, maxItemBytes: u64) -> Result<(), ClaudiaError> {
+    validateByteSize(body.len() as u64, maxItemBytes)
+}
+
+/// Same limit for raw binary payloads (e.g. pasted images)
+pub fn validateByteSize(size: u64, maxItemBytes: u64) -> Result<(), ClaudiaError> {
+    if maxItemBytes > 0 && size > maxItemBytes {
+        return Err(ClaudiaError::InvalidInput(format!(
+            "Invalid input: content is {} bytes, exceeding the {} byte limit",
+            size, maxItemBytes
+        )));
+    }
+    Ok(())
+}
+
+/// Clean a user-supplied title before it is stored
+/// Line breaks and tabs become spaces, other control characters and U+FFFD
+/// (left behind by invalid byte sequences) are dropped, then the result is trimmed
+/// With `emojiShortcodes`, `:name:` shortcodes are replaced by their emoji
+pub fn sanitizeTitle(title: &str, emojiShortcodes: bool) -> String {
+    let cleaned: String = title
+        .chars()
+        .filter_map(|c| match c {
+            '\n' | '\r' | '\t' => Some(' '),
+            '\u{FFFD}' => None,
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect();
+
+    let cleaned = if emojiShortcodes { replaceShortcodes(&cleaned) } else { cleaned };
+    cleaned.trim().to_string()
+}
+
+/// Replace known `:shortcode:` sequences, leaving unknown ones untouched
+fn replaceShortcodes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(':') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let end = after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '+'));
+
+        match end {
+            Some(end) if end > 0 && after[end..].starts_with(':') => {
+                match emojis::get_by_shortcode(&after[..end]) {
+                    Some(emoji) => {
+                        out.push_str(emoji.as_str());
+                        rest = &after[end + 1..];
+                    }
+                    None => {
+                        // Keep the text, but let the closing colon start the next shortcode
+                        out.push(':');
+                        out.push_str(&after[..end]);
+                        rest = &after[end..];
+                    }
+                }
+            }
+            _ => {
+                out.push(':');
+                rest = after;
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_offsets() {
+        let first = Page::of((0..5).collect(), 0, Some(2));
+        assert_eq!((first.items, first.total, first.nextOffset), (vec![0, 1], 5, Some(2)));
+
+        let last = Page::of((0..5).collect(), 4, Some(2));
+        assert_eq!((last.items, last.total, last.nextOffset), (vec![4], 5, None));
+
+        let past = Page::<i32>::of((0..5).collect(), 9, Some(2));
+        assert_eq!((past.items.len(), past.nextOffset), (0, None));
+
+        let rest = Page::of((0..5).collect(), 1, None);
+        assert_eq!((rest.items, rest.nextOffset), (vec![1, 2, 3, 4], None));
+    }
+
+    #[test]
+    fn test_oversized_content_is_invalid_input() {
+        assert!(validateItemSize("four", 4).is_ok());
+        assert!(validateItemSize("unlimited", 0).is_ok());
+
+        let json = serde_json::to_value(validateItemSize("five!", 4).unwrap_err()).unwrap();
+        assert_eq!(json["code"], "INVALID_INPUT");
+        assert_eq!(validateByteSize(5, 4).unwrap_err().code(), "INVALID_INPUT");
+    }
+}
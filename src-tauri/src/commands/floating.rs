@@ -1,8 +1,18 @@
 // Floating window commands - complete implementation
 
-use tauri::{Manager, WebviewWindowBuilder, WebviewUrl};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Manager, State, WebviewWindowBuilder, WebviewUrl};
 use urlencoding::encode;
 
+use crate::error::ClaudiaError;
+use crate::storage::{StorageState, foldersDir};
+use crate::models::{NoteFrontmatter, TaskFrontmatter};
+use super::item::rewriteMetadata;
+use super::note::{findNoteById, scanAllNotes};
+use super::task::{findTaskById, scanAllTasks};
+
 #[cfg(target_os = "macos")]
 use window_vibrancy::{apply_vibrancy, NSVisualEffectMaterial};
 
@@ -15,40 +25,85 @@ pub struct FloatingWindowConfig {
     pub color: String,
     pub x: f64,
     pub y: f64,
-    pub width: f64,
-    pub height: f64,
-    pub opacity: f64,
-    pub theme: String,  // 'light', 'dark', or 'system'
+    // Unset size/opacity/theme come from the floatingDefaults setting
+    #[serde(default)]
+    pub width: Option<f64>,
+    #[serde(default)]
+    pub height: Option<f64>,
+    #[serde(default)]
+    pub opacity: Option<f64>,
+    #[serde(default)]
+    pub theme: Option<String>,  // 'light', 'dark', or 'system'
+}
+
+/// Window appearance after filling unset config fields from settings
+struct FloatingAppearance {
+    width: f64,
+    height: f64,
+    opacity: f64,
+    theme: String,
+}
+
+fn resolveAppearance(app: &tauri::AppHandle, config: &FloatingWindowConfig) -> FloatingAppearance {
+    let settings = app.try_state::<StorageState>()
+        .map(|storage| storage.effectiveSettings())
+        .unwrap_or_default();
+    let defaults = &settings.floatingDefaults;
+
+    FloatingAppearance {
+        width: config.width.unwrap_or(defaults.width),
+        height: config.height.unwrap_or(defaults.height),
+        opacity: config.opacity.or(defaults.opacity).unwrap_or(settings.floatingOpacity),
+        theme: config.theme.clone()
+            .or_else(|| defaults.theme.clone())
+            .unwrap_or_else(|| settings.theme.clone()),
+    }
+}
+
+/// Window label of an item's floating window
+fn floatingLabel(itemType: &str, id: &str) -> String {
+    format!("float_{}_{}", itemType, id.replace("-", "_"))
+}
+
+/// Close an item's floating window if one is open (used when the item is deleted)
+pub(crate) fn closeItemFloatingWindow(app: &tauri::AppHandle, itemType: &str, id: &str) {
+    if let Some(window) = app.get_webview_window(&floatingLabel(itemType, id)) {
+        println!("[closeItemFloatingWindow] Closing floating window for {} {}", itemType, id);
+        let _ = window.close();
+    }
 }
 
 #[tauri::command]
-pub fn createFloatingWindow(app: tauri::AppHandle, config: FloatingWindowConfig) -> Result<(), String> {
+pub fn createFloatingWindow(app: tauri::AppHandle, config: FloatingWindowConfig) -> Result<(), ClaudiaError> {
     println!("[createFloatingWindow] Called with:");
     println!("  - note_id: {}", config.note_id);
     println!("  - item_type: {}", config.item_type);
     println!("  - title: {}", config.title);
     println!("  - position: ({}, {})", config.x, config.y);
-    println!("  - size: {}x{}", config.width, config.height);
+
+    let appearance = resolveAppearance(&app, &config);
+    println!("  - size: {}x{}", appearance.width, appearance.height);
 
     // Validate item_type - must be "task" or "note"
     if config.item_type != "task" && config.item_type != "note" {
-        return Err("Invalid item_type: must be 'task' or 'note'".to_string());
+        return Err(ClaudiaError::InvalidInput("Invalid item_type: must be 'task' or 'note'".to_string()));
     }
 
     // Validate note_id - must be alphanumeric with dashes (UUID format)
     if !config.note_id.chars().all(|c| c.is_alphanumeric() || c == '-') {
-        return Err("Invalid note_id format".to_string());
+        return Err(ClaudiaError::InvalidInput("Invalid note_id format".to_string()));
     }
 
     // Validate theme - must be "light", "dark", or "system"
-    if config.theme != "light" && config.theme != "dark" && config.theme != "system" {
-        return Err("Invalid theme: must be 'light', 'dark', or 'system'".to_string());
+    let theme = appearance.theme;
+    if theme != "light" && theme != "dark" && theme != "system" {
+        return Err(ClaudiaError::InvalidInput("Invalid theme: must be 'light', 'dark', or 'system'".to_string()));
     }
 
     // Validate opacity - must be between 0 and 1
-    let opacity = config.opacity.clamp(0.0, 1.0);
+    let opacity = appearance.opacity.clamp(0.0, 1.0);
 
-    let label = format!("float_{}_{}", config.item_type, config.note_id.replace("-", "_"));
+    let label = floatingLabel(&config.item_type, &config.note_id);
     println!("[createFloatingWindow] Window label: {}", label);
 
     // Check if window already exists
@@ -65,15 +120,21 @@ pub fn createFloatingWindow(app: tauri::AppHandle, config: FloatingWindowConfig)
         encode(&config.item_type),
         encode(&config.note_id),
         encode(&opacity.to_string()),
-        encode(&config.theme)
+        encode(&theme)
     );
     println!("[createFloatingWindow] Creating new window with URL: {}", url);
-    println!("[createFloatingWindow] Opacity: {}, Theme: {}", opacity, config.theme);
+    println!("[createFloatingWindow] Opacity: {}, Theme: {}", opacity, theme);
+
+    // Keep the window reachable if it was saved on a monitor that is gone
+    let (x, y, width, height) = clampToMonitors(config.x, config.y, appearance.width, appearance.height, &monitorRects(&app));
+    if (x, y) != (config.x, config.y) {
+        println!("[createFloatingWindow] Moved onto a visible monitor: ({}, {})", x, y);
+    }
 
     let window = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(url.into()))
         .title("")
-        .inner_size(config.width, config.height)
-        .position(config.x, config.y)
+        .inner_size(width, height)
+        .position(x, y)
         .decorations(false)
         .transparent(true)
         .always_on_top(true)
@@ -107,7 +168,7 @@ pub fn createFloatingWindow(app: tauri::AppHandle, config: FloatingWindowConfig)
 }
 
 #[tauri::command]
-pub fn showFloatingWindow(app: tauri::AppHandle, note_id: String) -> Result<(), String> {
+pub fn showFloatingWindow(app: tauri::AppHandle, note_id: String) -> Result<(), ClaudiaError> {
     println!("[showFloatingWindow] Called with note_id: {}", note_id);
 
     // Find any floating window with this ID
@@ -124,11 +185,11 @@ pub fn showFloatingWindow(app: tauri::AppHandle, note_id: String) -> Result<(),
         }
     }
     println!("[showFloatingWindow] ERROR - Window not found");
-    Err("Window not found".to_string())
+    Err(ClaudiaError::NotFound("Window not found".to_string()))
 }
 
 #[tauri::command]
-pub fn hideFloatingWindow(app: tauri::AppHandle, note_id: String) -> Result<(), String> {
+pub fn hideFloatingWindow(app: tauri::AppHandle, note_id: String) -> Result<(), ClaudiaError> {
     println!("[hideFloatingWindow] Called with note_id: {}", note_id);
 
     let windows = app.webview_windows();
@@ -144,7 +205,7 @@ pub fn hideFloatingWindow(app: tauri::AppHandle, note_id: String) -> Result<(),
 }
 
 #[tauri::command]
-pub fn closeFloatingWindow(app: tauri::AppHandle, note_id: String) -> Result<(), String> {
+pub fn closeFloatingWindow(app: tauri::AppHandle, note_id: String) -> Result<(), ClaudiaError> {
     println!("[closeFloatingWindow] Called with note_id: {}", note_id);
 
     let windows = app.webview_windows();
@@ -160,7 +221,7 @@ pub fn closeFloatingWindow(app: tauri::AppHandle, note_id: String) -> Result<(),
 }
 
 #[tauri::command]
-pub fn closeAllFloatingWindows(app: tauri::AppHandle) -> Result<(), String> {
+pub fn closeAllFloatingWindows(app: tauri::AppHandle) -> Result<(), ClaudiaError> {
     println!("[closeAllFloatingWindows] Called");
 
     let windows = app.webview_windows();
@@ -177,7 +238,7 @@ pub fn closeAllFloatingWindows(app: tauri::AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn toggleAllFloatingWindows(app: tauri::AppHandle) -> Result<(), String> {
+pub fn toggleAllFloatingWindows(app: tauri::AppHandle) -> Result<(), ClaudiaError> {
     println!("[toggleAllFloatingWindows] Called");
 
     let windows = app.webview_windows();
@@ -209,15 +270,18 @@ pub fn toggleAllFloatingWindows(app: tauri::AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn updateFloatingWindowPosition(app: tauri::AppHandle, note_id: String, x: f64, y: f64) -> Result<(), String> {
+pub fn updateFloatingWindowPosition(app: tauri::AppHandle, note_id: String, x: f64, y: f64) -> Result<(), ClaudiaError> {
     println!("[updateFloatingWindowPosition] note_id: {}, x: {}, y: {}", note_id, x, y);
 
     let windows = app.webview_windows();
     for (label, window) in windows {
         if label.contains(&note_id.replace("-", "_")) {
             println!("[updateFloatingWindowPosition] Found window {}, updating position", label);
+            let (width, height) = logicalSize(&window);
+            let (x, y, _, _) = clampToMonitors(x, y, width, height, &monitorRects(&app));
             window.set_position(tauri::Position::Logical(tauri::LogicalPosition::new(x, y)))
                 .map_err(|e| e.to_string())?;
+            scheduleGeometrySave(&app, &label, &note_id, GeometryUpdate { x: Some(x), y: Some(y), ..Default::default() });
             return Ok(());
         }
     }
@@ -226,7 +290,7 @@ pub fn updateFloatingWindowPosition(app: tauri::AppHandle, note_id: String, x: f
 }
 
 #[tauri::command]
-pub fn updateFloatingWindowSize(app: tauri::AppHandle, note_id: String, width: f64, height: f64) -> Result<(), String> {
+pub fn updateFloatingWindowSize(app: tauri::AppHandle, note_id: String, width: f64, height: f64) -> Result<(), ClaudiaError> {
     println!("[updateFloatingWindowSize] note_id: {}, width: {}, height: {}", note_id, width, height);
 
     let windows = app.webview_windows();
@@ -235,6 +299,7 @@ pub fn updateFloatingWindowSize(app: tauri::AppHandle, note_id: String, width: f
             println!("[updateFloatingWindowSize] Found window {}, updating size", label);
             window.set_size(tauri::Size::Logical(tauri::LogicalSize::new(width, height)))
                 .map_err(|e| e.to_string())?;
+            scheduleGeometrySave(&app, &label, &note_id, GeometryUpdate { w: Some(width), h: Some(height), ..Default::default() });
             return Ok(());
         }
     }
@@ -273,3 +338,309 @@ pub fn getFloatingWindowSize(app: tauri::AppHandle, note_id: String) -> Option<(
     println!("[getFloatingWindowSize] No matching window found");
     None
 }
+
+// ============================================
+// MONITOR BOUNDS
+// ============================================
+
+/// Width of a window that must stay on a monitor so it can still be grabbed
+const MIN_VISIBLE_WIDTH: f64 = 80.0;
+/// Height of the top strip (drag handle) that must stay on a monitor
+const TITLE_REGION_HEIGHT: f64 = 40.0;
+
+/// A monitor's area in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ScreenRect {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+}
+
+impl ScreenRect {
+    fn fromMonitor(monitor: &tauri::Monitor) -> Self {
+        let scale = monitor.scale_factor();
+        let position = monitor.position().to_logical::<f64>(scale);
+        let size = monitor.size().to_logical::<f64>(scale);
+        Self { x: position.x, y: position.y, w: size.width, h: size.height }
+    }
+
+    /// Area shared with another rect (0 when they don't overlap)
+    fn overlap(&self, x: f64, y: f64, w: f64, h: f64) -> f64 {
+        let width = (self.x + self.w).min(x + w) - self.x.max(x);
+        let height = (self.y + self.h).min(y + h) - self.y.max(y);
+        if width > 0.0 && height > 0.0 { width * height } else { 0.0 }
+    }
+
+    fn distanceTo(&self, x: f64, y: f64) -> f64 {
+        let dx = (self.x - x).max(0.0).max(x - (self.x + self.w));
+        let dy = (self.y - y).max(0.0).max(y - (self.y + self.h));
+        dx.hypot(dy)
+    }
+}
+
+fn monitorRects(app: &tauri::AppHandle) -> Vec<ScreenRect> {
+    app.available_monitors()
+        .map(|monitors| monitors.iter().map(ScreenRect::fromMonitor).collect())
+        .unwrap_or_default()
+}
+
+/// Logical outer size of a window
+fn logicalSize(window: &tauri::WebviewWindow) -> (f64, f64) {
+    let scale = window.scale_factor().unwrap_or(1.0);
+    window.outer_size()
+        .map(|size| { let size = size.to_logical::<f64>(scale); (size.width, size.height) })
+        .unwrap_or((0.0, 0.0))
+}
+
+/// Fit a window onto the monitors: it keeps the monitor showing most of its title region
+/// (or the nearest one when none does), shrinks to fit that monitor, and moves just enough
+/// for its title region to stay on it. With no monitor information nothing changes.
+fn clampToMonitors(x: f64, y: f64, w: f64, h: f64, monitors: &[ScreenRect]) -> (f64, f64, f64, f64) {
+    let titleOverlap = |m: &&ScreenRect| m.overlap(x, y, w, TITLE_REGION_HEIGHT);
+    let monitor = monitors.iter()
+        .filter(|m| titleOverlap(m) > 0.0)
+        .max_by(|a, b| titleOverlap(a).total_cmp(&titleOverlap(b)))
+        .or_else(|| monitors.iter().min_by(|a, b| a.distanceTo(x, y).total_cmp(&b.distanceTo(x, y))));
+    let Some(m) = monitor else {
+        return (x, y, w, h);
+    };
+
+    let w = w.min(m.w);
+    let h = h.min(m.h);
+    let visibleWidth = MIN_VISIBLE_WIDTH.min(w);
+    let x = x.clamp(m.x - (w - visibleWidth), m.x + m.w - visibleWidth);
+    let y = y.clamp(m.y, m.y + m.h - TITLE_REGION_HEIGHT.min(h));
+    (x, y, w, h)
+}
+
+/// Move a floating window back to the middle of the primary monitor, shrinking it if needed
+#[tauri::command]
+pub fn recenterFloatingWindow(app: tauri::AppHandle, note_id: String) -> Result<(), ClaudiaError> {
+    println!("[recenterFloatingWindow] note_id: {}", note_id);
+
+    let monitor = app.primary_monitor().ok().flatten()
+        .or_else(|| app.available_monitors().ok().and_then(|m| m.into_iter().next()))
+        .ok_or("No monitor available")?;
+    let screen = ScreenRect::fromMonitor(&monitor);
+
+    let windows = app.webview_windows();
+    for (label, window) in windows {
+        if label.contains(&note_id.replace("-", "_")) {
+            let (width, height) = logicalSize(&window);
+            let (x, y, width, height) = centeredOn(&screen, width, height);
+            println!("[recenterFloatingWindow] Moving {} to ({}, {})", label, x, y);
+
+            window.set_size(tauri::Size::Logical(tauri::LogicalSize::new(width, height)))
+                .map_err(|e| e.to_string())?;
+            window.set_position(tauri::Position::Logical(tauri::LogicalPosition::new(x, y)))
+                .map_err(|e| e.to_string())?;
+            window.show().map_err(|e| e.to_string())?;
+            scheduleGeometrySave(&app, &label, &note_id, GeometryUpdate { x: Some(x), y: Some(y), w: Some(width), h: Some(height) });
+            return Ok(());
+        }
+    }
+    Err(ClaudiaError::NotFound("Window not found".to_string()))
+}
+
+/// Position (and size, capped to the screen) that centers a window on a monitor
+fn centeredOn(screen: &ScreenRect, w: f64, h: f64) -> (f64, f64, f64, f64) {
+    let w = w.min(screen.w);
+    let h = h.min(screen.h);
+    (screen.x + (screen.w - w) / 2.0, screen.y + (screen.h - h) / 2.0, w, h)
+}
+
+// ============================================
+// GEOMETRY PERSISTENCE
+// ============================================
+// Window geometry is written back into the item's `float` frontmatter so it survives restarts.
+// Moves and resizes arrive in bursts while dragging, so writes wait until the window settles.
+
+/// How long a window must stay still before its geometry is written
+const GEOMETRY_SAVE_DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// Geometry fields changed since the last write; unset fields keep their stored value
+#[derive(Debug, Clone, Copy, Default)]
+struct GeometryUpdate {
+    x: Option<f64>,
+    y: Option<f64>,
+    w: Option<f64>,
+    h: Option<f64>,
+}
+
+impl GeometryUpdate {
+    fn merge(self, newer: GeometryUpdate) -> GeometryUpdate {
+        GeometryUpdate {
+            x: newer.x.or(self.x),
+            y: newer.y.or(self.y),
+            w: newer.w.or(self.w),
+            h: newer.h.or(self.h),
+        }
+    }
+
+    /// Only position and size: `show` belongs to open/close, and a save that lands after
+    /// the window was closed must not mark it open again
+    fn applyTo(&self, float: &mut crate::models::FloatWindow) {
+        if let Some(x) = self.x { float.x = x; }
+        if let Some(y) = self.y { float.y = y; }
+        if let Some(w) = self.w { float.w = w; }
+        if let Some(h) = self.h { float.h = h; }
+    }
+}
+
+/// Unsaved geometry per item id, tagged with a generation so only the latest change writes
+static PENDING_GEOMETRY: Mutex<Option<HashMap<String, (u64, GeometryUpdate)>>> = Mutex::new(None);
+
+/// Queue a geometry write for the item behind a floating window label, replacing any pending one
+fn scheduleGeometrySave(app: &tauri::AppHandle, label: &str, id: &str, update: GeometryUpdate) {
+    let Some(itemType) = label.strip_prefix("float_").and_then(|rest| rest.split('_').next()) else {
+        return;
+    };
+    let itemType = itemType.to_string();
+    let id = id.to_string();
+
+    let generation = {
+        let mut pending = PENDING_GEOMETRY.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = pending.get_or_insert_with(HashMap::new).entry(id.clone()).or_insert((0, GeometryUpdate::default()));
+        *entry = (entry.0 + 1, entry.1.merge(update));
+        entry.0
+    };
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(GEOMETRY_SAVE_DEBOUNCE);
+        let update = {
+            let mut pending = PENDING_GEOMETRY.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(map) = pending.as_mut() else { return };
+            match map.get(&id) {
+                Some((latest, _)) if *latest == generation => map.remove(&id).map(|(_, u)| u),
+                _ => None, // A newer change is pending; its own timer will write
+            }
+        };
+        let (Some(update), Some(storage)) = (update, app.try_state::<StorageState>()) else {
+            return;
+        };
+        if let Err(e) = persistGeometry(&storage, &itemType, &id, update) {
+            println!("[scheduleGeometrySave] Could not save geometry for {} {}: {}", itemType, id, e);
+        }
+    });
+}
+
+/// Write geometry into the item's frontmatter without touching its body or `updated` time
+fn persistGeometry(storage: &StorageState, itemType: &str, id: &str, update: GeometryUpdate) -> Result<(), ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    match itemType {
+        "note" => {
+            let note = findNoteById(storage, &wsPath, id, Some(&masterPassword)).ok_or_else(|| ClaudiaError::NotFound("Note not found".to_string()))?;
+            rewriteMetadata(&note.path, &masterPassword, |fm: &mut NoteFrontmatter| update.applyTo(&mut fm.float))?;
+        }
+        "task" => {
+            let task = findTaskById(storage, &wsPath, id, Some(&masterPassword)).ok_or_else(|| ClaudiaError::NotFound("Task not found".to_string()))?;
+            rewriteMetadata(&task.path, &masterPassword, |fm: &mut TaskFrontmatter| update.applyTo(&mut fm.float))?;
+        }
+        _ => return Err(ClaudiaError::InvalidInput(format!("Invalid item type: {}", itemType))),
+    }
+    Ok(())
+}
+
+/// Reopen a floating window for every note and task that was left open, at its saved geometry
+/// Returns how many windows were opened
+#[tauri::command]
+pub fn restoreFloatingWindows(app: tauri::AppHandle, storage: State<'_, StorageState>) -> Result<u32, ClaudiaError> {
+    println!("[restoreFloatingWindows] Called");
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+    let baseDir = foldersDir(&wsPath);
+
+    let notes = scanAllNotes(&baseDir, Some(&masterPassword)).into_iter()
+        .filter(|n| n.frontmatter.float.show)
+        .map(|n| ("note", n.frontmatter.id, n.frontmatter.title, n.frontmatter.color, n.frontmatter.float));
+    let tasks = scanAllTasks(&baseDir, Some(&masterPassword)).into_iter()
+        .filter(|t| t.frontmatter.float.show)
+        .map(|t| ("task", t.frontmatter.id, t.frontmatter.title, t.frontmatter.color, t.frontmatter.float));
+
+    let mut opened = 0;
+    for (itemType, id, title, color, float) in notes.chain(tasks) {
+        let config = FloatingWindowConfig {
+            note_id: id.clone(),
+            item_type: itemType.to_string(),
+            title,
+            color,
+            x: float.x,
+            y: float.y,
+            width: Some(float.w).filter(|w| *w > 0.0),
+            height: Some(float.h).filter(|h| *h > 0.0),
+            opacity: None,
+            theme: None,
+        };
+        match createFloatingWindow(app.clone(), config) {
+            Ok(()) => opened += 1,
+            Err(e) => println!("[restoreFloatingWindows] Could not reopen {} {}: {}", itemType, id, e),
+        }
+    }
+
+    println!("[restoreFloatingWindows] Reopened {} windows", opened);
+    Ok(opened)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN: ScreenRect = ScreenRect { x: 0.0, y: 0.0, w: 1440.0, h: 900.0 };
+    const RIGHT: ScreenRect = ScreenRect { x: 1440.0, y: 0.0, w: 1920.0, h: 1080.0 };
+
+    #[test]
+    fn test_clamp_keeps_visible_windows_and_rescues_lost_ones() {
+        // Fully visible on the second monitor: untouched
+        assert_eq!(clampToMonitors(2000.0, 100.0, 400.0, 300.0, &[MAIN, RIGHT]), (2000.0, 100.0, 400.0, 300.0));
+
+        // Saved on the second monitor, which is now unplugged: pulled back onto the main one
+        let (x, y, w, h) = clampToMonitors(2000.0, 100.0, 400.0, 300.0, &[MAIN]);
+        assert_eq!((w, h), (400.0, 300.0));
+        assert_eq!(x, MAIN.w - MIN_VISIBLE_WIDTH);
+        assert_eq!(y, 100.0);
+
+        // Above the screen: the title region comes back down
+        let (_, y, _, _) = clampToMonitors(100.0, -500.0, 400.0, 300.0, &[MAIN]);
+        assert_eq!(y, 0.0);
+
+        // Mostly off the left edge but still grabbable: left alone
+        assert_eq!(clampToMonitors(-300.0, 50.0, 400.0, 300.0, &[MAIN]), (-300.0, 50.0, 400.0, 300.0));
+
+        // Larger than the monitor: shrunk to fit
+        let (_, _, w, h) = clampToMonitors(0.0, 0.0, 3000.0, 2000.0, &[MAIN]);
+        assert_eq!((w, h), (MAIN.w, MAIN.h));
+
+        // No monitor information: nothing to clamp against
+        assert_eq!(clampToMonitors(-9000.0, 9000.0, 400.0, 300.0, &[]), (-9000.0, 9000.0, 400.0, 300.0));
+    }
+
+    #[test]
+    fn test_geometry_save_leaves_show_alone() {
+        // Window closed (show: false) while a move was still debouncing
+        let mut float = crate::models::FloatWindow { show: false, ..Default::default() };
+        GeometryUpdate { x: Some(40.0), w: Some(320.0), ..Default::default() }.applyTo(&mut float);
+        assert_eq!((float.x, float.w), (40.0, 320.0));
+        assert!(!float.show);
+    }
+
+    #[test]
+    fn test_centered_on_monitor() {
+        assert_eq!(centeredOn(&RIGHT, 400.0, 300.0), (1440.0 + 760.0, 390.0, 400.0, 300.0));
+        assert_eq!(centeredOn(&MAIN, 2000.0, 300.0), (0.0, 300.0, 1440.0, 300.0));
+    }
+}
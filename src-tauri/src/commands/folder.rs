@@ -1,13 +1,19 @@
 // Folder commands - unified folder tree implementation with encrypted metadata
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::State;
 
-use crate::storage::{StorageState, foldersDir, isValidUuidDir, trashNotesDir, trashTasksDir, trashPasswordsDir};
-use crate::encrypted_storage;
-use crate::models::{Folder, FolderFrontmatter, TaskStatus};
+use crate::error::ClaudiaError;
+use crate::storage::{StorageState, foldersDir, isValidUuidDir, parseUuidFilename, uuidFilename, trashNotesDir, trashTasksDir, trashPasswordsDir, recordTrashOrigin, itemsDir, resolveFolderArg, validateFolderDir, validateMoveTarget, writeFileAtomic};
+use crate::encrypted_storage::{self, fileIdOf};
+use crate::models::{Folder, FolderFrontmatter, TaskStatus, ResolvedColor, ItemKind, normalizeColor};
+use super::asset::{copyNoteAssets, retargetAssetReferences};
 use super::common::newId;
+use super::note::scanNotesInFolder;
+use super::task::scanTasksInFolder;
+use super::password::scanPasswordsInFolder;
+use super::item::rewriteMetadata;
 
 #[derive(serde::Serialize)]
 pub struct FolderInfo {
@@ -17,6 +23,7 @@ pub struct FolderInfo {
     pub pinned: bool,
     pub favorite: bool,
     pub color: String,
+    pub resolvedColor: ResolvedColor,
     pub icon: String,
     pub path: String,
     pub parentPath: Option<String>,
@@ -32,6 +39,7 @@ impl From<&Folder> for FolderInfo {
             pinned: f.frontmatter.pinned,
             favorite: f.frontmatter.favorite,
             color: f.frontmatter.color.clone(),
+            resolvedColor: ResolvedColor::of(&f.frontmatter.color),
             icon: f.frontmatter.icon.clone(),
             path: f.path.to_string_lossy().to_string(),
             parentPath: f.parentPath.as_ref().map(|p| p.to_string_lossy().to_string()),
@@ -42,6 +50,34 @@ impl From<&Folder> for FolderInfo {
 
 /// Scan folders recursively from a directory using encrypted format
 pub(crate) fn scanFolders(baseDir: &PathBuf, parentPath: Option<PathBuf>, masterPassword: Option<&str>) -> Vec<Folder> {
+    scanFolderLevel(baseDir, parentPath, masterPassword, true)
+}
+
+/// Decrypt a folder's .folder.md metadata
+pub(crate) fn readFolderFrontmatter(folderMdPath: &PathBuf, masterPassword: &str) -> Result<FolderFrontmatter, String> {
+    let content = fs::read_to_string(folderMdPath).map_err(|e| e.to_string())?;
+    if !encrypted_storage::isEncryptedFormat(&content) {
+        return Err("Folder metadata is not encrypted".to_string());
+    }
+    let encrypted = encrypted_storage::parseEncryptedFile(&content, &fileIdOf(folderMdPath))?;
+    let yamlContent = encrypted.decryptMetadata(masterPassword)?;
+    serde_yaml::from_str::<FolderFrontmatter>(&yamlContent)
+        .map_err(|e| format!("Failed to parse folder metadata: {}", e))
+}
+
+/// Load a single folder with its subfolder tree
+pub(crate) fn loadFolder(folderPath: &PathBuf, masterPassword: &str) -> Result<Folder, String> {
+    let frontmatter = readFolderFrontmatter(&folderPath.join(".folder.md"), masterPassword)?;
+    Ok(Folder {
+        path: folderPath.clone(),
+        parentPath: folderPath.parent().map(PathBuf::from),
+        frontmatter,
+        children: scanFolders(folderPath, Some(folderPath.clone()), Some(masterPassword)),
+    })
+}
+
+/// Scan the folders directly inside baseDir, descending into children only when `recursive`
+fn scanFolderLevel(baseDir: &PathBuf, parentPath: Option<PathBuf>, masterPassword: Option<&str>, recursive: bool) -> Vec<Folder> {
     let mut folders = Vec::new();
 
     if !baseDir.exists() {
@@ -75,10 +111,10 @@ pub(crate) fn scanFolders(baseDir: &PathBuf, parentPath: Option<PathBuf>, master
                     let frontmatter = if encrypted_storage::isEncryptedFormat(&content) {
                         // Need master password to decrypt
                         if let Some(password) = masterPassword {
-                            encrypted_storage::parseEncryptedFile(&content)
+                            encrypted_storage::parseEncryptedFile(&content, &fileIdOf(&folderMdPath))
                                 .ok()
                                 .and_then(|encrypted| {
-                                    encrypted_storage::decryptMetadata(&encrypted.metadata, password)
+                                    encrypted.decryptMetadata(password)
                                         .ok()
                                         .and_then(|yaml| serde_yaml::from_str::<FolderFrontmatter>(&yaml).ok())
                                 })
@@ -90,7 +126,11 @@ pub(crate) fn scanFolders(baseDir: &PathBuf, parentPath: Option<PathBuf>, master
                     };
 
                     if let Some(fm) = frontmatter {
-                        let children = scanFolders(&path, Some(path.clone()), masterPassword);
+                        let children = if recursive {
+                            scanFolderLevel(&path, Some(path.clone()), masterPassword, true)
+                        } else {
+                            Vec::new()
+                        };
 
                         folders.push(Folder {
                             path: path.clone(),
@@ -110,10 +150,10 @@ pub(crate) fn scanFolders(baseDir: &PathBuf, parentPath: Option<PathBuf>, master
 }
 
 #[tauri::command]
-pub fn getFolders(storage: State<'_, StorageState>) -> Result<Vec<FolderInfo>, String> {
+pub fn getFolders(storage: State<'_, StorageState>) -> Result<Vec<FolderInfo>, ClaudiaError> {
     println!("[getFolders] Called");
 
-    let wsPath = match storage.getWorkspacePath() {
+    let wsPath = match storage.getAvailableWorkspacePath()? {
         Some(p) => {
             println!("[getFolders] Workspace path: {}", p);
             p
@@ -126,7 +166,7 @@ pub fn getFolders(storage: State<'_, StorageState>) -> Result<Vec<FolderInfo>, S
 
     // Check if vault is unlocked
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
@@ -147,6 +187,84 @@ pub fn getFolders(storage: State<'_, StorageState>) -> Result<Vec<FolderInfo>, S
     Ok(result)
 }
 
+/// List only the immediate subfolders of a parent (root folders when parentPath is empty)
+/// Children are not scanned, so `children` is always empty - for lazy tree expansion
+#[tauri::command]
+pub fn getChildFolders(storage: State<'_, StorageState>, parentPath: Option<String>) -> Result<Vec<FolderInfo>, ClaudiaError> {
+    println!("[getChildFolders] Called with parentPath: {:?}", parentPath);
+
+    let folders = listChildFolders(&storage, parentPath.as_deref())?;
+    println!("[getChildFolders] Found {} folders", folders.len());
+
+    storage.updateActivity();
+    Ok(folders)
+}
+
+pub(crate) fn listChildFolders(storage: &StorageState, parentPath: Option<&str>) -> Result<Vec<FolderInfo>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword();
+    let dir = resolveFolderArg(&wsPath, parentPath)?;
+    let parent = (dir != foldersDir(&wsPath)).then(|| dir.clone());
+
+    let folders = scanFolderLevel(&dir, parent, masterPassword.as_deref(), false);
+    Ok(folders.iter().map(FolderInfo::from).collect())
+}
+
+#[derive(serde::Serialize)]
+pub struct FolderPathInfo {
+    pub id: String,
+    pub path: String,
+    pub displayPath: String,
+}
+
+/// Flatten the folder tree depth-first, joining names into a display path
+fn collectFolderPaths(folders: &[Folder], prefix: &str, out: &mut Vec<FolderPathInfo>) {
+    for folder in folders {
+        let displayPath = if prefix.is_empty() {
+            folder.frontmatter.name.clone()
+        } else {
+            format!("{}/{}", prefix, folder.frontmatter.name)
+        };
+        out.push(FolderPathInfo {
+            id: folder.frontmatter.id.clone(),
+            path: folder.path.to_string_lossy().to_string(),
+            displayPath: displayPath.clone(),
+        });
+        collectFolderPaths(&folder.children, &displayPath, out);
+    }
+}
+
+/// Flat list of all folders for pickers, sorted by display path
+#[tauri::command]
+pub fn getFolderPaths(storage: State<'_, StorageState>) -> Result<Vec<FolderPathInfo>, ClaudiaError> {
+    println!("[getFolderPaths] Called");
+
+    let wsPath = match storage.getAvailableWorkspacePath()? {
+        Some(p) => p,
+        None => return Ok(Vec::new()),
+    };
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword();
+    let folders = scanFolders(&foldersDir(&wsPath), None, masterPassword.as_deref());
+
+    let mut result: Vec<FolderPathInfo> = Vec::new();
+    collectFolderPaths(&folders, "", &mut result);
+    result.sort_by_key(|p| p.displayPath.to_lowercase());
+
+    println!("[getFolderPaths] Found {} folders", result.len());
+    storage.updateActivity();
+    Ok(result)
+}
+
 #[derive(serde::Deserialize)]
 pub struct CreateFolderInput {
     pub name: String,
@@ -154,17 +272,17 @@ pub struct CreateFolderInput {
 }
 
 #[tauri::command]
-pub fn createFolder(storage: State<'_, StorageState>, input: CreateFolderInput) -> Result<FolderInfo, String> {
+pub fn createFolder(storage: State<'_, StorageState>, input: CreateFolderInput) -> Result<FolderInfo, ClaudiaError> {
     println!("[createFolder] Called with name: {}, parentPath: {:?}",
              input.name, input.parentPath);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
     println!("[createFolder] Workspace path: {}", wsPath);
 
@@ -172,13 +290,23 @@ pub fn createFolder(storage: State<'_, StorageState>, input: CreateFolderInput)
     println!("[createFolder] Base directory: {:?}", baseDir);
 
     // Determine parent directory
-    let parentDir = input.parentPath
-        .map(PathBuf::from)
-        .unwrap_or(baseDir.clone());
+    let parentDir = resolveFolderArg(&wsPath, input.parentPath.as_deref())?;
     println!("[createFolder] Parent directory: {:?}", parentDir);
 
+    let layout = storage.effectiveSettings().folderLayout;
+    let folder = createFolderIn(&parentDir, &input.name, &masterPassword, &layout)?;
+
+    storage.updateActivity();
+
+    let result = FolderInfo::from(&folder);
+    println!("[createFolder] SUCCESS - created folder id: {}, path: {}", result.id, result.path);
+    Ok(result)
+}
+
+/// Create a folder directory with its encrypted .folder.md and item subdirectories
+fn createFolderIn(parentDir: &PathBuf, name: &str, masterPassword: &str, layout: &[String]) -> Result<Folder, String> {
     // Find next rank from existing folders
-    let existingFolders = scanFolders(&parentDir, None, Some(&masterPassword));
+    let existingFolders = scanFolders(parentDir, None, Some(masterPassword));
     let nextRank = existingFolders.iter().map(|f| f.frontmatter.rank).max().unwrap_or(0) + 1;
     println!("[createFolder] Next rank: {}", nextRank);
 
@@ -195,39 +323,139 @@ pub fn createFolder(storage: State<'_, StorageState>, input: CreateFolderInput)
     println!("[createFolder] Directory created successfully");
 
     // Create .folder.md with encrypted metadata (folders have no body content)
-    let fm = FolderFrontmatter::new(id.clone(), input.name.clone(), nextRank);
+    let fm = FolderFrontmatter::new(id.clone(), name.to_string(), nextRank);
     let fileContent = encrypted_storage::createEncryptedFile(
         &serde_yaml::to_string(&fm).map_err(|e| e.to_string())?,
         "", // Folders have no body content
-        &masterPassword,
+        masterPassword,
+        &id,
     )?;
 
-    fs::write(folderPath.join(".folder.md"), fileContent).map_err(|e| {
+    writeFileAtomic(folderPath.join(".folder.md"), fileContent).map_err(|e| {
         println!("[createFolder] ERROR writing .folder.md: {}", e);
         e.to_string()
     })?;
     println!("[createFolder] .folder.md created with id: {}", id);
 
-    // Create notes/, tasks/, and passwords/ subdirectories inside the folder
-    fs::create_dir_all(folderPath.join("notes")).map_err(|e| e.to_string())?;
-    fs::create_dir_all(folderPath.join("tasks")).map_err(|e| e.to_string())?;
-    fs::create_dir_all(folderPath.join("passwords")).map_err(|e| e.to_string())?;
-    // Create task status folders
-    for status in ["todo", "doing", "done"] {
-        fs::create_dir_all(folderPath.join("tasks").join(status)).map_err(|e| e.to_string())?;
-    }
+    createFolderLayout(&folderPath, layout)?;
 
-    let folder = Folder {
-        path: folderPath.clone(),
-        parentPath: Some(parentDir),
+    Ok(Folder {
+        path: folderPath,
+        parentPath: Some(parentDir.clone()),
         frontmatter: fm,
         children: Vec::new(),
-    };
+    })
+}
+
+/// Pre-create the item subdirectories listed in the folderLayout setting
+/// ("tasks" includes its status folders); anything else is created on first use
+pub(crate) fn createFolderLayout(folderPath: &Path, layout: &[String]) -> Result<(), String> {
+    for sub in layout {
+        match sub.as_str() {
+            "notes" | "passwords" => {
+                fs::create_dir_all(folderPath.join(sub)).map_err(|e| e.to_string())?;
+            }
+            "tasks" => {
+                for status in TaskStatus::all() {
+                    fs::create_dir_all(itemsDir(folderPath, ItemKind::Task).join(status.folderName())).map_err(|e| e.to_string())?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+pub struct FolderCreateFailure {
+    pub name: String,
+    pub parentPath: Option<String>,
+    pub error: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct CreateFoldersResult {
+    pub created: Vec<FolderInfo>,
+    pub failed: Vec<FolderCreateFailure>,
+}
+
+/// Create many folders in one call, e.g. to reproduce a project hierarchy.
+/// `parentPath` is either an absolute path of an existing folder, or a "/"-joined
+/// chain of names of folders in the same batch ("Projects/Client"); empty means root.
+/// Entries are created parents-first; a failure is recorded and the rest continue.
+#[tauri::command]
+pub fn createFolders(storage: State<'_, StorageState>, paths: Vec<CreateFolderInput>) -> Result<CreateFoldersResult, ClaudiaError> {
+    println!("[createFolders] Called with {} folders", paths.len());
+
+    let result = createFolderBatch(&storage, paths)?;
 
+    println!("[createFolders] Created {}, failed {}", result.created.len(), result.failed.len());
     storage.updateActivity();
+    Ok(result)
+}
+
+pub(crate) fn createFolderBatch(storage: &StorageState, paths: Vec<CreateFolderInput>) -> Result<CreateFoldersResult, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+    let layout = storage.effectiveSettings().folderLayout;
+    let baseDir = foldersDir(&wsPath);
+
+    // Sort by depth so parents in the batch are created before their children
+    let depth = |p: &Option<String>| match p.as_deref() {
+        Some(p) if !p.is_empty() && !p.starts_with('/') => p.split('/').filter(|s| !s.is_empty()).count(),
+        _ => 0,
+    };
+    let mut entries = paths;
+    entries.sort_by_key(|e| depth(&e.parentPath));
+
+    // Batch-relative name chain -> created directory
+    let mut createdDirs: std::collections::HashMap<String, PathBuf> = std::collections::HashMap::new();
+    let mut result = CreateFoldersResult { created: Vec::new(), failed: Vec::new() };
+
+    for entry in entries {
+        let parentKey = entry.parentPath.as_deref()
+            .filter(|p| !p.starts_with('/'))
+            .map(|p| p.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>().join("/"))
+            .unwrap_or_default();
+
+        let parentDir = match entry.parentPath.as_deref() {
+            Some(p) if p.starts_with('/') => resolveFolderArg(&wsPath, Some(p)).map_err(String::from),
+            _ if parentKey.is_empty() => Ok(baseDir.clone()),
+            _ => createdDirs.get(&parentKey).cloned()
+                .ok_or_else(|| "Parent folder not found or was not created".to_string()),
+        };
+
+        let outcome = parentDir.and_then(|dir| createFolderIn(&dir, &entry.name, &masterPassword, &layout));
+
+        match outcome {
+            Ok(folder) => {
+                let key = if parentKey.is_empty() {
+                    entry.name.clone()
+                } else {
+                    format!("{}/{}", parentKey, entry.name)
+                };
+                // Only batch-relative chains can be referenced by later entries
+                if !entry.parentPath.as_deref().unwrap_or("").starts_with('/') {
+                    createdDirs.insert(key, folder.path.clone());
+                }
+                result.created.push(FolderInfo::from(&folder));
+            }
+            Err(e) => {
+                println!("[createFolders] ERROR creating {}: {}", entry.name, e);
+                result.failed.push(FolderCreateFailure {
+                    name: entry.name,
+                    parentPath: entry.parentPath,
+                    error: e,
+                });
+            }
+        }
+    }
 
-    let result = FolderInfo::from(&folder);
-    println!("[createFolder] SUCCESS - created folder id: {}, path: {}", result.id, result.path);
     Ok(result)
 }
 
@@ -242,36 +470,33 @@ pub struct UpdateFolderInput {
 }
 
 #[tauri::command]
-pub fn updateFolder(storage: State<'_, StorageState>, input: UpdateFolderInput) -> Result<(), String> {
+pub fn updateFolder(storage: State<'_, StorageState>, input: UpdateFolderInput) -> Result<(), ClaudiaError> {
+    applyFolderUpdate(&storage, input)
+}
+
+pub(crate) fn applyFolderUpdate(storage: &StorageState, input: UpdateFolderInput) -> Result<(), ClaudiaError> {
     println!("[updateFolder] Called with path: {}", input.path);
     println!("[updateFolder] Updates - name: {:?}, pinned: {:?}, color: {:?}",
              input.name, input.pinned, input.color);
 
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
-    let folderPath = PathBuf::from(&input.path);
+    let folderPath = validateFolderDir(&wsPath, &input.path)?;
     let folderMdPath = folderPath.join(".folder.md");
     println!("[updateFolder] Looking for .folder.md at: {:?}", folderMdPath);
 
     if !folderMdPath.exists() {
-        return Err("Folder metadata (.folder.md) not found".to_string());
+        return Err(ClaudiaError::NotFound("Folder metadata (.folder.md) not found".to_string()));
     }
 
     // Load and decrypt existing frontmatter
-    let content = fs::read_to_string(&folderMdPath).map_err(|e| e.to_string())?;
-
-    let mut fm = if encrypted_storage::isEncryptedFormat(&content) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&content)?;
-        let yamlContent = encrypted_storage::decryptMetadata(&encrypted.metadata, &masterPassword)?;
-        serde_yaml::from_str::<FolderFrontmatter>(&yamlContent)
-            .map_err(|e| format!("Failed to parse folder metadata: {}", e))?
-    } else {
-        return Err("Folder metadata is not encrypted".to_string());
-    };
+    let mut fm = readFolderFrontmatter(&folderMdPath, &masterPassword)?;
 
     // Update fields
     if let Some(name) = input.name {
@@ -288,7 +513,7 @@ pub fn updateFolder(storage: State<'_, StorageState>, input: UpdateFolderInput)
     }
     if let Some(color) = input.color {
         println!("[updateFolder] Updating color to: {}", color);
-        fm.color = color;
+        fm.color = normalizeColor(&color)?;
     }
     if let Some(icon) = input.icon {
         println!("[updateFolder] Updating icon to: {}", icon);
@@ -300,9 +525,10 @@ pub fn updateFolder(storage: State<'_, StorageState>, input: UpdateFolderInput)
         &serde_yaml::to_string(&fm).map_err(|e| e.to_string())?,
         "", // Folders have no body content
         &masterPassword,
+        &fileIdOf(&folderMdPath),
     )?;
 
-    fs::write(&folderMdPath, fileContent).map_err(|e| {
+    writeFileAtomic(&folderMdPath, fileContent).map_err(|e| {
         println!("[updateFolder] ERROR writing file: {}", e);
         e.to_string()
     })?;
@@ -312,10 +538,36 @@ pub fn updateFolder(storage: State<'_, StorageState>, input: UpdateFolderInput)
     Ok(())
 }
 
+/// Mark or unmark a folder as favorite, leaving the rest of its metadata alone
+#[tauri::command]
+pub fn setFolderFavorite(storage: State<'_, StorageState>, path: String, favorite: bool) -> Result<bool, ClaudiaError> {
+    println!("[setFolderFavorite] Called with path: {}, favorite: {}", path, favorite);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let folderMdPath = validateFolderDir(&wsPath, &path)?.join(".folder.md");
+    if !folderMdPath.exists() {
+        return Err(ClaudiaError::NotFound("Folder metadata (.folder.md) not found".to_string()));
+    }
+
+    let fm = rewriteMetadata(&folderMdPath, &masterPassword, |fm: &mut FolderFrontmatter| {
+        fm.favorite = favorite;
+    })?;
+
+    storage.updateActivity();
+    Ok(fm.favorite)
+}
+
 /// Recursively move all items (notes, tasks, passwords) from a folder to trash
 fn moveAllItemsToTrash(folderPath: &PathBuf, wsPath: &str) -> Result<(), String> {
     // Move notes from this folder's notes/ directory
-    let notesPath = folderPath.join("notes");
+    let notesPath = itemsDir(folderPath, ItemKind::Note);
     if notesPath.exists() {
         let trashNotes = trashNotesDir(wsPath);
         fs::create_dir_all(&trashNotes).map_err(|e| e.to_string())?;
@@ -326,7 +578,11 @@ fn moveAllItemsToTrash(folderPath: &PathBuf, wsPath: &str) -> Result<(), String>
                 if path.is_file() && path.extension().map_or(false, |e| e == "md") {
                     if let Some(filename) = path.file_name() {
                         let trashPath = trashNotes.join(filename);
-                        let _ = fs::rename(&path, &trashPath);
+                        if fs::rename(&path, &trashPath).is_ok()
+                            && let Some(id) = filename.to_str().and_then(parseUuidFilename)
+                        {
+                            recordTrashOrigin(wsPath, &id, &notesPath);
+                        }
                     }
                 }
             }
@@ -334,11 +590,11 @@ fn moveAllItemsToTrash(folderPath: &PathBuf, wsPath: &str) -> Result<(), String>
     }
 
     // Move tasks from this folder's tasks/{status}/ directories
-    let tasksPath = folderPath.join("tasks");
+    let tasksPath = itemsDir(folderPath, ItemKind::Task);
     if tasksPath.exists() {
         let trashTasks = trashTasksDir(wsPath);
 
-        for status in [TaskStatus::Todo, TaskStatus::Doing, TaskStatus::Done] {
+        for status in TaskStatus::all() {
             let statusPath = tasksPath.join(status.folderName());
             if statusPath.exists() {
                 let trashStatusPath = trashTasks.join(status.folderName());
@@ -350,7 +606,11 @@ fn moveAllItemsToTrash(folderPath: &PathBuf, wsPath: &str) -> Result<(), String>
                         if path.is_file() && path.extension().map_or(false, |e| e == "md") {
                             if let Some(filename) = path.file_name() {
                                 let trashPath = trashStatusPath.join(filename);
-                                let _ = fs::rename(&path, &trashPath);
+                                if fs::rename(&path, &trashPath).is_ok()
+                                    && let Some(id) = filename.to_str().and_then(parseUuidFilename)
+                                {
+                                    recordTrashOrigin(wsPath, &id, &tasksPath);
+                                }
                             }
                         }
                     }
@@ -360,7 +620,7 @@ fn moveAllItemsToTrash(folderPath: &PathBuf, wsPath: &str) -> Result<(), String>
     }
 
     // Move passwords from this folder's passwords/ directory
-    let passwordsPath = folderPath.join("passwords");
+    let passwordsPath = itemsDir(folderPath, ItemKind::Password);
     if passwordsPath.exists() {
         let trashPasswords = trashPasswordsDir(wsPath);
         fs::create_dir_all(&trashPasswords).map_err(|e| e.to_string())?;
@@ -371,7 +631,11 @@ fn moveAllItemsToTrash(folderPath: &PathBuf, wsPath: &str) -> Result<(), String>
                 if path.is_file() && path.extension().map_or(false, |e| e == "md") {
                     if let Some(filename) = path.file_name() {
                         let trashPath = trashPasswords.join(filename);
-                        let _ = fs::rename(&path, &trashPath);
+                        if fs::rename(&path, &trashPath).is_ok()
+                            && let Some(id) = filename.to_str().and_then(parseUuidFilename)
+                        {
+                            recordTrashOrigin(wsPath, &id, &passwordsPath);
+                        }
                     }
                 }
             }
@@ -399,12 +663,12 @@ fn moveAllItemsToTrash(folderPath: &PathBuf, wsPath: &str) -> Result<(), String>
 }
 
 #[tauri::command]
-pub fn deleteFolder(storage: State<'_, StorageState>, path: String, permanent: Option<bool>) -> Result<(), String> {
+pub fn deleteFolder(storage: State<'_, StorageState>, path: String, permanent: Option<bool>) -> Result<(), ClaudiaError> {
     println!("[deleteFolder] Called with path: {}, permanent: {:?}", path, permanent);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
-    let folderPath = PathBuf::from(&path);
+    let folderPath = validateFolderDir(&wsPath, &path)?;
     if !folderPath.exists() {
         println!("[deleteFolder] Folder does not exist at path");
         return Ok(());
@@ -428,6 +692,92 @@ pub fn deleteFolder(storage: State<'_, StorageState>, path: String, permanent: O
     Ok(())
 }
 
+#[derive(serde::Serialize, Default)]
+pub struct FolderDeletionImpact {
+    pub notes: usize,
+    pub tasks: usize,
+    pub passwords: usize,
+    pub pinnedNotes: usize,
+    pub pinnedTasks: usize,
+    pub pinnedPasswords: usize,
+    pub subfolders: usize,
+}
+
+/// Count item files in one kind directory (no decryption needed)
+fn countItemFiles(dir: &PathBuf) -> usize {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_str().and_then(parseUuidFilename).is_some())
+        .count()
+}
+
+/// Accumulate the impact for a folder and all its subfolders
+/// Totals come from file counts; pinned counts need only the encrypted metadata
+fn collectDeletionImpact(folderPath: &PathBuf, masterPassword: &str, impact: &mut FolderDeletionImpact) {
+    let notesPath = itemsDir(folderPath, ItemKind::Note);
+    impact.notes += countItemFiles(&notesPath);
+    impact.pinnedNotes += scanNotesInFolder(&notesPath, Some(masterPassword))
+        .iter()
+        .filter(|n| n.frontmatter.pinned)
+        .count();
+
+    let tasksPath = itemsDir(folderPath, ItemKind::Task);
+    for status in TaskStatus::all() {
+        impact.tasks += countItemFiles(&tasksPath.join(status.folderName()));
+    }
+    impact.pinnedTasks += scanTasksInFolder(&tasksPath, Some(masterPassword))
+        .iter()
+        .filter(|t| t.frontmatter.pinned)
+        .count();
+
+    let passwordsPath = itemsDir(folderPath, ItemKind::Password);
+    impact.passwords += countItemFiles(&passwordsPath);
+    impact.pinnedPasswords += scanPasswordsInFolder(&passwordsPath, Some(masterPassword))
+        .iter()
+        .filter(|p| p.frontmatter.pinned)
+        .count();
+
+    if let Ok(entries) = fs::read_dir(folderPath) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let dirname = entry.file_name().to_string_lossy().to_string();
+            if path.is_dir() && isValidUuidDir(&dirname) && path.join(".folder.md").exists() {
+                impact.subfolders += 1;
+                collectDeletionImpact(&path, masterPassword, impact);
+            }
+        }
+    }
+}
+
+/// Read-only preview of what deleteFolder would move to trash, for confirmation dialogs
+#[tauri::command]
+pub fn getFolderDeletionImpact(storage: State<'_, StorageState>, path: String) -> Result<FolderDeletionImpact, ClaudiaError> {
+    println!("[getFolderDeletionImpact] Called with path: {}", path);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let folderPath = validateFolderDir(&wsPath, &path)?;
+    if !folderPath.join(".folder.md").exists() {
+        return Err(ClaudiaError::NotFound("Folder not found".to_string()));
+    }
+
+    let mut impact = FolderDeletionImpact::default();
+    collectDeletionImpact(&folderPath, &masterPassword, &mut impact);
+
+    println!("[getFolderDeletionImpact] {} notes, {} tasks, {} passwords, {} subfolders",
+             impact.notes, impact.tasks, impact.passwords, impact.subfolders);
+    storage.updateActivity();
+    Ok(impact)
+}
+
 #[derive(serde::Deserialize)]
 pub struct ReorderFoldersInput {
     pub parentPath: Option<String>,
@@ -435,19 +785,21 @@ pub struct ReorderFoldersInput {
 }
 
 #[tauri::command]
-pub fn reorderFolders(storage: State<'_, StorageState>, input: ReorderFoldersInput) -> Result<(), String> {
+pub fn reorderFolders(storage: State<'_, StorageState>, input: ReorderFoldersInput) -> Result<(), ClaudiaError> {
     println!("[reorderFolders] Called with parentPath: {:?}", input.parentPath);
     println!("[reorderFolders] Folder paths to reorder: {:?}", input.folderPaths);
 
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
     // Update rank in .folder.md
     for (index, folderPath) in input.folderPaths.iter().enumerate() {
-        let pathBuf = PathBuf::from(folderPath);
+        let pathBuf = validateMoveTarget(&wsPath, folderPath)?;
         let folderMdPath = pathBuf.join(".folder.md");
 
         if !folderMdPath.exists() {
@@ -456,11 +808,11 @@ pub fn reorderFolders(storage: State<'_, StorageState>, input: ReorderFoldersInp
         }
 
         // Load and decrypt frontmatter
-        let content = fs::read_to_string(&folderMdPath).map_err(|e| e.to_string())?;
+        let content = fs::read_to_string(&folderMdPath)?;
 
         let mut fm = if encrypted_storage::isEncryptedFormat(&content) {
-            let encrypted = encrypted_storage::parseEncryptedFile(&content)?;
-            let yamlContent = encrypted_storage::decryptMetadata(&encrypted.metadata, &masterPassword)?;
+            let encrypted = encrypted_storage::parseEncryptedFile(&content, &fileIdOf(&folderMdPath))?;
+            let yamlContent = encrypted.decryptMetadata(&masterPassword)?;
             serde_yaml::from_str::<FolderFrontmatter>(&yamlContent)
                 .map_err(|e| format!("Failed to parse folder metadata: {}", e))?
         } else {
@@ -478,9 +830,10 @@ pub fn reorderFolders(storage: State<'_, StorageState>, input: ReorderFoldersInp
                 &serde_yaml::to_string(&fm).map_err(|e| e.to_string())?,
                 "",
                 &masterPassword,
+                &fileIdOf(&folderMdPath),
             )?;
 
-            fs::write(&folderMdPath, fileContent).map_err(|e| {
+            writeFileAtomic(&folderMdPath, fileContent).map_err(|e| {
                 println!("[reorderFolders] ERROR: {}", e);
                 e.to_string()
             })?;
@@ -498,41 +851,69 @@ pub struct MoveFolderInput {
     pub newParentPath: Option<String>, // None means move to root
 }
 
+/// Reject a move that would put a folder inside itself
+/// Paths are compared component by component (symlinks resolved up to the deepest existing
+/// ancestor), so a sibling such as `/a/abc` is never taken for a child of `/a/ab`.
+fn checkFolderMoveTarget(oldPath: &Path, newParentDir: &Path) -> Result<(), String> {
+    let components = |path: &Path| -> Vec<std::ffi::OsString> {
+        let resolved = path.ancestors()
+            .find_map(|ancestor| {
+                let canonical = ancestor.canonicalize().ok()?;
+                Some(canonical.join(path.strip_prefix(ancestor).ok()?))
+            })
+            .unwrap_or_else(|| path.to_path_buf());
+        resolved.components()
+            .filter(|c| *c != std::path::Component::CurDir)
+            .map(|c| c.as_os_str().to_os_string())
+            .collect()
+    };
+    let folder = components(oldPath);
+    let target = components(newParentDir);
+    if !target.starts_with(&folder) {
+        return Ok(());
+    }
+
+    let itemsDirName = target.get(folder.len())
+        .and_then(|c| c.to_str())
+        .filter(|name| [ItemKind::Note, ItemKind::Task, ItemKind::Password].iter().any(|k| k.folderName() == *name));
+    match itemsDirName {
+        Some(name) => Err(format!("Cannot move folder into its own {} directory", name)),
+        None => Err("Cannot move folder into itself".to_string()),
+    }
+}
+
 #[tauri::command]
-pub fn moveFolder(storage: State<'_, StorageState>, input: MoveFolderInput) -> Result<FolderInfo, String> {
+pub fn moveFolder(storage: State<'_, StorageState>, input: MoveFolderInput) -> Result<FolderInfo, ClaudiaError> {
+    moveFolderTo(&storage, input)
+}
+
+pub(crate) fn moveFolderTo(storage: &StorageState, input: MoveFolderInput) -> Result<FolderInfo, ClaudiaError> {
     println!("[moveFolder] Called with folderPath: {}, newParentPath: {:?}",
              input.folderPath, input.newParentPath);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
-
-    let baseDir = foldersDir(&wsPath);
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
-    let oldPath = PathBuf::from(&input.folderPath);
+    let oldPath = validateFolderDir(&wsPath, &input.folderPath)?;
     if !oldPath.exists() {
-        return Err("Folder does not exist".to_string());
+        return Err(ClaudiaError::NotFound("Folder does not exist".to_string()));
     }
 
-    // Determine new parent directory
-    let newParentDir = input.newParentPath
-        .as_ref()
-        .map(|p| PathBuf::from(p))
-        .unwrap_or(baseDir.clone());
+    // Determine new parent directory: the folders root or an existing folder in the workspace
+    let newParentDir = resolveFolderArg(&wsPath, input.newParentPath.as_deref())?;
 
-    // Prevent moving folder into itself or its children
-    if newParentDir.starts_with(&oldPath) {
-        return Err("Cannot move folder into itself".to_string());
-    }
+    // Prevent moving folder into itself, its children or its item directories
+    checkFolderMoveTarget(&oldPath, &newParentDir)?;
 
     // Get folder UUID (directory name)
     let dirname = oldPath.file_name().and_then(|n| n.to_str()).ok_or("No directory name")?;
     if !isValidUuidDir(dirname) {
-        return Err("Invalid folder: directory name is not a valid UUID".to_string());
+        return Err(ClaudiaError::InvalidInput("Invalid folder: directory name is not a valid UUID".to_string()));
     }
 
     // Check if already in the target parent (same parent, no move needed)
@@ -542,26 +923,7 @@ pub fn moveFolder(storage: State<'_, StorageState>, input: MoveFolderInput) -> R
     if isSameParent {
         // Same parent - just return current folder info without moving
         println!("[moveFolder] Folder already in target location, returning current state");
-        let folderMdPath = oldPath.join(".folder.md");
-
-        let content = fs::read_to_string(&folderMdPath).map_err(|e| e.to_string())?;
-        let fm = if encrypted_storage::isEncryptedFormat(&content) {
-            let encrypted = encrypted_storage::parseEncryptedFile(&content)?;
-            let yamlContent = encrypted_storage::decryptMetadata(&encrypted.metadata, &masterPassword)?;
-            serde_yaml::from_str::<FolderFrontmatter>(&yamlContent)
-                .map_err(|e| format!("Failed to parse folder metadata: {}", e))?
-        } else {
-            return Err("Folder metadata is not encrypted".to_string());
-        };
-
-        let children = scanFolders(&oldPath, Some(oldPath.clone()), Some(&masterPassword));
-        let folder = Folder {
-            path: oldPath,
-            parentPath: Some(newParentDir),
-            frontmatter: fm,
-            children,
-        };
-        return Ok(FolderInfo::from(&folder));
+        return Ok(FolderInfo::from(&loadFolder(&oldPath, &masterPassword)?));
     }
 
     // Find next rank in new parent
@@ -581,16 +943,7 @@ pub fn moveFolder(storage: State<'_, StorageState>, input: MoveFolderInput) -> R
 
     // Update rank in .folder.md
     let folderMdPath = newPath.join(".folder.md");
-    let content = fs::read_to_string(&folderMdPath).map_err(|e| e.to_string())?;
-
-    let mut fm = if encrypted_storage::isEncryptedFormat(&content) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&content)?;
-        let yamlContent = encrypted_storage::decryptMetadata(&encrypted.metadata, &masterPassword)?;
-        serde_yaml::from_str::<FolderFrontmatter>(&yamlContent)
-            .map_err(|e| format!("Failed to parse folder metadata: {}", e))?
-    } else {
-        return Err("Folder metadata is not encrypted".to_string());
-    };
+    let mut fm = readFolderFrontmatter(&folderMdPath, &masterPassword)?;
 
     fm.rank = nextRank;
 
@@ -598,9 +951,10 @@ pub fn moveFolder(storage: State<'_, StorageState>, input: MoveFolderInput) -> R
         &serde_yaml::to_string(&fm).map_err(|e| e.to_string())?,
         "",
         &masterPassword,
+        &fileIdOf(&folderMdPath),
     )?;
 
-    fs::write(&folderMdPath, fileContent).map_err(|e| e.to_string())?;
+    writeFileAtomic(&folderMdPath, fileContent)?;
 
     let children = scanFolders(&newPath, Some(newPath.clone()), Some(&masterPassword));
 
@@ -615,3 +969,371 @@ pub fn moveFolder(storage: State<'_, StorageState>, input: MoveFolderInput) -> R
     println!("[moveFolder] SUCCESS");
     Ok(FolderInfo::from(&folder))
 }
+
+// ============================================
+// DUPLICATE FOLDER
+// ============================================
+
+/// Copy a folder, subfolders and items included, next to the original under a new name
+/// Every copied folder and item gets a fresh UUID; everything else in its metadata is kept.
+#[tauri::command]
+pub fn duplicateFolder(storage: State<'_, StorageState>, folderPath: String, newName: String) -> Result<FolderInfo, ClaudiaError> {
+    println!("[duplicateFolder] Called with folderPath: {}, newName: {}", folderPath, newName);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let name = newName.trim();
+    if name.is_empty() {
+        return Err(ClaudiaError::InvalidInput("Folder name cannot be empty".to_string()));
+    }
+
+    let source = validateFolderDir(&wsPath, &folderPath)?;
+    if !source.join(".folder.md").exists() {
+        return Err(ClaudiaError::NotFound("Folder does not exist".to_string()));
+    }
+
+    let folder = duplicateFolderTree(&wsPath, &source, name, &masterPassword)?;
+
+    storage.rebuildIndex();
+    storage.updateActivity();
+    println!("[duplicateFolder] SUCCESS - new folder: {:?}", folder.path);
+    Ok(FolderInfo::from(&folder))
+}
+
+/// Create the copy of `source` in the same parent, ranked after its siblings
+/// A half-written copy is removed again if anything fails.
+fn duplicateFolderTree(wsPath: &str, source: &Path, name: &str, masterPassword: &str) -> Result<Folder, String> {
+    let parentDir = source.parent().ok_or("No parent")?.to_path_buf();
+    let existingFolders = scanFolders(&parentDir, None, Some(masterPassword));
+    let nextRank = existingFolders.iter().map(|f| f.frontmatter.rank).max().unwrap_or(0) + 1;
+
+    let mut fm = readFolderFrontmatter(&source.join(".folder.md"), masterPassword)?;
+    fm.id = newId();
+    fm.name = name.to_string();
+    fm.rank = nextRank;
+
+    let target = parentDir.join(&fm.id);
+    let copied = writeFolderMetadata(&target, &fm, masterPassword)
+        .and_then(|_| copyFolderContents(wsPath, source, &target, masterPassword));
+    if let Err(e) = copied {
+        println!("[duplicateFolder] ERROR: {}, removing partial copy", e);
+        let _ = fs::remove_dir_all(&target);
+        return Err(e);
+    }
+
+    loadFolder(&target, masterPassword)
+}
+
+fn writeFolderMetadata(folderPath: &Path, fm: &FolderFrontmatter, masterPassword: &str) -> Result<(), String> {
+    fs::create_dir_all(folderPath).map_err(|e| e.to_string())?;
+    let fileContent = encrypted_storage::serializeAndEncrypt(fm, "", masterPassword, &fm.id)?;
+    writeFileAtomic(folderPath.join(".folder.md"), fileContent).map_err(|e| e.to_string())
+}
+
+/// Recreate `source`'s contents in `target`: subfolders recurse, item dirs (notes/, tasks/{status}/,
+/// passwords/) are mirrored, and item files are re-encrypted under new ids
+fn copyFolderContents(wsPath: &str, source: &Path, target: &Path, masterPassword: &str) -> Result<(), String> {
+    fs::create_dir_all(target).map_err(|e| e.to_string())?;
+
+    for entry in fs::read_dir(source).map_err(|e| e.to_string())?.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue; // .folder.md is written by the caller
+        }
+
+        if path.is_dir() {
+            if isValidUuidDir(&name) && path.join(".folder.md").exists() {
+                let mut fm = readFolderFrontmatter(&path.join(".folder.md"), masterPassword)?;
+                fm.id = newId();
+                let subfolder = target.join(&fm.id);
+                writeFolderMetadata(&subfolder, &fm, masterPassword)?;
+                copyFolderContents(wsPath, &path, &subfolder, masterPassword)?;
+            } else {
+                copyFolderContents(wsPath, &path, &target.join(&name), masterPassword)?;
+            }
+        } else if let Some(oldId) = parseUuidFilename(&name).filter(|_| name.ends_with(".md")) {
+            copyItemWithNewId(wsPath, &path, target, &oldId, masterPassword)?;
+        }
+    }
+    Ok(())
+}
+
+/// Decrypt an item and write it into `targetDir` under a new id, assets included
+fn copyItemWithNewId(wsPath: &str, path: &Path, targetDir: &Path, oldId: &str, masterPassword: &str) -> Result<(), String> {
+    let raw = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let encrypted = encrypted_storage::parseEncryptedFile(&raw, &fileIdOf(path))?;
+    let yaml = encrypted.decryptMetadata(masterPassword)?;
+    let body = encrypted.decryptContent(masterPassword)?;
+
+    let newItemId = newId();
+    let mut fm: serde_yaml::Value = serde_yaml::from_str(&yaml)
+        .map_err(|e| format!("Failed to parse metadata: {}", e))?;
+    if let Some(map) = fm.as_mapping_mut() {
+        map.insert("id".into(), newItemId.clone().into());
+    }
+
+    copyNoteAssets(wsPath, oldId, &newItemId)?;
+    let body = retargetAssetReferences(&body, oldId, &newItemId);
+
+    let fileContent = encrypted_storage::serializeAndEncrypt(&fm, &body, masterPassword, &newItemId)?;
+    writeFileAtomic(targetDir.join(uuidFilename(&newItemId)), fileContent).map_err(|e| e.to_string())
+}
+
+// ============================================
+// ORPHANED FOLDERS
+// ============================================
+
+#[derive(serde::Serialize)]
+pub struct OrphanedFolderInfo {
+    pub id: String,
+    pub path: String,
+    pub itemCount: usize,
+    pub childFolderCount: usize,
+}
+
+/// Count item files directly inside a folder's notes/, tasks/{status}/ and passwords/ dirs
+fn countFolderItems(folderPath: &Path) -> usize {
+    let mut count = countItemFiles(&itemsDir(folderPath, ItemKind::Note)) + countItemFiles(&itemsDir(folderPath, ItemKind::Password));
+    for status in TaskStatus::all() {
+        count += countItemFiles(&itemsDir(folderPath, ItemKind::Task).join(status.folderName()));
+    }
+    count
+}
+
+/// Walk the folder tree collecting UUID dirs that lack .folder.md but still hold content
+fn collectOrphanedFolders(dir: &PathBuf, orphans: &mut Vec<OrphanedFolderInfo>) {
+    let entries: Vec<_> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .collect();
+
+    for entry in entries {
+        let path = entry.path();
+        let dirname = entry.file_name().to_string_lossy().to_string();
+        if !isValidUuidDir(&dirname) {
+            continue;
+        }
+
+        if !path.join(".folder.md").exists() {
+            let itemCount = countFolderItems(&path);
+            let childFolderCount = fs::read_dir(&path)
+                .into_iter()
+                .flatten()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir() && isValidUuidDir(&e.file_name().to_string_lossy()))
+                .count();
+
+            if itemCount > 0 || childFolderCount > 0 {
+                orphans.push(OrphanedFolderInfo {
+                    id: dirname,
+                    path: path.to_string_lossy().to_string(),
+                    itemCount,
+                    childFolderCount,
+                });
+            }
+        }
+
+        collectOrphanedFolders(&path, orphans);
+    }
+}
+
+/// List folders whose .folder.md was lost - scanFolders skips them, hiding their items
+#[tauri::command]
+pub fn findOrphanedFolders(storage: State<'_, StorageState>) -> Result<Vec<OrphanedFolderInfo>, ClaudiaError> {
+    println!("[findOrphanedFolders] Called");
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    let mut orphans = Vec::new();
+    collectOrphanedFolders(&foldersDir(&wsPath), &mut orphans);
+
+    println!("[findOrphanedFolders] Found {} orphaned folders", orphans.len());
+    Ok(orphans)
+}
+
+/// Recreate the encrypted .folder.md for an orphaned folder, keeping its UUID
+#[tauri::command]
+pub fn repairOrphanedFolder(storage: State<'_, StorageState>, path: String, name: String) -> Result<FolderInfo, ClaudiaError> {
+    println!("[repairOrphanedFolder] Called with path: {}, name: {}", path, name);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let folderPath = validateFolderDir(&wsPath, &path)?;
+    let id = folderPath.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or("Invalid folder path")?;
+    if !folderPath.is_dir() {
+        return Err(ClaudiaError::InvalidInput("Invalid folder path".to_string()));
+    }
+    if folderPath.join(".folder.md").exists() {
+        return Err("Folder already has metadata".into());
+    }
+
+    let parentDir = folderPath.parent().map(PathBuf::from).ok_or("Invalid folder path")?;
+    let existingFolders = scanFolders(&parentDir, None, Some(&masterPassword));
+    let nextRank = existingFolders.iter().map(|f| f.frontmatter.rank).max().unwrap_or(0) + 1;
+
+    let fm = FolderFrontmatter::new(id, name, nextRank);
+    let fileContent = encrypted_storage::createEncryptedFile(
+        &serde_yaml::to_string(&fm).map_err(|e| e.to_string())?,
+        "",
+        &masterPassword,
+        &fm.id,
+    )?;
+    writeFileAtomic(folderPath.join(".folder.md"), fileContent).map_err(|e| {
+        println!("[repairOrphanedFolder] ERROR writing .folder.md: {}", e);
+        e.to_string()
+    })?;
+
+    createFolderLayout(&folderPath, &storage.effectiveSettings().folderLayout)?;
+
+    let folder = Folder {
+        path: folderPath.clone(),
+        parentPath: Some(parentDir),
+        frontmatter: fm,
+        children: scanFolders(&folderPath, Some(folderPath.clone()), Some(&masterPassword)),
+    };
+
+    println!("[repairOrphanedFolder] SUCCESS");
+    storage.updateActivity();
+    Ok(FolderInfo::from(&folder))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NoteFrontmatter;
+    use crate::models::config::defaultFolderLayout;
+
+    const TEST_PASSWORD: &str = "correct horse battery staple";
+
+    #[test]
+    fn test_move_target_compares_components() {
+        let root = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let ab = root.join("ab");
+        let abc = root.join("abc");
+        for dir in [ab.join("child"), ab.join("notes"), abc.clone()] {
+            fs::create_dir_all(dir).unwrap();
+        }
+
+        // A sibling sharing a name prefix is a valid target
+        assert!(checkFolderMoveTarget(&ab, &abc).is_ok());
+        assert!(checkFolderMoveTarget(&abc, &ab).is_ok());
+        assert!(checkFolderMoveTarget(&ab, &root.join("./abc")).is_ok());
+
+        assert_eq!(checkFolderMoveTarget(&ab, &ab).unwrap_err(), "Cannot move folder into itself");
+        assert_eq!(checkFolderMoveTarget(&ab, &ab.join("child")).unwrap_err(), "Cannot move folder into itself");
+        assert_eq!(checkFolderMoveTarget(&ab, &ab.join("notes")).unwrap_err(), "Cannot move folder into its own notes directory");
+        // Item directories that do not exist yet are caught too
+        assert_eq!(checkFolderMoveTarget(&ab, &ab.join("tasks")).unwrap_err(), "Cannot move folder into its own tasks directory");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_folder_commands_reject_paths_outside_workspace() {
+        use crate::mcp::api;
+
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        fs::create_dir_all(&ws).unwrap();
+        let storage: StorageState = std::sync::Arc::new(crate::storage::Storage::new());
+        storage.setCurrentWorkspace(Some(wsPath.clone()));
+        storage.setDerivedKey(vec![7u8; 32]);
+        let key = storage.getMasterPassword().unwrap();
+
+        let folder = createFolderIn(&foldersDir(&wsPath), "Inside", &key, &defaultFolderLayout()).unwrap();
+        let folderStr = folder.path.to_string_lossy().to_string();
+        let outside = ws.join("outside");
+        let stray = outside.join(newId());
+        fs::create_dir_all(&stray).unwrap();
+        fs::write(stray.join(".folder.md"), "").unwrap();
+        let strayStr = stray.to_string_lossy().to_string();
+
+        // A folder can't be moved out of the workspace, under an item directory, or to a relative path
+        let targets = [outside.to_string_lossy().to_string(), folder.path.join("notes").to_string_lossy().to_string(), "../../etc".to_string()];
+        for target in targets {
+            let input = MoveFolderInput { folderPath: folderStr.clone(), newParentPath: Some(target) };
+            assert!(moveFolderTo(&storage, input).is_err());
+        }
+        assert!(folder.path.join(".folder.md").is_file());
+
+        // Folders outside the workspace can't be updated, moved or deleted
+        let update = UpdateFolderInput { path: strayStr.clone(), name: Some("Renamed".to_string()), pinned: None, favorite: None, color: None, icon: None };
+        assert!(applyFolderUpdate(&storage, update).is_err());
+        assert!(moveFolderTo(&storage, MoveFolderInput { folderPath: strayStr.clone(), newParentPath: None }).is_err());
+        assert!(api::delete_folder(&storage, &strayStr).is_err());
+        assert!(stray.join(".folder.md").is_file());
+
+        // Parent paths are resolved, not compared lexically: "folders/../outside/x" starts with folders/
+        let dotted = foldersDir(&wsPath).join("..").join("outside").join(stray.file_name().unwrap());
+        let dottedStr = dotted.to_string_lossy().to_string();
+        assert!(listChildFolders(&storage, Some(&dottedStr)).is_err());
+        let batch = createFolderBatch(&storage, vec![CreateFolderInput { name: "Escaped".to_string(), parentPath: Some(dottedStr) }]).unwrap();
+        assert!(batch.created.is_empty());
+        assert_eq!(batch.failed.len(), 1);
+        assert_eq!(listChildFolders(&storage, Some(&folderStr)).unwrap().len(), 0);
+        assert_eq!(listChildFolders(&storage, None).unwrap().len(), 1);
+
+        // Nor created there through MCP
+        assert!(api::create_folder(&storage, "Escaped", Some(&outside.to_string_lossy())).is_err());
+        assert!(api::create_folder(&storage, "Escaped", Some("../../etc")).is_err());
+        assert_eq!(fs::read_dir(&outside).unwrap().count(), 1);
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_duplicate_folder_gets_all_new_ids() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        let baseDir = foldersDir(&wsPath);
+        let layout = defaultFolderLayout();
+
+        let original = createFolderIn(&baseDir, "Projects", TEST_PASSWORD, &layout).unwrap();
+        createFolderIn(&original.path, "Archive", TEST_PASSWORD, &layout).unwrap();
+        let notesDir = itemsDir(&original.path, ItemKind::Note);
+        let mut originalIds = vec![original.frontmatter.id.clone()];
+        for title in ["First", "Second"] {
+            let fm = NoteFrontmatter::new(newId(), title.to_string(), 0);
+            let path = notesDir.join(uuidFilename(&fm.id));
+            fs::write(&path, encrypted_storage::serializeAndEncrypt(&fm, &format!("{} body", title), TEST_PASSWORD, &fm.id).unwrap()).unwrap();
+            originalIds.push(fm.id);
+        }
+
+        let copy = duplicateFolderTree(&wsPath, &original.path, "Projects copy", TEST_PASSWORD).unwrap();
+        assert_eq!(copy.frontmatter.name, "Projects copy");
+        assert_eq!(copy.children.len(), 1);
+        assert_eq!(copy.children[0].frontmatter.name, "Archive");
+
+        let mut notes = scanNotesInFolder(&itemsDir(&copy.path, ItemKind::Note), Some(TEST_PASSWORD));
+        notes.sort_by(|a, b| a.frontmatter.title.cmp(&b.frontmatter.title));
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].frontmatter.title, "First");
+        assert_eq!(encrypted_storage::readDecryptedBody(&notes[0].path, TEST_PASSWORD).unwrap().as_deref(), Some("First body"));
+
+        let copiedIds = [&copy.frontmatter.id, &copy.children[0].frontmatter.id, &notes[0].frontmatter.id, &notes[1].frontmatter.id];
+        for id in copiedIds {
+            assert!(!originalIds.contains(id));
+        }
+        // The original is untouched
+        assert_eq!(scanNotesInFolder(&notesDir, Some(TEST_PASSWORD)).len(), 2);
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+}
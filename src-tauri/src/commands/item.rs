@@ -0,0 +1,1309 @@
+// Item commands - kind-agnostic operations on note, task and password files
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use tauri::{Manager, State};
+
+use crate::error::ClaudiaError;
+use crate::storage::{StorageState, foldersDir, forgetTrashOrigin, historyDir, isItemPathOfKind, parseFrontmatter, parseUuidFilename, writeFileAtomic};
+use crate::encrypted_storage::{self, fileIdOf};
+use crate::mcp::api::{self, ContentSearchHit, MAX_CONTENT_SEARCH_RESULTS};
+use crate::models::{ItemKind, NoteFrontmatter, PasswordContent, PasswordFrontmatter, SortSpec, TagMatch, TaskFrontmatter, TaskStatus};
+use crate::undo::UndoAction;
+use super::note::{NoteInfo, scanAllNotes};
+use super::password::{PasswordInfo, scanAllPasswords};
+use super::task::{TaskInfo, scanAllTasks};
+use super::common::{Page, now};
+use super::vault::checkReauth;
+
+/// Explain why an item id did not come back from a scan: the file is missing,
+/// isn't encrypted, is malformed, or doesn't decrypt with the current key
+pub(crate) fn missingItemError(storage: &StorageState, kind: ItemKind, id: &str) -> ClaudiaError {
+    use encrypted_storage::{codedError, ERR_NOT_FOUND, ERR_NOT_ENCRYPTED, ERR_CORRUPT_FILE, ERR_METADATA_DECRYPT};
+
+    let label = match kind {
+        ItemKind::Note => "Note",
+        ItemKind::Task => "Task",
+        ItemKind::Password => "Password",
+    };
+
+    let Some(path) = storage.itemPath(kind, id) else {
+        return ClaudiaError::NotFound(codedError(ERR_NOT_FOUND, format!("{} not found", label)));
+    };
+    match fs::read_to_string(&path) {
+        Ok(raw) if !encrypted_storage::isEncryptedFormat(&raw) => {
+            ClaudiaError::Decrypt(codedError(ERR_NOT_ENCRYPTED, format!("{} file is not in encrypted format", label)))
+        }
+        Ok(raw) if encrypted_storage::parseEncryptedFile(&raw, &fileIdOf(&path)).is_err() => {
+            ClaudiaError::Decrypt(codedError(ERR_CORRUPT_FILE, format!("{} file is malformed", label)))
+        }
+        Ok(_) => ClaudiaError::Decrypt(codedError(ERR_METADATA_DECRYPT, format!("{} file could not be decrypted with the current key", label))),
+        Err(e) => ClaudiaError::Io(format!("Failed to read file: {}", e)),
+    }
+}
+
+/// Update only the frontmatter of an encrypted file
+/// The content section is written back as-is, so the body is never decrypted
+/// (v1 files are the exception: their content is re-sealed so both sections carry the file id)
+pub(crate) fn rewriteMetadata<T>(path: &Path, masterPassword: &str, update: impl FnOnce(&mut T)) -> Result<T, ClaudiaError>
+where
+    T: serde::de::DeserializeOwned + serde::Serialize,
+{
+    let raw = fs::read_to_string(path)?;
+    if !encrypted_storage::isEncryptedFormat(&raw) {
+        return Err(ClaudiaError::Decrypt("File is not in encrypted format".to_string()));
+    }
+
+    let fileId = fileIdOf(path);
+    let encrypted = encrypted_storage::parseEncryptedFile(&raw, &fileId)?;
+    let yaml = encrypted.decryptMetadata(masterPassword)?;
+    let mut fm: T = serde_yaml::from_str(&yaml).map_err(|e| ClaudiaError::Decrypt(format!("Failed to parse metadata: {}", e)))?;
+
+    update(&mut fm);
+
+    let yaml = serde_yaml::to_string(&fm).map_err(|e| e.to_string())?;
+    let metadata = encrypted_storage::encryptMetadata(&yaml, masterPassword, &fileId)?;
+    let content = if encrypted.version < 2 && !encrypted.content.is_empty() {
+        encrypted_storage::encryptContent(&encrypted.decryptContent(masterPassword)?, masterPassword, &fileId)?
+    } else {
+        encrypted.content
+    };
+    writeFileAtomic(path, encrypted_storage::toEncryptedFile(&metadata, &content))?;
+    Ok(fm)
+}
+
+// ============================================
+// SKIPPED FILES
+// ============================================
+
+#[derive(serde::Serialize)]
+pub struct SkippedItem {
+    pub path: String,
+    pub reason: String,
+}
+
+/// List command result: a plain array by default, or items plus skipped files when requested
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+pub enum ItemList<T> {
+    Items(Vec<T>),
+    WithSkipped { items: Vec<T>, skipped: Vec<SkippedItem> },
+    /// Returned when offset or limit is given
+    Paged {
+        #[serde(flatten)]
+        page: Page<T>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        skipped: Option<Vec<SkippedItem>>,
+    },
+}
+
+impl<T> ItemList<T> {
+    pub(crate) fn new(items: Vec<T>, skipped: Option<Vec<SkippedItem>>) -> Self {
+        match skipped {
+            Some(skipped) => Self::WithSkipped { items, skipped },
+            None => Self::Items(items),
+        }
+    }
+
+    /// Like `new`, but pages the list when the caller asked for it
+    /// `items` must be the full, sorted result so `total` and offsets stay consistent across calls
+    pub(crate) fn paged(items: Vec<T>, skipped: Option<Vec<SkippedItem>>, offset: Option<usize>, limit: Option<usize>) -> Self {
+        if offset.is_none() && limit.is_none() {
+            return Self::new(items, skipped);
+        }
+        Self::Paged { page: Page::of(items, offset.unwrap_or(0), limit), skipped }
+    }
+}
+
+/// Optional filters, ordering and paging shared by getNotes and getTasks
+#[derive(serde::Deserialize, Default)]
+pub struct ListOptions {
+    pub includeErrors: Option<bool>,
+    pub tags: Option<Vec<String>>,
+    pub tagMatch: Option<TagMatch>,
+    pub includeArchived: Option<bool>,
+    pub sort: Option<SortSpec>,
+    pub pinnedFirst: Option<bool>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// Find item files of `kind` under `root` that a scan did not return, and explain why
+/// Mirrors the scanners' checks so the reason matches what made them drop the file
+pub(crate) fn collectSkippedItems<T: serde::de::DeserializeOwned>(
+    root: &Path,
+    kind: ItemKind,
+    loaded: &[&Path],
+    masterPassword: Option<&str>,
+) -> Vec<SkippedItem> {
+    use encrypted_storage::{codedError, ERR_NOT_ENCRYPTED, ERR_CORRUPT_FILE, ERR_METADATA_DECRYPT};
+
+    let reasonFor = |path: &Path| -> String {
+        let raw = match fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(e) => return format!("Failed to read file: {}", e),
+        };
+        if !encrypted_storage::isEncryptedFormat(&raw) {
+            return codedError(ERR_NOT_ENCRYPTED, "File is not encrypted and has no readable frontmatter");
+        }
+        let Some(password) = masterPassword else {
+            return "Vault key unavailable".to_string();
+        };
+        let encrypted = match encrypted_storage::parseEncryptedFile(&raw, &fileIdOf(path)) {
+            Ok(encrypted) => encrypted,
+            Err(e) => return codedError(ERR_CORRUPT_FILE, e),
+        };
+        match encrypted.decryptMetadata(password) {
+            Err(_) => codedError(ERR_METADATA_DECRYPT, "File could not be decrypted with the current key"),
+            Ok(yaml) => match serde_yaml::from_str::<T>(&yaml) {
+                Err(e) => format!("Invalid metadata: {}", e),
+                Ok(_) => "Skipped by scanner".to_string(),
+            },
+        }
+    };
+
+    walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_type().is_file()
+                && e.file_name().to_str().and_then(parseUuidFilename).is_some()
+                && isItemPathOfKind(e.path(), kind)
+                && !loaded.contains(&e.path())
+        })
+        .map(|e| SkippedItem {
+            path: e.path().to_string_lossy().to_string(),
+            reason: reasonFor(e.path()),
+        })
+        .collect()
+}
+
+// ============================================
+// ITEM FILE INFO
+// ============================================
+
+#[derive(serde::Serialize)]
+pub struct ItemFileInfo {
+    pub path: String,
+    pub sizeBytes: u64,
+    pub format: String,
+    pub modifiedAt: Option<i64>,
+}
+
+/// Report on-disk details of an item for debugging.
+/// Only the header line is read, so this works while the vault is locked.
+#[tauri::command]
+pub fn getItemFileInfo(storage: State<'_, StorageState>, id: String, kind: String) -> Result<ItemFileInfo, ClaudiaError> {
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+    let kind = ItemKind::fromStr(&kind).ok_or_else(|| format!("Invalid item kind: {}", kind))?;
+
+    let path = storage.itemPath(kind, &id).ok_or_else(|| ClaudiaError::NotFound("Item not found".to_string()))?;
+    let metadata = fs::metadata(&path)?;
+
+    let file = fs::File::open(&path).map_err(|e| e.to_string())?;
+    let mut firstLine = String::new();
+    BufReader::new(file).read_line(&mut firstLine).map_err(|e| e.to_string())?;
+    let format = match encrypted_storage::formatVersion(&firstLine) {
+        Some(version) => format!("encrypted-v{}", version),
+        None => "plaintext".to_string(),
+    };
+
+    let modifiedAt = metadata.modified().ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64);
+
+    Ok(ItemFileInfo {
+        path: path.to_string_lossy().to_string(),
+        sizeBytes: metadata.len(),
+        format,
+        modifiedAt,
+    })
+}
+
+// ============================================
+// ITEM CONTENT HASH
+// ============================================
+
+/// BLAKE3 hash (hex) of the raw encrypted file bytes, for cheap change detection.
+/// Any rewrite re-encrypts with fresh nonces, so the hash changes on every save
+/// but stays the same when a file is merely touched.
+#[tauri::command]
+pub fn getItemContentHash(storage: State<'_, StorageState>, id: String, kind: String) -> Result<String, ClaudiaError> {
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+    let kind = ItemKind::fromStr(&kind).ok_or_else(|| format!("Invalid item kind: {}", kind))?;
+
+    let path = storage.itemPath(kind, &id).ok_or_else(|| ClaudiaError::NotFound("Item not found".to_string()))?;
+    let bytes = fs::read(&path).map_err(|e| ClaudiaError::Io(format!("Failed to read file: {}", e)))?;
+
+    Ok(blake3::hash(&bytes).to_hex().to_string())
+}
+
+// ============================================
+// PIN
+// ============================================
+
+/// Flip `pinned` on a live note, task or password; returns the new state
+/// Only the metadata section is decrypted and rewritten - the encrypted body is kept verbatim.
+/// Like reordering, this leaves `updated` alone.
+#[tauri::command]
+pub fn togglePin(storage: State<'_, StorageState>, kind: String, id: String) -> Result<bool, ClaudiaError> {
+    println!("[togglePin] Called with kind: {}, id: {}", kind, id);
+
+    let pinned = toggleItemPin(&storage, &kind, &id)?;
+
+    println!("[togglePin] {} is now {}", id, if pinned { "pinned" } else { "unpinned" });
+    storage.updateActivity();
+    Ok(pinned)
+}
+
+pub(crate) fn toggleItemPin(storage: &StorageState, kind: &str, id: &str) -> Result<bool, ClaudiaError> {
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+    let kind = ItemKind::fromStr(kind).ok_or_else(|| ClaudiaError::InvalidInput(format!("Invalid item kind: {}", kind)))?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+    let path = storage.itemPath(kind, id).ok_or_else(|| missingItemError(storage, kind, id))?;
+
+    Ok(match kind {
+        ItemKind::Note => rewriteMetadata(&path, &masterPassword, |fm: &mut NoteFrontmatter| fm.pinned = !fm.pinned)?.pinned,
+        ItemKind::Task => rewriteMetadata(&path, &masterPassword, |fm: &mut TaskFrontmatter| fm.pinned = !fm.pinned)?.pinned,
+        ItemKind::Password => rewriteMetadata(&path, &masterPassword, |fm: &mut PasswordFrontmatter| fm.pinned = !fm.pinned)?.pinned,
+    })
+}
+
+// ============================================
+// ITEM JSON EXPORT
+// ============================================
+
+/// Export an item as one JSON object: { kind, status?, metadata, content }.
+/// Metadata is the decrypted frontmatter; content is the markdown body, or the
+/// decrypted credential fields for passwords (which need passwords access).
+/// With reauthForSensitive on, `reauthToken` must come from verifyMasterPasswordFresh.
+#[tauri::command]
+pub fn exportItemJson(
+    storage: State<'_, StorageState>,
+    id: String,
+    kind: String,
+    reauthToken: Option<String>,
+) -> Result<serde_json::Value, ClaudiaError> {
+    println!("[exportItemJson] Called with id: {}, kind: {}", id, kind);
+    checkReauth(&storage, "exportItemJson", reauthToken.as_deref())?;
+
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+    let kind = ItemKind::fromStr(&kind).ok_or_else(|| format!("Invalid item kind: {}", kind))?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+    if kind == ItemKind::Password && !storage.isPasswordsAccessUnlocked() {
+        return Err(ClaudiaError::Auth("Passwords access is locked".to_string()));
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let path = storage.itemPath(kind, &id).ok_or_else(|| ClaudiaError::NotFound("Item not found".to_string()))?;
+    let fileContent = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let (metadata, body) = if encrypted_storage::isEncryptedFormat(&fileContent) {
+        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&path))?;
+        let yaml = encrypted.decryptMetadata(&masterPassword)?;
+        let metadata: serde_json::Value = serde_yaml::from_str(&yaml)
+            .map_err(|e| format!("Failed to parse metadata: {}", e))?;
+        let body = if encrypted.content.is_empty() {
+            String::new()
+        } else {
+            encrypted.decryptContent(&masterPassword)?
+        };
+        (metadata, body)
+    } else if kind == ItemKind::Password {
+        return Err("Passwords must be encrypted".into());
+    } else {
+        // Legacy unencrypted format
+        parseFrontmatter::<serde_json::Value>(&fileContent).ok_or("Failed to parse frontmatter")?
+    };
+
+    let content = if kind == ItemKind::Password {
+        let fields: PasswordContent = if body.is_empty() {
+            PasswordContent::default()
+        } else {
+            serde_json::from_str(&body).map_err(|e| format!("Failed to parse password content: {}", e))?
+        };
+        serde_json::to_value(fields).map_err(|e| e.to_string())?
+    } else {
+        serde_json::Value::String(body)
+    };
+
+    let mut result = serde_json::json!({
+        "kind": kind,
+        "metadata": metadata,
+        "content": content,
+    });
+    if kind == ItemKind::Task {
+        // Task status lives in the directory name, not the frontmatter
+        let status = path.parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .and_then(TaskStatus::fromFolder);
+        result["status"] = serde_json::to_value(status).map_err(|e| e.to_string())?;
+    }
+
+    storage.updateActivity();
+    Ok(result)
+}
+
+// ============================================
+// ITEMS BY TAG
+// ============================================
+
+#[derive(serde::Serialize)]
+pub struct TaggedItems {
+    pub notes: Vec<NoteInfo>,
+    pub tasks: Vec<TaskInfo>,
+    pub passwords: Vec<PasswordInfo>,
+}
+
+/// All live items carrying a tag (case-insensitive), grouped by kind
+/// Passwords are matched on metadata only - their content is never decrypted here
+#[tauri::command]
+pub fn getItemsByTag(storage: State<'_, StorageState>, tag: String) -> Result<TaggedItems, ClaudiaError> {
+    println!("[getItemsByTag] Called with tag: {}", tag);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword();
+    let passwordRef = masterPassword.as_deref();
+    let base = foldersDir(&wsPath);
+
+    let tag = tag.trim().to_lowercase();
+    let hasTag = |tags: &[String]| tags.iter().any(|t| t.to_lowercase() == tag);
+
+    let result = TaggedItems {
+        notes: scanAllNotes(&base, passwordRef).iter()
+            .filter(|n| hasTag(&n.frontmatter.tags))
+            .map(NoteInfo::from)
+            .collect(),
+        tasks: scanAllTasks(&base, passwordRef).iter()
+            .filter(|t| hasTag(&t.frontmatter.tags))
+            .map(TaskInfo::from)
+            .collect(),
+        passwords: scanAllPasswords(&base, passwordRef).iter()
+            .filter(|p| hasTag(&p.frontmatter.tags))
+            .map(PasswordInfo::from)
+            .collect(),
+    };
+
+    println!("[getItemsByTag] Found {} notes, {} tasks, {} passwords",
+             result.notes.len(), result.tasks.len(), result.passwords.len());
+    storage.updateActivity();
+    Ok(result)
+}
+
+// ============================================
+// ARCHIVE
+// ============================================
+
+#[derive(serde::Serialize)]
+pub struct ArchivedItems {
+    pub notes: Vec<NoteInfo>,
+    pub tasks: Vec<TaskInfo>,
+}
+
+/// Archived notes and tasks across the workspace
+#[tauri::command]
+pub fn listArchived(storage: State<'_, StorageState>) -> Result<ArchivedItems, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword();
+    let passwordRef = masterPassword.as_deref();
+    let base = foldersDir(&wsPath);
+
+    let result = ArchivedItems {
+        notes: scanAllNotes(&base, passwordRef).iter()
+            .filter(|n| n.frontmatter.archived)
+            .map(NoteInfo::from)
+            .collect(),
+        tasks: scanAllTasks(&base, passwordRef).iter()
+            .filter(|t| t.frontmatter.archived)
+            .map(TaskInfo::from)
+            .collect(),
+    };
+
+    println!("[listArchived] Found {} notes, {} tasks", result.notes.len(), result.tasks.len());
+    storage.updateActivity();
+    Ok(result)
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct TagCount {
+    pub tag: String,
+    pub notes: usize,
+    pub tasks: usize,
+    pub passwords: usize,
+    pub total: usize,
+}
+
+/// Count every tag across live items, most used first
+/// Tags are grouped case-insensitively (as getItemsByTag matches them), keeping the first spelling seen
+pub(crate) fn countTags(storage: &StorageState) -> Result<Vec<TagCount>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword();
+    let passwordRef = masterPassword.as_deref();
+    let base = foldersDir(&wsPath);
+
+    let mut counts: std::collections::HashMap<String, TagCount> = std::collections::HashMap::new();
+    let mut add = |tags: &[String], kind: ItemKind| {
+        for tag in tags {
+            let entry = counts.entry(tag.trim().to_lowercase())
+                .or_insert_with(|| TagCount { tag: tag.trim().to_string(), ..Default::default() });
+            match kind {
+                ItemKind::Note => entry.notes += 1,
+                ItemKind::Task => entry.tasks += 1,
+                ItemKind::Password => entry.passwords += 1,
+            }
+            entry.total += 1;
+        }
+    };
+    for note in scanAllNotes(&base, passwordRef) {
+        add(&note.frontmatter.tags, ItemKind::Note);
+    }
+    for task in scanAllTasks(&base, passwordRef) {
+        add(&task.frontmatter.tags, ItemKind::Task);
+    }
+    for password in scanAllPasswords(&base, passwordRef) {
+        add(&password.frontmatter.tags, ItemKind::Password);
+    }
+
+    let mut result: Vec<TagCount> = counts.into_values().filter(|c| !c.tag.is_empty()).collect();
+    result.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.tag.to_lowercase().cmp(&b.tag.to_lowercase())));
+
+    storage.updateActivity();
+    Ok(result)
+}
+
+/// All tags in the workspace with per-kind usage counts
+#[tauri::command]
+pub fn getAllTags(storage: State<'_, StorageState>) -> Result<Vec<TagCount>, ClaudiaError> {
+    println!("[getAllTags] Called");
+    let tags = countTags(&storage)?;
+    println!("[getAllTags] Found {} tags", tags.len());
+    Ok(tags)
+}
+
+// ============================================
+// TAG RENAME
+// ============================================
+// renameTag and deleteTag rewrite every live item carrying a tag. Only the metadata section
+// of changed files is re-encrypted (see rewriteMetadata), each file is written atomically,
+// and the work runs on a blocking thread while TAG_PROGRESS_EVENT reports how far it got.
+
+/// Event emitted after each item renameTag/deleteTag rewrites
+pub const TAG_PROGRESS_EVENT: &str = "tag-update-progress";
+
+#[derive(serde::Serialize, Clone)]
+pub struct TagUpdateProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Replace `oldTag` (case-insensitive) with `newTag`, or drop it when `newTag` is None
+/// The new tag takes the old one's position; an item that already has it keeps a single copy.
+/// Returns whether the list changed.
+fn replaceTag(tags: &mut Vec<String>, oldTag: &str, newTag: Option<&str>) -> bool {
+    let isOld = |t: &String| t.trim().to_lowercase() == oldTag;
+    let Some(position) = tags.iter().position(isOld) else { return false };
+    tags.retain(|t| !isOld(t));
+    if let Some(newTag) = newTag {
+        let newKey = newTag.to_lowercase();
+        if !tags.iter().any(|t| t.trim().to_lowercase() == newKey) {
+            tags.insert(position, newTag.to_string());
+        }
+    }
+    true
+}
+
+/// Rename (or delete, when `newTag` is None) a tag on every live note, task and password
+/// Returns the number of items rewritten; `onProgress(done, total)` runs after each one.
+pub(crate) fn updateTagEverywhere(
+    wsPath: &str,
+    masterPassword: &str,
+    oldTag: &str,
+    newTag: Option<&str>,
+    mut onProgress: impl FnMut(usize, usize),
+) -> Result<usize, String> {
+    let oldTag = oldTag.trim().to_lowercase();
+    let newTag = newTag.map(str::trim);
+    if oldTag.is_empty() || newTag.is_some_and(str::is_empty) {
+        return Err("Invalid input: tag is empty".to_string());
+    }
+
+    let base = foldersDir(wsPath);
+    let hasTag = |tags: &[String]| tags.iter().any(|t| t.trim().to_lowercase() == oldTag);
+    let mut targets: Vec<(ItemKind, PathBuf)> = Vec::new();
+    targets.extend(scanAllNotes(&base, Some(masterPassword)).into_iter()
+        .filter(|n| hasTag(&n.frontmatter.tags))
+        .map(|n| (ItemKind::Note, n.path)));
+    targets.extend(scanAllTasks(&base, Some(masterPassword)).into_iter()
+        .filter(|t| hasTag(&t.frontmatter.tags))
+        .map(|t| (ItemKind::Task, t.path)));
+    targets.extend(scanAllPasswords(&base, Some(masterPassword)).into_iter()
+        .filter(|p| hasTag(&p.frontmatter.tags))
+        .map(|p| (ItemKind::Password, p.path)));
+
+    let total = targets.len();
+    let mut changed = 0;
+    for (done, (kind, path)) in targets.iter().enumerate() {
+        let mut updated = false;
+        match kind {
+            ItemKind::Note => { rewriteMetadata(path, masterPassword, |fm: &mut NoteFrontmatter| updated = replaceTag(&mut fm.tags, &oldTag, newTag))?; }
+            ItemKind::Task => { rewriteMetadata(path, masterPassword, |fm: &mut TaskFrontmatter| updated = replaceTag(&mut fm.tags, &oldTag, newTag))?; }
+            ItemKind::Password => { rewriteMetadata(path, masterPassword, |fm: &mut PasswordFrontmatter| updated = replaceTag(&mut fm.tags, &oldTag, newTag))?; }
+        }
+        if updated {
+            changed += 1;
+        }
+        onProgress(done + 1, total);
+    }
+    Ok(changed)
+}
+
+/// Shared by renameTag and deleteTag: runs updateTagEverywhere off the main thread
+async fn runTagUpdate(app: tauri::AppHandle, oldTag: String, newTag: Option<String>) -> Result<usize, ClaudiaError> {
+    println!("[runTagUpdate] Called with oldTag: {}, newTag: {:?}", oldTag, newTag);
+
+    tauri::async_runtime::spawn_blocking(move || -> Result<usize, ClaudiaError> {
+        let storage = app.state::<StorageState>();
+        let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+        if !storage.isUnlocked() {
+            return Err(ClaudiaError::VaultLocked);
+        }
+
+        let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+        let changed = updateTagEverywhere(&wsPath, &masterPassword, &oldTag, newTag.as_deref(), |done, total| {
+            storage.emitEvent(TAG_PROGRESS_EVENT, TagUpdateProgress { done, total });
+        })?;
+
+        println!("[runTagUpdate] Updated {} items", changed);
+        storage.updateActivity();
+        Ok(changed)
+    })
+    .await
+    .map_err(|e| ClaudiaError::Other(e.to_string()))?
+}
+
+/// Rename a tag on every item that carries it (case-insensitive); returns the number of items changed
+#[tauri::command]
+pub async fn renameTag(app: tauri::AppHandle, oldTag: String, newTag: String) -> Result<usize, ClaudiaError> {
+    runTagUpdate(app, oldTag, Some(newTag)).await
+}
+
+/// Remove a tag from every item that carries it (case-insensitive); returns the number of items changed
+#[tauri::command]
+pub async fn deleteTag(app: tauri::AppHandle, tag: String) -> Result<usize, ClaudiaError> {
+    runTagUpdate(app, tag, None).await
+}
+
+// ============================================
+// TIMESTAMP ANOMALIES
+// ============================================
+
+/// Allowed drift before a timestamp counts as "in the future" (5 minutes)
+const CLOCK_SKEW_TOLERANCE_MS: i64 = 5 * 60 * 1000;
+
+#[derive(serde::Serialize)]
+pub struct TimestampAnomaly {
+    pub id: String,
+    pub kind: ItemKind,
+    pub title: String,
+    pub path: String,
+    pub created: i64,
+    pub updated: i64,
+    /// How far the latest timestamp is ahead of now, in milliseconds
+    pub aheadByMs: i64,
+}
+
+/// Live items whose created/updated lie beyond now + tolerance (metadata only)
+fn collectTimestampAnomalies(wsPath: &str, masterPassword: Option<&str>, now: i64) -> Vec<TimestampAnomaly> {
+    let base = foldersDir(wsPath);
+    let mut entries: Vec<(ItemKind, String, String, PathBuf, i64, i64)> = Vec::new();
+
+    for n in scanAllNotes(&base, masterPassword) {
+        entries.push((ItemKind::Note, n.frontmatter.id, n.frontmatter.title, n.path, n.frontmatter.created, n.frontmatter.updated));
+    }
+    for t in scanAllTasks(&base, masterPassword) {
+        entries.push((ItemKind::Task, t.frontmatter.id, t.frontmatter.title, t.path, t.frontmatter.created, t.frontmatter.updated));
+    }
+    for p in scanAllPasswords(&base, masterPassword) {
+        entries.push((ItemKind::Password, p.frontmatter.id, p.frontmatter.title, p.path, p.frontmatter.created, p.frontmatter.updated));
+    }
+
+    entries.into_iter()
+        .filter_map(|(kind, id, title, path, created, updated)| {
+            let aheadByMs = created.max(updated) - now;
+            (aheadByMs > CLOCK_SKEW_TOLERANCE_MS).then(|| TimestampAnomaly {
+                id,
+                kind,
+                title,
+                path: path.to_string_lossy().to_string(),
+                created,
+                updated,
+                aheadByMs,
+            })
+        })
+        .collect()
+}
+
+/// List items with timestamps implausibly in the future (e.g. synced from a machine whose clock is ahead)
+#[tauri::command]
+pub fn getTimestampAnomalies(storage: State<'_, StorageState>) -> Result<Vec<TimestampAnomaly>, ClaudiaError> {
+    println!("[getTimestampAnomalies] Called");
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword();
+    let anomalies = collectTimestampAnomalies(&wsPath, masterPassword.as_deref(), now());
+
+    println!("[getTimestampAnomalies] Found {} items with future timestamps", anomalies.len());
+    storage.updateActivity();
+    Ok(anomalies)
+}
+
+/// Clamp future created/updated timestamps to now; returns the number of items repaired
+/// Only metadata is rewritten - bodies are left encrypted as they are
+#[tauri::command]
+pub fn repairTimestamps(storage: State<'_, StorageState>) -> Result<usize, ClaudiaError> {
+    println!("[repairTimestamps] Called");
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let now = now();
+    let clamp = |ts: &mut i64| *ts = (*ts).min(now);
+
+    let anomalies = collectTimestampAnomalies(&wsPath, Some(&masterPassword), now);
+    for anomaly in &anomalies {
+        let path = Path::new(&anomaly.path);
+        match anomaly.kind {
+            ItemKind::Note => rewriteMetadata(path, &masterPassword, |fm: &mut NoteFrontmatter| {
+                clamp(&mut fm.created);
+                clamp(&mut fm.updated);
+            }).map(|_| ())?,
+            ItemKind::Task => rewriteMetadata(path, &masterPassword, |fm: &mut TaskFrontmatter| {
+                clamp(&mut fm.created);
+                clamp(&mut fm.updated);
+            }).map(|_| ())?,
+            ItemKind::Password => rewriteMetadata(path, &masterPassword, |fm: &mut PasswordFrontmatter| {
+                clamp(&mut fm.created);
+                clamp(&mut fm.updated);
+            }).map(|_| ())?,
+        }
+        println!("[repairTimestamps] Clamped {:?} {}", anomaly.kind, anomaly.id);
+    }
+
+    storage.updateActivity();
+    Ok(anomalies.len())
+}
+
+// ============================================
+// QUICK SWITCHER
+// ============================================
+
+/// Upper bound on quick switcher results
+const MAX_TITLE_MATCHES: usize = 50;
+
+#[derive(serde::Serialize)]
+pub struct TitleMatch {
+    pub id: String,
+    pub kind: ItemKind,
+    pub title: String,
+    pub score: i64,
+    pub folderPath: String,
+}
+
+/// Fuzzy-match item titles for the quick switcher, best matches first
+/// Uses scanned metadata only, so no body is decrypted; `kind` limits the search to one item kind
+#[tauri::command]
+pub fn findItemByTitle(storage: State<'_, StorageState>, query: String, kind: Option<String>) -> Result<Vec<TitleMatch>, ClaudiaError> {
+    println!("[findItemByTitle] Called with query: {}, kind: {:?}", query, kind);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let kind = match kind {
+        Some(k) => Some(ItemKind::fromStr(&k).ok_or_else(|| format!("Invalid item kind: {}", k))?),
+        None => None,
+    };
+    let wants = |k: ItemKind| kind.is_none_or(|wanted| wanted == k);
+
+    let masterPassword = storage.getMasterPassword();
+    let passwordRef = masterPassword.as_deref();
+    let base = foldersDir(&wsPath);
+
+    // (kind, id, title, folderPath) for every candidate
+    let mut candidates: Vec<(ItemKind, String, String, String)> = Vec::new();
+    if wants(ItemKind::Note) {
+        candidates.extend(scanAllNotes(&base, passwordRef).iter().map(NoteInfo::from)
+            .map(|n| (ItemKind::Note, n.id, n.title, n.folderPath)));
+    }
+    if wants(ItemKind::Task) {
+        candidates.extend(scanAllTasks(&base, passwordRef).iter().map(TaskInfo::from)
+            .map(|t| (ItemKind::Task, t.id, t.title, t.folderPath)));
+    }
+    if wants(ItemKind::Password) {
+        candidates.extend(scanAllPasswords(&base, passwordRef).iter().map(PasswordInfo::from)
+            .map(|p| (ItemKind::Password, p.id, p.title, p.folderPath)));
+    }
+
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut matches: Vec<TitleMatch> = candidates.into_iter()
+        .filter_map(|(kind, id, title, folderPath)| {
+            let score = matcher.fuzzy_match(&title, &query).filter(|s| *s > 0)?;
+            Some(TitleMatch { id, kind, title, score, folderPath })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.title.cmp(&b.title)));
+    matches.truncate(MAX_TITLE_MATCHES);
+
+    println!("[findItemByTitle] Returning {} matches", matches.len());
+    storage.updateActivity();
+    Ok(matches)
+}
+
+// ============================================
+// FULL-TEXT SEARCH
+// ============================================
+
+/// Search titles and decrypted bodies of notes and tasks, notes first
+/// `kinds` limits the search (e.g. ["note"]); passwords are never searched. Stops at MAX_CONTENT_SEARCH_RESULTS.
+#[tauri::command]
+pub fn searchContent(storage: State<'_, StorageState>, query: String, kinds: Option<Vec<String>>) -> Result<Vec<ContentSearchHit>, ClaudiaError> {
+    println!("[searchContent] Called with query: {}, kinds: {:?}", query, kinds);
+
+    let hits = api::search_content(&storage, &query, kinds.as_deref(), MAX_CONTENT_SEARCH_RESULTS)?;
+
+    println!("[searchContent] Returning {} hits", hits.len());
+    Ok(hits)
+}
+
+// ============================================
+// BATCH CONTENT
+// ============================================
+// Note and task bodies for many ids in one call, so features like export previews make a
+// single IPC round-trip and a single vault scan instead of one per item.
+
+#[derive(serde::Serialize)]
+pub struct ItemContent {
+    pub id: String,
+    pub content: String,
+}
+
+/// Decrypt the bodies of `ids`, in request order, from already scanned (id, path, parsed body) items
+/// Unknown ids are left out. Legacy unencrypted items keep their parsed body.
+pub(crate) fn readContentsBatch<'a>(
+    ids: Vec<String>,
+    items: impl IntoIterator<Item = (&'a str, &'a Path, &'a str)>,
+    masterPassword: &str,
+) -> Result<Vec<ItemContent>, String> {
+    let byId: std::collections::HashMap<&str, (&Path, &str)> = items.into_iter()
+        .map(|(id, path, body)| (id, (path, body)))
+        .collect();
+
+    let mut results = Vec::with_capacity(ids.len());
+    for id in ids {
+        let Some(&(path, body)) = byId.get(id.as_str()) else { continue };
+        let content = encrypted_storage::readDecryptedBody(path, masterPassword)?
+            .unwrap_or_else(|| body.to_string());
+        results.push(ItemContent { id, content });
+    }
+    Ok(results)
+}
+
+// ============================================
+// VERSION HISTORY
+// ============================================
+// Before an update overwrites a note or task, the previous encrypted file is copied to
+// {itemsDir}/.history/{uuid}/{timestamp}.md. Copies stay encrypted under the item's
+// UUID, so a version can be restored by copying it back over the item file.
+
+/// Saved version timestamps of an item, newest first
+pub(crate) fn listVersions(itemsDirPath: &Path, id: &str) -> Vec<i64> {
+    let mut versions: Vec<i64> = fs::read_dir(historyDir(itemsDirPath, id))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.file_name().to_str()?.strip_suffix(".md")?.parse().ok())
+        .collect();
+    versions.sort_unstable_by(|a, b| b.cmp(a));
+    versions
+}
+
+/// Copy the current item file into its history, keeping only the newest `keep` versions
+/// A `keep` of 0 turns history off
+pub(crate) fn snapshotVersion(itemsDirPath: &Path, itemPath: &Path, id: &str, keep: u32) -> Result<(), String> {
+    if keep == 0 || !itemPath.is_file() {
+        return Ok(());
+    }
+
+    let dir = historyDir(itemsDirPath, id);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    // Always newer than the last version, so saves within the same millisecond stay
+    // distinct and the pruning below never drops the snapshot just taken
+    let timestamp = match listVersions(itemsDirPath, id).first() {
+        Some(&newest) => now().max(newest + 1),
+        None => now(),
+    };
+    fs::copy(itemPath, dir.join(format!("{}.md", timestamp)))
+        .map_err(|e| format!("Failed to save version: {}", e))?;
+
+    for old in listVersions(itemsDirPath, id).into_iter().skip(keep as usize) {
+        let _ = fs::remove_file(dir.join(format!("{}.md", old)));
+    }
+    Ok(())
+}
+
+/// Swap the current item file with a saved version
+/// The current file becomes a new version, so a restore can itself be undone
+pub(crate) fn restoreVersion(itemsDirPath: &Path, itemPath: &Path, id: &str, timestamp: i64, keep: u32) -> Result<(), ClaudiaError> {
+    let versionPath = historyDir(itemsDirPath, id).join(format!("{}.md", timestamp));
+    let version = fs::read(&versionPath).map_err(|_| ClaudiaError::NotFound(format!("Version {} not found", timestamp)))?;
+
+    snapshotVersion(itemsDirPath, itemPath, id, keep.max(1))?;
+    writeFileAtomic(itemPath, version)?;
+    let _ = fs::remove_file(versionPath);
+    Ok(())
+}
+
+/// Carry an item's history along when it moves to another items dir
+pub(crate) fn moveVersionHistory(fromItemsDir: &Path, toItemsDir: &Path, id: &str) {
+    let from = historyDir(fromItemsDir, id);
+    if from.exists() && fromItemsDir != toItemsDir {
+        let to = historyDir(toItemsDir, id);
+        if let Some(parent) = to.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::remove_dir_all(&to);
+        if let Err(e) = fs::rename(&from, &to) {
+            println!("[moveVersionHistory] Failed to move history for {}: {}", id, e);
+        }
+    }
+}
+
+/// Drop an item's history when the item is permanently deleted
+pub(crate) fn removeVersionHistory(itemsDirPath: &Path, id: &str) {
+    let _ = fs::remove_dir_all(historyDir(itemsDirPath, id));
+}
+
+// ============================================
+// UNDO
+// ============================================
+// Delete, move and reorder commands record their inverse with storage.recordUndo.
+// The stack is in memory only: it is empty after a restart.
+
+/// Items dir of an item file ({folder}/notes, {folder}/tasks or {folder}/passwords)
+fn itemsDirOfItemPath(path: &Path, kind: ItemKind) -> Option<&Path> {
+    match kind {
+        ItemKind::Task => path.parent().and_then(Path::parent),
+        _ => path.parent(),
+    }
+}
+
+/// Set the rank of any item without decrypting its body
+pub(crate) fn setItemRank(path: &Path, rank: u32, masterPassword: &str) -> Result<(), String> {
+    rewriteMetadata::<serde_yaml::Value>(path, masterPassword, |fm| {
+        fm["rank"] = serde_yaml::Value::from(rank);
+    })?;
+    Ok(())
+}
+
+/// Replay one inverse action
+/// Refuse a move-back whose file is gone or whose old place is taken, before anything moves
+fn checkUndoAction(action: &UndoAction) -> Result<(), String> {
+    if let UndoAction::MoveBack { current, original, .. } = action {
+        if !current.is_file() {
+            return Err(format!("Cannot undo: {} no longer exists", current.display()));
+        }
+        if original.exists() {
+            return Err(format!("Cannot undo: {} is already taken", original.display()));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn applyUndoAction(action: &UndoAction, masterPassword: &str) -> Result<(), String> {
+    checkUndoAction(action)?;
+    match action {
+        UndoAction::MoveBack { kind, id, current, original, rank } => {
+            if let Some(parent) = original.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            // Same UUID filename, so the encrypted sections stay bound to the file
+            fs::rename(current, original).map_err(|e| e.to_string())?;
+            if let (Some(from), Some(to)) = (itemsDirOfItemPath(current, *kind), itemsDirOfItemPath(original, *kind)) {
+                moveVersionHistory(from, to, id);
+            }
+            setItemRank(original, *rank, masterPassword)
+        }
+        UndoAction::RestoreRanks { ranks } => {
+            for (path, rank) in ranks.iter().filter(|(path, _)| path.is_file()) {
+                setItemRank(path, *rank, masterPassword)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Undo the most recent delete, move or reorder
+/// Returns the name of the undone command, or None when there is nothing to undo
+#[tauri::command]
+pub fn undoLastOperation(storage: State<'_, StorageState>) -> Result<Option<String>, ClaudiaError> {
+    println!("[undoLastOperation] Called");
+
+    let label = undoLast(&storage)?;
+    if let Some(label) = &label {
+        println!("[undoLastOperation] SUCCESS - undid {}", label);
+    }
+    storage.updateActivity();
+    Ok(label)
+}
+
+/// Replay the newest undo entry of the current workspace
+/// Every move-back is checked before the first file moves, and an entry that fails the check
+/// is dropped. If an action still fails midway, the actions not yet undone go back on the
+/// stack so the undo can be retried
+pub(crate) fn undoLast(storage: &StorageState) -> Result<Option<String>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let Some(mut entry) = storage.takeUndo() else {
+        return Ok(None);
+    };
+    for action in &entry.actions {
+        checkUndoAction(action)?;
+    }
+
+    // Undo in reverse order of the original changes
+    while let Some(action) = entry.actions.last() {
+        if let Err(e) = applyUndoAction(action, &masterPassword) {
+            storage.returnUndo(entry);
+            return Err(e.into());
+        }
+        if let UndoAction::MoveBack { kind, id, original, .. } = action {
+            storage.indexItem(*kind, id, original);
+            forgetTrashOrigin(&wsPath, id);
+        }
+        entry.actions.pop();
+    }
+
+    Ok(Some(entry.label))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{itemsDir, uuidFilename};
+    use crate::commands::common::newId;
+
+    const TEST_PASSWORD: &str = "correct horse battery staple";
+
+    fn writeNote(dir: &Path, title: &str, rank: u32) -> (String, PathBuf) {
+        fs::create_dir_all(dir).unwrap();
+        let fm = NoteFrontmatter::new(newId(), title.to_string(), rank);
+        let path = dir.join(uuidFilename(&fm.id));
+        fs::write(&path, encrypted_storage::serializeAndEncrypt(&fm, "body", TEST_PASSWORD, &fm.id).unwrap()).unwrap();
+        (fm.id, path)
+    }
+
+    fn rankOf(path: &Path) -> u32 {
+        let raw = fs::read_to_string(path).unwrap();
+        let encrypted = encrypted_storage::parseEncryptedFile(&raw, &fileIdOf(path)).unwrap();
+        let fm: NoteFrontmatter = serde_yaml::from_str(&encrypted.decryptMetadata(TEST_PASSWORD).unwrap()).unwrap();
+        fm.rank
+    }
+
+    #[test]
+    fn test_contents_batch_keeps_request_order() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let dir = itemsDir(&ws.join("folders"), ItemKind::Note);
+        let (first, firstPath) = writeNote(&dir, "First", 1);
+        let (second, secondPath) = writeNote(&dir, "Second", 2);
+
+        let items = [(first.as_str(), firstPath.as_path(), ""), (second.as_str(), secondPath.as_path(), "")];
+        let results = readContentsBatch(vec![second.clone(), "missing".to_string(), first.clone()], items, TEST_PASSWORD).unwrap();
+
+        let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, [second.as_str(), first.as_str()]);
+        assert!(results.iter().all(|r| r.content == "body"));
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    fn writeTagged(dir: &Path, kind: ItemKind, tags: &[&str]) -> PathBuf {
+        fs::create_dir_all(dir).unwrap();
+        let id = newId();
+        let path = dir.join(uuidFilename(&id));
+        let tags = tags.iter().map(|t| t.to_string()).collect();
+        let file = match kind {
+            ItemKind::Note => encrypted_storage::serializeAndEncrypt(&NoteFrontmatter { tags, ..NoteFrontmatter::new(id.clone(), "Note".to_string(), 0) }, "body", TEST_PASSWORD, &id),
+            _ => encrypted_storage::serializeAndEncrypt(&TaskFrontmatter { tags, ..TaskFrontmatter::new(id.clone(), "Task".to_string(), 0) }, "body", TEST_PASSWORD, &id),
+        };
+        fs::write(&path, file.unwrap()).unwrap();
+        path
+    }
+
+    fn tagsOf<T: serde::de::DeserializeOwned>(path: &Path, tags: impl Fn(T) -> Vec<String>) -> Vec<String> {
+        let raw = fs::read_to_string(path).unwrap();
+        let encrypted = encrypted_storage::parseEncryptedFile(&raw, &fileIdOf(path)).unwrap();
+        tags(serde_yaml::from_str(&encrypted.decryptMetadata(TEST_PASSWORD).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_rename_tag_across_workspace() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        let notes = itemsDir(&ws.join("folders"), ItemKind::Note);
+        let tasks = itemsDir(&ws.join("folders"), ItemKind::Task).join(TaskStatus::Todo.folderName());
+        let first = writeTagged(&notes, ItemKind::Note, &["Work", "urgent"]);
+        let second = writeTagged(&notes, ItemKind::Note, &["work", "done"]);
+        let untagged = writeTagged(&notes, ItemKind::Note, &["home"]);
+        let task = writeTagged(&tasks, ItemKind::Task, &["done", "WORK"]);
+        let untouched = fs::read_to_string(&untagged).unwrap();
+
+        let mut progress = Vec::new();
+        let changed = updateTagEverywhere(&wsPath, TEST_PASSWORD, "work", Some("job"), |done, total| progress.push((done, total))).unwrap();
+
+        assert_eq!(changed, 3);
+        assert_eq!(progress, [(1, 3), (2, 3), (3, 3)]);
+        let noteTags = |fm: NoteFrontmatter| fm.tags;
+        assert_eq!(tagsOf(&first, noteTags), ["job", "urgent"]);
+        assert_eq!(tagsOf(&second, noteTags), ["job", "done"]);
+        assert_eq!(tagsOf(&task, |fm: TaskFrontmatter| fm.tags), ["done", "job"]);
+        // Items without the tag are not rewritten
+        assert_eq!(fs::read_to_string(&untagged).unwrap(), untouched);
+
+        // Deleting a tag drops it; renaming onto an existing tag keeps a single copy
+        assert_eq!(updateTagEverywhere(&wsPath, TEST_PASSWORD, "urgent", None, |_, _| {}).unwrap(), 1);
+        assert_eq!(tagsOf(&first, noteTags), ["job"]);
+        assert_eq!(updateTagEverywhere(&wsPath, TEST_PASSWORD, "done", Some("job"), |_, _| {}).unwrap(), 2);
+        assert_eq!(tagsOf(&task, |fm: TaskFrontmatter| fm.tags), ["job"]);
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_toggle_pin_keeps_body_verbatim() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let (_, path) = writeNote(&itemsDir(&ws.join("folders"), ItemKind::Note), "Pinned", 1);
+        let contentLine = |path: &Path| fs::read_to_string(path).unwrap().lines().last().unwrap().to_string();
+        let body = contentLine(&path);
+
+        let fm = rewriteMetadata(&path, TEST_PASSWORD, |fm: &mut NoteFrontmatter| fm.pinned = !fm.pinned).unwrap();
+        assert!(fm.pinned);
+        assert_eq!(contentLine(&path), body);
+        assert_eq!(encrypted_storage::readDecryptedBody(&path, TEST_PASSWORD).unwrap().as_deref(), Some("body"));
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_toggle_pin_command_reaches_the_trash() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        let storage = undoStorage(&ws);
+        let key = storage.getMasterPassword().unwrap();
+
+        let password = PasswordFrontmatter::new(newId(), "Bank".to_string(), 0);
+        let passwordPath = itemsDir(&foldersDir(&wsPath).join(newId()), ItemKind::Password).join(uuidFilename(&password.id));
+        fs::create_dir_all(passwordPath.parent().unwrap()).unwrap();
+        fs::write(&passwordPath, encrypted_storage::serializeAndEncrypt(&password, "secret fields", &key, &password.id).unwrap()).unwrap();
+
+        assert!(toggleItemPin(&storage, "password", &password.id).unwrap());
+        assert!(!toggleItemPin(&storage, "password", &password.id).unwrap());
+        assert_eq!(encrypted_storage::readDecryptedBody(&passwordPath, &key).unwrap().as_deref(), Some("secret fields"));
+
+        let trashed = NoteFrontmatter::new(newId(), "Trashed".to_string(), 0);
+        let trashedPath = crate::storage::trashNotesDir(&wsPath).join(uuidFilename(&trashed.id));
+        fs::create_dir_all(trashedPath.parent().unwrap()).unwrap();
+        fs::write(&trashedPath, encrypted_storage::serializeAndEncrypt(&trashed, "body", &key, &trashed.id).unwrap()).unwrap();
+
+        // The pin sticks to the trashed file, which stays in the trash
+        assert!(toggleItemPin(&storage, "note", &trashed.id).unwrap());
+        assert!(!toggleItemPin(&storage, "note", &trashed.id).unwrap());
+        assert!(trashedPath.is_file());
+        assert_eq!(encrypted_storage::readDecryptedBody(&trashedPath, &key).unwrap().as_deref(), Some("body"));
+
+        assert_eq!(toggleItemPin(&storage, "note", &newId()).unwrap_err().code(), "NOT_FOUND");
+        assert_eq!(toggleItemPin(&storage, "folder", &trashed.id).unwrap_err().code(), "INVALID_INPUT");
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_history_versions_decrypt_with_file_id() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let dir = itemsDir(&ws.join("folders"), ItemKind::Note);
+        let (id, path) = writeNote(&dir, "Versioned", 1);
+
+        snapshotVersion(&dir, &path, &id, 20).unwrap();
+        let versions = listVersions(&dir, &id);
+        assert_eq!(versions.len(), 1);
+
+        let versionPath = historyDir(&dir, &id).join(format!("{}.md", versions[0]));
+        assert_eq!(fileIdOf(&versionPath), id);
+        assert_eq!(rankOf(&versionPath), 1);
+        assert_eq!(encrypted_storage::readDecryptedBody(&versionPath, TEST_PASSWORD).unwrap().as_deref(), Some("body"));
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_undo_move() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let fromDir = itemsDir(&ws.join("folders"), ItemKind::Note);
+        let toDir = itemsDir(&ws.join("folders").join(newId()), ItemKind::Note);
+        let (id, original) = writeNote(&fromDir, "Moved", 3);
+
+        // What moveNoteToFolder does: same filename in the target, next rank there
+        fs::create_dir_all(&toDir).unwrap();
+        let current = toDir.join(uuidFilename(&id));
+        fs::rename(&original, &current).unwrap();
+        setItemRank(&current, 1, TEST_PASSWORD).unwrap();
+
+        let action = UndoAction::MoveBack { kind: ItemKind::Note, id, current: current.clone(), original: original.clone(), rank: 3 };
+        applyUndoAction(&action, TEST_PASSWORD).unwrap();
+
+        assert!(!current.exists());
+        assert_eq!(rankOf(&original), 3);
+        // A second replay has nothing left to move
+        assert!(applyUndoAction(&action, TEST_PASSWORD).is_err());
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_undo_reorder() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let dir = itemsDir(&ws.join("folders"), ItemKind::Note);
+        let (_, first) = writeNote(&dir, "First", 1);
+        let (_, second) = writeNote(&dir, "Second", 2);
+
+        // What reorderNotes records before swapping the two
+        let action = UndoAction::RestoreRanks { ranks: vec![(first.clone(), 1), (second.clone(), 2)] };
+        setItemRank(&first, 2, TEST_PASSWORD).unwrap();
+        setItemRank(&second, 1, TEST_PASSWORD).unwrap();
+
+        applyUndoAction(&action, TEST_PASSWORD).unwrap();
+        assert_eq!(rankOf(&first), 1);
+        assert_eq!(rankOf(&second), 2);
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    /// Workspace with an unlocked storage, plus a note moved from the root into a folder
+    fn movedNote(storage: &StorageState, ws: &Path, title: &str) -> (String, PathBuf, PathBuf) {
+        let key = storage.getMasterPassword().unwrap();
+        let fromDir = itemsDir(&ws.join("folders"), ItemKind::Note);
+        let toDir = itemsDir(&ws.join("folders").join(newId()), ItemKind::Note);
+        fs::create_dir_all(&fromDir).unwrap();
+        fs::create_dir_all(&toDir).unwrap();
+        let fm = NoteFrontmatter::new(newId(), title.to_string(), 1);
+        let original = fromDir.join(uuidFilename(&fm.id));
+        let current = toDir.join(uuidFilename(&fm.id));
+        fs::write(&current, encrypted_storage::serializeAndEncrypt(&fm, "body", &key, &fm.id).unwrap()).unwrap();
+        (fm.id, current, original)
+    }
+
+    fn undoStorage(ws: &Path) -> StorageState {
+        let storage: StorageState = std::sync::Arc::new(crate::storage::Storage::new());
+        storage.setCurrentWorkspace(Some(ws.to_string_lossy().to_string()));
+        storage.setDerivedKey(vec![7u8; 32]);
+        storage
+    }
+
+    #[test]
+    fn test_undo_checks_every_action_before_moving() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let storage = undoStorage(&ws);
+        let (blockedId, blockedCurrent, blockedOriginal) = movedNote(&storage, &ws, "Blocked");
+        let (id, current, original) = movedNote(&storage, &ws, "Free");
+        // Something else now lives where the first note came from
+        fs::write(&blockedOriginal, "taken").unwrap();
+
+        storage.recordUndo("deleteNotes", vec![
+            UndoAction::MoveBack { kind: ItemKind::Note, id: blockedId, current: blockedCurrent.clone(), original: blockedOriginal, rank: 1 },
+            UndoAction::MoveBack { kind: ItemKind::Note, id, current: current.clone(), original: original.clone(), rank: 1 },
+        ]);
+        assert!(undoLast(&storage).is_err());
+
+        // The later action would have run first; nothing may move when any action is stale
+        assert!(current.is_file());
+        assert!(!original.exists());
+        assert!(blockedCurrent.is_file());
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_undo_keeps_actions_that_did_not_run() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let storage = undoStorage(&ws);
+        let (id, current, original) = movedNote(&storage, &ws, "Moved");
+        let unreadable = ws.join("folders").join("notes").join(uuidFilename(&newId()));
+        fs::write(&unreadable, "not an encrypted item").unwrap();
+
+        let restoreRanks = UndoAction::RestoreRanks { ranks: vec![(unreadable, 4)] };
+        storage.recordUndo("reorderNotes", vec![
+            restoreRanks.clone(),
+            UndoAction::MoveBack { kind: ItemKind::Note, id, current: current.clone(), original: original.clone(), rank: 1 },
+        ]);
+        assert!(undoLast(&storage).is_err());
+
+        // The move-back ran; the failed rank restore is still there to retry
+        assert!(original.is_file());
+        assert!(!current.exists());
+        let remaining = storage.takeUndo().unwrap();
+        assert_eq!(remaining.label, "reorderNotes");
+        assert_eq!(remaining.actions, vec![restoreRanks]);
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+}
@@ -0,0 +1,272 @@
+// Maintenance commands - reclaim space from leftovers the normal item flows never clean up
+// Only empty item directories, orphaned assets and expired trash are removed; live items are never touched
+
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+use crate::error::ClaudiaError;
+use crate::encrypted_storage::{self, fileIdOf};
+use crate::storage::{StorageState, assetsDir, findItemFile, folderArg, foldersDir, parseUuidFilename, trashDir, validateMoveTarget};
+use crate::models::ItemKind;
+use super::trash::runTrashRetention;
+
+#[derive(serde::Serialize, Default)]
+pub struct CompactReport {
+    pub removedEmptyDirs: usize,
+    pub removedAssetDirs: usize,
+    pub purgedTrashItems: usize,
+    pub reclaimedBytes: u64,
+}
+
+/// Total size of the files below a path
+fn dirSize(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Remove asset directories whose note no longer exists (in the tree or the trash)
+fn removeOrphanedAssets(wsPath: &str, report: &mut CompactReport) {
+    for entry in fs::read_dir(assetsDir(wsPath)).into_iter().flatten().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !path.is_dir() || parseUuidFilename(&name).is_none() {
+            continue;
+        }
+        if findItemFile(wsPath, ItemKind::Note, &name).is_none() {
+            let size = dirSize(&path);
+            if fs::remove_dir_all(&path).is_ok() {
+                println!("[compactWorkspace] Removed orphaned assets: {}", path.display());
+                report.removedAssetDirs += 1;
+                report.reclaimedBytes += size;
+            }
+        }
+    }
+}
+
+/// Remove empty notes/, passwords/, tasks/{status}/ and tasks/ directories below root
+/// Children are visited first so a tasks/ dir left empty by its status dirs goes too
+fn removeEmptyItemDirs(root: &Path, report: &mut CompactReport) {
+    let entries = walkdir::WalkDir::new(root)
+        .contents_first(true)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok());
+
+    for entry in entries {
+        if entry.depth() == 0 || !entry.file_type().is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy();
+        let inTasksDir = entry.path().parent()
+            .and_then(|p| p.file_name())
+            .is_some_and(|n| n.to_string_lossy() == "tasks");
+        let isItemDir = ["notes", "tasks", "passwords"].contains(&name.as_ref()) || inTasksDir;
+
+        // remove_dir only succeeds on empty directories
+        if isItemDir && fs::remove_dir(entry.path()).is_ok() {
+            report.removedEmptyDirs += 1;
+        }
+    }
+}
+
+/// Vacuum the workspace: purge expired trash (trashRetentionDays), drop orphaned
+/// note assets and prune empty item directories
+#[tauri::command]
+pub fn compactWorkspace(storage: State<'_, StorageState>) -> Result<CompactReport, ClaudiaError> {
+    println!("[compactWorkspace] Called");
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let mut report = CompactReport::default();
+
+    if let Some(purged) = runTrashRetention(&storage) {
+        report.purgedTrashItems = purged.purged;
+        report.reclaimedBytes += purged.reclaimedBytes;
+    }
+
+    // After the trash purge, so assets of purged notes are reclaimed too
+    removeOrphanedAssets(&wsPath, &mut report);
+
+    removeEmptyItemDirs(&foldersDir(&wsPath), &mut report);
+    removeEmptyItemDirs(&trashDir(&wsPath), &mut report);
+
+    println!("[compactWorkspace] Removed {} empty dirs, {} asset dirs, {} trash items ({} bytes)",
+             report.removedEmptyDirs, report.removedAssetDirs, report.purgedTrashItems, report.reclaimedBytes);
+    storage.updateActivity();
+    Ok(report)
+}
+
+// ============================================
+// HEALTH SCAN
+// ============================================
+// List commands skip files they cannot read, so a damaged item just disappears.
+// scanHealth finds those files and says why they were skipped.
+
+/// Why an encrypted file could not be read
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthProblem {
+    /// Structure is damaged: missing sections, invalid encoding, or content that no longer decrypts
+    BadFormat,
+    /// Metadata does not decrypt with the current key
+    WrongPassword,
+    /// The file could not be read at all
+    IoError,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct UnhealthyFile {
+    pub path: String,
+    pub problem: HealthProblem,
+    pub error: String,
+}
+
+#[derive(serde::Serialize, Default, Debug)]
+pub struct HealthReport {
+    pub checked: usize,
+    pub problems: Vec<UnhealthyFile>,
+}
+
+/// Problem with one .md/.asset file, None when it is healthy or not encrypted at all
+fn checkFileHealth(path: &Path, key: &str) -> Option<(HealthProblem, String)> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+            return Some((HealthProblem::BadFormat, format!("File is not valid text: {}", e)));
+        }
+        Err(e) => return Some((HealthProblem::IoError, e.to_string())),
+    };
+    if !encrypted_storage::isEncryptedFormat(&raw) {
+        return None;
+    }
+
+    let encrypted = match encrypted_storage::parseEncryptedFile(&raw, &fileIdOf(path)) {
+        Ok(encrypted) => encrypted,
+        Err(e) => return Some((HealthProblem::BadFormat, e.to_string())),
+    };
+    if let Err(e) = encrypted.decryptMetadata(key) {
+        return Some((HealthProblem::WrongPassword, e.to_string()));
+    }
+    if !encrypted.content.is_empty()
+        && let Err(e) = encrypted.decryptContent(key)
+    {
+        return Some((HealthProblem::BadFormat, format!("Metadata decrypted but content did not: {}", e)));
+    }
+    None
+}
+
+/// Check every .md/.asset file below `root`, version history included
+fn scanHealthIn(root: &Path, key: &str, report: &mut HealthReport) {
+    let files = walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().map(|ext| ext == "md" || ext == "asset").unwrap_or(false));
+
+    for entry in files {
+        report.checked += 1;
+        if let Some((problem, error)) = checkFileHealth(entry.path(), key) {
+            report.problems.push(UnhealthyFile { path: entry.path().to_string_lossy().to_string(), problem, error });
+        }
+    }
+}
+
+/// Find encrypted files that fail to parse or decrypt
+/// Scans one folder subtree, or the whole vault (folders, trash and assets) without `folderPath`
+#[tauri::command]
+pub fn scanHealth(storage: State<'_, StorageState>, folderPath: Option<String>) -> Result<HealthReport, ClaudiaError> {
+    println!("[scanHealth] Called with folderPath: {:?}", folderPath);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let key = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let roots = match folderArg(folderPath.as_deref()) {
+        Some(folder) => vec![validateMoveTarget(&wsPath, &folder.to_string_lossy())?.to_path_buf()],
+        None => vec![foldersDir(&wsPath), trashDir(&wsPath), assetsDir(&wsPath)],
+    };
+
+    let mut report = HealthReport::default();
+    for root in &roots {
+        scanHealthIn(root, &key, &mut report);
+    }
+
+    println!("[scanHealth] Checked {} files, {} problems", report.checked, report.problems.len());
+    storage.updateActivity();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::common::newId;
+
+    #[test]
+    fn test_health_scan_categorizes_problems() {
+        let root = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        fs::create_dir_all(&root).unwrap();
+        let write = |key: &str| {
+            let path = root.join(format!("{}.md", newId()));
+            let content = encrypted_storage::createEncryptedFile("title: x", "body", key, &fileIdOf(&path)).unwrap();
+            fs::write(&path, &content).unwrap();
+            (path, content)
+        };
+
+        write("right key");
+        let (wrongKey, _) = write("wrong key");
+        let (truncated, content) = write("right key");
+        fs::write(&truncated, content.lines().next().unwrap()).unwrap();
+        fs::write(root.join("plain.md"), "---\ntitle: plain\n---\n").unwrap();
+        fs::write(root.join("binary.asset"), [0xffu8, 0xfe, 0x00]).unwrap();
+
+        let mut report = HealthReport::default();
+        scanHealthIn(&root, "right key", &mut report);
+        assert_eq!(report.checked, 5);
+
+        let problemOf = |path: &Path| report.problems.iter()
+            .find(|p| p.path == path.to_string_lossy())
+            .map(|p| p.problem);
+        assert_eq!(problemOf(&wrongKey), Some(HealthProblem::WrongPassword));
+        assert_eq!(problemOf(&truncated), Some(HealthProblem::BadFormat));
+        assert_eq!(problemOf(&root.join("binary.asset")), Some(HealthProblem::BadFormat));
+        assert_eq!(report.problems.len(), 3);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_health_scan_accepts_history_versions() {
+        use crate::commands::item::snapshotVersion;
+        use crate::models::ItemKind;
+        use crate::storage::{itemsDir, uuidFilename};
+
+        let root = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let notes = itemsDir(&root, ItemKind::Note);
+        fs::create_dir_all(&notes).unwrap();
+        let id = newId();
+        let path = notes.join(uuidFilename(&id));
+        fs::write(&path, encrypted_storage::createEncryptedFile("title: x", "body", "right key", &id).unwrap()).unwrap();
+        snapshotVersion(&notes, &path, &id, 20).unwrap();
+
+        let mut report = HealthReport::default();
+        scanHealthIn(&root, "right key", &mut report);
+        assert_eq!(report.checked, 2);
+        assert!(report.problems.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,9 +1,13 @@
 // Commands module - exports all command handlers
 // Submodules must be public for Tauri's generate_handler! macro
 
+pub mod asset;
+pub mod backup;
 pub mod common;
 pub mod folder;
 pub mod floating;
+pub mod item;
+pub mod maintenance;
 pub mod note;
 pub mod password;
 pub mod settings;
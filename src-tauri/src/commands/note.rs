@@ -1,13 +1,22 @@
 // Note commands - complete implementation with encryption
 
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use chrono::format::{Item, StrftimeItems};
 use tauri::State;
 
-use crate::storage::{StorageState, notesDir, foldersDir, parseUuidFilename, uuidFilename, parseFrontmatter, trashNotesDir};
-use crate::encrypted_storage;
-use crate::models::{Note, NoteFrontmatter, FloatWindow};
-use super::common::newId;
+use crate::error::ClaudiaError;
+use crate::storage::{StorageState, validateMoveTarget, notesDir, foldersDir, parseUuidFilename, uuidFilename, parseFrontmatter, trashNotesDir, recordTrashOrigin, forgetTrashOrigin, noteAssetsDir, itemsDir, folderOfItemsDir, folderArg, itemsDirFor, isHistoryPath, writeFileAtomic};
+use crate::encrypted_storage::{self, fileIdOf};
+use crate::links;
+use crate::models::{Note, NoteFrontmatter, FloatWindow, ItemKind, ResolvedColor, TemplateType, normalizeColor};
+use crate::undo::UndoAction;
+use super::floating::closeItemFloatingWindow;
+use super::common::{BatchResult, newId, validateItemSize, sanitizeTitle};
+use super::asset::{copyNoteAssets, retargetAssetReferences};
+use super::item::{ItemContent, ItemList, ListOptions, readContentsBatch, collectSkippedItems, missingItemError, rewriteMetadata, setItemRank, listVersions, snapshotVersion, restoreVersion, moveVersionHistory, removeVersionHistory};
+use super::template::templateContentBySlug;
 
 #[derive(serde::Serialize)]
 pub struct NoteInfo {
@@ -15,39 +24,93 @@ pub struct NoteInfo {
     pub title: String,
     pub rank: u32,
     pub color: String,
+    pub resolvedColor: ResolvedColor,
     pub pinned: bool,
+    pub archived: bool,
     pub tags: Vec<String>,
     pub created: i64,
     pub updated: i64,
     pub folderPath: String,
     pub path: String,
     pub float: FloatWindow,
+    pub lastAccessed: Option<i64>,
+    pub openCount: u32,
 }
 
 impl From<&Note> for NoteInfo {
     fn from(n: &Note) -> Self {
-        // folderPath should be the parent folder, not the /notes subdirectory
-        // e.g., /folders/{uuid} instead of /folders/{uuid}/notes
-        let folderPath = n.folderPath.parent()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_default();
+        // Report the folder itself, not its /notes items dir (see itemsDir)
+        let folderPath = folderOfItemsDir(&n.folderPath).to_string_lossy().to_string();
 
         Self {
             id: n.frontmatter.id.clone(),
             title: n.frontmatter.title.clone(),
             rank: n.frontmatter.rank,
             color: n.frontmatter.color.clone(),
+            resolvedColor: ResolvedColor::of(&n.frontmatter.color),
             pinned: n.frontmatter.pinned,
+            archived: n.frontmatter.archived,
             tags: n.frontmatter.tags.clone(),
             created: n.frontmatter.created,
             updated: n.frontmatter.updated,
             folderPath,
             path: n.path.to_string_lossy().to_string(),
             float: n.frontmatter.float.clone(),
+            lastAccessed: n.frontmatter.lastAccessed,
+            openCount: n.frontmatter.openCount,
         }
     }
 }
 
+/// Process a single note file and return Note if valid
+fn processNoteFile(path: &PathBuf, folderPath: &Path, masterPassword: Option<&str>) -> Option<Note> {
+    let filename = path.file_name().and_then(|n| n.to_str())?;
+
+    // Validate filename is a UUID (with .md extension)
+    parseUuidFilename(filename)?;
+
+    let content = fs::read_to_string(path).ok()?;
+
+    // Check if file is encrypted
+    if encrypted_storage::isEncryptedFormat(&content) {
+        let password = masterPassword?;
+        let encrypted = encrypted_storage::parseEncryptedFile(&content, &fileIdOf(path)).ok()?;
+        let yamlContent = encrypted.decryptMetadata(password).ok()?;
+        let fm: NoteFrontmatter = serde_yaml::from_str(&yamlContent).ok()?;
+
+        // Don't decrypt content here - it will be decrypted on demand
+        Some(Note {
+            path: path.clone(),
+            folderPath: folderPath.to_path_buf(),
+            frontmatter: fm,
+            content: String::new(), // Content loaded on demand
+        })
+    } else {
+        // Legacy unencrypted format
+        let (fm, body) = parseFrontmatter::<NoteFrontmatter>(&content)?;
+        Some(Note {
+            path: path.clone(),
+            folderPath: folderPath.to_path_buf(),
+            frontmatter: fm,
+            content: body,
+        })
+    }
+}
+
+/// Find a live note by id: the index points at its file, a full scan is the fallback
+pub(crate) fn findNoteById(storage: &StorageState, wsPath: &str, id: &str, masterPassword: Option<&str>) -> Option<Note> {
+    if let Some(path) = storage.indexedPath(ItemKind::Note, id) {
+        let notesDirPath = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        if let Some(note) = processNoteFile(&path, &notesDirPath, masterPassword).filter(|n| n.frontmatter.id == id) {
+            return Some(note);
+        }
+    }
+
+    let note = scanAllNotes(&foldersDir(wsPath), masterPassword).into_iter().find(|n| n.frontmatter.id == id)?;
+    storage.indexItem(ItemKind::Note, id, &note.path);
+    Some(note)
+}
+
 /// Scan notes from a directory (non-recursive within folder, but called per folder)
 /// When masterPassword is provided, decrypts encrypted files
 pub(crate) fn scanNotesInFolder(folderPath: &PathBuf, masterPassword: Option<&str>) -> Vec<Note> {
@@ -64,47 +127,14 @@ pub(crate) fn scanNotesInFolder(folderPath: &PathBuf, masterPassword: Option<&st
         .filter(|e| {
             e.path().is_file() &&
             e.path().extension().map(|ext| ext == "md").unwrap_or(false) &&
-            !e.file_name().to_string_lossy().starts_with('.')
+            !e.file_name().to_string_lossy().starts_with('.') &&
+            !isHistoryPath(&e.path())
         })
         .collect();
 
     for entry in entries {
-        let path = entry.path();
-        let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-        // Validate filename is a UUID (with .md extension)
-        if parseUuidFilename(filename).is_some() {
-            if let Ok(content) = fs::read_to_string(&path) {
-                // Check if file is encrypted
-                if encrypted_storage::isEncryptedFormat(&content) {
-                    // Need master password to decrypt
-                    if let Some(password) = masterPassword {
-                        if let Ok(encrypted) = encrypted_storage::parseEncryptedFile(&content) {
-                            if let Ok(yamlContent) = encrypted_storage::decryptMetadata(&encrypted.metadata, password) {
-                                if let Ok(fm) = serde_yaml::from_str::<NoteFrontmatter>(&yamlContent) {
-                                    // Don't decrypt content here - it will be decrypted on demand
-                                    notes.push(Note {
-                                        path: path.clone(),
-                                        folderPath: folderPath.clone(),
-                                        frontmatter: fm,
-                                        content: String::new(), // Content loaded on demand
-                                    });
-                                }
-                            }
-                        }
-                    }
-                } else {
-                    // Legacy unencrypted format
-                    if let Some((fm, body)) = parseFrontmatter::<NoteFrontmatter>(&content) {
-                        notes.push(Note {
-                            path: path.clone(),
-                            folderPath: folderPath.clone(),
-                            frontmatter: fm,
-                            content: body,
-                        });
-                    }
-                }
-            }
+        if let Some(note) = processNoteFile(&entry.path(), folderPath, masterPassword) {
+            notes.push(note);
         }
     }
 
@@ -119,7 +149,7 @@ pub(crate) fn scanAllNotes(foldersBaseDir: &PathBuf, masterPassword: Option<&str
     let mut allNotes = Vec::new();
 
     // Notes in root /folders/notes/
-    let rootNotesDir = foldersBaseDir.join("notes");
+    let rootNotesDir = itemsDir(foldersBaseDir, ItemKind::Note);
     if rootNotesDir.exists() {
         allNotes.extend(scanNotesInFolder(&rootNotesDir, masterPassword));
     }
@@ -144,7 +174,7 @@ fn scanNotesInFoldersRecursive(dir: &PathBuf, notes: &mut Vec<Note>, masterPassw
 
             if path.is_dir() {
                 // Check if this folder has a notes subdirectory
-                let notesSubdir = path.join("notes");
+                let notesSubdir = itemsDir(&path, ItemKind::Note);
                 if notesSubdir.exists() && notesSubdir.is_dir() {
                     notes.extend(scanNotesInFolder(&notesSubdir, masterPassword));
                 }
@@ -157,42 +187,47 @@ fn scanNotesInFoldersRecursive(dir: &PathBuf, notes: &mut Vec<Note>, masterPassw
 }
 
 #[tauri::command]
-pub fn getNotes(storage: State<'_, StorageState>, folderPath: Option<String>) -> Result<Vec<NoteInfo>, String> {
-    println!("[getNotes] Called with folderPath: {:?}", folderPath);
-
-    let wsPath = match storage.getWorkspacePath() {
+pub fn getNotes(
+    storage: State<'_, StorageState>,
+    folderPath: Option<String>,
+    options: Option<ListOptions>,
+) -> Result<ItemList<NoteInfo>, ClaudiaError> {
+    let ListOptions { includeErrors, tags, tagMatch, includeArchived, sort, pinnedFirst, offset, limit } = options.unwrap_or_default();
+    println!("[getNotes] Called with folderPath: {:?}, includeErrors: {:?}, tags: {:?}", folderPath, includeErrors, tags);
+
+    let wsPath = match storage.getAvailableWorkspacePath()? {
         Some(p) => {
             println!("[getNotes] Workspace path: {}", p);
             p
         },
         None => {
             println!("[getNotes] No workspace path, returning empty");
-            return Ok(Vec::new());
+            return Ok(ItemList::Items(Vec::new()));
         }
     };
 
     // Check if vault is unlocked
     if !storage.isUnlocked() {
         println!("[getNotes] Vault is locked");
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     // Get master password for decryption
     let masterPassword = storage.getMasterPassword();
     let passwordRef = masterPassword.as_deref();
 
-    let notes = match &folderPath {
-        Some(fp) if !fp.is_empty() => {
+    let (notes, scanRoot) = match folderArg(folderPath.as_deref()) {
+        Some(folder) => {
             // Scan the notes subdirectory within the specified folder
-            let notesSubdir = PathBuf::from(fp).join("notes");
+            let notesSubdir = itemsDir(folder, ItemKind::Note);
             println!("[getNotes] Scanning folder's notes dir: {:?}", notesSubdir);
-            scanNotesInFolder(&notesSubdir, passwordRef)
+            (scanNotesInFolder(&notesSubdir, passwordRef), notesSubdir)
         },
-        _ => {
+        None => {
             // Scan all notes across all folders
             let foldersBase = foldersDir(&wsPath);
             println!("[getNotes] Scanning all folders: {:?}", foldersBase);
-            scanAllNotes(&foldersBase, passwordRef)
+            (scanAllNotes(&foldersBase, passwordRef), foldersBase)
         }
     };
 
@@ -201,28 +236,48 @@ pub fn getNotes(storage: State<'_, StorageState>, folderPath: Option<String>) ->
         println!("[getNotes]   - {} (id: {}, path: {})", n.frontmatter.title, n.frontmatter.id, n.path.display());
     }
 
+    let skipped = includeErrors.unwrap_or(false).then(|| {
+        let loaded: Vec<&Path> = notes.iter().map(|n| n.path.as_path()).collect();
+        collectSkippedItems::<NoteFrontmatter>(&scanRoot, ItemKind::Note, &loaded, passwordRef)
+    });
+
+    // Filter by tags if provided (scan order, i.e. rank, is kept)
+    let tags = tags.unwrap_or_default();
+    let tagMatch = tagMatch.unwrap_or_default();
+    let includeArchived = includeArchived.unwrap_or(false);
+    let mut notes: Vec<_> = notes.into_iter()
+        .filter(|n| tagMatch.matches(&n.frontmatter.tags, &tags))
+        .filter(|n| includeArchived || !n.frontmatter.archived)
+        .collect();
+
+    // Without sort options the scan order (rank within each folder) is kept,
+    // unless paging: pages need an order that can't shift between calls
+    let pinnedFirst = pinnedFirst.unwrap_or(false);
+    if sort.is_some() || pinnedFirst || offset.is_some() || limit.is_some() {
+        sort.unwrap_or_default().sort(&mut notes, pinnedFirst, |n| &n.frontmatter);
+    }
+
     // Update activity to reset auto-lock timer
     storage.updateActivity();
 
-    Ok(notes.iter().map(NoteInfo::from).collect())
+    Ok(ItemList::paged(notes.iter().map(NoteInfo::from).collect(), skipped, offset, limit))
 }
 
 
 #[tauri::command]
-pub fn getNoteById(storage: State<'_, StorageState>, id: String) -> Result<Option<NoteInfo>, String> {
+pub fn getNoteById(storage: State<'_, StorageState>, id: String) -> Result<Option<NoteInfo>, ClaudiaError> {
     println!("[getNoteById] Called with id: {}", id);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
     let passwordRef = masterPassword.as_deref();
 
-    let notes = scanAllNotes(&foldersDir(&wsPath), passwordRef);
-    let result = notes.iter().find(|n| n.frontmatter.id == id).map(NoteInfo::from);
+    let result = findNoteById(&storage, &wsPath, &id, passwordRef).as_ref().map(NoteInfo::from);
 
     if result.is_some() {
         println!("[getNoteById] Found note");
@@ -235,16 +290,17 @@ pub fn getNoteById(storage: State<'_, StorageState>, id: String) -> Result<Optio
 }
 
 #[tauri::command]
-pub fn getNoteContent(storage: State<'_, StorageState>, id: String) -> Result<String, String> {
+pub fn getNoteContent(storage: State<'_, StorageState>, id: String, sessionToken: Option<String>) -> Result<String, ClaudiaError> {
     println!("[getNoteContent] Called with id: {}", id);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
+    storage.checkSessionToken(sessionToken.as_deref())?;
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
     // Search in regular folders first
     let notes = scanAllNotes(&foldersDir(&wsPath), Some(&masterPassword));
@@ -258,27 +314,51 @@ pub fn getNoteContent(storage: State<'_, StorageState>, id: String) -> Result<St
         let trashNotesPath = trashNotesDir(&wsPath);
         let trashNotes = scanNotesInFolder(&trashNotesPath, Some(&masterPassword));
         trashNote = trashNotes.into_iter().find(|n| n.frontmatter.id == id)
-            .ok_or_else(|| "Note not found".to_string())?;
+            .ok_or_else(|| missingItemError(&storage, ItemKind::Note, &id))?;
         &trashNote
     };
 
-    // Read file and decrypt content
-    let fileContent = fs::read_to_string(&note.path)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+    // Read file and decrypt content (legacy unencrypted notes keep their parsed body)
+    let content = encrypted_storage::readDecryptedBody(&note.path, &masterPassword)?
+        .unwrap_or_else(|| note.content.clone());
 
-    let content = if encrypted_storage::isEncryptedFormat(&fileContent) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-        encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
-    } else {
-        // Legacy unencrypted format
-        note.content.clone()
-    };
+    if storage.effectiveSettings().trackAccess {
+        let mut fm = note.frontmatter.clone();
+        fm.lastAccessed = Some(chrono::Utc::now().timestamp_millis());
+        fm.openCount = fm.openCount.saturating_add(1);
+        let fileContent = encrypted_storage::serializeAndEncrypt(&fm, &content, &masterPassword, &fileIdOf(&note.path))?;
+        writeFileAtomic(&note.path, fileContent)?;
+    }
 
     println!("[getNoteContent] Found content ({} bytes)", content.len());
     storage.updateActivity();
     Ok(content)
 }
 
+/// Batch decrypt the bodies of several notes with a single scan
+/// Unlike getNoteContent, this does not count as opening the notes (no access tracking).
+#[tauri::command]
+pub fn getNoteContentsBatch(storage: State<'_, StorageState>, ids: Vec<String>) -> Result<Vec<ItemContent>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    // Scan all notes once
+    let notes = scanAllNotes(&foldersDir(&wsPath), Some(&masterPassword));
+    let results = readContentsBatch(
+        ids,
+        notes.iter().map(|n| (n.frontmatter.id.as_str(), n.path.as_path(), n.content.as_str())),
+        &masterPassword,
+    )?;
+
+    storage.updateActivity();
+    Ok(results)
+}
+
 #[derive(serde::Deserialize)]
 pub struct CreateNoteInput {
     pub title: String,
@@ -289,31 +369,30 @@ pub struct CreateNoteInput {
 }
 
 #[tauri::command]
-pub fn createNote(storage: State<'_, StorageState>, input: CreateNoteInput) -> Result<NoteInfo, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace selected")?;
+pub fn createNote(storage: State<'_, StorageState>, input: CreateNoteInput) -> Result<NoteInfo, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    // Reject oversized bodies before doing any decryption/encryption work
+    if let Some(ref content) = input.content {
+        validateItemSize(content, storage.effectiveSettings().maxItemBytes)?;
+    }
 
     println!("[createNote] Received folderPath: {:?}", input.folderPath);
     println!("[createNote] Workspace path: {}", wsPath);
 
     // If folderPath is provided, create notes in folderPath/notes/
     // Otherwise use the root workspace/folders/notes/
-    let folderPath = match &input.folderPath {
-        Some(p) if !p.is_empty() && p != "null" && p.starts_with('/') => {
-            // Create notes in the folder's notes subdirectory
-            PathBuf::from(p).join("notes")
-        }
-        _ => notesDir(&wsPath, ""),
-    };
+    let folderPath = itemsDirFor(&wsPath, input.folderPath.as_deref(), ItemKind::Note)?;
 
     println!("[createNote] Using folderPath: {:?}", folderPath);
 
-    fs::create_dir_all(&folderPath).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&folderPath)?;
 
     // Find next rank from existing notes
     let existingNotes = scanNotesInFolder(&folderPath, Some(&masterPassword));
@@ -324,9 +403,9 @@ pub fn createNote(storage: State<'_, StorageState>, input: CreateNoteInput) -> R
     let filename = uuidFilename(&id);
     let notePath = folderPath.join(&filename);
 
-    let mut fm = NoteFrontmatter::new(id, input.title.clone(), nextRank);
+    let mut fm = NoteFrontmatter::new(id, sanitizeTitle(&input.title, storage.effectiveSettings().emojiShortcodes), nextRank);
     if let Some(color) = input.color {
-        fm.color = color;
+        fm.color = normalizeColor(&color)?;
     }
     if let Some(tags) = input.tags {
         fm.tags = tags;
@@ -335,8 +414,9 @@ pub fn createNote(storage: State<'_, StorageState>, input: CreateNoteInput) -> R
     let body = input.content.unwrap_or_default();
 
     // Encrypt and save
-    let fileContent = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
-    fs::write(&notePath, fileContent).map_err(|e| e.to_string())?;
+    let fileContent = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&notePath))?;
+    writeFileAtomic(&notePath, fileContent)?;
+    storage.indexItem(ItemKind::Note, &fm.id, &notePath);
 
     let note = Note {
         path: notePath,
@@ -349,6 +429,55 @@ pub fn createNote(storage: State<'_, StorageState>, input: CreateNoteInput) -> R
     Ok(NoteInfo::from(&note))
 }
 
+/// Copy a note into its own folder, titled "... (copy)" and ranked right after the original
+#[tauri::command]
+pub fn duplicateNote(storage: State<'_, StorageState>, id: String) -> Result<NoteInfo, ClaudiaError> {
+    println!("[duplicateNote] Called with id: {}", id);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let original = findNoteById(&storage, &wsPath, &id, Some(&masterPassword)).ok_or_else(|| ClaudiaError::NotFound("Note not found".to_string()))?;
+    let body = encrypted_storage::readDecryptedBody(&original.path, &masterPassword)?.unwrap_or_default();
+
+    // Make room right after the original
+    for sibling in scanNotesInFolder(&original.folderPath, Some(&masterPassword)) {
+        if sibling.frontmatter.rank > original.frontmatter.rank {
+            setItemRank(&sibling.path, sibling.frontmatter.rank + 1, &masterPassword)?;
+        }
+    }
+
+    let copyId = newId();
+    let notePath = original.folderPath.join(uuidFilename(&copyId));
+    let mut fm = NoteFrontmatter::new(copyId.clone(), format!("{} (copy)", original.frontmatter.title), original.frontmatter.rank + 1);
+    fm.color = original.frontmatter.color.clone();
+    fm.pinned = original.frontmatter.pinned;
+    fm.tags = original.frontmatter.tags.clone();
+
+    copyNoteAssets(&wsPath, &original.frontmatter.id, &copyId)?;
+    let body = retargetAssetReferences(&body, &original.frontmatter.id, &copyId);
+
+    let fileContent = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&notePath))?;
+    writeFileAtomic(&notePath, fileContent)?;
+    storage.indexItem(ItemKind::Note, &copyId, &notePath);
+
+    let note = Note {
+        path: notePath,
+        folderPath: original.folderPath,
+        frontmatter: fm,
+        content: body,
+    };
+
+    storage.updateActivity();
+    println!("[duplicateNote] SUCCESS - copy id: {}", copyId);
+    Ok(NoteInfo::from(&note))
+}
+
 #[derive(serde::Deserialize)]
 pub struct UpdateNoteInput {
     pub id: String,
@@ -361,7 +490,7 @@ pub struct UpdateNoteInput {
 }
 
 #[tauri::command]
-pub fn updateNote(storage: State<'_, StorageState>, input: UpdateNoteInput) -> Result<(), String> {
+pub fn updateNote(storage: State<'_, StorageState>, input: UpdateNoteInput) -> Result<(), ClaudiaError> {
     println!("[updateNote] Called with id: {}", input.id);
     println!("[updateNote] Updates - title: {:?}, content: {:?}, color: {:?}, pinned: {:?}",
              input.title.as_ref().map(|_| "[set]"),
@@ -369,13 +498,18 @@ pub fn updateNote(storage: State<'_, StorageState>, input: UpdateNoteInput) -> R
              input.color,
              input.pinned);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    // Reject oversized bodies before doing any decryption/encryption work
+    if let Some(ref content) = input.content {
+        validateItemSize(content, storage.effectiveSettings().maxItemBytes)?;
+    }
 
     // Search in regular folders first
     let notes = scanAllNotes(&foldersDir(&wsPath), Some(&masterPassword));
@@ -389,7 +523,7 @@ pub fn updateNote(storage: State<'_, StorageState>, input: UpdateNoteInput) -> R
         let trashNotesPath = trashNotesDir(&wsPath);
         let trashNotes = scanNotesInFolder(&trashNotesPath, Some(&masterPassword));
         trashNote = trashNotes.into_iter().find(|n| n.frontmatter.id == input.id)
-            .ok_or("Note not found")?;
+            .ok_or_else(|| ClaudiaError::NotFound("Note not found".to_string()))?;
         &trashNote
     };
     println!("[updateNote] Found note at: {}", note.path.display());
@@ -401,8 +535,8 @@ pub fn updateNote(storage: State<'_, StorageState>, input: UpdateNoteInput) -> R
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
     let mut body = if encrypted_storage::isEncryptedFormat(&fileContent) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-        encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
+        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&note.path))?;
+        encrypted.decryptContent(&masterPassword)?
     } else {
         note.content.clone()
     };
@@ -410,7 +544,7 @@ pub fn updateNote(storage: State<'_, StorageState>, input: UpdateNoteInput) -> R
     // Handle title change (filename no longer changes with title)
     if let Some(ref title) = input.title {
         println!("[updateNote] Updating title to: {}", title);
-        fm.title = title.clone();
+        fm.title = sanitizeTitle(title, storage.effectiveSettings().emojiShortcodes);
     }
     if let Some(content) = input.content {
         println!("[updateNote] Updating content ({} bytes)", content.len());
@@ -418,7 +552,7 @@ pub fn updateNote(storage: State<'_, StorageState>, input: UpdateNoteInput) -> R
     }
     if let Some(color) = input.color {
         println!("[updateNote] Updating color to: {}", color);
-        fm.color = color;
+        fm.color = normalizeColor(&color)?;
     }
     if let Some(pinned) = input.pinned {
         println!("[updateNote] Updating pinned to: {}", pinned);
@@ -435,26 +569,140 @@ pub fn updateNote(storage: State<'_, StorageState>, input: UpdateNoteInput) -> R
 
     fm.updated = chrono::Utc::now().timestamp_millis();
 
+    snapshotVersion(&note.folderPath, &note.path, &fm.id, storage.effectiveSettings().historyVersions)?;
+
     // Encrypt and save
-    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
-    fs::write(&note.path, content).map_err(|e| {
+    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&note.path))?;
+    writeFileAtomic(&note.path, content).map_err(|e| {
         println!("[updateNote] ERROR writing file: {}", e);
         e.to_string()
     })?;
 
+    storage.invalidateLinks(&fm.id);
     println!("[updateNote] SUCCESS");
     storage.updateActivity();
     Ok(())
 }
 
+/// Template used for newly created daily notes
+const DAILY_NOTE_TEMPLATE: &str = "daily-journal";
+
+/// Find today's daily note in the configured folder, creating it from the journal template if missing
+/// The title is today's date rendered with the `dateFormat` setting
+#[tauri::command]
+pub fn getOrCreateDailyNote(storage: State<'_, StorageState>) -> Result<NoteInfo, ClaudiaError> {
+    println!("[getOrCreateDailyNote] Called");
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+    let settings = storage.effectiveSettings();
+
+    let items: Vec<Item> = StrftimeItems::new(&settings.dateFormat).collect();
+    if items.iter().any(|i| matches!(i, Item::Error)) {
+        return Err(ClaudiaError::InvalidInput(format!("Invalid input: date format '{}' is not valid", settings.dateFormat)));
+    }
+    let title = chrono::Local::now().format_with_items(items.into_iter()).to_string();
+
+    let (notesPath, folderPath) = if settings.dailyNotesFolder.is_empty() {
+        (notesDir(&wsPath, ""), None)
+    } else {
+        let folder = PathBuf::from(&settings.dailyNotesFolder);
+        if !folder.join(".folder.md").exists() {
+            return Err(ClaudiaError::NotFound(format!("Daily notes folder not found: {}", settings.dailyNotesFolder)));
+        }
+        (itemsDir(&folder, ItemKind::Note), Some(settings.dailyNotesFolder.clone()))
+    };
+
+    if let Some(existing) = scanNotesInFolder(&notesPath, Some(&masterPassword))
+        .iter()
+        .find(|n| n.frontmatter.title == title)
+    {
+        println!("[getOrCreateDailyNote] Found existing note: {}", existing.frontmatter.id);
+        storage.updateActivity();
+        return Ok(NoteInfo::from(existing));
+    }
+
+    println!("[getOrCreateDailyNote] Creating daily note: {}", title);
+    createNote(storage, CreateNoteInput {
+        title,
+        folderPath,
+        content: templateContentBySlug(TemplateType::Note, DAILY_NOTE_TEMPLATE),
+        color: None,
+        tags: None,
+    })
+}
+
+/// Pin or unpin a note without touching its body
 #[tauri::command]
-pub fn deleteNote(storage: State<'_, StorageState>, id: String, permanent: Option<bool>) -> Result<(), String> {
+pub fn setNotePinned(storage: State<'_, StorageState>, id: String, pinned: bool) -> Result<bool, ClaudiaError> {
+    println!("[setNotePinned] Called with id: {}, pinned: {}", id, pinned);
+
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let path = storage.itemPath(ItemKind::Note, &id).ok_or_else(|| ClaudiaError::NotFound("Note not found".to_string()))?;
+    let fm = rewriteMetadata(&path, &masterPassword, |fm: &mut NoteFrontmatter| {
+        fm.pinned = pinned;
+    })?;
+
+    storage.updateActivity();
+    Ok(fm.pinned)
+}
+
+/// Hide a note from listings without trashing it
+#[tauri::command]
+pub fn archiveNote(storage: State<'_, StorageState>, id: String) -> Result<(), ClaudiaError> {
+    setNoteArchived(&storage, &id, true)
+}
+
+#[tauri::command]
+pub fn unarchiveNote(storage: State<'_, StorageState>, id: String) -> Result<(), ClaudiaError> {
+    setNoteArchived(&storage, &id, false)
+}
+
+fn setNoteArchived(storage: &StorageState, id: &str, archived: bool) -> Result<(), ClaudiaError> {
+    println!("[setNoteArchived] Called with id: {}, archived: {}", id, archived);
+
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let path = storage.itemPath(ItemKind::Note, id).ok_or_else(|| ClaudiaError::NotFound("Note not found".to_string()))?;
+    rewriteMetadata(&path, &masterPassword, |fm: &mut NoteFrontmatter| {
+        fm.archived = archived;
+    })?;
+
+    storage.updateActivity();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn deleteNote(
+    app: tauri::AppHandle,
+    storage: State<'_, StorageState>,
+    id: String,
+    permanent: Option<bool>,
+) -> Result<(), ClaudiaError> {
     println!("[deleteNote] Called with id: {}, permanent: {:?}", id, permanent);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
@@ -475,23 +723,47 @@ pub fn deleteNote(storage: State<'_, StorageState>, id: String, permanent: Optio
         let trashNotesPath = trashNotesDir(&wsPath);
         let trashNotes = scanNotesInFolder(&trashNotesPath, passwordRef);
         trashNote = trashNotes.into_iter().find(|n| n.frontmatter.id == id)
-            .ok_or("Note not found")?;
+            .ok_or_else(|| ClaudiaError::NotFound("Note not found".to_string()))?;
         isInTrash = true;
         &trashNote
     };
     println!("[deleteNote] Found note at: {} (in trash: {})", note.path.display(), isInTrash);
 
     // If item is in trash, always permanently delete
-    if permanent.unwrap_or(false) || isInTrash {
-        // Permanent delete
+    let permanent = permanent.unwrap_or(false) || isInTrash;
+    removeNoteFile(&wsPath, note, permanent)?;
+    storage.unindexItem(&id);
+    if !permanent {
+        storage.recordUndo("deleteNote", vec![UndoAction::MoveBack {
+            kind: ItemKind::Note,
+            id: id.clone(),
+            current: trashNotesDir(&wsPath).join(uuidFilename(&id)),
+            original: note.path.clone(),
+            rank: note.frontmatter.rank,
+        }]);
+    }
+
+    // The item is gone from its folder, so a floating window would only show a ghost
+    closeItemFloatingWindow(&app, "note", &id);
+
+    storage.updateActivity();
+    Ok(())
+}
+
+/// Move a note file to trash, or remove it (and its assets) when `permanent`
+pub(crate) fn removeNoteFile(wsPath: &str, note: &Note, permanent: bool) -> Result<(), String> {
+    if permanent {
         fs::remove_file(&note.path).map_err(|e| {
             println!("[deleteNote] ERROR: {}", e);
             e.to_string()
         })?;
+        // Assets and history are only reachable through the note
+        let _ = fs::remove_dir_all(noteAssetsDir(wsPath, &note.frontmatter.id));
+        removeVersionHistory(&note.folderPath, &note.frontmatter.id);
+        forgetTrashOrigin(wsPath, &note.frontmatter.id);
         println!("[deleteNote] SUCCESS - permanently deleted");
     } else {
-        // Move to trash
-        let trashDir = trashNotesDir(&wsPath);
+        let trashDir = trashNotesDir(wsPath);
         fs::create_dir_all(&trashDir).map_err(|e| e.to_string())?;
 
         let trashPath = trashDir.join(note.path.file_name().ok_or("Invalid file name")?);
@@ -499,11 +771,54 @@ pub fn deleteNote(storage: State<'_, StorageState>, id: String, permanent: Optio
             println!("[deleteNote] ERROR moving to trash: {}", e);
             e.to_string()
         })?;
+        moveVersionHistory(&note.folderPath, &trashDir, &note.frontmatter.id);
+        recordTrashOrigin(wsPath, &note.frontmatter.id, &note.folderPath);
         println!("[deleteNote] SUCCESS - moved to trash at: {}", trashPath.display());
     }
+    Ok(())
+}
 
+/// Delete many notes with a single scan; notes already in trash are always removed permanently
+#[tauri::command]
+pub fn deleteNotesBatch(
+    app: tauri::AppHandle,
+    storage: State<'_, StorageState>,
+    ids: Vec<String>,
+    permanent: Option<bool>,
+) -> Result<Vec<BatchResult>, ClaudiaError> {
+    println!("[deleteNotesBatch] Called with {} ids, permanent: {:?}", ids.len(), permanent);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword();
+    let passwordRef = masterPassword.as_deref();
+
+    let notes = scanAllNotes(&foldersDir(&wsPath), passwordRef);
+    let trashNotes = scanNotesInFolder(&trashNotesDir(&wsPath), passwordRef);
+    let permanent = permanent.unwrap_or(false);
+
+    let results: Vec<BatchResult> = ids.into_iter().map(|id| {
+        let result = if let Some(note) = notes.iter().find(|n| n.frontmatter.id == id) {
+            removeNoteFile(&wsPath, note, permanent)
+        } else if let Some(note) = trashNotes.iter().find(|n| n.frontmatter.id == id) {
+            removeNoteFile(&wsPath, note, true)
+        } else {
+            Err("Note not found".to_string())
+        };
+        if result.is_ok() {
+            storage.unindexItem(&id);
+            closeItemFloatingWindow(&app, "note", &id);
+        }
+        BatchResult::new(id, result)
+    }).collect();
+
+    println!("[deleteNotesBatch] Deleted {} of {}", results.iter().filter(|r| r.success).count(), results.len());
     storage.updateActivity();
-    Ok(())
+    Ok(results)
 }
 
 #[derive(serde::Deserialize)]
@@ -513,31 +828,30 @@ pub struct ReorderNotesInput {
 }
 
 #[tauri::command]
-pub fn reorderNotes(storage: State<'_, StorageState>, input: ReorderNotesInput) -> Result<(), String> {
+pub fn reorderNotes(storage: State<'_, StorageState>, input: ReorderNotesInput) -> Result<(), ClaudiaError> {
     println!("[reorderNotes] Called with folderPath: {}", input.folderPath);
     println!("[reorderNotes] Note IDs to reorder: {:?}", input.noteIds);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
     // Determine the actual notes directory
     // If folderPath is provided, notes are in {folderPath}/notes/
     // If empty, notes are in the root notes folder
-    let notesDirPath = if input.folderPath.is_empty() {
-        notesDir(&wsPath, "")
-    } else {
-        PathBuf::from(&input.folderPath).join("notes")
-    };
+    let notesDirPath = itemsDirFor(&wsPath, Some(&input.folderPath), ItemKind::Note)?;
 
     println!("[reorderNotes] Scanning notes in: {:?}", notesDirPath);
     let notes = scanNotesInFolder(&notesDirPath, Some(&masterPassword));
     println!("[reorderNotes] Found {} notes", notes.len());
 
+    // Ranks before the reorder, for undo
+    let mut previousRanks = Vec::new();
+
     // Update rank in frontmatter instead of renaming files
     for (index, noteId) in input.noteIds.iter().enumerate() {
         if let Some(note) = notes.iter().find(|n| n.frontmatter.id == *noteId) {
@@ -554,36 +868,38 @@ pub fn reorderNotes(storage: State<'_, StorageState>, input: ReorderNotesInput)
                     .map_err(|e| format!("Failed to read file: {}", e))?;
 
                 let body = if encrypted_storage::isEncryptedFormat(&fileContent) {
-                    let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-                    encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
+                    let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&note.path))?;
+                    encrypted.decryptContent(&masterPassword)?
                 } else {
                     note.content.clone()
                 };
 
-                let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
-                fs::write(&note.path, content).map_err(|e| {
+                let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&note.path))?;
+                writeFileAtomic(&note.path, content).map_err(|e| {
                     println!("[reorderNotes] ERROR: {}", e);
                     e.to_string()
                 })?;
+                previousRanks.push((note.path.clone(), note.frontmatter.rank));
             }
         }
     }
+    storage.recordUndo("reorderNotes", vec![UndoAction::RestoreRanks { ranks: previousRanks }]);
     println!("[reorderNotes] SUCCESS");
     storage.updateActivity();
     Ok(())
 }
 
 #[tauri::command]
-pub fn moveNoteToFolder(storage: State<'_, StorageState>, id: String, targetFolderPath: String) -> Result<NoteInfo, String> {
+pub fn moveNoteToFolder(storage: State<'_, StorageState>, id: String, targetFolderPath: String) -> Result<NoteInfo, ClaudiaError> {
     println!("[moveNoteToFolder] Called with id: {}, targetFolderPath: {}", id, targetFolderPath);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
     // Search in regular folders first
     let notes = scanAllNotes(&foldersDir(&wsPath), Some(&masterPassword));
@@ -597,42 +913,61 @@ pub fn moveNoteToFolder(storage: State<'_, StorageState>, id: String, targetFold
         let trashNotesPath = trashNotesDir(&wsPath);
         let trashNotes = scanNotesInFolder(&trashNotesPath, Some(&masterPassword));
         trashNote = trashNotes.into_iter().find(|n| n.frontmatter.id == id)
-            .ok_or("Note not found")?;
+            .ok_or_else(|| ClaudiaError::NotFound("Note not found".to_string()))?;
         &trashNote
     };
     println!("[moveNoteToFolder] Found note at: {}", note.path.display());
 
     // Target is the notes subdirectory within the folder
-    let targetNotesDir = PathBuf::from(&targetFolderPath).join("notes");
+    let targetNotesDir = itemsDir(Path::new(&targetFolderPath), ItemKind::Note);
 
     // Create target folder if it doesn't exist
-    fs::create_dir_all(&targetNotesDir).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&targetNotesDir)?;
 
     // Find next rank in target folder
     let existingNotes = scanNotesInFolder(&targetNotesDir, Some(&masterPassword));
     let nextRank = existingNotes.iter().map(|n| n.frontmatter.rank).max().unwrap_or(0) + 1;
 
+    let movedNote = relocateNote(note, &targetNotesDir, nextRank, &masterPassword)?;
+    storage.indexItem(ItemKind::Note, &id, &movedNote.path);
+    if movedNote.path != note.path {
+        storage.recordUndo("moveNoteToFolder", vec![UndoAction::MoveBack {
+            kind: ItemKind::Note,
+            id: id.clone(),
+            current: movedNote.path.clone(),
+            original: note.path.clone(),
+            rank: note.frontmatter.rank,
+        }]);
+    }
+
+    println!("[moveNoteToFolder] SUCCESS");
+    storage.updateActivity();
+    Ok(NoteInfo::from(&movedNote))
+}
+
+/// Re-encrypt a note into `targetNotesDir` with a new rank and remove the old file
+fn relocateNote(note: &Note, targetNotesDir: &Path, rank: u32, masterPassword: &str) -> Result<Note, String> {
     // Same UUID filename, new location
     let newPath = targetNotesDir.join(uuidFilename(&note.frontmatter.id));
 
     // Update frontmatter with new rank
     let mut fm = note.frontmatter.clone();
-    fm.rank = nextRank;
+    fm.rank = rank;
 
     // Get content from file
     let fileContent = fs::read_to_string(&note.path)
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
     let body = if encrypted_storage::isEncryptedFormat(&fileContent) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-        encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
+        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&note.path))?;
+        encrypted.decryptContent(masterPassword)?
     } else {
         note.content.clone()
     };
 
     // Encrypt and write to new location
-    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
-    fs::write(&newPath, &content).map_err(|e| e.to_string())?;
+    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, masterPassword, &fileIdOf(&newPath))?;
+    writeFileAtomic(&newPath, &content).map_err(|e| e.to_string())?;
 
     // Remove old file
     fs::remove_file(&note.path).map_err(|e| {
@@ -640,17 +975,321 @@ pub fn moveNoteToFolder(storage: State<'_, StorageState>, id: String, targetFold
         e.to_string()
     })?;
 
+    moveVersionHistory(&note.folderPath, targetNotesDir, &note.frontmatter.id);
     println!("[moveNoteToFolder] Moved {} -> {}", note.path.display(), newPath.display());
 
-    // Build and return updated NoteInfo
-    let movedNote = Note {
+    Ok(Note {
         path: newPath,
-        folderPath: targetNotesDir,
+        folderPath: targetNotesDir.to_path_buf(),
         frontmatter: fm,
         content: body,
+    })
+}
+
+/// Move many notes into one folder with a single scan
+/// Notes keep their relative order and are appended after the target's existing notes;
+/// notes already in the target are returned unchanged
+#[tauri::command]
+pub fn moveNotesToFolder(storage: State<'_, StorageState>, ids: Vec<String>, targetFolderPath: String) -> Result<Vec<NoteInfo>, ClaudiaError> {
+    println!("[moveNotesToFolder] Called with {} ids, targetFolderPath: {}", ids.len(), targetFolderPath);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let targetFolder = validateMoveTarget(&wsPath, &targetFolderPath)?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let mut notes = scanAllNotes(&foldersDir(&wsPath), Some(&masterPassword));
+    notes.extend(scanNotesInFolder(&trashNotesDir(&wsPath), Some(&masterPassword)));
+
+    let targetNotesDir = itemsDir(targetFolder, ItemKind::Note);
+    fs::create_dir_all(&targetNotesDir)?;
+
+    // Next rank is computed once; each moved note takes the following one
+    let mut nextRank = notes.iter()
+        .filter(|n| n.folderPath == targetNotesDir)
+        .map(|n| n.frontmatter.rank)
+        .max()
+        .unwrap_or(0) + 1;
+
+    // Resolve every id before touching any file
+    let sources = ids.iter()
+        .map(|id| notes.iter().find(|n| &n.frontmatter.id == id).ok_or_else(|| ClaudiaError::NotFound(format!("Note not found: {}", id))))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut moved = Vec::with_capacity(sources.len());
+    for note in sources {
+        if note.folderPath == targetNotesDir {
+            moved.push(NoteInfo::from(note));
+            continue;
+        }
+        let movedNote = relocateNote(note, &targetNotesDir, nextRank, &masterPassword)?;
+        storage.indexItem(ItemKind::Note, &movedNote.frontmatter.id, &movedNote.path);
+        nextRank += 1;
+        moved.push(NoteInfo::from(&movedNote));
+    }
+
+    println!("[moveNotesToFolder] SUCCESS - {} notes", moved.len());
+    storage.updateActivity();
+    Ok(moved)
+}
+
+#[tauri::command]
+pub fn mergeNotes(storage: State<'_, StorageState>, sourceId: String, targetId: String, separator: Option<String>) -> Result<NoteInfo, ClaudiaError> {
+    println!("[mergeNotes] Called with sourceId: {}, targetId: {}", sourceId, targetId);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    if sourceId == targetId {
+        return Err(ClaudiaError::InvalidInput("Cannot merge a note into itself".to_string()));
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    // Only notes outside the trash can be merged
+    let notes = scanAllNotes(&foldersDir(&wsPath), Some(&masterPassword));
+    let source = notes.iter().find(|n| n.frontmatter.id == sourceId)
+        .ok_or_else(|| ClaudiaError::NotFound("Source note not found".to_string()))?;
+    let target = notes.iter().find(|n| n.frontmatter.id == targetId)
+        .ok_or_else(|| ClaudiaError::NotFound("Target note not found".to_string()))?;
+
+    // Decrypt both bodies
+    let readBody = |note: &Note| -> Result<String, ClaudiaError> {
+        let fileContent = fs::read_to_string(&note.path)
+            .map_err(|e| ClaudiaError::Io(format!("Failed to read file: {}", e)))?;
+        if encrypted_storage::isEncryptedFormat(&fileContent) {
+            let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&note.path))?;
+            encrypted.decryptContent(&masterPassword)
+        } else {
+            Ok(note.content.clone())
+        }
     };
+    let sourceBody = readBody(source)?;
+    let targetBody = readBody(target)?;
+
+    let separator = separator.unwrap_or_default();
+    let body = format!("{}{}{}", targetBody, separator, sourceBody);
+    validateItemSize(&body, storage.effectiveSettings().maxItemBytes)?;
+
+    // Union tags, keeping the target's order first
+    let mut fm = target.frontmatter.clone();
+    for tag in &source.frontmatter.tags {
+        if !fm.tags.contains(tag) {
+            fm.tags.push(tag.clone());
+        }
+    }
+    fm.updated = chrono::Utc::now().timestamp_millis();
 
-    println!("[moveNoteToFolder] SUCCESS");
+    snapshotVersion(&target.folderPath, &target.path, &fm.id, storage.effectiveSettings().historyVersions)?;
+
+    // Write the target before touching the source so a failure never loses content
+    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&target.path))?;
+    writeFileAtomic(&target.path, content).map_err(|e| {
+        println!("[mergeNotes] ERROR writing target: {}", e);
+        e.to_string()
+    })?;
+
+    // Move the source to trash
+    let trashDir = trashNotesDir(&wsPath);
+    fs::create_dir_all(&trashDir)?;
+    let trashPath = trashDir.join(source.path.file_name().ok_or("Invalid file name")?);
+    fs::rename(&source.path, &trashPath).map_err(|e| {
+        println!("[mergeNotes] ERROR moving source to trash: {}", e);
+        e.to_string()
+    })?;
+    moveVersionHistory(&source.folderPath, &trashDir, &source.frontmatter.id);
+    storage.unindexItem(&source.frontmatter.id);
+    storage.invalidateLinks(&fm.id);
+
+    let merged = Note {
+        path: target.path.clone(),
+        folderPath: target.folderPath.clone(),
+        frontmatter: fm,
+        content: body,
+    };
+
+    println!("[mergeNotes] SUCCESS - merged {} into {}", sourceId, targetId);
     storage.updateActivity();
-    Ok(NoteInfo::from(&movedNote))
+    Ok(NoteInfo::from(&merged))
+}
+
+// ============================================
+// VERSION HISTORY
+// ============================================
+
+/// Saved versions of a note, newest first (timestamps in ms)
+#[tauri::command]
+pub fn getNoteHistory(storage: State<'_, StorageState>, id: String) -> Result<Vec<i64>, ClaudiaError> {
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let path = storage.itemPath(ItemKind::Note, &id)
+        .ok_or_else(|| missingItemError(&storage, ItemKind::Note, &id))?;
+    let notesDirPath = path.parent().ok_or("Invalid note path")?;
+
+    storage.updateActivity();
+    Ok(listVersions(notesDirPath, &id))
+}
+
+/// Replace a note with one of its saved versions; the replaced content is kept as a new version
+#[tauri::command]
+pub fn restoreNoteVersion(storage: State<'_, StorageState>, id: String, timestamp: i64) -> Result<(), ClaudiaError> {
+    println!("[restoreNoteVersion] Called with id: {}, timestamp: {}", id, timestamp);
+
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let path = storage.itemPath(ItemKind::Note, &id)
+        .ok_or_else(|| missingItemError(&storage, ItemKind::Note, &id))?;
+    let notesDirPath = path.parent().ok_or("Invalid note path")?;
+
+    restoreVersion(notesDirPath, &path, &id, timestamp, storage.effectiveSettings().historyVersions)?;
+    storage.invalidateLinks(&id);
+
+    println!("[restoreNoteVersion] SUCCESS");
+    storage.updateActivity();
+    Ok(())
+}
+
+// ============================================
+// BACKLINKS
+// ============================================
+
+/// `[[...]]` targets of a note, from the cache or by decrypting its body
+fn noteLinks(storage: &StorageState, wsPath: &str, note: &Note, masterPassword: &str) -> Vec<String> {
+    if let Some(cached) = storage.cachedLinks(wsPath, &note.frontmatter.id) {
+        return cached;
+    }
+    // Legacy plaintext notes are scanned with their body
+    let body = if note.content.is_empty() {
+        encrypted_storage::readDecryptedBody(&note.path, masterPassword).ok().flatten().unwrap_or_default()
+    } else {
+        note.content.clone()
+    };
+    let parsed = links::parseLinks(&body);
+    storage.cacheLinks(wsPath, &note.frontmatter.id, parsed.clone());
+    parsed
+}
+
+/// Notes linking to a note with `[[title]]` or `[[uuid]]`
+#[tauri::command]
+pub fn getBacklinks(storage: State<'_, StorageState>, id: String) -> Result<Vec<NoteInfo>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let notes = scanAllNotes(&foldersDir(&wsPath), Some(&masterPassword));
+    let linksOf: HashMap<String, Vec<String>> = notes.iter()
+        .map(|n| (n.frontmatter.id.clone(), noteLinks(&storage, &wsPath, n, &masterPassword)))
+        .collect();
+
+    let backlinks = links::backlinksTo(&id, &notes, &linksOf);
+    println!("[getBacklinks] {} notes link to {}", backlinks.len(), id);
+    storage.updateActivity();
+    Ok(backlinks.into_iter().map(NoteInfo::from).collect())
+}
+
+/// Note a `[[...]]` link points to: exact title first, then UUID
+#[tauri::command]
+pub fn resolveLink(storage: State<'_, StorageState>, text: String) -> Result<Option<NoteInfo>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword();
+
+    let notes = scanAllNotes(&foldersDir(&wsPath), masterPassword.as_deref());
+    storage.updateActivity();
+    Ok(links::resolveLink(&text, &notes).map(NoteInfo::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::toMarkdown;
+
+    /// Write a note into the folder given to createNote and read it back the way getNotes does
+    fn roundTripFolderPath(wsPath: &str, folderPath: Option<&str>) -> String {
+        let dir = itemsDirFor(wsPath, folderPath, ItemKind::Note).unwrap();
+        fs::create_dir_all(&dir).unwrap();
+        let fm = NoteFrontmatter::new(newId(), "Round trip".to_string(), 0);
+        fs::write(dir.join(uuidFilename(&fm.id)), toMarkdown(&fm, "body").unwrap()).unwrap();
+
+        let scanDir = match folderArg(folderPath) {
+            Some(folder) => itemsDir(folder, ItemKind::Note),
+            None => notesDir(wsPath, ""),
+        };
+        let notes = scanNotesInFolder(&scanDir, None);
+        assert_eq!(notes.len(), 1);
+        NoteInfo::from(&notes[0]).folderPath
+    }
+
+    #[test]
+    fn test_folder_path_round_trip() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+
+        let folder = foldersDir(&wsPath).join(newId());
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join(".folder.md"), "").unwrap();
+        let folderStr = folder.to_string_lossy().to_string();
+        assert_eq!(roundTripFolderPath(&wsPath, Some(&folderStr)), folderStr);
+
+        let root = foldersDir(&wsPath).to_string_lossy().to_string();
+        assert_eq!(roundTripFolderPath(&wsPath, None), root);
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_version_history_snapshot_and_restore() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let dir = notesDir(&ws.to_string_lossy(), "");
+        fs::create_dir_all(&dir).unwrap();
+        let id = newId();
+        let path = dir.join(uuidFilename(&id));
+
+        for version in ["v1", "v2", "v3", "v4"] {
+            snapshotVersion(&dir, &path, &id, 2).unwrap();
+            fs::write(&path, version).unwrap();
+        }
+
+        // The first write had nothing to snapshot, and only the newest two are kept
+        let versions = listVersions(&dir, &id);
+        assert_eq!(versions.len(), 2);
+        assert!(versions[0] > versions[1]);
+
+        // History files never show up as notes
+        fs::write(&path, toMarkdown(&NoteFrontmatter::new(id.clone(), "Current".to_string(), 0), "v4").unwrap()).unwrap();
+        assert_eq!(scanNotesInFolder(&dir, None).len(), 1);
+        assert_eq!(scanNotesInFolder(&dir.join(".history").join(&id), None).len(), 0);
+
+        // Restoring swaps: v2 comes back and the current file becomes a version
+        restoreVersion(&dir, &path, &id, versions[1], 2).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v2");
+        let after = listVersions(&dir, &id);
+        assert_eq!(after.len(), 2);
+        assert!(!after.contains(&versions[1]));
+
+        let _ = fs::remove_dir_all(&ws);
+    }
 }
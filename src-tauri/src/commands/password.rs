@@ -2,13 +2,20 @@
 // Both metadata and content are encrypted using CLAUDIA-ENCRYPTED-v1 format
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::State;
-
-use crate::storage::{StorageState, passwordsDir, foldersDir, parseUuidFilename, uuidFilename, trashPasswordsDir};
-use crate::encrypted_storage;
-use crate::models::{Password, PasswordFrontmatter, PasswordContent};
-use super::common::newId;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use zeroize::Zeroizing;
+
+use crate::error::ClaudiaError;
+use crate::storage::{StorageState, validateMoveTarget, foldersDir, parseUuidFilename, uuidFilename, trashPasswordsDir, recordTrashOrigin, forgetTrashOrigin, itemsDir, folderOfItemsDir, folderArg, itemsDirFor, writeFileAtomic};
+use crate::encrypted_storage::{self, EncryptedFile, fileIdOf};
+use crate::models::{Password, PasswordFrontmatter, PasswordContent, ItemKind, ResolvedColor, normalizeColor};
+use crate::password_strength::{self, StrengthReport};
+use crate::undo::UndoAction;
+use crate::totp::{self, TotpCode};
+use super::common::{BatchResult, newId, now, sanitizeTitle};
+use super::item::{ItemList, collectSkippedItems, missingItemError};
 
 #[derive(serde::Serialize)]
 pub struct PasswordInfo {
@@ -16,8 +23,11 @@ pub struct PasswordInfo {
     pub title: String,
     pub rank: u32,
     pub color: String,
+    pub resolvedColor: ResolvedColor,
     pub pinned: bool,
     pub tags: Vec<String>,
+    pub strength: Option<u8>,
+    pub expiresAt: Option<i64>,
     pub created: i64,
     pub updated: i64,
     pub folderPath: String,
@@ -26,17 +36,19 @@ pub struct PasswordInfo {
 
 impl From<&Password> for PasswordInfo {
     fn from(p: &Password) -> Self {
-        let folderPath = p.folderPath.parent()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_default();
+        // Report the folder itself, not its /passwords items dir (see itemsDir)
+        let folderPath = folderOfItemsDir(&p.folderPath).to_string_lossy().to_string();
 
         Self {
             id: p.frontmatter.id.clone(),
             title: p.frontmatter.title.clone(),
             rank: p.frontmatter.rank,
             color: p.frontmatter.color.clone(),
+            resolvedColor: ResolvedColor::of(&p.frontmatter.color),
             pinned: p.frontmatter.pinned,
             tags: p.frontmatter.tags.clone(),
+            strength: p.frontmatter.strength,
+            expiresAt: p.frontmatter.expiresAt,
             created: p.frontmatter.created,
             updated: p.frontmatter.updated,
             folderPath,
@@ -46,12 +58,25 @@ impl From<&Password> for PasswordInfo {
 }
 
 /// Decrypted password content returned to frontend
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Default)]
 pub struct DecryptedPasswordContent {
     pub url: String,
     pub username: String,
     pub password: String,
     pub notes: String,
+    pub totpSecret: String,
+}
+
+impl From<PasswordContent> for DecryptedPasswordContent {
+    fn from(c: PasswordContent) -> Self {
+        Self {
+            url: c.url,
+            username: c.username,
+            password: c.password,
+            notes: c.notes,
+            totpSecret: c.totpSecret,
+        }
+    }
 }
 
 /// Process a single password file and return Password if valid
@@ -66,23 +91,37 @@ fn processPasswordFile(path: &PathBuf, folderPath: &PathBuf, masterPassword: Opt
     // Check if file is encrypted (passwords are always encrypted)
     if encrypted_storage::isEncryptedFormat(&content) {
         let password = masterPassword?;
-        let encrypted = encrypted_storage::parseEncryptedFile(&content).ok()?;
-        let yamlContent = encrypted_storage::decryptMetadata(&encrypted.metadata, password).ok()?;
+        let encrypted = encrypted_storage::parseEncryptedFile(&content, &fileIdOf(path)).ok()?;
+        let yamlContent = encrypted.decryptMetadata(password).ok()?;
         let fm: PasswordFrontmatter = serde_yaml::from_str(&yamlContent).ok()?;
 
         Some(Password {
             path: path.clone(),
             folderPath: folderPath.clone(),
             frontmatter: fm,
-            encryptedContent: encrypted.content,
+            encrypted,
         })
     } else {
         None // Passwords must be encrypted
     }
 }
 
+/// Find a live password by id: the index points at its file, a full scan is the fallback
+pub(crate) fn findPasswordById(storage: &StorageState, wsPath: &str, id: &str, masterPassword: Option<&str>) -> Option<Password> {
+    if let Some(path) = storage.indexedPath(ItemKind::Password, id) {
+        let passwordsDirPath = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        if let Some(password) = processPasswordFile(&path, &passwordsDirPath, masterPassword).filter(|p| p.frontmatter.id == id) {
+            return Some(password);
+        }
+    }
+
+    let password = scanAllPasswords(&foldersDir(wsPath), masterPassword).into_iter().find(|p| p.frontmatter.id == id)?;
+    storage.indexItem(ItemKind::Password, id, &password.path);
+    Some(password)
+}
+
 /// Scan passwords from a directory using encrypted format
-fn scanPasswordsInFolder(folderPath: &PathBuf, masterPassword: Option<&str>) -> Vec<Password> {
+pub(crate) fn scanPasswordsInFolder(folderPath: &PathBuf, masterPassword: Option<&str>) -> Vec<Password> {
     let mut passwords = Vec::new();
 
     if !folderPath.exists() {
@@ -112,11 +151,11 @@ fn scanPasswordsInFolder(folderPath: &PathBuf, masterPassword: Option<&str>) ->
 }
 
 /// Scan all passwords recursively from the folders directory
-fn scanAllPasswords(foldersBaseDir: &PathBuf, masterPassword: Option<&str>) -> Vec<Password> {
+pub(crate) fn scanAllPasswords(foldersBaseDir: &PathBuf, masterPassword: Option<&str>) -> Vec<Password> {
     let mut allPasswords = Vec::new();
 
     // Passwords in root /folders/passwords/
-    let rootPasswordsDir = foldersBaseDir.join("passwords");
+    let rootPasswordsDir = itemsDir(foldersBaseDir, ItemKind::Password);
     if rootPasswordsDir.exists() {
         allPasswords.extend(scanPasswordsInFolder(&rootPasswordsDir, masterPassword));
     }
@@ -140,7 +179,7 @@ fn scanPasswordsInFoldersRecursive(dir: &PathBuf, passwords: &mut Vec<Password>,
             }
 
             if path.is_dir() {
-                let passwordsSubdir = path.join("passwords");
+                let passwordsSubdir = itemsDir(&path, ItemKind::Password);
                 if passwordsSubdir.exists() && passwordsSubdir.is_dir() {
                     passwords.extend(scanPasswordsInFolder(&passwordsSubdir, masterPassword));
                 }
@@ -155,53 +194,57 @@ fn scanPasswordsInFoldersRecursive(dir: &PathBuf, passwords: &mut Vec<Password>,
 // ============================================
 
 #[tauri::command]
-pub fn getPasswords(storage: State<'_, StorageState>, folderPath: Option<String>) -> Result<Vec<PasswordInfo>, String> {
-    println!("[getPasswords] Called with folderPath: {:?}", folderPath);
+pub fn getPasswords(storage: State<'_, StorageState>, folderPath: Option<String>, includeErrors: Option<bool>) -> Result<ItemList<PasswordInfo>, ClaudiaError> {
+    println!("[getPasswords] Called with folderPath: {:?}, includeErrors: {:?}", folderPath, includeErrors);
 
-    let wsPath = match storage.getWorkspacePath() {
+    let wsPath = match storage.getAvailableWorkspacePath()? {
         Some(p) => p,
-        None => return Ok(Vec::new()),
+        None => return Ok(ItemList::Items(Vec::new())),
     };
 
     // Check if vault is unlocked
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     // Get master password for decryption
     let masterPassword = storage.getMasterPassword();
     let passwordRef = masterPassword.as_deref();
 
-    let passwords = match &folderPath {
-        Some(fp) if !fp.is_empty() => {
-            let passwordsSubdir = PathBuf::from(fp).join("passwords");
-            scanPasswordsInFolder(&passwordsSubdir, passwordRef)
+    let (passwords, scanRoot) = match folderArg(folderPath.as_deref()) {
+        Some(folder) => {
+            let passwordsSubdir = itemsDir(folder, ItemKind::Password);
+            (scanPasswordsInFolder(&passwordsSubdir, passwordRef), passwordsSubdir)
         },
-        _ => {
+        None => {
             let foldersBase = foldersDir(&wsPath);
-            scanAllPasswords(&foldersBase, passwordRef)
+            (scanAllPasswords(&foldersBase, passwordRef), foldersBase)
         }
     };
 
     println!("[getPasswords] Found {} passwords", passwords.len());
 
+    let skipped = includeErrors.unwrap_or(false).then(|| {
+        let loaded: Vec<&Path> = passwords.iter().map(|p| p.path.as_path()).collect();
+        collectSkippedItems::<PasswordFrontmatter>(&scanRoot, ItemKind::Password, &loaded, passwordRef)
+    });
+
     storage.updateActivity();
-    Ok(passwords.iter().map(PasswordInfo::from).collect())
+    Ok(ItemList::new(passwords.iter().map(PasswordInfo::from).collect(), skipped))
 }
 
 #[tauri::command]
-pub fn getPasswordById(storage: State<'_, StorageState>, id: String) -> Result<Option<PasswordInfo>, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn getPasswordById(storage: State<'_, StorageState>, id: String) -> Result<Option<PasswordInfo>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
     let passwordRef = masterPassword.as_deref();
 
-    let passwords = scanAllPasswords(&foldersDir(&wsPath), passwordRef);
-    let result = passwords.iter().find(|p| p.frontmatter.id == id).map(PasswordInfo::from);
+    let result = findPasswordById(&storage, &wsPath, &id, passwordRef).as_ref().map(PasswordInfo::from);
 
     storage.updateActivity();
     Ok(result)
@@ -211,16 +254,18 @@ pub fn getPasswordById(storage: State<'_, StorageState>, id: String) -> Result<O
 pub fn getPasswordContent(
     storage: State<'_, StorageState>,
     id: String,
-) -> Result<DecryptedPasswordContent, String> {
+    sessionToken: Option<String>,
+) -> Result<DecryptedPasswordContent, ClaudiaError> {
     println!("[getPasswordContent] Called with id: {}", id);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
+    storage.checkSessionToken(sessionToken.as_deref())?;
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
     // Search in regular folders first
     let passwords = scanAllPasswords(&foldersDir(&wsPath), Some(&masterPassword));
@@ -234,33 +279,25 @@ pub fn getPasswordContent(
         let trashPasswordsPath = trashPasswordsDir(&wsPath);
         let trashPasswords = scanPasswordsInFolder(&trashPasswordsPath, Some(&masterPassword));
         trashPassword = trashPasswords.into_iter().find(|p| p.frontmatter.id == id)
-            .ok_or("Password not found")?;
+            .ok_or_else(|| missingItemError(&storage, ItemKind::Password, &id))?;
         &trashPassword
     };
 
     // Decrypt content section
-    if password.encryptedContent.is_empty() {
-        return Ok(DecryptedPasswordContent {
-            url: String::new(),
-            username: String::new(),
-            password: String::new(),
-            notes: String::new(),
-        });
+    if password.encrypted.content.is_empty() {
+        return Ok(DecryptedPasswordContent::default());
     }
 
-    let decrypted = encrypted_storage::decryptContent(&password.encryptedContent, &masterPassword)?;
+    // Metadata already decrypted during the scan, so a failure here is the content section itself
+    let decrypted = password.encrypted.decryptContent(&masterPassword)
+        .map_err(|e| encrypted_storage::codedError(encrypted_storage::ERR_CONTENT_DECRYPT, format!("Metadata decrypted but content did not ({}) - the file may be corrupt", e)))?;
     let content: PasswordContent = serde_json::from_str(&decrypted)
         .map_err(|e| format!("Failed to parse password content: {}", e))?;
 
     println!("[getPasswordContent] Successfully decrypted content");
     storage.updateActivity();
 
-    Ok(DecryptedPasswordContent {
-        url: content.url,
-        username: content.username,
-        password: content.password,
-        notes: content.notes,
-    })
+    Ok(content.into())
 }
 
 /// Batch decrypt multiple passwords at once - much more efficient
@@ -274,14 +311,14 @@ pub struct BatchDecryptedContent {
 pub fn getPasswordContentsBatch(
     storage: State<'_, StorageState>,
     ids: Vec<String>,
-) -> Result<Vec<BatchDecryptedContent>, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+) -> Result<Vec<BatchDecryptedContent>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
     let foldersBase = foldersDir(&wsPath);
 
     // Scan all passwords once
@@ -291,23 +328,13 @@ pub fn getPasswordContentsBatch(
 
     for id in ids {
         if let Some(password) = allPasswords.iter().find(|p| p.frontmatter.id == id) {
-            let content = if password.encryptedContent.is_empty() {
-                DecryptedPasswordContent {
-                    url: String::new(),
-                    username: String::new(),
-                    password: String::new(),
-                    notes: String::new(),
-                }
+            let content = if password.encrypted.content.is_empty() {
+                DecryptedPasswordContent::default()
             } else {
-                let decrypted = encrypted_storage::decryptContent(&password.encryptedContent, &masterPassword)?;
+                let decrypted = password.encrypted.decryptContent(&masterPassword)?;
                 let parsed: PasswordContent = serde_json::from_str(&decrypted)
                     .map_err(|e| format!("Failed to parse password content: {}", e))?;
-                DecryptedPasswordContent {
-                    url: parsed.url,
-                    username: parsed.username,
-                    password: parsed.password,
-                    notes: parsed.notes,
-                }
+                parsed.into()
             };
 
             results.push(BatchDecryptedContent { id, content });
@@ -318,6 +345,279 @@ pub fn getPasswordContentsBatch(
     Ok(results)
 }
 
+// ============================================
+// PASSWORD STRENGTH
+// ============================================
+
+/// Estimate the strength of a password as the user types it
+#[tauri::command]
+pub fn passwordStrength(password: String) -> StrengthReport {
+    password_strength::estimate(&password)
+}
+
+/// Score cached in the frontmatter so the list can flag weak passwords without decrypting
+fn storedStrength(password: &str) -> Option<u8> {
+    (!password.is_empty()).then(|| password_strength::estimate(password).score)
+}
+
+/// Decrypt a password's content section into its fields (empty content = all fields empty)
+pub(crate) fn decryptPasswordFields(password: &Password, masterPassword: &str) -> Result<PasswordContent, String> {
+    if password.encrypted.content.is_empty() {
+        return Ok(PasswordContent::default());
+    }
+    let decrypted = password.encrypted.decryptContent(masterPassword)?;
+    serde_json::from_str(&decrypted).map_err(|e| format!("Failed to parse password content: {}", e))
+}
+
+// ============================================
+// CLIPBOARD
+// ============================================
+
+/// Error returned while password access is locked (also matched by the MCP tools)
+pub(crate) const PASSWORDS_LOCKED: &str = "Passwords access is locked";
+
+/// Clear delay used when the caller doesn't pass one
+const DEFAULT_CLIPBOARD_CLEAR_SECS: u64 = 30;
+/// Longest a copied secret may stay on the clipboard
+const MAX_CLIPBOARD_CLEAR_SECS: u64 = 600;
+
+/// Copy one field of a password entry to the clipboard and clear it after `clearAfterSecs`
+/// The clipboard is only cleared if it still holds the copied value.
+/// Returns the time (ms) at which the clear fires so the UI can show a countdown.
+#[tauri::command]
+pub fn copyPasswordToClipboard(
+    app: tauri::AppHandle,
+    storage: State<'_, StorageState>,
+    id: String,
+    field: String,
+    clearAfterSecs: Option<u64>,
+) -> Result<i64, ClaudiaError> {
+    println!("[copyPasswordToClipboard] Called with id: {}, field: {}", id, field);
+
+    let clearAt = copyPasswordField(&app, &storage, &id, &field, clearAfterSecs)?;
+
+    storage.updatePasswordsActivity();
+    storage.updateActivity();
+    Ok(clearAt)
+}
+
+/// Shared by copyPasswordToClipboard and the MCP copy_password tool, which leaves the
+/// activity timers alone so an agent can't keep password access open
+pub(crate) fn copyPasswordField(
+    app: &tauri::AppHandle,
+    storage: &StorageState,
+    id: &str,
+    field: &str,
+    clearAfterSecs: Option<u64>,
+) -> Result<i64, String> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isPasswordsAccessUnlocked() {
+        return Err(PASSWORDS_LOCKED.to_string());
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let password = findPasswordById(storage, &wsPath, id, Some(&masterPassword))
+        .ok_or_else(|| missingItemError(storage, ItemKind::Password, id))?;
+
+    let content = decryptPasswordFields(&password, &masterPassword)?;
+    let value = Zeroizing::new(match field {
+        "password" => content.password,
+        "username" => content.username,
+        "url" => content.url,
+        "notes" => content.notes,
+        _ => return Err(format!("Invalid input: unknown password field '{}'", field)),
+    });
+    if value.is_empty() {
+        return Err(format!("Invalid input: {} is empty", field));
+    }
+
+    app.clipboard().write_text(value.as_str()).map_err(|e| format!("Failed to write clipboard: {}", e))?;
+
+    let delay = clearAfterSecs.unwrap_or(DEFAULT_CLIPBOARD_CLEAR_SECS).clamp(1, MAX_CLIPBOARD_CLEAR_SECS);
+    let clearAt = now() + (delay * 1000) as i64;
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_secs(delay));
+        // Leave the clipboard alone if the user has copied something else since
+        let current = app.clipboard().read_text().map(Zeroizing::new);
+        if current.is_ok_and(|text| *text == *value) {
+            let _ = app.clipboard().clear();
+            println!("[copyPasswordToClipboard] Clipboard cleared");
+        }
+    });
+
+    Ok(clearAt)
+}
+
+// ============================================
+// REUSED PASSWORDS
+// ============================================
+
+/// Entries sharing one secret; `passwordHash` is salted per call, so it only identifies
+/// the group within one response and can't be compared against other hashes
+#[derive(serde::Serialize, Debug)]
+pub struct ReuseGroup {
+    pub passwordHash: String,
+    pub ids: Vec<String>,
+}
+
+/// Group (id, secret) pairs by a keyed hash of the secret, keeping groups of two or more
+/// Empty secrets are ignored; groups come out largest first
+fn groupReusedPasswords(entries: &[(String, Zeroizing<String>)], salt: &[u8; 32]) -> Vec<ReuseGroup> {
+    let mut groups: std::collections::HashMap<String, Vec<String>> = std::collections::HashMap::new();
+    for (id, secret) in entries.iter().filter(|(_, secret)| !secret.is_empty()) {
+        let hash = blake3::keyed_hash(salt, secret.as_bytes()).to_hex().to_string();
+        groups.entry(hash).or_default().push(id.clone());
+    }
+
+    let mut reused: Vec<ReuseGroup> = groups.into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(passwordHash, ids)| ReuseGroup { passwordHash, ids })
+        .collect();
+    reused.sort_by(|a, b| b.ids.len().cmp(&a.ids.len()).then_with(|| a.ids.cmp(&b.ids)));
+    reused
+}
+
+/// Find live password entries that share the same secret
+#[tauri::command]
+pub fn findReusedPasswords(storage: State<'_, StorageState>) -> Result<Vec<ReuseGroup>, ClaudiaError> {
+    println!("[findReusedPasswords] Called");
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isPasswordsAccessUnlocked() {
+        return Err(PASSWORDS_LOCKED.to_string().into());
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    // Scan and decrypt every entry once
+    let passwords = scanAllPasswords(&foldersDir(&wsPath), Some(&masterPassword));
+    let mut entries = Vec::with_capacity(passwords.len());
+    for password in &passwords {
+        match decryptPasswordFields(password, &masterPassword) {
+            Ok(content) => entries.push((password.frontmatter.id.clone(), Zeroizing::new(content.password))),
+            Err(e) => println!("[findReusedPasswords] Skipping {}: {}", password.frontmatter.id, e),
+        }
+    }
+
+    let mut salt = [0u8; 32];
+    rand::Rng::fill(&mut rand::thread_rng(), &mut salt);
+    let groups = groupReusedPasswords(&entries, &salt);
+
+    println!("[findReusedPasswords] Found {} reused secrets across {} entries", groups.len(), entries.len());
+    storage.updatePasswordsActivity();
+    storage.updateActivity();
+    Ok(groups)
+}
+
+// ============================================
+// EXPIRY & AGE
+// ============================================
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExpiryReason {
+    /// expiresAt is in the past
+    Expired,
+    /// expiresAt falls within the requested window
+    ExpiringSoon,
+    /// Not updated for longer than the passwordMaxAgeDays setting
+    Stale,
+}
+
+#[derive(serde::Serialize)]
+pub struct ExpiringPassword {
+    #[serde(flatten)]
+    pub password: PasswordInfo,
+    pub reason: ExpiryReason,
+}
+
+/// Why an entry needs attention at `nowMs`, if it does; expiry wins over age
+fn expiryReason(fm: &PasswordFrontmatter, nowMs: i64, withinDays: u32, maxAgeDays: u32) -> Option<ExpiryReason> {
+    match fm.expiresAt {
+        Some(expiresAt) if expiresAt <= nowMs => return Some(ExpiryReason::Expired),
+        Some(expiresAt) if expiresAt <= nowMs + withinDays as i64 * DAY_MS => return Some(ExpiryReason::ExpiringSoon),
+        _ => {}
+    }
+    (maxAgeDays > 0 && nowMs - fm.updated > maxAgeDays as i64 * DAY_MS).then_some(ExpiryReason::Stale)
+}
+
+/// Entries that are expired, expire within `withinDays` (default 30), or are older than passwordMaxAgeDays
+/// Reads metadata only, so passwords access doesn't need to be unlocked
+#[tauri::command]
+pub fn getExpiringPasswords(storage: State<'_, StorageState>, withinDays: Option<u32>) -> Result<Vec<ExpiringPassword>, ClaudiaError> {
+    println!("[getExpiringPasswords] Called with withinDays: {:?}", withinDays);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword();
+    let passwordRef = masterPassword.as_deref();
+
+    let withinDays = withinDays.unwrap_or(30);
+    let maxAgeDays = storage.effectiveSettings().passwordMaxAgeDays;
+    let nowMs = now();
+
+    let result: Vec<ExpiringPassword> = scanAllPasswords(&foldersDir(&wsPath), passwordRef).iter()
+        .filter_map(|p| {
+            let reason = expiryReason(&p.frontmatter, nowMs, withinDays, maxAgeDays)?;
+            Some(ExpiringPassword { password: PasswordInfo::from(p), reason })
+        })
+        .collect();
+
+    println!("[getExpiringPasswords] Found {} entries", result.len());
+    storage.updateActivity();
+    Ok(result)
+}
+
+// ============================================
+// TOTP
+// ============================================
+
+/// Normalize a TOTP secret for storage; empty clears it, anything else must be valid base32
+fn validTotpSecret(secret: String) -> Result<String, String> {
+    let secret: String = secret.split_whitespace().collect::<String>().to_uppercase();
+    if !secret.is_empty() {
+        totp::decodeBase32(&secret)?;
+    }
+    Ok(secret)
+}
+
+/// Current 2FA code for a password entry; the secret itself never leaves the backend here
+#[tauri::command]
+pub fn getPasswordTotp(storage: State<'_, StorageState>, id: String, sessionToken: Option<String>) -> Result<TotpCode, ClaudiaError> {
+    println!("[getPasswordTotp] Called with id: {}", id);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+    storage.checkSessionToken(sessionToken.as_deref())?;
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let passwords = scanAllPasswords(&foldersDir(&wsPath), Some(&masterPassword));
+    let password = passwords.iter().find(|p| p.frontmatter.id == id)
+        .ok_or_else(|| missingItemError(&storage, ItemKind::Password, &id))?;
+
+    let content = decryptPasswordFields(password, &masterPassword)?;
+    if content.totpSecret.is_empty() {
+        return Err(ClaudiaError::InvalidInput("Invalid input: this entry has no TOTP secret".to_string()));
+    }
+
+    storage.updateActivity();
+    Ok(totp::currentCode(&content.totpSecret)?)
+}
+
 // ============================================
 // CREATE COMMAND
 // ============================================
@@ -330,48 +630,61 @@ pub struct CreatePasswordInput {
     pub username: Option<String>,
     pub password: Option<String>,
     pub notes: Option<String>,
+    pub totpSecret: Option<String>,
     pub color: Option<String>,
     pub tags: Option<Vec<String>>,
+    pub expiresAt: Option<i64>,
 }
 
 #[tauri::command]
 pub fn createPassword(
     storage: State<'_, StorageState>,
     input: CreatePasswordInput,
-) -> Result<PasswordInfo, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace selected")?;
+) -> Result<PasswordInfo, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
-    let folderPath = match &input.folderPath {
-        Some(p) if !p.is_empty() && p != "null" && p.starts_with('/') => {
-            PathBuf::from(p).join("passwords")
-        }
-        _ => passwordsDir(&wsPath, ""),
-    };
+    let folderPath = itemsDirFor(&wsPath, input.folderPath.as_deref(), ItemKind::Password)?;
 
-    fs::create_dir_all(&folderPath).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&folderPath)?;
 
     // Find next rank from existing passwords
     let existingPasswords = scanPasswordsInFolder(&folderPath, Some(&masterPassword));
     let nextRank = existingPasswords.iter().map(|p| p.frontmatter.rank).max().unwrap_or(0) + 1;
 
+    let password = writeNewPassword(&storage, &folderPath, input, nextRank, &masterPassword)?;
+
+    storage.updateActivity();
+    Ok(PasswordInfo::from(&password))
+}
+
+/// Encrypt and write a new password file into an existing passwords dir
+/// `input.folderPath` is ignored; the caller has already resolved `folderPath`
+fn writeNewPassword(
+    storage: &StorageState,
+    folderPath: &Path,
+    input: CreatePasswordInput,
+    rank: u32,
+    masterPassword: &str,
+) -> Result<Password, String> {
     // UUID is the filename
     let id = newId();
     let filename = uuidFilename(&id);
     let passwordPath = folderPath.join(&filename);
 
-    let mut fm = PasswordFrontmatter::new(id, input.title.clone(), nextRank);
+    let mut fm = PasswordFrontmatter::new(id, sanitizeTitle(&input.title, storage.effectiveSettings().emojiShortcodes), rank);
     if let Some(color) = input.color {
-        fm.color = color;
+        fm.color = normalizeColor(&color)?;
     }
     if let Some(tags) = input.tags {
         fm.tags = tags;
     }
+    fm.expiresAt = input.expiresAt.filter(|t| *t > 0);
 
     // Create content with all sensitive fields
     let passwordContent = PasswordContent {
@@ -379,7 +692,9 @@ pub fn createPassword(
         username: input.username.unwrap_or_default(),
         password: input.password.unwrap_or_default(),
         notes: input.notes.unwrap_or_default(),
+        totpSecret: validTotpSecret(input.totpSecret.unwrap_or_default())?,
     };
+    fm.strength = storedStrength(&passwordContent.password);
 
     let contentJson = serde_json::to_string(&passwordContent)
         .map_err(|e| format!("Failed to serialize password content: {}", e))?;
@@ -388,20 +703,219 @@ pub fn createPassword(
     let fileContent = encrypted_storage::createEncryptedFile(
         &serde_yaml::to_string(&fm).map_err(|e| e.to_string())?,
         &contentJson,
-        &masterPassword,
+        masterPassword,
+        &fileIdOf(&passwordPath),
     )?;
 
-    fs::write(&passwordPath, fileContent).map_err(|e| e.to_string())?;
+    writeFileAtomic(&passwordPath, fileContent).map_err(|e| e.to_string())?;
+    storage.indexItem(ItemKind::Password, &fm.id, &passwordPath);
 
-    let password = Password {
+    Ok(Password {
         path: passwordPath,
-        folderPath,
+        folderPath: folderPath.to_path_buf(),
         frontmatter: fm,
-        encryptedContent: String::new(), // Content is in file, not needed here
+        encrypted: EncryptedFile::default(), // Content is in file, not needed here
+    })
+}
+
+// ============================================
+// CSV IMPORT
+// ============================================
+
+/// Column order assumed when the first row isn't a header
+const CSV_DEFAULT_COLUMNS: [CsvColumn; 5] = [CsvColumn::Name, CsvColumn::Url, CsvColumn::Username, CsvColumn::Password, CsvColumn::Notes];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CsvColumn {
+    Name,
+    Url,
+    Username,
+    Password,
+    Notes,
+    Ignored,
+}
+
+impl CsvColumn {
+    /// Header names used by the common browser and password manager exports
+    fn fromHeader(header: &str) -> Self {
+        match header.trim().to_lowercase().as_str() {
+            "name" | "title" => Self::Name,
+            "url" | "uri" | "website" | "login_uri" => Self::Url,
+            "username" | "user" | "login" | "email" | "login_username" => Self::Username,
+            "password" | "login_password" => Self::Password,
+            "notes" | "note" | "extra" | "comments" => Self::Notes,
+            _ => Self::Ignored,
+        }
+    }
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+pub struct CsvImportError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct CsvImportResult {
+    pub imported: usize,
+    pub skipped: usize,
+    pub errors: Vec<CsvImportError>,
+}
+
+/// Split CSV text into records with the line each starts on (1-based)
+/// Quoted fields may contain commas, newlines and "" escapes; an unterminated quote ends parsing with an error
+fn parseCsv(text: &str) -> (Vec<(usize, Vec<String>)>, Option<CsvImportError>) {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut inQuotes = false;
+    let mut line = 1;
+    let mut recordLine = 1;
+
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        if inQuotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => inQuotes = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => inQuotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                records.push((recordLine, std::mem::take(&mut record)));
+                line += 1;
+                recordLine = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if inQuotes {
+        return (records, Some(CsvImportError { line: recordLine, message: "Unterminated quoted field".to_string() }));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((recordLine, record));
+    }
+    (records, None)
+}
+
+/// Column layout from the first record if it is a header row
+fn csvHeader(record: &[String]) -> Option<Vec<CsvColumn>> {
+    let columns: Vec<CsvColumn> = record.iter().map(|h| CsvColumn::fromHeader(h)).collect();
+    (columns.contains(&CsvColumn::Password) && (columns.contains(&CsvColumn::Name) || columns.contains(&CsvColumn::Url)))
+        .then_some(columns)
+}
+
+/// Build a create input from one data row, or None if the row carries nothing to import
+fn csvRowInput(columns: &[CsvColumn], row: &[String]) -> Option<CreatePasswordInput> {
+    let get = |column: CsvColumn| {
+        columns.iter().zip(row)
+            .find(|(c, v)| **c == column && !v.trim().is_empty())
+            .map(|(_, v)| v.clone())
+    };
+
+    let url = get(CsvColumn::Url);
+    let username = get(CsvColumn::Username);
+    let password = get(CsvColumn::Password);
+    if url.is_none() && username.is_none() && password.is_none() {
+        return None;
+    }
+
+    // Some exports leave the name blank; fall back to what identifies the login
+    let title = get(CsvColumn::Name)
+        .or_else(|| url.clone())
+        .or_else(|| username.clone())
+        .unwrap_or_else(|| "Imported password".to_string());
+
+    Some(CreatePasswordInput {
+        title,
+        folderPath: None,
+        url,
+        username,
+        password,
+        notes: get(CsvColumn::Notes),
+        totpSecret: None,
+        color: None,
+        tags: None,
+        expiresAt: None,
+    })
+}
+
+/// Import a `name,url,username,password,notes` CSV export into a folder
+/// Columns are matched by header when present; empty rows are counted as skipped
+#[tauri::command]
+pub fn importPasswordsCsv(
+    storage: State<'_, StorageState>,
+    csvText: String,
+    targetFolderPath: String,
+) -> Result<CsvImportResult, ClaudiaError> {
+    println!("[importPasswordsCsv] Called for folder: {}", targetFolderPath);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let targetFolder = validateMoveTarget(&wsPath, &targetFolderPath)?;
+    let folderPath = itemsDir(targetFolder, ItemKind::Password);
+    fs::create_dir_all(&folderPath)?;
+
+    let csvText = Zeroizing::new(csvText);
+    let (records, parseError) = parseCsv(&csvText);
+    let mut errors: Vec<CsvImportError> = parseError.into_iter().collect();
+
+    let mut rows = records.into_iter().peekable();
+    let columns = match rows.peek().and_then(|(_, first)| csvHeader(first)) {
+        Some(columns) => {
+            rows.next();
+            columns
+        }
+        None => CSV_DEFAULT_COLUMNS.to_vec(),
     };
 
+    let mut nextRank = scanPasswordsInFolder(&folderPath, Some(&masterPassword)).iter()
+        .map(|p| p.frontmatter.rank)
+        .max()
+        .unwrap_or(0) + 1;
+    let mut imported = 0;
+    let mut skipped = 0;
+
+    for (line, row) in rows {
+        let Some(input) = csvRowInput(&columns, &row) else {
+            skipped += 1;
+            continue;
+        };
+        match writeNewPassword(&storage, &folderPath, input, nextRank, &masterPassword) {
+            Ok(_) => {
+                imported += 1;
+                nextRank += 1;
+            }
+            Err(message) => errors.push(CsvImportError { line, message }),
+        }
+    }
+
+    println!("[importPasswordsCsv] Imported {}, skipped {}, {} errors", imported, skipped, errors.len());
     storage.updateActivity();
-    Ok(PasswordInfo::from(&password))
+    Ok(CsvImportResult { imported, skipped, errors })
 }
 
 // ============================================
@@ -416,23 +930,26 @@ pub struct UpdatePasswordInput {
     pub username: Option<String>,
     pub password: Option<String>,
     pub notes: Option<String>,
+    pub totpSecret: Option<String>,
     pub color: Option<String>,
     pub pinned: Option<bool>,
     pub tags: Option<Vec<String>>,
+    /// New expiry in ms; 0 or less clears it
+    pub expiresAt: Option<i64>,
 }
 
 #[tauri::command]
 pub fn updatePassword(
     storage: State<'_, StorageState>,
     input: UpdatePasswordInput,
-) -> Result<(), String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+) -> Result<(), ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
     // Search in regular folders first
     let passwords = scanAllPasswords(&foldersDir(&wsPath), Some(&masterPassword));
@@ -446,7 +963,7 @@ pub fn updatePassword(
         let trashPasswordsPath = trashPasswordsDir(&wsPath);
         let trashPasswords = scanPasswordsInFolder(&trashPasswordsPath, Some(&masterPassword));
         trashPassword = trashPasswords.into_iter().find(|p| p.frontmatter.id == input.id)
-            .ok_or("Password not found")?;
+            .ok_or_else(|| ClaudiaError::NotFound("Password not found".to_string()))?;
         &trashPassword
     };
 
@@ -454,10 +971,10 @@ pub fn updatePassword(
 
     // Update metadata fields
     if let Some(title) = input.title {
-        fm.title = title;
+        fm.title = sanitizeTitle(&title, storage.effectiveSettings().emojiShortcodes);
     }
     if let Some(color) = input.color {
-        fm.color = color;
+        fm.color = normalizeColor(&color)?;
     }
     if let Some(pinned) = input.pinned {
         fm.pinned = pinned;
@@ -465,12 +982,15 @@ pub fn updatePassword(
     if let Some(tags) = input.tags {
         fm.tags = tags;
     }
+    if let Some(expiresAt) = input.expiresAt {
+        fm.expiresAt = (expiresAt > 0).then_some(expiresAt);
+    }
 
     fm.updated = chrono::Utc::now().timestamp_millis();
 
     // Get existing content and update if needed
-    let currentContent: PasswordContent = if !password.encryptedContent.is_empty() {
-        let decrypted = encrypted_storage::decryptContent(&password.encryptedContent, &masterPassword)?;
+    let currentContent: PasswordContent = if !password.encrypted.content.is_empty() {
+        let decrypted = password.encrypted.decryptContent(&masterPassword)?;
         serde_json::from_str(&decrypted).unwrap_or_default()
     } else {
         PasswordContent::default()
@@ -482,7 +1002,12 @@ pub fn updatePassword(
         username: input.username.unwrap_or(currentContent.username),
         password: input.password.unwrap_or(currentContent.password),
         notes: input.notes.unwrap_or(currentContent.notes),
+        totpSecret: match input.totpSecret {
+            Some(secret) => validTotpSecret(secret)?,
+            None => currentContent.totpSecret,
+        },
     };
+    fm.strength = storedStrength(&newContent.password);
 
     let contentJson = serde_json::to_string(&newContent)
         .map_err(|e| format!("Failed to serialize password content: {}", e))?;
@@ -492,9 +1017,10 @@ pub fn updatePassword(
         &serde_yaml::to_string(&fm).map_err(|e| e.to_string())?,
         &contentJson,
         &masterPassword,
+        &fileIdOf(&password.path),
     )?;
 
-    fs::write(&password.path, fileContent).map_err(|e| e.to_string())?;
+    writeFileAtomic(&password.path, fileContent)?;
 
     storage.updateActivity();
     Ok(())
@@ -505,13 +1031,13 @@ pub fn updatePassword(
 // ============================================
 
 #[tauri::command]
-pub fn deletePassword(storage: State<'_, StorageState>, id: String, permanent: Option<bool>) -> Result<(), String> {
+pub fn deletePassword(storage: State<'_, StorageState>, id: String, permanent: Option<bool>) -> Result<(), ClaudiaError> {
     println!("[deletePassword] Called with id: {}, permanent: {:?}", id, permanent);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
@@ -531,20 +1057,38 @@ pub fn deletePassword(storage: State<'_, StorageState>, id: String, permanent: O
         let trashPasswordsPath = trashPasswordsDir(&wsPath);
         let trashPasswords = scanPasswordsInFolder(&trashPasswordsPath, passwordRef);
         trashPassword = trashPasswords.into_iter().find(|p| p.frontmatter.id == id)
-            .ok_or("Password not found")?;
+            .ok_or_else(|| ClaudiaError::NotFound("Password not found".to_string()))?;
         isInTrash = true;
         &trashPassword
     };
     println!("[deletePassword] Found password at: {} (in trash: {})", password.path.display(), isInTrash);
 
     // If item is in trash, always permanently delete
-    if permanent.unwrap_or(false) || isInTrash {
-        // Permanent delete
+    let permanent = permanent.unwrap_or(false) || isInTrash;
+    removePasswordFile(&wsPath, password, permanent)?;
+    storage.unindexItem(&id);
+    if !permanent {
+        storage.recordUndo("deletePassword", vec![UndoAction::MoveBack {
+            kind: ItemKind::Password,
+            id: id.clone(),
+            current: trashPasswordsDir(&wsPath).join(uuidFilename(&id)),
+            original: password.path.clone(),
+            rank: password.frontmatter.rank,
+        }]);
+    }
+
+    storage.updateActivity();
+    Ok(())
+}
+
+/// Move a password file to trash, or remove it when `permanent`
+fn removePasswordFile(wsPath: &str, password: &Password, permanent: bool) -> Result<(), String> {
+    if permanent {
         fs::remove_file(&password.path).map_err(|e| e.to_string())?;
+        forgetTrashOrigin(wsPath, &password.frontmatter.id);
         println!("[deletePassword] SUCCESS - permanently deleted");
     } else {
-        // Move to trash
-        let trashDir = trashPasswordsDir(&wsPath);
+        let trashDir = trashPasswordsDir(wsPath);
         fs::create_dir_all(&trashDir).map_err(|e| e.to_string())?;
 
         let trashPath = trashDir.join(password.path.file_name().ok_or("Invalid file name")?);
@@ -552,11 +1096,47 @@ pub fn deletePassword(storage: State<'_, StorageState>, id: String, permanent: O
             println!("[deletePassword] ERROR moving to trash: {}", e);
             e.to_string()
         })?;
+        recordTrashOrigin(wsPath, &password.frontmatter.id, &password.folderPath);
         println!("[deletePassword] SUCCESS - moved to trash at: {}", trashPath.display());
     }
+    Ok(())
+}
+
+/// Delete many passwords with a single scan; passwords already in trash are always removed permanently
+#[tauri::command]
+pub fn deletePasswordsBatch(storage: State<'_, StorageState>, ids: Vec<String>, permanent: Option<bool>) -> Result<Vec<BatchResult>, ClaudiaError> {
+    println!("[deletePasswordsBatch] Called with {} ids, permanent: {:?}", ids.len(), permanent);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword();
+    let passwordRef = masterPassword.as_deref();
+
+    let passwords = scanAllPasswords(&foldersDir(&wsPath), passwordRef);
+    let trashPasswords = scanPasswordsInFolder(&trashPasswordsDir(&wsPath), passwordRef);
+    let permanent = permanent.unwrap_or(false);
+
+    let results: Vec<BatchResult> = ids.into_iter().map(|id| {
+        let result = if let Some(password) = passwords.iter().find(|p| p.frontmatter.id == id) {
+            removePasswordFile(&wsPath, password, permanent)
+        } else if let Some(password) = trashPasswords.iter().find(|p| p.frontmatter.id == id) {
+            removePasswordFile(&wsPath, password, true)
+        } else {
+            Err("Password not found".to_string())
+        };
+        if result.is_ok() {
+            storage.unindexItem(&id);
+        }
+        BatchResult::new(id, result)
+    }).collect();
 
+    println!("[deletePasswordsBatch] Deleted {} of {}", results.iter().filter(|r| r.success).count(), results.len());
     storage.updateActivity();
-    Ok(())
+    Ok(results)
 }
 
 // ============================================
@@ -570,26 +1150,25 @@ pub struct ReorderPasswordsInput {
 }
 
 #[tauri::command]
-pub fn reorderPasswords(storage: State<'_, StorageState>, input: ReorderPasswordsInput) -> Result<(), String> {
+pub fn reorderPasswords(storage: State<'_, StorageState>, input: ReorderPasswordsInput) -> Result<(), ClaudiaError> {
     println!("[reorderPasswords] Called with folderPath: {}", input.folderPath);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
     // Determine the actual passwords directory
-    let passwordsDirPath = if input.folderPath.is_empty() {
-        passwordsDir(&wsPath, "")
-    } else {
-        PathBuf::from(&input.folderPath).join("passwords")
-    };
+    let passwordsDirPath = itemsDirFor(&wsPath, Some(&input.folderPath), ItemKind::Password)?;
 
     let passwords = scanPasswordsInFolder(&passwordsDirPath, Some(&masterPassword));
 
+    // Ranks before the reorder, for undo
+    let mut previousRanks = Vec::new();
+
     // Update rank and re-encrypt
     for (index, passwordId) in input.passwordIds.iter().enumerate() {
         if let Some(password) = passwords.iter().find(|p| p.frontmatter.id == *passwordId) {
@@ -601,21 +1180,24 @@ pub fn reorderPasswords(storage: State<'_, StorageState>, input: ReorderPassword
                 fm.rank = newRank;
 
                 // Read and decrypt existing content
-                let fileContent = fs::read_to_string(&password.path).map_err(|e| e.to_string())?;
-                let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-                let contentJson = encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?;
+                let fileContent = fs::read_to_string(&password.path)?;
+                let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&password.path))?;
+                let contentJson = encrypted.decryptContent(&masterPassword)?;
 
                 // Re-encrypt with updated metadata
                 let newFileContent = encrypted_storage::createEncryptedFile(
                     &serde_yaml::to_string(&fm).map_err(|e| e.to_string())?,
                     &contentJson,
                     &masterPassword,
+                    &fileIdOf(&password.path),
                 )?;
 
-                fs::write(&password.path, newFileContent).map_err(|e| e.to_string())?;
+                writeFileAtomic(&password.path, newFileContent)?;
+                previousRanks.push((password.path.clone(), password.frontmatter.rank));
             }
         }
     }
+    storage.recordUndo("reorderPasswords", vec![UndoAction::RestoreRanks { ranks: previousRanks }]);
 
     storage.updateActivity();
     println!("[reorderPasswords] SUCCESS");
@@ -623,16 +1205,16 @@ pub fn reorderPasswords(storage: State<'_, StorageState>, input: ReorderPassword
 }
 
 #[tauri::command]
-pub fn movePasswordToFolder(storage: State<'_, StorageState>, id: String, targetFolderPath: String) -> Result<PasswordInfo, String> {
+pub fn movePasswordToFolder(storage: State<'_, StorageState>, id: String, targetFolderPath: String) -> Result<PasswordInfo, ClaudiaError> {
     println!("[movePasswordToFolder] Called with id: {}, targetFolderPath: {}", id, targetFolderPath);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
     // Search in regular folders first
     let passwords = scanAllPasswords(&foldersDir(&wsPath), Some(&masterPassword));
@@ -646,15 +1228,15 @@ pub fn movePasswordToFolder(storage: State<'_, StorageState>, id: String, target
         let trashPasswordsPath = trashPasswordsDir(&wsPath);
         let trashPasswords = scanPasswordsInFolder(&trashPasswordsPath, Some(&masterPassword));
         trashPassword = trashPasswords.into_iter().find(|p| p.frontmatter.id == id)
-            .ok_or("Password not found")?;
+            .ok_or_else(|| ClaudiaError::NotFound("Password not found".to_string()))?;
         &trashPassword
     };
 
     // Target is the passwords subdirectory within the folder
-    let targetPasswordsDir = PathBuf::from(&targetFolderPath).join("passwords");
+    let targetPasswordsDir = itemsDir(Path::new(&targetFolderPath), ItemKind::Password);
 
     // Create target folder if it doesn't exist
-    fs::create_dir_all(&targetPasswordsDir).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&targetPasswordsDir)?;
 
     // Find next rank in target folder
     let existingPasswords = scanPasswordsInFolder(&targetPasswordsDir, Some(&masterPassword));
@@ -668,31 +1250,137 @@ pub fn movePasswordToFolder(storage: State<'_, StorageState>, id: String, target
     fm.rank = nextRank;
 
     // Read and decrypt existing content
-    let fileContent = fs::read_to_string(&password.path).map_err(|e| e.to_string())?;
-    let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-    let contentJson = encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?;
+    let fileContent = fs::read_to_string(&password.path)?;
+    let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&password.path))?;
+    let contentJson = encrypted.decryptContent(&masterPassword)?;
 
     // Re-encrypt with updated metadata
     let newFileContent = encrypted_storage::createEncryptedFile(
         &serde_yaml::to_string(&fm).map_err(|e| e.to_string())?,
         &contentJson,
         &masterPassword,
+        &fileIdOf(&newPath),
     )?;
 
-    fs::write(&newPath, &newFileContent).map_err(|e| e.to_string())?;
+    writeFileAtomic(&newPath, &newFileContent)?;
+    storage.indexItem(ItemKind::Password, &id, &newPath);
 
     // Remove old file
-    fs::remove_file(&password.path).map_err(|e| e.to_string())?;
+    if newPath != password.path {
+        fs::remove_file(&password.path)?;
+        storage.recordUndo("movePasswordToFolder", vec![UndoAction::MoveBack {
+            kind: ItemKind::Password,
+            id: id.clone(),
+            current: newPath.clone(),
+            original: password.path.clone(),
+            rank: password.frontmatter.rank,
+        }]);
+    }
 
     // Build and return updated PasswordInfo
     let movedPassword = Password {
         path: newPath,
         folderPath: targetPasswordsDir,
         frontmatter: fm,
-        encryptedContent: String::new(),
+        encrypted: EncryptedFile::default(),
     };
 
     storage.updateActivity();
     println!("[movePasswordToFolder] SUCCESS");
     Ok(PasswordInfo::from(&movedPassword))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, secret: &str) -> (String, Zeroizing<String>) {
+        (id.to_string(), Zeroizing::new(secret.to_string()))
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_csv_quoting() {
+        let text = "name,url,username,password,notes\r\n\"Bank, Inc\",https://bank.example,me,\"p\"\"w,d\",\"line one\nline two\"\nMail,,bob,hunter2,\n";
+        let (records, error) = parseCsv(text);
+        assert!(error.is_none());
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1], (2, strings(&["Bank, Inc", "https://bank.example", "me", "p\"w,d", "line one\nline two"])));
+        assert_eq!(records[2], (4, strings(&["Mail", "", "bob", "hunter2", ""])));
+    }
+
+    #[test]
+    fn test_parse_csv_unterminated_quote() {
+        let (records, error) = parseCsv("a,b\nc,\"d\ne,f");
+        assert_eq!(records.len(), 1);
+        assert_eq!(error, Some(CsvImportError { line: 2, message: "Unterminated quoted field".to_string() }));
+    }
+
+    #[test]
+    fn test_csv_header_detection() {
+        let header = csvHeader(&strings(&["url", "username", "password", "name"])).unwrap();
+        let input = csvRowInput(&header, &strings(&["https://x.example", "me", "pw", "X"])).unwrap();
+        assert_eq!(input.title, "X");
+        assert_eq!(input.url.as_deref(), Some("https://x.example"));
+        assert_eq!(input.password.as_deref(), Some("pw"));
+
+        // Data rows aren't mistaken for headers
+        assert!(csvHeader(&strings(&["Bank", "https://bank.example", "me", "secret", ""])).is_none());
+
+        // Rows without a name fall back to the url; empty rows are skipped
+        let input = csvRowInput(&CSV_DEFAULT_COLUMNS, &strings(&["", "https://y.example", "", "pw"])).unwrap();
+        assert_eq!(input.title, "https://y.example");
+        assert!(csvRowInput(&CSV_DEFAULT_COLUMNS, &strings(&["Only a name", "", "", "", "note"])).is_none());
+    }
+
+    #[test]
+    fn test_expiry_reason() {
+        let nowMs = 1_000 * DAY_MS;
+        let mut fm = PasswordFrontmatter::new("id".to_string(), "Bank".to_string(), 1);
+        fm.updated = nowMs - DAY_MS;
+
+        assert_eq!(expiryReason(&fm, nowMs, 30, 365), None);
+
+        fm.expiresAt = Some(nowMs - 1);
+        assert_eq!(expiryReason(&fm, nowMs, 30, 365), Some(ExpiryReason::Expired));
+
+        fm.expiresAt = Some(nowMs + 10 * DAY_MS);
+        assert_eq!(expiryReason(&fm, nowMs, 30, 365), Some(ExpiryReason::ExpiringSoon));
+        assert_eq!(expiryReason(&fm, nowMs, 7, 365), None);
+
+        fm.expiresAt = None;
+        fm.updated = nowMs - 400 * DAY_MS;
+        assert_eq!(expiryReason(&fm, nowMs, 30, 365), Some(ExpiryReason::Stale));
+        assert_eq!(expiryReason(&fm, nowMs, 30, 0), None);
+    }
+
+    #[test]
+    fn test_group_reused_passwords() {
+        let entries = vec![
+            entry("a", "hunter2"),
+            entry("b", "correct horse battery staple"),
+            entry("c", "hunter2"),
+        ];
+        let groups = groupReusedPasswords(&entries, &[7u8; 32]);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].ids, vec!["a".to_string(), "c".to_string()]);
+        // The plaintext never appears in the report
+        assert!(!groups[0].passwordHash.contains("hunter2"));
+        assert_eq!(groups[0].passwordHash.len(), 64);
+    }
+
+    #[test]
+    fn test_group_reused_passwords_ignores_empty_and_uses_salt() {
+        let entries = vec![entry("a", ""), entry("b", ""), entry("c", "same"), entry("d", "same")];
+        let first = groupReusedPasswords(&entries, &[1u8; 32]);
+        let second = groupReusedPasswords(&entries, &[2u8; 32]);
+
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].ids, vec!["c".to_string(), "d".to_string()]);
+        assert_ne!(first[0].passwordHash, second[0].passwordHash);
+    }
+}
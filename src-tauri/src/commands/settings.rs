@@ -1,10 +1,13 @@
 // Settings commands - complete implementation
 
+use std::collections::BTreeMap;
 use std::fs;
 use tauri::State;
 
+use crate::error::ClaudiaError;
 use crate::storage::{StorageState, saveGlobalConfig, workspaceConfigPath, parseFrontmatter, toMarkdown};
-use crate::models::{Settings, SettingsOverride};
+use crate::models::{FloatingDefaults, MasterPasswordPolicy, Settings, SettingsOverride};
+use crate::models::config::parseMcpBindAddress;
 
 #[derive(serde::Serialize)]
 pub struct SettingsInfo {
@@ -15,6 +18,28 @@ pub struct SettingsInfo {
     pub notificationSound: bool,
     pub notificationMinutesBefore: i32,
     pub floatingOpacity: f64,
+    pub maxItemBytes: u64,
+    pub lockOnQuit: bool,
+    pub lockOnHide: bool,
+    pub backupSchedule: String,
+    pub backupDir: String,
+    pub backupRetention: u32,
+    pub trackAccess: bool,
+    pub folderLayout: Vec<String>,
+    pub emojiShortcodes: bool,
+    pub dateFormat: String,
+    pub dailyNotesFolder: String,
+    pub trashRetentionDays: u32,
+    pub reauthForSensitive: bool,
+    pub floatingDefaults: FloatingDefaults,
+    pub passwordMaxAgeDays: u32,
+    pub historyVersions: u32,
+    pub watchVault: bool,
+    pub autoLockMinutes: u32,
+    pub passwordsAccessMinutes: u32,
+    pub mcpPort: u16,
+    pub mcpBindAddress: String,
+    pub masterPasswordPolicy: MasterPasswordPolicy,
 }
 
 impl From<Settings> for SettingsInfo {
@@ -27,6 +52,28 @@ impl From<Settings> for SettingsInfo {
             notificationSound: s.notificationSound,
             notificationMinutesBefore: s.notificationMinutesBefore,
             floatingOpacity: s.floatingOpacity,
+            maxItemBytes: s.maxItemBytes,
+            lockOnQuit: s.lockOnQuit,
+            lockOnHide: s.lockOnHide,
+            backupSchedule: s.backupSchedule,
+            backupDir: s.backupDir,
+            backupRetention: s.backupRetention,
+            trackAccess: s.trackAccess,
+            folderLayout: s.folderLayout,
+            emojiShortcodes: s.emojiShortcodes,
+            dateFormat: s.dateFormat,
+            dailyNotesFolder: s.dailyNotesFolder,
+            trashRetentionDays: s.trashRetentionDays,
+            reauthForSensitive: s.reauthForSensitive,
+            floatingDefaults: s.floatingDefaults,
+            passwordMaxAgeDays: s.passwordMaxAgeDays,
+            historyVersions: s.historyVersions,
+            watchVault: s.watchVault,
+            autoLockMinutes: s.autoLockMinutes,
+            passwordsAccessMinutes: s.passwordsAccessMinutes,
+            mcpPort: s.mcpPort,
+            mcpBindAddress: s.mcpBindAddress,
+            masterPasswordPolicy: s.masterPasswordPolicy,
         }
     }
 }
@@ -47,6 +94,50 @@ pub fn getGlobalSettings(storage: State<'_, StorageState>) -> SettingsInfo {
     settings.into()
 }
 
+/// Where an effective setting value comes from
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingSource {
+    Global,
+    Workspace,
+}
+
+#[derive(serde::Serialize)]
+pub struct SettingWithSource {
+    pub value: serde_json::Value,
+    pub source: SettingSource,
+}
+
+/// Effective settings keyed by field, each tagged with whether the workspace overrides it
+#[tauri::command]
+pub fn getSettingsWithSource(storage: State<'_, StorageState>) -> Result<BTreeMap<String, SettingWithSource>, ClaudiaError> {
+    println!("[getSettingsWithSource] Called");
+
+    let effective = serde_json::to_value(SettingsInfo::from(storage.effectiveSettings()))
+        .map_err(|e| e.to_string())?;
+    // Unset override fields are skipped when serializing, so present keys are the overridden ones
+    let overridden = serde_json::to_value(&*storage.workspaceOverride.read())
+        .map_err(|e| e.to_string())?;
+
+    let serde_json::Value::Object(fields) = effective else {
+        return Err("Failed to serialize settings".into());
+    };
+    let result: BTreeMap<String, SettingWithSource> = fields.into_iter()
+        .map(|(key, value)| {
+            let source = if overridden.get(&key).is_some_and(|v| !v.is_null()) {
+                SettingSource::Workspace
+            } else {
+                SettingSource::Global
+            };
+            (key, SettingWithSource { value, source })
+        })
+        .collect();
+
+    let overrideCount = result.values().filter(|s| s.source == SettingSource::Workspace).count();
+    println!("[getSettingsWithSource] {} fields, {} overridden by workspace", result.len(), overrideCount);
+    Ok(result)
+}
+
 #[derive(serde::Deserialize)]
 pub struct UpdateSettingsInput {
     pub theme: Option<String>,
@@ -56,14 +147,42 @@ pub struct UpdateSettingsInput {
     pub notificationSound: Option<bool>,
     pub notificationMinutesBefore: Option<i32>,
     pub floatingOpacity: Option<f64>,
+    pub maxItemBytes: Option<u64>,
+    pub lockOnQuit: Option<bool>,
+    pub lockOnHide: Option<bool>,
+    pub backupSchedule: Option<String>,
+    pub backupDir: Option<String>,
+    pub backupRetention: Option<u32>,
+    pub trackAccess: Option<bool>,
+    pub folderLayout: Option<Vec<String>>,
+    pub emojiShortcodes: Option<bool>,
+    pub dateFormat: Option<String>,
+    pub dailyNotesFolder: Option<String>,
+    pub trashRetentionDays: Option<u32>,
+    pub reauthForSensitive: Option<bool>,
+    pub floatingDefaults: Option<FloatingDefaults>,
+    pub passwordMaxAgeDays: Option<u32>,
+    pub historyVersions: Option<u32>,
+    pub watchVault: Option<bool>,
+    pub autoLockMinutes: Option<u32>,
+    pub passwordsAccessMinutes: Option<u32>,
+    pub mcpPort: Option<u16>,
+    pub mcpBindAddress: Option<String>,
+    pub masterPasswordPolicy: Option<MasterPasswordPolicy>,
 }
 
 #[tauri::command]
-pub fn updateGlobalSettings(storage: State<'_, StorageState>, input: UpdateSettingsInput) -> Result<(), String> {
+pub fn updateGlobalSettings(storage: State<'_, StorageState>, input: UpdateSettingsInput) -> Result<(), ClaudiaError> {
     println!("[updateGlobalSettings] Called");
     println!("[updateGlobalSettings] Updates - theme: {:?}, defaultMode: {:?}, defaultColor: {:?}",
              input.theme, input.defaultMode, input.defaultColor);
 
+    // Validate before touching anything so a bad address doesn't leave a partial update
+    let mcpBindAddress = input.mcpBindAddress.as_deref()
+        .map(parseMcpBindAddress)
+        .transpose()
+        .map_err(ClaudiaError::InvalidInput)?;
+
     {
         let mut settings = storage.globalSettings.write();
         if let Some(theme) = input.theme {
@@ -94,17 +213,110 @@ pub fn updateGlobalSettings(storage: State<'_, StorageState>, input: UpdateSetti
             println!("[updateGlobalSettings] Setting floatingOpacity to: {}", floatingOpacity);
             settings.floatingOpacity = floatingOpacity;
         }
+        if let Some(maxItemBytes) = input.maxItemBytes {
+            println!("[updateGlobalSettings] Setting maxItemBytes to: {}", maxItemBytes);
+            settings.maxItemBytes = maxItemBytes;
+        }
+        if let Some(lockOnQuit) = input.lockOnQuit {
+            println!("[updateGlobalSettings] Setting lockOnQuit to: {}", lockOnQuit);
+            settings.lockOnQuit = lockOnQuit;
+        }
+        if let Some(lockOnHide) = input.lockOnHide {
+            println!("[updateGlobalSettings] Setting lockOnHide to: {}", lockOnHide);
+            settings.lockOnHide = lockOnHide;
+        }
+        if let Some(backupSchedule) = input.backupSchedule {
+            println!("[updateGlobalSettings] Setting backupSchedule to: {}", backupSchedule);
+            settings.backupSchedule = backupSchedule;
+        }
+        if let Some(backupDir) = input.backupDir {
+            println!("[updateGlobalSettings] Setting backupDir to: {}", backupDir);
+            settings.backupDir = backupDir;
+        }
+        if let Some(backupRetention) = input.backupRetention {
+            println!("[updateGlobalSettings] Setting backupRetention to: {}", backupRetention);
+            settings.backupRetention = backupRetention;
+        }
+        if let Some(trackAccess) = input.trackAccess {
+            println!("[updateGlobalSettings] Setting trackAccess to: {}", trackAccess);
+            settings.trackAccess = trackAccess;
+        }
+        if let Some(folderLayout) = input.folderLayout {
+            println!("[updateGlobalSettings] Setting folderLayout to: {:?}", folderLayout);
+            settings.folderLayout = folderLayout;
+        }
+        if let Some(emojiShortcodes) = input.emojiShortcodes {
+            println!("[updateGlobalSettings] Setting emojiShortcodes to: {}", emojiShortcodes);
+            settings.emojiShortcodes = emojiShortcodes;
+        }
+        if let Some(dateFormat) = input.dateFormat {
+            println!("[updateGlobalSettings] Setting dateFormat to: {}", dateFormat);
+            settings.dateFormat = dateFormat;
+        }
+        if let Some(dailyNotesFolder) = input.dailyNotesFolder {
+            println!("[updateGlobalSettings] Setting dailyNotesFolder to: {}", dailyNotesFolder);
+            settings.dailyNotesFolder = dailyNotesFolder;
+        }
+        if let Some(trashRetentionDays) = input.trashRetentionDays {
+            println!("[updateGlobalSettings] Setting trashRetentionDays to: {}", trashRetentionDays);
+            settings.trashRetentionDays = trashRetentionDays;
+        }
+        if let Some(reauthForSensitive) = input.reauthForSensitive {
+            println!("[updateGlobalSettings] Setting reauthForSensitive to: {}", reauthForSensitive);
+            settings.reauthForSensitive = reauthForSensitive;
+        }
+        if let Some(floatingDefaults) = input.floatingDefaults {
+            println!("[updateGlobalSettings] Setting floatingDefaults to: {:?}", floatingDefaults);
+            settings.floatingDefaults = floatingDefaults;
+        }
+        if let Some(passwordMaxAgeDays) = input.passwordMaxAgeDays {
+            println!("[updateGlobalSettings] Setting passwordMaxAgeDays to: {}", passwordMaxAgeDays);
+            settings.passwordMaxAgeDays = passwordMaxAgeDays;
+        }
+        if let Some(historyVersions) = input.historyVersions {
+            println!("[updateGlobalSettings] Setting historyVersions to: {}", historyVersions);
+            settings.historyVersions = historyVersions;
+        }
+        if let Some(watchVault) = input.watchVault {
+            println!("[updateGlobalSettings] Setting watchVault to: {}", watchVault);
+            settings.watchVault = watchVault;
+        }
+        if let Some(autoLockMinutes) = input.autoLockMinutes {
+            println!("[updateGlobalSettings] Setting autoLockMinutes to: {}", autoLockMinutes);
+            settings.autoLockMinutes = autoLockMinutes;
+        }
+        if let Some(passwordsAccessMinutes) = input.passwordsAccessMinutes {
+            println!("[updateGlobalSettings] Setting passwordsAccessMinutes to: {}", passwordsAccessMinutes);
+            settings.passwordsAccessMinutes = passwordsAccessMinutes;
+        }
+        if let Some(mcpPort) = input.mcpPort {
+            println!("[updateGlobalSettings] Setting mcpPort to: {:?}", mcpPort);
+            settings.mcpPort = mcpPort;
+        }
+        if let Some(mcpBindAddress) = mcpBindAddress {
+            println!("[updateGlobalSettings] Setting mcpBindAddress to: {}", mcpBindAddress);
+            settings.mcpBindAddress = mcpBindAddress.to_string();
+        }
+        if let Some(masterPasswordPolicy) = input.masterPasswordPolicy {
+            println!("[updateGlobalSettings] Setting masterPasswordPolicy to: {:?}", masterPasswordPolicy);
+            settings.masterPasswordPolicy = masterPasswordPolicy;
+        }
     }
     saveGlobalConfig(&storage)?;
+    crate::watcher::syncVaultWatcher(&storage);
     println!("[updateGlobalSettings] SUCCESS");
     Ok(())
 }
 
 #[tauri::command]
-pub fn updateWorkspaceSettings(storage: State<'_, StorageState>, input: UpdateSettingsInput) -> Result<(), String> {
+pub fn updateWorkspaceSettings(storage: State<'_, StorageState>, input: UpdateSettingsInput) -> Result<(), ClaudiaError> {
     println!("[updateWorkspaceSettings] Called");
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    if input.mcpPort.is_some() || input.mcpBindAddress.is_some() {
+        return Err(ClaudiaError::InvalidInput("Invalid input: MCP port and bind address are global-only settings".to_string()));
+    }
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
     let configPath = workspaceConfigPath(&wsPath);
     println!("[updateWorkspaceSettings] Config path: {:?}", configPath);
 
@@ -149,6 +361,86 @@ pub fn updateWorkspaceSettings(storage: State<'_, StorageState>, input: UpdateSe
         println!("[updateWorkspaceSettings] Setting floatingOpacity: {:?}", input.floatingOpacity);
         override_settings.floatingOpacity = input.floatingOpacity;
     }
+    if input.maxItemBytes.is_some() {
+        println!("[updateWorkspaceSettings] Setting maxItemBytes: {:?}", input.maxItemBytes);
+        override_settings.maxItemBytes = input.maxItemBytes;
+    }
+    if input.lockOnQuit.is_some() {
+        println!("[updateWorkspaceSettings] Setting lockOnQuit: {:?}", input.lockOnQuit);
+        override_settings.lockOnQuit = input.lockOnQuit;
+    }
+    if input.lockOnHide.is_some() {
+        println!("[updateWorkspaceSettings] Setting lockOnHide: {:?}", input.lockOnHide);
+        override_settings.lockOnHide = input.lockOnHide;
+    }
+    if input.backupSchedule.is_some() {
+        println!("[updateWorkspaceSettings] Setting backupSchedule: {:?}", input.backupSchedule);
+        override_settings.backupSchedule = input.backupSchedule;
+    }
+    if input.backupDir.is_some() {
+        println!("[updateWorkspaceSettings] Setting backupDir: {:?}", input.backupDir);
+        override_settings.backupDir = input.backupDir;
+    }
+    if input.backupRetention.is_some() {
+        println!("[updateWorkspaceSettings] Setting backupRetention: {:?}", input.backupRetention);
+        override_settings.backupRetention = input.backupRetention;
+    }
+    if input.trackAccess.is_some() {
+        println!("[updateWorkspaceSettings] Setting trackAccess: {:?}", input.trackAccess);
+        override_settings.trackAccess = input.trackAccess;
+    }
+    if input.folderLayout.is_some() {
+        println!("[updateWorkspaceSettings] Setting folderLayout: {:?}", input.folderLayout);
+        override_settings.folderLayout = input.folderLayout;
+    }
+    if input.emojiShortcodes.is_some() {
+        println!("[updateWorkspaceSettings] Setting emojiShortcodes: {:?}", input.emojiShortcodes);
+        override_settings.emojiShortcodes = input.emojiShortcodes;
+    }
+    if input.dateFormat.is_some() {
+        println!("[updateWorkspaceSettings] Setting dateFormat: {:?}", input.dateFormat);
+        override_settings.dateFormat = input.dateFormat;
+    }
+    if input.dailyNotesFolder.is_some() {
+        println!("[updateWorkspaceSettings] Setting dailyNotesFolder: {:?}", input.dailyNotesFolder);
+        override_settings.dailyNotesFolder = input.dailyNotesFolder;
+    }
+    if input.trashRetentionDays.is_some() {
+        println!("[updateWorkspaceSettings] Setting trashRetentionDays: {:?}", input.trashRetentionDays);
+        override_settings.trashRetentionDays = input.trashRetentionDays;
+    }
+    if input.reauthForSensitive.is_some() {
+        println!("[updateWorkspaceSettings] Setting reauthForSensitive: {:?}", input.reauthForSensitive);
+        override_settings.reauthForSensitive = input.reauthForSensitive;
+    }
+    if input.floatingDefaults.is_some() {
+        println!("[updateWorkspaceSettings] Setting floatingDefaults: {:?}", input.floatingDefaults);
+        override_settings.floatingDefaults = input.floatingDefaults;
+    }
+    if input.passwordMaxAgeDays.is_some() {
+        println!("[updateWorkspaceSettings] Setting passwordMaxAgeDays: {:?}", input.passwordMaxAgeDays);
+        override_settings.passwordMaxAgeDays = input.passwordMaxAgeDays;
+    }
+    if input.historyVersions.is_some() {
+        println!("[updateWorkspaceSettings] Setting historyVersions: {:?}", input.historyVersions);
+        override_settings.historyVersions = input.historyVersions;
+    }
+    if input.watchVault.is_some() {
+        println!("[updateWorkspaceSettings] Setting watchVault: {:?}", input.watchVault);
+        override_settings.watchVault = input.watchVault;
+    }
+    if input.autoLockMinutes.is_some() {
+        println!("[updateWorkspaceSettings] Setting autoLockMinutes: {:?}", input.autoLockMinutes);
+        override_settings.autoLockMinutes = input.autoLockMinutes;
+    }
+    if input.passwordsAccessMinutes.is_some() {
+        println!("[updateWorkspaceSettings] Setting passwordsAccessMinutes: {:?}", input.passwordsAccessMinutes);
+        override_settings.passwordsAccessMinutes = input.passwordsAccessMinutes;
+    }
+    if input.masterPasswordPolicy.is_some() {
+        println!("[updateWorkspaceSettings] Setting masterPasswordPolicy: {:?}", input.masterPasswordPolicy);
+        override_settings.masterPasswordPolicy = input.masterPasswordPolicy;
+    }
 
     // Save to workspace config
     let content = toMarkdown(&override_settings, "")?;
@@ -158,8 +450,54 @@ pub fn updateWorkspaceSettings(storage: State<'_, StorageState>, input: UpdateSe
     })?;
 
     // Update in-memory override
-    *storage.workspaceOverride.write() = override_settings;
+    storage.setWorkspaceOverride(override_settings);
+    crate::watcher::syncVaultWatcher(&storage);
 
     println!("[updateWorkspaceSettings] SUCCESS");
     Ok(())
 }
+
+/// Drop one field from the workspace override so the global value applies again
+/// Returns the new effective settings
+#[tauri::command]
+pub fn clearWorkspaceSettingOverride(storage: State<'_, StorageState>, field: String) -> Result<SettingsInfo, ClaudiaError> {
+    println!("[clearWorkspaceSettingOverride] Called with field: {}", field);
+
+    // Only settings fields can be reset; taskStatuses goes through setTaskStatuses
+    let known = serde_json::to_value(SettingsInfo::from(Settings::default()))
+        .map_err(|e| e.to_string())?;
+    if known.get(&field).is_none() {
+        return Err(ClaudiaError::InvalidInput(format!("Invalid input: unknown setting '{}'", field)));
+    }
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+    let configPath = workspaceConfigPath(&wsPath);
+
+    let override_settings = if configPath.exists() {
+        fs::read_to_string(&configPath)
+            .ok()
+            .and_then(|content| parseFrontmatter::<SettingsOverride>(&content).map(|(s, _)| s))
+            .unwrap_or_default()
+    } else {
+        SettingsOverride::default()
+    };
+
+    let mut fields = serde_json::to_value(&override_settings).map_err(|e| e.to_string())?;
+    let removed = fields.as_object_mut().and_then(|m| m.remove(&field)).is_some();
+    if !removed {
+        println!("[clearWorkspaceSettingOverride] {} was not overridden", field);
+        return Ok(storage.effectiveSettings().into());
+    }
+    let override_settings: SettingsOverride = serde_json::from_value(fields).map_err(|e| e.to_string())?;
+
+    let content = toMarkdown(&override_settings, "")?;
+    fs::write(&configPath, content).map_err(|e| {
+        println!("[clearWorkspaceSettingOverride] ERROR writing file: {}", e);
+        e.to_string()
+    })?;
+    storage.setWorkspaceOverride(override_settings);
+    crate::watcher::syncVaultWatcher(&storage);
+
+    println!("[clearWorkspaceSettingOverride] SUCCESS - {} now follows global settings", field);
+    Ok(storage.effectiveSettings().into())
+}
@@ -1,13 +1,17 @@
 // Task commands - complete implementation with encryption
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::State;
 
-use crate::storage::{StorageState, tasksDir, foldersDir, parseUuidFilename, uuidFilename, parseFrontmatter, trashTasksDir};
-use crate::encrypted_storage;
-use crate::models::{Task, TaskFrontmatter, TaskStatus, FloatWindow};
-use super::common::newId;
+use crate::error::ClaudiaError;
+use crate::storage::{StorageState, validateMoveTarget, foldersDir, parseUuidFilename, uuidFilename, parseFrontmatter, toMarkdown, trashTasksDir, recordTrashOrigin, forgetTrashOrigin, workspaceConfigPath, itemsDir, folderOfItemsDir, folderArg, itemsDirFor, isHistoryPath, writeFileAtomic};
+use crate::encrypted_storage::{self, fileIdOf};
+use crate::models::{Task, TaskFrontmatter, TaskStatus, FloatWindow, ItemKind, Recurrence, ResolvedColor, SettingsOverride, normalizeColor};
+use crate::undo::UndoAction;
+use super::floating::closeItemFloatingWindow;
+use super::common::{BatchResult, newId, now, validateItemSize, sanitizeTitle};
+use super::item::{ItemContent, ItemList, ListOptions, readContentsBatch, collectSkippedItems, missingItemError, rewriteMetadata, setItemRank, listVersions, snapshotVersion, restoreVersion, moveVersionHistory, removeVersionHistory};
 
 #[derive(serde::Serialize)]
 pub struct TaskInfo {
@@ -16,38 +20,49 @@ pub struct TaskInfo {
     pub rank: u32,
     pub status: TaskStatus,
     pub color: String,
+    pub resolvedColor: ResolvedColor,
     pub pinned: bool,
+    pub archived: bool,
     pub tags: Vec<String>,
     pub due: Option<i64>,
+    pub recurrence: Option<Recurrence>,
     pub created: i64,
     pub updated: i64,
     pub folderPath: String,
     pub path: String,
     pub float: FloatWindow,
+    pub lastAccessed: Option<i64>,
+    pub openCount: u32,
+    /// Only filled by getTasksWithProgress, which decrypts task bodies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checklistProgress: Option<ChecklistProgress>,
 }
 
 impl From<&Task> for TaskInfo {
     fn from(t: &Task) -> Self {
-        // folderPath should be the parent folder, not the /tasks subdirectory
-        // e.g., /folders/{uuid} instead of /folders/{uuid}/tasks
-        let folderPath = t.folderPath.parent()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_default();
+        // Report the folder itself, not its /tasks items dir (see itemsDir)
+        let folderPath = folderOfItemsDir(&t.folderPath).to_string_lossy().to_string();
 
         Self {
             id: t.frontmatter.id.clone(),
             title: t.frontmatter.title.clone(),
             rank: t.frontmatter.rank,
-            status: t.status,
+            status: t.status.clone(),
             color: t.frontmatter.color.clone(),
+            resolvedColor: ResolvedColor::of(&t.frontmatter.color),
             pinned: t.frontmatter.pinned,
+            archived: t.frontmatter.archived,
             tags: t.frontmatter.tags.clone(),
             due: t.frontmatter.due,
+            recurrence: t.frontmatter.recurrence,
             created: t.frontmatter.created,
             updated: t.frontmatter.updated,
             folderPath,
             path: t.path.to_string_lossy().to_string(),
             float: t.frontmatter.float.clone(),
+            lastAccessed: t.frontmatter.lastAccessed,
+            openCount: t.frontmatter.openCount,
+            checklistProgress: None,
         }
     }
 }
@@ -64,8 +79,8 @@ fn processTaskFile(path: &PathBuf, folderPath: &PathBuf, status: TaskStatus, mas
     // Check if file is encrypted
     if encrypted_storage::isEncryptedFormat(&content) {
         let password = masterPassword?;
-        let encrypted = encrypted_storage::parseEncryptedFile(&content).ok()?;
-        let yamlContent = encrypted_storage::decryptMetadata(&encrypted.metadata, password).ok()?;
+        let encrypted = encrypted_storage::parseEncryptedFile(&content, &fileIdOf(path)).ok()?;
+        let yamlContent = encrypted.decryptMetadata(password).ok()?;
         let fm: TaskFrontmatter = serde_yaml::from_str(&yamlContent).ok()?;
 
         Some(Task {
@@ -88,6 +103,24 @@ fn processTaskFile(path: &PathBuf, folderPath: &PathBuf, status: TaskStatus, mas
     }
 }
 
+/// Find a live task by id: the index points at its file, a full scan is the fallback
+pub(crate) fn findTaskById(storage: &StorageState, wsPath: &str, id: &str, masterPassword: Option<&str>) -> Option<Task> {
+    if let Some(path) = storage.indexedPath(ItemKind::Task, id) {
+        let statusDir = path.parent();
+        let status = statusDir.and_then(|d| d.file_name()).and_then(|n| TaskStatus::fromFolder(&n.to_string_lossy()));
+        let tasksDirPath = statusDir.and_then(Path::parent).map(Path::to_path_buf);
+        if let (Some(status), Some(tasksDirPath)) = (status, tasksDirPath)
+            && let Some(task) = processTaskFile(&path, &tasksDirPath, status, masterPassword).filter(|t| t.frontmatter.id == id)
+        {
+            return Some(task);
+        }
+    }
+
+    let task = scanAllTasks(&foldersDir(wsPath), masterPassword).into_iter().find(|t| t.frontmatter.id == id)?;
+    storage.indexItem(ItemKind::Task, id, &task.path);
+    Some(task)
+}
+
 /// Scan tasks in a status folder
 pub(crate) fn scanTasksInStatus(statusPath: &PathBuf, folderPath: &PathBuf, status: TaskStatus, masterPassword: Option<&str>) -> Vec<Task> {
     if !statusPath.exists() {
@@ -100,15 +133,15 @@ pub(crate) fn scanTasksInStatus(statusPath: &PathBuf, folderPath: &PathBuf, stat
     for entry in entries.into_iter().flatten().filter_map(|e| e.ok()) {
         let path = entry.path();
 
-        // Skip hidden files and non-markdown
-        if !path.is_file() || path.extension().map(|ext| ext != "md").unwrap_or(true) {
+        // Skip hidden files, non-markdown and saved versions
+        if !path.is_file() || path.extension().map(|ext| ext != "md").unwrap_or(true) || isHistoryPath(&path) {
             continue;
         }
         if entry.file_name().to_string_lossy().starts_with('.') {
             continue;
         }
 
-        if let Some(task) = processTaskFile(&path, folderPath, status, masterPassword) {
+        if let Some(task) = processTaskFile(&path, folderPath, status.clone(), masterPassword) {
             tasks.push(task);
         }
     }
@@ -122,7 +155,7 @@ pub(crate) fn scanTasksInStatus(statusPath: &PathBuf, folderPath: &PathBuf, stat
 pub(crate) fn scanTasksInFolder(folderPath: &PathBuf, masterPassword: Option<&str>) -> Vec<Task> {
     let mut allTasks = Vec::new();
 
-    for status in [TaskStatus::Todo, TaskStatus::Doing, TaskStatus::Done] {
+    for status in TaskStatus::all() {
         let statusPath = folderPath.join(status.folderName());
         allTasks.extend(scanTasksInStatus(&statusPath, folderPath, status, masterPassword));
     }
@@ -136,7 +169,7 @@ pub(crate) fn scanAllTasks(foldersBaseDir: &PathBuf, masterPassword: Option<&str
     let mut allTasks = Vec::new();
 
     // Tasks in root /folders/tasks/
-    let rootTasksDir = foldersBaseDir.join("tasks");
+    let rootTasksDir = itemsDir(foldersBaseDir, ItemKind::Task);
     if rootTasksDir.exists() {
         allTasks.extend(scanTasksInFolder(&rootTasksDir, masterPassword));
     }
@@ -161,7 +194,7 @@ fn scanTasksInFoldersRecursive(dir: &PathBuf, tasks: &mut Vec<Task>, masterPassw
 
             if path.is_dir() {
                 // Check if this folder has a tasks subdirectory
-                let tasksSubdir = path.join("tasks");
+                let tasksSubdir = itemsDir(&path, ItemKind::Task);
                 if tasksSubdir.exists() && tasksSubdir.is_dir() {
                     tasks.extend(scanTasksInFolder(&tasksSubdir, masterPassword));
                 }
@@ -174,68 +207,131 @@ fn scanTasksInFoldersRecursive(dir: &PathBuf, tasks: &mut Vec<Task>, masterPassw
 }
 
 #[tauri::command]
-pub fn getTasks(storage: State<'_, StorageState>, folderPath: Option<String>, status: Option<String>) -> Result<Vec<TaskInfo>, String> {
-    let wsPath = match storage.getWorkspacePath() {
+pub fn getTasks(
+    storage: State<'_, StorageState>,
+    folderPath: Option<String>,
+    status: Option<String>,
+    options: Option<ListOptions>,
+) -> Result<ItemList<TaskInfo>, ClaudiaError> {
+    let ListOptions { includeErrors, tags, tagMatch, includeArchived, sort, pinnedFirst, offset, limit } = options.unwrap_or_default();
+    let wsPath = match storage.getAvailableWorkspacePath()? {
         Some(p) => p,
-        None => return Ok(Vec::new()),
+        None => return Ok(ItemList::Items(Vec::new())),
     };
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
     let passwordRef = masterPassword.as_deref();
 
-    let tasks = match &folderPath {
-        Some(fp) if !fp.is_empty() => {
+    let (tasks, scanRoot) = match folderArg(folderPath.as_deref()) {
+        Some(folder) => {
             // Scan the tasks subdirectory within the specified folder
-            let tasksSubdir = PathBuf::from(fp).join("tasks");
-            scanTasksInFolder(&tasksSubdir, passwordRef)
+            let tasksSubdir = itemsDir(folder, ItemKind::Task);
+            (scanTasksInFolder(&tasksSubdir, passwordRef), tasksSubdir)
         },
-        _ => {
+        None => {
             // Scan all tasks across all folders
-            scanAllTasks(&foldersDir(&wsPath), passwordRef)
+            let foldersBase = foldersDir(&wsPath);
+            (scanAllTasks(&foldersBase, passwordRef), foldersBase)
         }
     };
 
+    // Compare against the unfiltered scan so status filtering isn't reported as skipped
+    let skipped = includeErrors.unwrap_or(false).then(|| {
+        let loaded: Vec<&Path> = tasks.iter().map(|t| t.path.as_path()).collect();
+        collectSkippedItems::<TaskFrontmatter>(&scanRoot, ItemKind::Task, &loaded, passwordRef)
+    });
+
     // Filter by status if provided
     let filteredTasks: Vec<_> = if let Some(statusStr) = status {
         let targetStatus = TaskStatus::fromFolder(&statusStr);
-        tasks.into_iter().filter(|t| targetStatus.map(|s| t.status == s).unwrap_or(true)).collect()
+        tasks.into_iter().filter(|t| targetStatus.as_ref().map(|s| &t.status == s).unwrap_or(true)).collect()
     } else {
         tasks
     };
 
+    // Filter by tags if provided
+    let tags = tags.unwrap_or_default();
+    let tagMatch = tagMatch.unwrap_or_default();
+    let includeArchived = includeArchived.unwrap_or(false);
+    let mut filteredTasks: Vec<_> = filteredTasks.into_iter()
+        .filter(|t| tagMatch.matches(&t.frontmatter.tags, &tags))
+        .filter(|t| includeArchived || !t.frontmatter.archived)
+        .collect();
+
+    // Without sort options the scan order (status, then rank) is kept,
+    // unless paging: pages need an order that can't shift between calls
+    let pinnedFirst = pinnedFirst.unwrap_or(false);
+    if sort.is_some() || pinnedFirst || offset.is_some() || limit.is_some() {
+        sort.unwrap_or_default().sort(&mut filteredTasks, pinnedFirst, |t| &t.frontmatter);
+    }
+
+    storage.updateActivity();
+    Ok(ItemList::paged(filteredTasks.iter().map(TaskInfo::from).collect(), skipped, offset, limit))
+}
+
+/// Tasks of a folder with their checklist progress; slower than getTasks since every body is decrypted
+#[tauri::command]
+pub fn getTasksWithProgress(storage: State<'_, StorageState>, folderPath: Option<String>) -> Result<Vec<TaskInfo>, ClaudiaError> {
+    let wsPath = match storage.getAvailableWorkspacePath()? {
+        Some(p) => p,
+        None => return Ok(Vec::new()),
+    };
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let tasks = match folderArg(folderPath.as_deref()) {
+        Some(folder) => scanTasksInFolder(&itemsDir(folder, ItemKind::Task), Some(&masterPassword)),
+        None => scanAllTasks(&foldersDir(&wsPath), Some(&masterPassword)),
+    };
+
+    let infos = tasks.iter().map(|t| {
+        // Legacy plaintext tasks already carry their body
+        let body = if t.content.is_empty() {
+            encrypted_storage::readDecryptedBody(&t.path, &masterPassword).ok().flatten().unwrap_or_default()
+        } else {
+            t.content.clone()
+        };
+        TaskInfo { checklistProgress: Some(checklistProgress(&body)), ..TaskInfo::from(t) }
+    }).collect();
+
     storage.updateActivity();
-    Ok(filteredTasks.iter().map(TaskInfo::from).collect())
+    Ok(infos)
 }
 
 #[tauri::command]
-pub fn getTaskById(storage: State<'_, StorageState>, id: String) -> Result<Option<TaskInfo>, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn getTaskById(storage: State<'_, StorageState>, id: String) -> Result<Option<TaskInfo>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
     let passwordRef = masterPassword.as_deref();
 
-    let tasks = scanAllTasks(&foldersDir(&wsPath), passwordRef);
+    let task = findTaskById(&storage, &wsPath, &id, passwordRef);
     storage.updateActivity();
-    Ok(tasks.iter().find(|t| t.frontmatter.id == id).map(TaskInfo::from))
+    Ok(task.as_ref().map(TaskInfo::from))
 }
 
 #[tauri::command]
-pub fn getTaskContent(storage: State<'_, StorageState>, id: String) -> Result<String, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn getTaskContent(storage: State<'_, StorageState>, id: String, sessionToken: Option<String>) -> Result<String, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
+    storage.checkSessionToken(sessionToken.as_deref())?;
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
     // Search in regular folders first
     let tasks = scanAllTasks(&foldersDir(&wsPath), Some(&masterPassword));
@@ -246,35 +342,52 @@ pub fn getTaskContent(storage: State<'_, StorageState>, id: String) -> Result<St
     let task = if let Some(t) = taskOpt {
         t
     } else {
-        // Scan all status folders in trash
-        let trashTasksPath = trashTasksDir(&wsPath);
-        let mut trashTasks = Vec::new();
-        for status in [TaskStatus::Todo, TaskStatus::Doing, TaskStatus::Done] {
-            let statusPath = trashTasksPath.join(status.folderName());
-            if statusPath.exists() {
-                trashTasks.extend(scanTasksInStatus(&statusPath, &trashTasksPath, status, Some(&masterPassword)));
-            }
-        }
+        let trashTasks = scanTrashTasks(&wsPath, Some(&masterPassword));
         trashTask = trashTasks.into_iter().find(|t| t.frontmatter.id == id)
-            .ok_or_else(|| "Task not found".to_string())?;
+            .ok_or_else(|| missingItemError(&storage, ItemKind::Task, &id))?;
         &trashTask
     };
 
-    // Read and decrypt content from file
-    let fileContent = fs::read_to_string(&task.path)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+    // Read and decrypt content (legacy unencrypted tasks keep their parsed body)
+    let content = encrypted_storage::readDecryptedBody(&task.path, &masterPassword)?
+        .unwrap_or_else(|| task.content.clone());
 
-    let content = if encrypted_storage::isEncryptedFormat(&fileContent) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-        encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
-    } else {
-        task.content.clone()
-    };
+    if storage.effectiveSettings().trackAccess {
+        let mut fm = task.frontmatter.clone();
+        fm.lastAccessed = Some(chrono::Utc::now().timestamp_millis());
+        fm.openCount = fm.openCount.saturating_add(1);
+        let fileContent = encrypted_storage::serializeAndEncrypt(&fm, &content, &masterPassword, &fileIdOf(&task.path))?;
+        writeFileAtomic(&task.path, fileContent)?;
+    }
 
     storage.updateActivity();
     Ok(content)
 }
 
+/// Batch decrypt the bodies of several tasks with a single scan
+/// Unlike getTaskContent, this does not count as opening the tasks (no access tracking).
+#[tauri::command]
+pub fn getTaskContentsBatch(storage: State<'_, StorageState>, ids: Vec<String>) -> Result<Vec<ItemContent>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    // Scan all tasks once
+    let tasks = scanAllTasks(&foldersDir(&wsPath), Some(&masterPassword));
+    let results = readContentsBatch(
+        ids,
+        tasks.iter().map(|t| (t.frontmatter.id.as_str(), t.path.as_path(), t.content.as_str())),
+        &masterPassword,
+    )?;
+
+    storage.updateActivity();
+    Ok(results)
+}
+
 #[derive(serde::Deserialize)]
 pub struct CreateTaskInput {
     pub title: String,
@@ -283,41 +396,42 @@ pub struct CreateTaskInput {
     pub content: Option<String>,
     pub color: Option<String>,
     pub due: Option<i64>,
+    pub recurrence: Option<Recurrence>,
 }
 
 #[tauri::command]
-pub fn createTask(storage: State<'_, StorageState>, input: CreateTaskInput) -> Result<TaskInfo, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace selected")?;
+pub fn createTask(storage: State<'_, StorageState>, input: CreateTaskInput) -> Result<TaskInfo, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    // Reject oversized bodies before doing any decryption/encryption work
+    if let Some(ref content) = input.content {
+        validateItemSize(content, storage.effectiveSettings().maxItemBytes)?;
+    }
 
     println!("[createTask] Received folderPath: {:?}", input.folderPath);
     println!("[createTask] Workspace path: {}", wsPath);
 
     // If folderPath is provided, create tasks in folderPath/tasks/
     // Otherwise use the root workspace/folders/tasks/
-    let tasksBasePath = match &input.folderPath {
-        Some(p) if !p.is_empty() && p != "null" && p.starts_with('/') => {
-            // Create tasks in the folder's tasks subdirectory
-            PathBuf::from(p).join("tasks")
-        }
-        _ => tasksDir(&wsPath, ""),
-    };
+    let tasksBasePath = itemsDirFor(&wsPath, input.folderPath.as_deref(), ItemKind::Task)?;
 
     println!("[createTask] Using tasksBasePath: {:?}", tasksBasePath);
     let status = input.status
         .and_then(|s| TaskStatus::fromFolder(&s))
-        .unwrap_or(TaskStatus::Todo);
+        .or_else(|| TaskStatus::all().into_iter().next())
+        .unwrap_or_default();
 
     let statusPath = tasksBasePath.join(status.folderName());
-    fs::create_dir_all(&statusPath).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&statusPath)?;
 
     // Find next rank from existing tasks
-    let existingTasks = scanTasksInStatus(&statusPath, &tasksBasePath, status, Some(&masterPassword));
+    let existingTasks = scanTasksInStatus(&statusPath, &tasksBasePath, status.clone(), Some(&masterPassword));
     let nextRank = existingTasks.iter().map(|t| t.frontmatter.rank).max().unwrap_or(0) + 1;
 
     // UUID is the filename
@@ -325,19 +439,21 @@ pub fn createTask(storage: State<'_, StorageState>, input: CreateTaskInput) -> R
     let filename = uuidFilename(&id);
     let taskPath = statusPath.join(&filename);
 
-    let mut fm = TaskFrontmatter::new(id, input.title.clone(), nextRank);
+    let mut fm = TaskFrontmatter::new(id, sanitizeTitle(&input.title, storage.effectiveSettings().emojiShortcodes), nextRank);
     if let Some(color) = input.color {
-        fm.color = color;
+        fm.color = normalizeColor(&color)?;
     }
     if let Some(due) = input.due {
         fm.due = Some(due);
     }
+    fm.recurrence = input.recurrence.filter(|r| r.every > 0);
 
     let body = input.content.unwrap_or_default();
 
     // Encrypt and save
-    let fileContent = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
-    fs::write(&taskPath, fileContent).map_err(|e| e.to_string())?;
+    let fileContent = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&taskPath))?;
+    writeFileAtomic(&taskPath, fileContent)?;
+    storage.indexItem(ItemKind::Task, &fm.id, &taskPath);
 
     let task = Task {
         path: taskPath,
@@ -351,6 +467,56 @@ pub fn createTask(storage: State<'_, StorageState>, input: CreateTaskInput) -> R
     Ok(TaskInfo::from(&task))
 }
 
+/// Copy a task into its own folder and status, titled "... (copy)" and ranked right after the original
+#[tauri::command]
+pub fn duplicateTask(storage: State<'_, StorageState>, id: String) -> Result<TaskInfo, ClaudiaError> {
+    println!("[duplicateTask] Called with id: {}", id);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let original = findTaskById(&storage, &wsPath, &id, Some(&masterPassword)).ok_or_else(|| ClaudiaError::NotFound("Task not found".to_string()))?;
+    let body = encrypted_storage::readDecryptedBody(&original.path, &masterPassword)?.unwrap_or_default();
+    let statusPath = original.path.parent().ok_or("No parent")?.to_path_buf();
+
+    // Make room right after the original
+    for sibling in scanTasksInStatus(&statusPath, &original.folderPath, original.status.clone(), Some(&masterPassword)) {
+        if sibling.frontmatter.rank > original.frontmatter.rank {
+            setItemRank(&sibling.path, sibling.frontmatter.rank + 1, &masterPassword)?;
+        }
+    }
+
+    let copyId = newId();
+    let taskPath = statusPath.join(uuidFilename(&copyId));
+    let mut fm = TaskFrontmatter::new(copyId.clone(), format!("{} (copy)", original.frontmatter.title), original.frontmatter.rank + 1);
+    fm.color = original.frontmatter.color.clone();
+    fm.pinned = original.frontmatter.pinned;
+    fm.tags = original.frontmatter.tags.clone();
+    fm.due = original.frontmatter.due;
+    fm.recurrence = original.frontmatter.recurrence;
+
+    let fileContent = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&taskPath))?;
+    writeFileAtomic(&taskPath, fileContent)?;
+    storage.indexItem(ItemKind::Task, &copyId, &taskPath);
+
+    let task = Task {
+        path: taskPath,
+        folderPath: original.folderPath,
+        status: original.status,
+        frontmatter: fm,
+        content: body,
+    };
+
+    storage.updateActivity();
+    println!("[duplicateTask] SUCCESS - copy id: {}", copyId);
+    Ok(TaskInfo::from(&task))
+}
+
 #[derive(serde::Deserialize)]
 pub struct UpdateTaskInput {
     pub id: String,
@@ -361,18 +527,25 @@ pub struct UpdateTaskInput {
     pub pinned: Option<bool>,
     pub tags: Option<Vec<String>>,
     pub due: Option<i64>,
+    /// New recurrence; `every: 0` stops the task from recurring
+    pub recurrence: Option<Recurrence>,
     pub float: Option<FloatWindow>,
 }
 
 #[tauri::command]
-pub fn updateTask(storage: State<'_, StorageState>, input: UpdateTaskInput) -> Result<(), String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn updateTask(storage: State<'_, StorageState>, input: UpdateTaskInput) -> Result<(), ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    // Reject oversized bodies before doing any decryption/encryption work
+    if let Some(ref content) = input.content {
+        validateItemSize(content, storage.effectiveSettings().maxItemBytes)?;
+    }
 
     // Search in regular folders first
     let tasks = scanAllTasks(&foldersDir(&wsPath), Some(&masterPassword));
@@ -383,17 +556,9 @@ pub fn updateTask(storage: State<'_, StorageState>, input: UpdateTaskInput) -> R
     let task = if let Some(t) = taskOpt {
         t
     } else {
-        // Scan all status folders in trash
-        let trashTasksPath = trashTasksDir(&wsPath);
-        let mut trashTasks = Vec::new();
-        for status in [TaskStatus::Todo, TaskStatus::Doing, TaskStatus::Done] {
-            let statusPath = trashTasksPath.join(status.folderName());
-            if statusPath.exists() {
-                trashTasks.extend(scanTasksInStatus(&statusPath, &trashTasksPath, status, Some(&masterPassword)));
-            }
-        }
+        let trashTasks = scanTrashTasks(&wsPath, Some(&masterPassword));
         trashTask = trashTasks.into_iter().find(|t| t.frontmatter.id == input.id)
-            .ok_or("Task not found")?;
+            .ok_or_else(|| ClaudiaError::NotFound("Task not found".to_string()))?;
         &trashTask
     };
 
@@ -405,21 +570,21 @@ pub fn updateTask(storage: State<'_, StorageState>, input: UpdateTaskInput) -> R
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
     let mut body = if encrypted_storage::isEncryptedFormat(&fileContent) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-        encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
+        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&task.path))?;
+        encrypted.decryptContent(&masterPassword)?
     } else {
         task.content.clone()
     };
 
     // Handle title change (filename no longer changes with title)
     if let Some(ref title) = input.title {
-        fm.title = title.clone();
+        fm.title = sanitizeTitle(title, storage.effectiveSettings().emojiShortcodes);
     }
     if let Some(content) = input.content {
         body = content;
     }
     if let Some(color) = input.color {
-        fm.color = color;
+        fm.color = normalizeColor(&color)?;
     }
     if let Some(pinned) = input.pinned {
         fm.pinned = pinned;
@@ -428,7 +593,10 @@ pub fn updateTask(storage: State<'_, StorageState>, input: UpdateTaskInput) -> R
         fm.tags = tags;
     }
     if let Some(due) = input.due {
-        fm.due = Some(due);
+        fm.setDue(due);
+    }
+    if let Some(recurrence) = input.recurrence {
+        fm.recurrence = (recurrence.every > 0).then_some(recurrence);
     }
     if let Some(float) = input.float {
         fm.float = float;
@@ -438,14 +606,14 @@ pub fn updateTask(storage: State<'_, StorageState>, input: UpdateTaskInput) -> R
     let targetStatus = input.status
         .as_ref()
         .and_then(|s| TaskStatus::fromFolder(s))
-        .unwrap_or(task.status);
+        .unwrap_or_else(|| task.status.clone());
 
     let statusChanged = targetStatus != task.status;
 
     // Handle status change (move file to different status folder)
     if statusChanged {
         let targetStatusPath = task.folderPath.join(targetStatus.folderName());
-        fs::create_dir_all(&targetStatusPath).map_err(|e| e.to_string())?;
+        fs::create_dir_all(&targetStatusPath)?;
 
         // Same UUID filename, different status folder
         newPath = targetStatusPath.join(uuidFilename(&task.frontmatter.id));
@@ -454,29 +622,101 @@ pub fn updateTask(storage: State<'_, StorageState>, input: UpdateTaskInput) -> R
 
     fm.updated = chrono::Utc::now().timestamp_millis();
 
+    snapshotVersion(&task.folderPath, &task.path, &fm.id, storage.effectiveSettings().historyVersions)?;
+
     // Encrypt and save
-    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
+    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&newPath))?;
 
     // If path changed (status change), write to new location and remove old
     if newPath != task.path {
-        fs::write(&newPath, &content).map_err(|e| e.to_string())?;
-        fs::remove_file(&task.path).map_err(|e| e.to_string())?;
+        writeFileAtomic(&newPath, &content)?;
+        fs::remove_file(&task.path)?;
     } else {
-        fs::write(&newPath, content).map_err(|e| e.to_string())?;
+        writeFileAtomic(&newPath, content)?;
+    }
+    if taskOpt.is_some() {
+        storage.indexItem(ItemKind::Task, &fm.id, &newPath);
+    }
+
+    // Trashed tasks don't recur
+    if statusChanged
+        && targetStatus == TaskStatus::Done
+        && taskOpt.is_some()
+        && let Some(next) = spawnRecurringTask(&fm, &body, &task.folderPath, &masterPassword)?
+    {
+        storage.indexItem(ItemKind::Task, &next.frontmatter.id, &next.path);
+    }
+
+    storage.updateActivity();
+    Ok(())
+}
+
+/// Pin or unpin a task without touching its body
+#[tauri::command]
+pub fn setTaskPinned(storage: State<'_, StorageState>, id: String, pinned: bool) -> Result<bool, ClaudiaError> {
+    println!("[setTaskPinned] Called with id: {}, pinned: {}", id, pinned);
+
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let path = storage.itemPath(ItemKind::Task, &id).ok_or_else(|| ClaudiaError::NotFound("Task not found".to_string()))?;
+    let fm = rewriteMetadata(&path, &masterPassword, |fm: &mut TaskFrontmatter| {
+        fm.pinned = pinned;
+    })?;
+
+    storage.updateActivity();
+    Ok(fm.pinned)
+}
+
+/// Hide a task from listings without trashing it
+#[tauri::command]
+pub fn archiveTask(storage: State<'_, StorageState>, id: String) -> Result<(), ClaudiaError> {
+    setTaskArchived(&storage, &id, true)
+}
+
+#[tauri::command]
+pub fn unarchiveTask(storage: State<'_, StorageState>, id: String) -> Result<(), ClaudiaError> {
+    setTaskArchived(&storage, &id, false)
+}
+
+fn setTaskArchived(storage: &StorageState, id: &str, archived: bool) -> Result<(), ClaudiaError> {
+    println!("[setTaskArchived] Called with id: {}, archived: {}", id, archived);
+
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
     }
 
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let path = storage.itemPath(ItemKind::Task, id).ok_or_else(|| ClaudiaError::NotFound("Task not found".to_string()))?;
+    rewriteMetadata(&path, &masterPassword, |fm: &mut TaskFrontmatter| {
+        fm.archived = archived;
+    })?;
+
     storage.updateActivity();
     Ok(())
 }
 
 #[tauri::command]
-pub fn deleteTask(storage: State<'_, StorageState>, id: String, permanent: Option<bool>) -> Result<(), String> {
+pub fn deleteTask(
+    app: tauri::AppHandle,
+    storage: State<'_, StorageState>,
+    id: String,
+    permanent: Option<bool>,
+) -> Result<(), ClaudiaError> {
     println!("[deleteTask] Called with id: {}, permanent: {:?}", id, permanent);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
@@ -493,30 +733,58 @@ pub fn deleteTask(storage: State<'_, StorageState>, id: String, permanent: Optio
         isInTrash = false;
         t
     } else {
-        // Scan all status folders in trash
-        let trashTasksPath = trashTasksDir(&wsPath);
-        let mut trashTasks = Vec::new();
-        for status in [TaskStatus::Todo, TaskStatus::Doing, TaskStatus::Done] {
-            let statusPath = trashTasksPath.join(status.folderName());
-            if statusPath.exists() {
-                trashTasks.extend(scanTasksInStatus(&statusPath, &trashTasksPath, status, passwordRef));
-            }
-        }
+        let trashTasks = scanTrashTasks(&wsPath, passwordRef);
         trashTask = trashTasks.into_iter().find(|t| t.frontmatter.id == id)
-            .ok_or("Task not found")?;
+            .ok_or_else(|| ClaudiaError::NotFound("Task not found".to_string()))?;
         isInTrash = true;
         &trashTask
     };
     println!("[deleteTask] Found task at: {} (in trash: {})", task.path.display(), isInTrash);
 
     // If item is in trash, always permanently delete
-    if permanent.unwrap_or(false) || isInTrash {
-        // Permanent delete
+    let permanent = permanent.unwrap_or(false) || isInTrash;
+    removeTaskFile(&wsPath, task, permanent)?;
+    storage.unindexItem(&id);
+    if !permanent {
+        storage.recordUndo("deleteTask", vec![UndoAction::MoveBack {
+            kind: ItemKind::Task,
+            id: id.clone(),
+            current: trashTasksDir(&wsPath).join(task.status.folderName()).join(uuidFilename(&id)),
+            original: task.path.clone(),
+            rank: task.frontmatter.rank,
+        }]);
+    }
+
+    // The item is gone from its folder, so a floating window would only show a ghost
+    closeItemFloatingWindow(&app, "task", &id);
+
+    storage.updateActivity();
+    Ok(())
+}
+
+/// Tasks in trash, across all status folders
+fn scanTrashTasks(wsPath: &str, passwordRef: Option<&str>) -> Vec<Task> {
+    let trashTasksPath = trashTasksDir(wsPath);
+    let mut trashTasks = Vec::new();
+    for status in TaskStatus::all() {
+        let statusPath = trashTasksPath.join(status.folderName());
+        if statusPath.exists() {
+            trashTasks.extend(scanTasksInStatus(&statusPath, &trashTasksPath, status, passwordRef));
+        }
+    }
+    trashTasks
+}
+
+/// Move a task file to trash (keeping its status folder), or remove it when `permanent`
+fn removeTaskFile(wsPath: &str, task: &Task, permanent: bool) -> Result<(), String> {
+    if permanent {
         fs::remove_file(&task.path).map_err(|e| e.to_string())?;
+        removeVersionHistory(&task.folderPath, &task.frontmatter.id);
+        forgetTrashOrigin(wsPath, &task.frontmatter.id);
         println!("[deleteTask] SUCCESS - permanently deleted");
     } else {
         // Move to trash - preserve status folder structure
-        let trashDir = trashTasksDir(&wsPath);
+        let trashDir = trashTasksDir(wsPath);
         let statusDir = trashDir.join(task.status.folderName());
         fs::create_dir_all(&statusDir).map_err(|e| e.to_string())?;
 
@@ -525,24 +793,67 @@ pub fn deleteTask(storage: State<'_, StorageState>, id: String, permanent: Optio
             println!("[deleteTask] ERROR moving to trash: {}", e);
             e.to_string()
         })?;
+        moveVersionHistory(&task.folderPath, &trashDir, &task.frontmatter.id);
+        recordTrashOrigin(wsPath, &task.frontmatter.id, &task.folderPath);
         println!("[deleteTask] SUCCESS - moved to trash at: {}", trashPath.display());
     }
+    Ok(())
+}
+
+/// Delete many tasks with a single scan; tasks already in trash are always removed permanently
+#[tauri::command]
+pub fn deleteTasksBatch(
+    app: tauri::AppHandle,
+    storage: State<'_, StorageState>,
+    ids: Vec<String>,
+    permanent: Option<bool>,
+) -> Result<Vec<BatchResult>, ClaudiaError> {
+    println!("[deleteTasksBatch] Called with {} ids, permanent: {:?}", ids.len(), permanent);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword();
+    let passwordRef = masterPassword.as_deref();
 
+    let tasks = scanAllTasks(&foldersDir(&wsPath), passwordRef);
+    let trashTasks = scanTrashTasks(&wsPath, passwordRef);
+    let permanent = permanent.unwrap_or(false);
+
+    let results: Vec<BatchResult> = ids.into_iter().map(|id| {
+        let result = if let Some(task) = tasks.iter().find(|t| t.frontmatter.id == id) {
+            removeTaskFile(&wsPath, task, permanent)
+        } else if let Some(task) = trashTasks.iter().find(|t| t.frontmatter.id == id) {
+            removeTaskFile(&wsPath, task, true)
+        } else {
+            Err("Task not found".to_string())
+        };
+        if result.is_ok() {
+            storage.unindexItem(&id);
+            closeItemFloatingWindow(&app, "task", &id);
+        }
+        BatchResult::new(id, result)
+    }).collect();
+
+    println!("[deleteTasksBatch] Deleted {} of {}", results.iter().filter(|r| r.success).count(), results.len());
     storage.updateActivity();
-    Ok(())
+    Ok(results)
 }
 
 #[tauri::command]
-pub fn moveTaskToFolder(storage: State<'_, StorageState>, id: String, targetFolderPath: String) -> Result<TaskInfo, String> {
+pub fn moveTaskToFolder(storage: State<'_, StorageState>, id: String, targetFolderPath: String) -> Result<TaskInfo, ClaudiaError> {
     println!("[moveTaskToFolder] Called with id: {}, targetFolderPath: {}", id, targetFolderPath);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
     // Search in regular folders first
     let tasks = scanAllTasks(&foldersDir(&wsPath), Some(&masterPassword));
@@ -553,53 +864,65 @@ pub fn moveTaskToFolder(storage: State<'_, StorageState>, id: String, targetFold
     let task = if let Some(t) = taskOpt {
         t
     } else {
-        // Scan all status folders in trash
-        let trashTasksPath = trashTasksDir(&wsPath);
-        let mut trashTasks = Vec::new();
-        for status in [TaskStatus::Todo, TaskStatus::Doing, TaskStatus::Done] {
-            let statusPath = trashTasksPath.join(status.folderName());
-            if statusPath.exists() {
-                trashTasks.extend(scanTasksInStatus(&statusPath, &trashTasksPath, status, Some(&masterPassword)));
-            }
-        }
+        let trashTasks = scanTrashTasks(&wsPath, Some(&masterPassword));
         trashTask = trashTasks.into_iter().find(|t| t.frontmatter.id == id)
-            .ok_or("Task not found")?;
+            .ok_or_else(|| ClaudiaError::NotFound("Task not found".to_string()))?;
         &trashTask
     };
     println!("[moveTaskToFolder] Found task at: {}", task.path.display());
 
     // Target is the tasks subdirectory within the folder
-    let targetTasksDir = PathBuf::from(&targetFolderPath).join("tasks");
+    let targetTasksDir = itemsDir(Path::new(&targetFolderPath), ItemKind::Task);
 
     // Ensure target folder and status subfolder exist
     let statusPath = targetTasksDir.join(task.status.folderName());
-    fs::create_dir_all(&statusPath).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&statusPath)?;
 
     // Find next rank in target status folder
-    let existingTasks = scanTasksInStatus(&statusPath, &targetTasksDir, task.status, Some(&masterPassword));
+    let existingTasks = scanTasksInStatus(&statusPath, &targetTasksDir, task.status.clone(), Some(&masterPassword));
     let nextRank = existingTasks.iter().map(|t| t.frontmatter.rank).max().unwrap_or(0) + 1;
 
+    let movedTask = relocateTask(task, &targetTasksDir, nextRank, &masterPassword)?;
+    storage.indexItem(ItemKind::Task, &id, &movedTask.path);
+    if movedTask.path != task.path {
+        storage.recordUndo("moveTaskToFolder", vec![UndoAction::MoveBack {
+            kind: ItemKind::Task,
+            id: id.clone(),
+            current: movedTask.path.clone(),
+            original: task.path.clone(),
+            rank: task.frontmatter.rank,
+        }]);
+    }
+
+    println!("[moveTaskToFolder] SUCCESS");
+    storage.updateActivity();
+    Ok(TaskInfo::from(&movedTask))
+}
+
+/// Re-encrypt a task into the same status folder under `targetTasksDir` with a new rank
+/// and remove the old file
+fn relocateTask(task: &Task, targetTasksDir: &Path, rank: u32, masterPassword: &str) -> Result<Task, String> {
     // Same UUID filename, new location
-    let newPath = statusPath.join(uuidFilename(&task.frontmatter.id));
+    let newPath = targetTasksDir.join(task.status.folderName()).join(uuidFilename(&task.frontmatter.id));
 
     // Update frontmatter with new rank
     let mut fm = task.frontmatter.clone();
-    fm.rank = nextRank;
+    fm.rank = rank;
 
     // Get content from file
     let fileContent = fs::read_to_string(&task.path)
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
     let body = if encrypted_storage::isEncryptedFormat(&fileContent) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-        encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
+        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&task.path))?;
+        encrypted.decryptContent(masterPassword)?
     } else {
         task.content.clone()
     };
 
     // Encrypt and write to new location
-    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
-    fs::write(&newPath, &content).map_err(|e| e.to_string())?;
+    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, masterPassword, &fileIdOf(&newPath))?;
+    writeFileAtomic(&newPath, &content).map_err(|e| e.to_string())?;
 
     // Remove old file
     fs::remove_file(&task.path).map_err(|e| {
@@ -607,20 +930,71 @@ pub fn moveTaskToFolder(storage: State<'_, StorageState>, id: String, targetFold
         e.to_string()
     })?;
 
+    moveVersionHistory(&task.folderPath, targetTasksDir, &task.frontmatter.id);
     println!("[moveTaskToFolder] Moved {} -> {}", task.path.display(), newPath.display());
 
-    // Build and return updated TaskInfo
-    let movedTask = Task {
+    Ok(Task {
         path: newPath,
-        folderPath: targetTasksDir,
-        status: task.status,
+        folderPath: targetTasksDir.to_path_buf(),
+        status: task.status.clone(),
         frontmatter: fm,
         content: body,
-    };
+    })
+}
 
-    println!("[moveTaskToFolder] SUCCESS");
+/// Move many tasks into one folder with a single scan, keeping each task's status
+/// Tasks are appended after the existing tasks of their status column;
+/// tasks already in the target are returned unchanged
+#[tauri::command]
+pub fn moveTasksToFolder(storage: State<'_, StorageState>, ids: Vec<String>, targetFolderPath: String) -> Result<Vec<TaskInfo>, ClaudiaError> {
+    println!("[moveTasksToFolder] Called with {} ids, targetFolderPath: {}", ids.len(), targetFolderPath);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let targetFolder = validateMoveTarget(&wsPath, &targetFolderPath)?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let mut tasks = scanAllTasks(&foldersDir(&wsPath), Some(&masterPassword));
+    tasks.extend(scanTrashTasks(&wsPath, Some(&masterPassword)));
+
+    // Resolve every id before touching any file
+    let sources = ids.iter()
+        .map(|id| tasks.iter().find(|t| &t.frontmatter.id == id).ok_or_else(|| ClaudiaError::NotFound(format!("Task not found: {}", id))))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let targetTasksDir = itemsDir(targetFolder, ItemKind::Task);
+
+    // Next rank per status column is computed once; each moved task takes the following one
+    let mut nextRanks: std::collections::HashMap<String, u32> = std::collections::HashMap::new();
+
+    let mut moved = Vec::with_capacity(sources.len());
+    for task in sources {
+        if task.folderPath == targetTasksDir {
+            moved.push(TaskInfo::from(task));
+            continue;
+        }
+        let status = task.status.folderName().to_string();
+        fs::create_dir_all(targetTasksDir.join(&status))?;
+        let rank = nextRanks.entry(status).or_insert_with(|| {
+            tasks.iter()
+                .filter(|t| t.folderPath == targetTasksDir && t.status == task.status)
+                .map(|t| t.frontmatter.rank)
+                .max()
+                .unwrap_or(0) + 1
+        });
+        let movedTask = relocateTask(task, &targetTasksDir, *rank, &masterPassword)?;
+        storage.indexItem(ItemKind::Task, &movedTask.frontmatter.id, &movedTask.path);
+        *rank += 1;
+        moved.push(TaskInfo::from(&movedTask));
+    }
+
+    println!("[moveTasksToFolder] SUCCESS - {} tasks", moved.len());
     storage.updateActivity();
-    Ok(TaskInfo::from(&movedTask))
+    Ok(moved)
 }
 
 #[derive(serde::Deserialize)]
@@ -631,29 +1005,25 @@ pub struct ReorderTasksInput {
 }
 
 #[tauri::command]
-pub fn reorderTasks(storage: State<'_, StorageState>, input: ReorderTasksInput) -> Result<(), String> {
+pub fn reorderTasks(storage: State<'_, StorageState>, input: ReorderTasksInput) -> Result<(), ClaudiaError> {
     println!("[reorderTasks] Called with folderPath: {}, status: {}", input.folderPath, input.status);
     println!("[reorderTasks] Task IDs to reorder: {:?}", input.taskIds);
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
 
     // Parse the status
-    let status = TaskStatus::fromFolder(&input.status).ok_or("Invalid status")?;
+    let status = TaskStatus::fromFolder(&input.status).ok_or_else(|| ClaudiaError::InvalidInput("Invalid status".to_string()))?;
 
     // Determine the tasks directory
     // If folderPath is provided, tasks are in {folderPath}/tasks/{status}/
     // If empty, tasks are in the root tasks folder
-    let tasksDirPath = if input.folderPath.is_empty() {
-        tasksDir(&wsPath, "")
-    } else {
-        PathBuf::from(&input.folderPath).join("tasks")
-    };
+    let tasksDirPath = itemsDirFor(&wsPath, Some(&input.folderPath), ItemKind::Task)?;
 
     let statusPath = tasksDirPath.join(status.folderName());
     println!("[reorderTasks] Scanning tasks in: {:?}", statusPath);
@@ -661,6 +1031,9 @@ pub fn reorderTasks(storage: State<'_, StorageState>, input: ReorderTasksInput)
     let tasks = scanTasksInStatus(&statusPath, &tasksDirPath, status, Some(&masterPassword));
     println!("[reorderTasks] Found {} tasks", tasks.len());
 
+    // Ranks before the reorder, for undo
+    let mut previousRanks = Vec::new();
+
     // Update rank in frontmatter instead of renaming files
     for (index, taskId) in input.taskIds.iter().enumerate() {
         if let Some(task) = tasks.iter().find(|t| t.frontmatter.id == *taskId) {
@@ -677,21 +1050,399 @@ pub fn reorderTasks(storage: State<'_, StorageState>, input: ReorderTasksInput)
                     .map_err(|e| format!("Failed to read file: {}", e))?;
 
                 let body = if encrypted_storage::isEncryptedFormat(&fileContent) {
-                    let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-                    encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
+                    let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&task.path))?;
+                    encrypted.decryptContent(&masterPassword)?
                 } else {
                     task.content.clone()
                 };
 
-                let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
-                fs::write(&task.path, content).map_err(|e| {
+                let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&task.path))?;
+                writeFileAtomic(&task.path, content).map_err(|e| {
                     println!("[reorderTasks] ERROR: {}", e);
                     e.to_string()
                 })?;
+                previousRanks.push((task.path.clone(), task.frontmatter.rank));
             }
         }
     }
+    storage.recordUndo("reorderTasks", vec![UndoAction::RestoreRanks { ranks: previousRanks }]);
     println!("[reorderTasks] SUCCESS");
     storage.updateActivity();
     Ok(())
 }
+
+/// Status columns of the current workspace, in display order
+#[tauri::command]
+pub fn getTaskStatuses(_storage: State<'_, StorageState>) -> Vec<String> {
+    TaskStatus::all().iter().map(|s| s.folderName().to_string()).collect()
+}
+
+/// Count task files filed under a status folder anywhere in the workspace (including trash)
+fn countTasksWithStatus(wsPath: &str, status: &str) -> usize {
+    walkdir::WalkDir::new(wsPath)
+        .into_iter()
+        .filter_entry(|e| e.file_name().to_str() != Some(".assets"))
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let path = e.path();
+            let statusDir = path.parent();
+            e.file_type().is_file()
+                && e.file_name().to_str().and_then(parseUuidFilename).is_some()
+                && statusDir.and_then(|d| d.file_name()).and_then(|n| n.to_str()) == Some(status)
+                && statusDir.and_then(|d| d.parent()).and_then(|d| d.file_name()).and_then(|n| n.to_str()) == Some("tasks")
+        })
+        .count()
+}
+
+/// Normalize a requested status list, rejecting duplicates
+/// todo and done must stay: completion, recurrence and the MCP tools move tasks into them
+fn validateStatusList(statuses: &[String]) -> Result<Vec<String>, ClaudiaError> {
+    let mut names: Vec<String> = Vec::new();
+    for status in statuses {
+        let name = TaskStatus::validateName(status).map_err(ClaudiaError::InvalidInput)?;
+        if names.contains(&name) {
+            return Err(ClaudiaError::InvalidInput(format!("Invalid input: duplicate status '{}'", name)));
+        }
+        names.push(name);
+    }
+    for required in [TaskStatus::Todo, TaskStatus::Done] {
+        if !names.iter().any(|n| n == required.folderName()) {
+            return Err(ClaudiaError::InvalidInput(format!("Invalid input: the '{}' status cannot be removed", required.folderName())));
+        }
+    }
+    Ok(names)
+}
+
+/// Define the workspace's status columns (stored in the workspace config.md)
+/// A status can only be dropped once no task lives in its folder
+#[tauri::command]
+pub fn setTaskStatuses(storage: State<'_, StorageState>, statuses: Vec<String>) -> Result<Vec<String>, ClaudiaError> {
+    println!("[setTaskStatuses] Called with: {:?}", statuses);
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+    let names = validateStatusList(&statuses)?;
+
+    for removed in TaskStatus::all().iter().filter(|s| !names.iter().any(|n| n == s.folderName())) {
+        let count = countTasksWithStatus(&wsPath, removed.folderName());
+        if count > 0 {
+            return Err(format!(
+                "Status '{}' still has {} task(s); move them to another status first",
+                removed.folderName(), count
+            ).into());
+        }
+    }
+
+    let configPath = workspaceConfigPath(&wsPath);
+    let mut override_settings = fs::read_to_string(&configPath)
+        .ok()
+        .and_then(|content| parseFrontmatter::<SettingsOverride>(&content).map(|(s, _)| s))
+        .unwrap_or_default();
+    override_settings.taskStatuses = Some(names.clone());
+
+    writeFileAtomic(&configPath, toMarkdown(&override_settings, "")?)?;
+    storage.setWorkspaceOverride(override_settings);
+
+    println!("[setTaskStatuses] SUCCESS");
+    Ok(names)
+}
+
+// ============================================
+// VERSION HISTORY
+// ============================================
+
+/// Items dir ({folder}/tasks) of a task file at {folder}/tasks/{status}/{uuid}.md
+fn tasksDirOf(taskPath: &Path) -> Result<&Path, String> {
+    taskPath.parent().and_then(Path::parent).ok_or_else(|| "Invalid task path".to_string())
+}
+
+/// Saved versions of a task, newest first (timestamps in ms)
+#[tauri::command]
+pub fn getTaskHistory(storage: State<'_, StorageState>, id: String) -> Result<Vec<i64>, ClaudiaError> {
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let path = storage.itemPath(ItemKind::Task, &id)
+        .ok_or_else(|| missingItemError(&storage, ItemKind::Task, &id))?;
+
+    storage.updateActivity();
+    Ok(listVersions(tasksDirOf(&path)?, &id))
+}
+
+/// Replace a task with one of its saved versions; the task keeps its current status column
+#[tauri::command]
+pub fn restoreTaskVersion(storage: State<'_, StorageState>, id: String, timestamp: i64) -> Result<(), ClaudiaError> {
+    println!("[restoreTaskVersion] Called with id: {}, timestamp: {}", id, timestamp);
+
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let path = storage.itemPath(ItemKind::Task, &id)
+        .ok_or_else(|| missingItemError(&storage, ItemKind::Task, &id))?;
+
+    restoreVersion(tasksDirOf(&path)?, &path, &id, timestamp, storage.effectiveSettings().historyVersions)?;
+
+    println!("[restoreTaskVersion] SUCCESS");
+    storage.updateActivity();
+    Ok(())
+}
+
+// ============================================
+// REMINDERS
+// ============================================
+
+/// Minutes before the due date a reminder fires when the caller doesn't say
+pub const DEFAULT_REMINDER_MINUTES: u32 = 15;
+
+/// Open tasks whose reminder time has passed; polled by the reminder scheduler
+#[tauri::command]
+pub fn getDueReminders(storage: State<'_, StorageState>, minutesBefore: Option<u32>) -> Result<Vec<TaskInfo>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword();
+    let minutesBefore = minutesBefore.unwrap_or(DEFAULT_REMINDER_MINUTES);
+    let nowMs = now();
+
+    let due: Vec<TaskInfo> = scanAllTasks(&foldersDir(&wsPath), masterPassword.as_deref())
+        .iter()
+        .filter(|t| t.status != TaskStatus::Done && t.frontmatter.isReminderDue(nowMs, minutesBefore))
+        .map(TaskInfo::from)
+        .collect();
+
+    storage.updateActivity();
+    Ok(due)
+}
+
+/// Postpone a task's reminder by `minutes` from now; returns the new reminder time (ms)
+#[tauri::command]
+pub fn snoozeTaskReminder(storage: State<'_, StorageState>, id: String, minutes: u32) -> Result<i64, ClaudiaError> {
+    println!("[snoozeTaskReminder] Called with id: {}, minutes: {}", id, minutes);
+
+    if minutes == 0 {
+        return Err(ClaudiaError::InvalidInput("Snooze duration must be at least one minute".to_string()));
+    }
+
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let until = now() + minutes as i64 * 60_000;
+    let path = storage.itemPath(ItemKind::Task, &id).ok_or_else(|| ClaudiaError::NotFound("Task not found".to_string()))?;
+    rewriteMetadata(&path, &masterPassword, |fm: &mut TaskFrontmatter| fm.snoozeReminder(until))?;
+
+    storage.updateActivity();
+    Ok(until)
+}
+
+/// Stop reminding about a task until its due date changes
+#[tauri::command]
+pub fn dismissTaskReminder(storage: State<'_, StorageState>, id: String) -> Result<(), ClaudiaError> {
+    println!("[dismissTaskReminder] Called with id: {}", id);
+
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let path = storage.itemPath(ItemKind::Task, &id).ok_or_else(|| ClaudiaError::NotFound("Task not found".to_string()))?;
+    rewriteMetadata(&path, &masterPassword, |fm: &mut TaskFrontmatter| fm.dismissReminder())?;
+
+    storage.updateActivity();
+    Ok(())
+}
+
+// ============================================
+// RECURRING TASKS
+// ============================================
+
+/// Next occurrence of a recurring task: same title, color, tags and recurrence,
+/// a new UUID, and the due date advanced by one step (from `now` if it had none)
+fn nextOccurrence(fm: &TaskFrontmatter, rank: u32, now: i64) -> Option<TaskFrontmatter> {
+    let recurrence = fm.recurrence?;
+    let mut next = TaskFrontmatter::new(newId(), fm.title.clone(), rank);
+    next.color = fm.color.clone();
+    next.tags = fm.tags.clone();
+    next.recurrence = Some(recurrence);
+    next.due = Some(recurrence.advance(fm.due.unwrap_or(now)));
+    Some(next)
+}
+
+/// Create the next occurrence of a recurring task that was just completed
+/// The copy goes into the first status column of the same folder, after its existing tasks
+pub(crate) fn spawnRecurringTask(fm: &TaskFrontmatter, body: &str, tasksDirPath: &Path, masterPassword: &str) -> Result<Option<Task>, String> {
+    if fm.recurrence.is_none() {
+        return Ok(None);
+    }
+
+    let status = TaskStatus::all().into_iter().next().unwrap_or_default();
+    let statusPath = tasksDirPath.join(status.folderName());
+    fs::create_dir_all(&statusPath).map_err(|e| e.to_string())?;
+
+    let nextRank = scanTasksInStatus(&statusPath, &tasksDirPath.to_path_buf(), status.clone(), Some(masterPassword))
+        .iter()
+        .map(|t| t.frontmatter.rank)
+        .max()
+        .unwrap_or(0) + 1;
+    let Some(next) = nextOccurrence(fm, nextRank, now()) else {
+        return Ok(None);
+    };
+
+    let path = statusPath.join(uuidFilename(&next.id));
+    let content = encrypted_storage::serializeAndEncrypt(&next, body, masterPassword, &fileIdOf(&path))?;
+    writeFileAtomic(&path, content).map_err(|e| e.to_string())?;
+    println!("[spawnRecurringTask] Created next occurrence {} of {}", next.id, fm.id);
+
+    Ok(Some(Task {
+        path,
+        folderPath: tasksDirPath.to_path_buf(),
+        status,
+        frontmatter: next,
+        content: body.to_string(),
+    }))
+}
+
+// ============================================
+// CHECKLIST PROGRESS
+// ============================================
+
+/// Checked and total `- [ ]` / `- [x]` items in a task body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct ChecklistProgress {
+    pub done: u32,
+    pub total: u32,
+}
+
+/// Count checklist items at any nesting depth, skipping fenced code blocks
+pub fn checklistProgress(body: &str) -> ChecklistProgress {
+    let mut progress = ChecklistProgress { done: 0, total: 0 };
+    // Opening fence character and length while inside a code block
+    let mut fence: Option<(char, usize)> = None;
+
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        let fenceChar = trimmed.chars().next().filter(|c| *c == '`' || *c == '~');
+        if let Some(c) = fenceChar {
+            let len = trimmed.chars().take_while(|x| *x == c).count();
+            if len >= 3 {
+                match fence {
+                    None => fence = Some((c, len)),
+                    // A closing fence uses the same character, is at least as long and has no info string
+                    Some((open, openLen)) if open == c && len >= openLen && trimmed[len..].trim().is_empty() => fence = None,
+                    Some(_) => {}
+                }
+                continue;
+            }
+        }
+        if fence.is_some() {
+            continue;
+        }
+
+        if let Some(checked) = checkboxState(trimmed) {
+            progress.total += 1;
+            if checked {
+                progress.done += 1;
+            }
+        }
+    }
+    progress
+}
+
+/// Some(checked) if the (indent-stripped) line is a list item starting with a checkbox
+fn checkboxState(line: &str) -> Option<bool> {
+    let rest = if let Some(rest) = line.strip_prefix(['-', '*', '+']) {
+        rest
+    } else {
+        // Ordered list marker: "1." or "1)"
+        let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 {
+            return None;
+        }
+        line[digits..].strip_prefix(['.', ')'])?
+    };
+
+    let rest = rest.strip_prefix([' ', '\t'])?.trim_start();
+    let checked = match rest.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    rest[3..].chars().next().is_none_or(char::is_whitespace).then_some(checked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task::RecurrenceInterval;
+
+    #[test]
+    fn test_completing_weekly_task_spawns_next_week() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let tasksDirPath = itemsDir(&ws.join("folders"), ItemKind::Task);
+        let password = "correct horse battery staple";
+
+        let due = 1_700_000_000_000;
+        let mut fm = TaskFrontmatter::new(newId(), "Water plants".to_string(), 1);
+        fm.due = Some(due);
+        fm.tags = vec!["home".to_string()];
+        fm.recurrence = Some(Recurrence { interval: RecurrenceInterval::Weekly, every: 1 });
+
+        let spawned = spawnRecurringTask(&fm, "Both balconies", &tasksDirPath, password).unwrap().unwrap();
+        assert_ne!(spawned.frontmatter.id, fm.id);
+        assert_eq!(spawned.frontmatter.due, Some(due + 7 * 24 * 60 * 60 * 1000));
+
+        let todo = scanTasksInStatus(&tasksDirPath.join("todo"), &tasksDirPath, TaskStatus::Todo, Some(password));
+        assert_eq!(todo.len(), 1);
+        assert_eq!(todo[0].frontmatter.id, spawned.frontmatter.id);
+        assert_eq!(todo[0].frontmatter.title, "Water plants");
+        assert_eq!(todo[0].frontmatter.tags, fm.tags);
+        assert_eq!(todo[0].frontmatter.recurrence, fm.recurrence);
+        assert_eq!(encrypted_storage::readDecryptedBody(&todo[0].path, password).unwrap().as_deref(), Some("Both balconies"));
+
+        // Tasks without a recurrence don't spawn anything
+        fm.recurrence = None;
+        assert!(spawnRecurringTask(&fm, "", &tasksDirPath, password).unwrap().is_none());
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_checklist_progress_counts_nested_items() {
+        let body = "# Steps\n- [x] one\n- [ ] two\n  - [X] nested\n    * [ ] deeper\n1. [x] ordered\n- not a task\n- [link](x)\n";
+        assert_eq!(checklistProgress(body), ChecklistProgress { done: 3, total: 5 });
+    }
+
+    #[test]
+    fn test_checklist_progress_ignores_code_blocks() {
+        let body = "- [ ] real\n```md\n- [x] example\n```\n~~~~\n- [ ] tilde\n```\n- [ ] still code\n~~~~\n- [x] done\n";
+        assert_eq!(checklistProgress(body), ChecklistProgress { done: 1, total: 2 });
+        assert_eq!(checklistProgress(""), ChecklistProgress { done: 0, total: 0 });
+    }
+
+    #[test]
+    fn test_status_list_keeps_todo_and_done() {
+        let list = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(validateStatusList(&list(&[" Todo", "review", "done"])).unwrap(), list(&["todo", "review", "done"]));
+        assert_eq!(validateStatusList(&list(&["todo", "doing"])).unwrap_err().code(), "INVALID_INPUT");
+        assert_eq!(validateStatusList(&list(&["backlog", "done"])).unwrap_err().code(), "INVALID_INPUT");
+        assert_eq!(validateStatusList(&list(&["todo", "done", "Todo"])).unwrap_err().code(), "INVALID_INPUT");
+        assert_eq!(validateStatusList(&list(&["todo", "tasks", "done"])).unwrap_err().code(), "INVALID_INPUT");
+        assert!(validateStatusList(&[]).is_err());
+    }
+}
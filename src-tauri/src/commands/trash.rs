@@ -4,12 +4,18 @@ use std::fs;
 use std::path::PathBuf;
 use tauri::State;
 
+use crate::error::ClaudiaError;
 use crate::storage::{
     StorageState, trashNotesDir, trashTasksDir, trashPasswordsDir,
-    trashDir, parseUuidFilename,
+    trashDir, parseUuidFilename, uuidFilename, noteAssetsDir,
+    readTrashOrigins, forgetTrashOrigin, forgetTrashOrigins, stampTrashOrigins, trashRestoreDir,
 };
-use crate::encrypted_storage;
-use crate::models::{NoteFrontmatter, TaskFrontmatter, PasswordFrontmatter, TaskStatus};
+use crate::encrypted_storage::{self, fileIdOf};
+use crate::models::{NoteFrontmatter, TaskFrontmatter, PasswordFrontmatter, TaskStatus, ItemKind};
+use super::item::{moveVersionHistory, removeVersionHistory};
+use super::note::{NoteInfo, findNoteById};
+use super::task::{TaskInfo, findTaskById};
+use super::password::{PasswordInfo, findPasswordById};
 
 // ============================================
 // TRASH NOTE INFO
@@ -56,25 +62,22 @@ fn scanTrashNotes(trashNotesPath: &PathBuf, masterPassword: Option<&str>) -> Vec
         };
 
         // Parse encrypted frontmatter
-        if encrypted_storage::isEncryptedFormat(&content) {
-            if let Some(password) = masterPassword {
-                if let Ok(encrypted) = encrypted_storage::parseEncryptedFile(&content) {
-                    if let Ok(yamlContent) = encrypted_storage::decryptMetadata(&encrypted.metadata, password) {
-                        if let Ok(fm) = serde_yaml::from_str::<NoteFrontmatter>(&yamlContent) {
-                            notes.push(TrashNoteInfo {
-                                id: fm.id,
-                                title: fm.title,
-                                color: fm.color,
-                                pinned: fm.pinned,
-                                tags: fm.tags,
-                                created: fm.created,
-                                updated: fm.updated,
-                                path: path.to_string_lossy().to_string(),
-                            });
-                        }
-                    }
-                }
-            }
+        if encrypted_storage::isEncryptedFormat(&content)
+            && let Some(password) = masterPassword
+            && let Ok(encrypted) = encrypted_storage::parseEncryptedFile(&content, &fileIdOf(&path))
+            && let Ok(yamlContent) = encrypted.decryptMetadata(password)
+            && let Ok(fm) = serde_yaml::from_str::<NoteFrontmatter>(&yamlContent)
+        {
+            notes.push(TrashNoteInfo {
+                id: fm.id,
+                title: fm.title,
+                color: fm.color,
+                pinned: fm.pinned,
+                tags: fm.tags,
+                created: fm.created,
+                updated: fm.updated,
+                path: path.to_string_lossy().to_string(),
+            });
         }
     }
 
@@ -107,7 +110,7 @@ fn scanTrashTasks(trashTasksPath: &PathBuf, masterPassword: Option<&str>) -> Vec
     }
 
     // Scan each status folder
-    for status in [TaskStatus::Todo, TaskStatus::Doing, TaskStatus::Done] {
+    for status in TaskStatus::all() {
         let statusPath = trashTasksPath.join(status.folderName());
         if !statusPath.exists() {
             continue;
@@ -133,27 +136,24 @@ fn scanTrashTasks(trashTasksPath: &PathBuf, masterPassword: Option<&str>) -> Vec
                 Err(_) => continue,
             };
 
-            if encrypted_storage::isEncryptedFormat(&content) {
-                if let Some(password) = masterPassword {
-                    if let Ok(encrypted) = encrypted_storage::parseEncryptedFile(&content) {
-                        if let Ok(yamlContent) = encrypted_storage::decryptMetadata(&encrypted.metadata, password) {
-                            if let Ok(fm) = serde_yaml::from_str::<TaskFrontmatter>(&yamlContent) {
-                                tasks.push(TrashTaskInfo {
-                                    id: fm.id,
-                                    title: fm.title,
-                                    status,
-                                    color: fm.color,
-                                    pinned: fm.pinned,
-                                    tags: fm.tags,
-                                    due: fm.due,
-                                    created: fm.created,
-                                    updated: fm.updated,
-                                    path: path.to_string_lossy().to_string(),
-                                });
-                            }
-                        }
-                    }
-                }
+            if encrypted_storage::isEncryptedFormat(&content)
+                && let Some(password) = masterPassword
+                && let Ok(encrypted) = encrypted_storage::parseEncryptedFile(&content, &fileIdOf(&path))
+                && let Ok(yamlContent) = encrypted.decryptMetadata(password)
+                && let Ok(fm) = serde_yaml::from_str::<TaskFrontmatter>(&yamlContent)
+            {
+                tasks.push(TrashTaskInfo {
+                    id: fm.id,
+                    title: fm.title,
+                    status: status.clone(),
+                    color: fm.color,
+                    pinned: fm.pinned,
+                    tags: fm.tags,
+                    due: fm.due,
+                    created: fm.created,
+                    updated: fm.updated,
+                    path: path.to_string_lossy().to_string(),
+                });
             }
         }
     }
@@ -204,25 +204,22 @@ fn scanTrashPasswords(trashPasswordsPath: &PathBuf, masterPassword: Option<&str>
             Err(_) => continue,
         };
 
-        if encrypted_storage::isEncryptedFormat(&content) {
-            if let Some(password) = masterPassword {
-                if let Ok(encrypted) = encrypted_storage::parseEncryptedFile(&content) {
-                    if let Ok(yamlContent) = encrypted_storage::decryptMetadata(&encrypted.metadata, password) {
-                        if let Ok(fm) = serde_yaml::from_str::<PasswordFrontmatter>(&yamlContent) {
-                            passwords.push(TrashPasswordInfo {
-                                id: fm.id,
-                                title: fm.title,
-                                color: fm.color,
-                                pinned: fm.pinned,
-                                tags: fm.tags,
-                                created: fm.created,
-                                updated: fm.updated,
-                                path: path.to_string_lossy().to_string(),
-                            });
-                        }
-                    }
-                }
-            }
+        if encrypted_storage::isEncryptedFormat(&content)
+            && let Some(password) = masterPassword
+            && let Ok(encrypted) = encrypted_storage::parseEncryptedFile(&content, &fileIdOf(&path))
+            && let Ok(yamlContent) = encrypted.decryptMetadata(password)
+            && let Ok(fm) = serde_yaml::from_str::<PasswordFrontmatter>(&yamlContent)
+        {
+            passwords.push(TrashPasswordInfo {
+                id: fm.id,
+                title: fm.title,
+                color: fm.color,
+                pinned: fm.pinned,
+                tags: fm.tags,
+                created: fm.created,
+                updated: fm.updated,
+                path: path.to_string_lossy().to_string(),
+            });
         }
     }
 
@@ -234,11 +231,11 @@ fn scanTrashPasswords(trashPasswordsPath: &PathBuf, masterPassword: Option<&str>
 // ============================================
 
 #[tauri::command]
-pub fn listTrashNotes(storage: State<'_, StorageState>) -> Result<Vec<TrashNoteInfo>, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn listTrashNotes(storage: State<'_, StorageState>) -> Result<Vec<TrashNoteInfo>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
@@ -248,11 +245,11 @@ pub fn listTrashNotes(storage: State<'_, StorageState>) -> Result<Vec<TrashNoteI
 }
 
 #[tauri::command]
-pub fn listTrashTasks(storage: State<'_, StorageState>) -> Result<Vec<TrashTaskInfo>, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn listTrashTasks(storage: State<'_, StorageState>) -> Result<Vec<TrashTaskInfo>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
@@ -262,11 +259,11 @@ pub fn listTrashTasks(storage: State<'_, StorageState>) -> Result<Vec<TrashTaskI
 }
 
 #[tauri::command]
-pub fn listTrashPasswords(storage: State<'_, StorageState>) -> Result<Vec<TrashPasswordInfo>, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn listTrashPasswords(storage: State<'_, StorageState>) -> Result<Vec<TrashPasswordInfo>, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
@@ -275,6 +272,23 @@ pub fn listTrashPasswords(storage: State<'_, StorageState>) -> Result<Vec<TrashP
     Ok(scanTrashPasswords(&trashPath, masterPassword.as_deref()))
 }
 
+/// A trashed item of any kind, tagged with `kind` when serialized
+#[derive(serde::Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TrashItem {
+    Note(TrashNoteInfo),
+    Task(TrashTaskInfo),
+    Password(TrashPasswordInfo),
+}
+
+/// Every trashed note, task and password, in that order
+pub(crate) fn listAllTrash(wsPath: &str, masterPassword: Option<&str>) -> Vec<TrashItem> {
+    scanTrashNotes(&trashNotesDir(wsPath), masterPassword).into_iter().map(TrashItem::Note)
+        .chain(scanTrashTasks(&trashTasksDir(wsPath), masterPassword).into_iter().map(TrashItem::Task))
+        .chain(scanTrashPasswords(&trashPasswordsDir(wsPath), masterPassword).into_iter().map(TrashItem::Password))
+        .collect()
+}
+
 #[derive(serde::Serialize)]
 pub struct TrashCounts {
     pub notes: usize,
@@ -284,11 +298,11 @@ pub struct TrashCounts {
 }
 
 #[tauri::command]
-pub fn getTrashCounts(storage: State<'_, StorageState>) -> Result<TrashCounts, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn getTrashCounts(storage: State<'_, StorageState>) -> Result<TrashCounts, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
@@ -307,85 +321,86 @@ pub fn getTrashCounts(storage: State<'_, StorageState>) -> Result<TrashCounts, S
 }
 
 #[tauri::command]
-pub fn emptyTrash(storage: State<'_, StorageState>) -> Result<(), String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn emptyTrash(storage: State<'_, StorageState>) -> Result<(), ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
-    let trash = trashDir(&wsPath);
+    clearTrash(&wsPath)?;
+
+    storage.updateActivity();
+    Ok(())
+}
+
+/// Permanently delete everything in trash, with the assets of trashed notes
+pub(crate) fn clearTrash(wsPath: &str) -> Result<(), String> {
+    // Drop assets of trashed notes along with them
+    if let Ok(entries) = fs::read_dir(trashNotesDir(wsPath)) {
+        for entry in entries.flatten() {
+            if let Some(id) = entry.file_name().to_str().and_then(parseUuidFilename) {
+                let _ = fs::remove_dir_all(noteAssetsDir(wsPath, &id));
+            }
+        }
+    }
+
+    let trash = trashDir(wsPath);
     if trash.exists() {
         fs::remove_dir_all(&trash).map_err(|e| e.to_string())?;
     }
-
-    storage.updateActivity();
     Ok(())
 }
 
 #[tauri::command]
-pub fn restoreAllFromTrash(storage: State<'_, StorageState>) -> Result<(), String> {
-    use crate::storage::{notesDir, tasksDir, passwordsDir};
+pub fn restoreAllFromTrash(storage: State<'_, StorageState>) -> Result<(), ClaudiaError> {
+    restoreAllItems(&storage)?;
+    storage.updateActivity();
+    Ok(())
+}
 
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+/// Restore every trashed item, indexing each one like the single-item restores do
+pub(crate) fn restoreAllItems(storage: &StorageState) -> Result<(), ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
+    // Items go back to the folder they were deleted from, or the root if it is gone
+    let origins = readTrashOrigins(&wsPath);
+    let mdFiles = |dir: &PathBuf| -> Vec<(PathBuf, String)> {
+        fs::read_dir(dir).into_iter().flatten().flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == "md"))
+            .filter_map(|path| {
+                let id = path.file_name().and_then(|n| n.to_str()).and_then(parseUuidFilename)?;
+                Some((path, id))
+            })
+            .collect()
+    };
+
     // Restore notes
     let trashNotesPath = trashNotesDir(&wsPath);
-    if trashNotesPath.exists() {
-        let targetDir = notesDir(&wsPath, "");
-        fs::create_dir_all(&targetDir).map_err(|e| e.to_string())?;
-
-        if let Ok(entries) = fs::read_dir(&trashNotesPath) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_file() && path.extension().map_or(false, |e| e == "md") {
-                    let filename = path.file_name().ok_or("Invalid filename")?;
-                    let targetPath = targetDir.join(filename);
-                    fs::rename(&path, &targetPath).map_err(|e| e.to_string())?;
-                }
-            }
-        }
+    for (path, id) in mdFiles(&trashNotesPath) {
+        let targetDir = trashRestoreDir(&wsPath, &origins, &id, ItemKind::Note);
+        let restoredPath = restoreTrashedFile(&path, &targetDir)?;
+        moveVersionHistory(&trashNotesPath, &targetDir, &id);
+        storage.indexItem(ItemKind::Note, &id, &restoredPath);
+        storage.invalidateLinks(&id);
     }
 
     // Restore tasks (preserve status folders)
     let trashTasksPath = trashTasksDir(&wsPath);
-    if trashTasksPath.exists() {
-        for status in [TaskStatus::Todo, TaskStatus::Doing, TaskStatus::Done] {
-            let statusPath = trashTasksPath.join(status.folderName());
-            if statusPath.exists() {
-                let targetDir = tasksDir(&wsPath, "").join(status.folderName());
-                fs::create_dir_all(&targetDir).map_err(|e| e.to_string())?;
-
-                if let Ok(entries) = fs::read_dir(&statusPath) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if path.is_file() && path.extension().map_or(false, |e| e == "md") {
-                            let filename = path.file_name().ok_or("Invalid filename")?;
-                            let targetPath = targetDir.join(filename);
-                            fs::rename(&path, &targetPath).map_err(|e| e.to_string())?;
-                        }
-                    }
-                }
-            }
+    for status in TaskStatus::all() {
+        for (path, id) in mdFiles(&trashTasksPath.join(status.folderName())) {
+            let targetDir = trashRestoreDir(&wsPath, &origins, &id, ItemKind::Task);
+            let restoredPath = restoreTrashedFile(&path, &targetDir.join(status.folderName()))?;
+            moveVersionHistory(&trashTasksPath, &targetDir, &id);
+            storage.indexItem(ItemKind::Task, &id, &restoredPath);
         }
     }
 
     // Restore passwords
-    let trashPasswordsPath = trashPasswordsDir(&wsPath);
-    if trashPasswordsPath.exists() {
-        let targetDir = passwordsDir(&wsPath, "");
-        fs::create_dir_all(&targetDir).map_err(|e| e.to_string())?;
-
-        if let Ok(entries) = fs::read_dir(&trashPasswordsPath) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_file() && path.extension().map_or(false, |e| e == "md") {
-                    let filename = path.file_name().ok_or("Invalid filename")?;
-                    let targetPath = targetDir.join(filename);
-                    fs::rename(&path, &targetPath).map_err(|e| e.to_string())?;
-                }
-            }
-        }
+    for (path, id) in mdFiles(&trashPasswordsDir(&wsPath)) {
+        let restoredPath = restoreTrashedFile(&path, &trashRestoreDir(&wsPath, &origins, &id, ItemKind::Password))?;
+        storage.indexItem(ItemKind::Password, &id, &restoredPath);
     }
 
     // Clean up empty trash directories
@@ -393,7 +408,352 @@ pub fn restoreAllFromTrash(storage: State<'_, StorageState>) -> Result<(), Strin
     if trash.exists() {
         let _ = fs::remove_dir_all(&trash);
     }
+    Ok(())
+}
+
+// ============================================
+// SINGLE ITEM RESTORE
+// ============================================
+
+/// Move one trashed file into `targetDir`, refusing to overwrite a live item with the same id
+fn restoreTrashedFile(trashedPath: &PathBuf, targetDir: &PathBuf) -> Result<PathBuf, String> {
+    fs::create_dir_all(targetDir).map_err(|e| e.to_string())?;
+    let filename = trashedPath.file_name().ok_or("Invalid filename")?;
+    let targetPath = targetDir.join(filename);
+    if targetPath.exists() {
+        return Err("An item with this id already exists".to_string());
+    }
+    fs::rename(trashedPath, &targetPath).map_err(|e| e.to_string())?;
+    Ok(targetPath)
+}
+
+#[tauri::command]
+pub fn restoreNoteFromTrash(storage: State<'_, StorageState>, id: String) -> Result<NoteInfo, ClaudiaError> {
+    restoreNote(&storage, &id)
+}
+
+pub(crate) fn restoreNote(storage: &StorageState, id: &str) -> Result<NoteInfo, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+    let masterPassword = storage.getMasterPassword();
+
+    let trashNotesPath = trashNotesDir(&wsPath);
+    let trashedPath = trashNotesPath.join(uuidFilename(id));
+    if !trashedPath.is_file() {
+        return Err(ClaudiaError::NotFound("Note not found in trash".to_string()));
+    }
+
+    let targetDir = trashRestoreDir(&wsPath, &readTrashOrigins(&wsPath), id, ItemKind::Note);
+    let restoredPath = restoreTrashedFile(&trashedPath, &targetDir)?;
+    moveVersionHistory(&trashNotesPath, &targetDir, id);
+    forgetTrashOrigin(&wsPath, id);
+    storage.indexItem(ItemKind::Note, id, &restoredPath);
+    storage.invalidateLinks(id);
+
+    let note = findNoteById(storage, &wsPath, id, masterPassword.as_deref())
+        .ok_or("Restored note could not be read")?;
+    println!("[restoreNoteFromTrash] Restored {}", id);
 
     storage.updateActivity();
-    Ok(())
+    Ok(NoteInfo::from(&note))
+}
+
+#[tauri::command]
+pub fn restoreTaskFromTrash(storage: State<'_, StorageState>, id: String) -> Result<TaskInfo, ClaudiaError> {
+    restoreTask(&storage, &id)
+}
+
+pub(crate) fn restoreTask(storage: &StorageState, id: &str) -> Result<TaskInfo, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+    let masterPassword = storage.getMasterPassword();
+
+    // Tasks keep their status subfolder in the trash
+    let trashTasksPath = trashTasksDir(&wsPath);
+    let filename = uuidFilename(id);
+    let (trashedPath, status) = TaskStatus::all().into_iter()
+        .map(|status| (trashTasksPath.join(status.folderName()).join(&filename), status))
+        .find(|(path, _)| path.is_file())
+        .ok_or_else(|| ClaudiaError::NotFound("Task not found in trash".to_string()))?;
+
+    let targetDir = trashRestoreDir(&wsPath, &readTrashOrigins(&wsPath), id, ItemKind::Task);
+    let restoredPath = restoreTrashedFile(&trashedPath, &targetDir.join(status.folderName()))?;
+    moveVersionHistory(&trashTasksPath, &targetDir, id);
+    forgetTrashOrigin(&wsPath, id);
+    storage.indexItem(ItemKind::Task, id, &restoredPath);
+
+    let task = findTaskById(storage, &wsPath, id, masterPassword.as_deref())
+        .ok_or("Restored task could not be read")?;
+    println!("[restoreTaskFromTrash] Restored {}", id);
+
+    storage.updateActivity();
+    Ok(TaskInfo::from(&task))
+}
+
+#[tauri::command]
+pub fn restorePasswordFromTrash(storage: State<'_, StorageState>, id: String) -> Result<PasswordInfo, ClaudiaError> {
+    restorePassword(&storage, &id)
+}
+
+pub(crate) fn restorePassword(storage: &StorageState, id: &str) -> Result<PasswordInfo, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+    let masterPassword = storage.getMasterPassword();
+
+    let trashedPath = trashPasswordsDir(&wsPath).join(uuidFilename(id));
+    if !trashedPath.is_file() {
+        return Err(ClaudiaError::NotFound("Password not found in trash".to_string()));
+    }
+
+    let targetDir = trashRestoreDir(&wsPath, &readTrashOrigins(&wsPath), id, ItemKind::Password);
+    let restoredPath = restoreTrashedFile(&trashedPath, &targetDir)?;
+    forgetTrashOrigin(&wsPath, id);
+    storage.indexItem(ItemKind::Password, id, &restoredPath);
+
+    let password = findPasswordById(storage, &wsPath, id, masterPassword.as_deref())
+        .ok_or("Restored password could not be read")?;
+    println!("[restorePasswordFromTrash] Restored {}", id);
+
+    storage.updateActivity();
+    Ok(PasswordInfo::from(&password))
+}
+
+// ============================================
+// RETENTION
+// ============================================
+
+#[derive(serde::Serialize, Default, Debug)]
+pub struct PurgeReport {
+    pub purged: usize,
+    pub reclaimedBytes: u64,
+}
+
+/// Permanently delete trashed items that have been in trash for more than `retentionDays`
+/// Items whose metadata can't be decrypted are left alone. Items without a recorded
+/// deletion time are stamped with `now` instead: their mtime is the last edit, not the
+/// deletion, so judging by it could purge something deleted yesterday
+pub(crate) fn purgeExpiredItems(wsPath: &str, masterPassword: Option<&str>, retentionDays: u32, now: i64) -> PurgeReport {
+    let mut report = PurgeReport::default();
+    let cutoff = now - i64::from(retentionDays) * 24 * 60 * 60 * 1000;
+    let origins = readTrashOrigins(wsPath);
+    let mut purgedIds = Vec::new();
+
+    let trashNotesPath = trashNotesDir(wsPath);
+    let trashTasksPath = trashTasksDir(wsPath);
+    let trashed: Vec<_> = scanTrashNotes(&trashNotesPath, masterPassword).into_iter()
+        .map(|n| (ItemKind::Note, n.id, PathBuf::from(n.path)))
+        .chain(scanTrashTasks(&trashTasksPath, masterPassword).into_iter()
+            .map(|t| (ItemKind::Task, t.id, PathBuf::from(t.path))))
+        .chain(scanTrashPasswords(&trashPasswordsDir(wsPath), masterPassword).into_iter()
+            .map(|p| (ItemKind::Password, p.id, PathBuf::from(p.path))))
+        .collect();
+
+    let unrecorded: Vec<String> = trashed.iter()
+        .filter(|(_, id, _)| !origins.contains_key(id))
+        .map(|(_, id, _)| id.clone())
+        .collect();
+    if !unrecorded.is_empty() {
+        stampTrashOrigins(wsPath, &unrecorded, now);
+    }
+
+    let expired = trashed.into_iter()
+        .filter(|(_, id, _)| origins.get(id).is_some_and(|origin| origin.trashedAt < cutoff));
+
+    for (kind, id, path) in expired {
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if fs::remove_file(&path).is_err() {
+            continue;
+        }
+        // History and assets are only reachable through the item
+        match kind {
+            ItemKind::Note => {
+                removeVersionHistory(&trashNotesPath, &id);
+                let _ = fs::remove_dir_all(noteAssetsDir(wsPath, &id));
+            }
+            ItemKind::Task => removeVersionHistory(&trashTasksPath, &id),
+            ItemKind::Password => {}
+        }
+        println!("[purgeExpiredTrash] Purged expired trash item: {}", path.display());
+        report.purged += 1;
+        report.reclaimedBytes += size;
+        purgedIds.push(id);
+    }
+
+    forgetTrashOrigins(wsPath, &purgedIds);
+    report
+}
+
+/// Apply trashRetentionDays to the open workspace
+/// None when there is nothing to do: no workspace, vault locked or retention off
+pub(crate) fn runTrashRetention(storage: &StorageState) -> Option<PurgeReport> {
+    let retentionDays = storage.effectiveSettings().trashRetentionDays;
+    if retentionDays == 0 || !storage.isUnlocked() {
+        return None;
+    }
+    let wsPath = storage.getAvailableWorkspacePath().ok()??;
+    let masterPassword = storage.getMasterPassword()?;
+    Some(purgeExpiredItems(&wsPath, Some(&masterPassword), retentionDays, chrono::Utc::now().timestamp_millis()))
+}
+
+/// Purge items kept in trash longer than trashRetentionDays (nothing when it is 0)
+#[tauri::command]
+pub fn purgeExpiredTrash(storage: State<'_, StorageState>) -> Result<PurgeReport, ClaudiaError> {
+    storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let report = runTrashRetention(&storage).unwrap_or_default();
+    println!("[purgeExpiredTrash] Purged {} items ({} bytes)", report.purged, report.reclaimedBytes);
+
+    storage.updateActivity();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use crate::storage::{foldersDir, itemsDir, recordTrashOrigin};
+    use super::super::common::newId;
+    use super::super::note::{removeNoteFile, scanNotesInFolder};
+
+    /// Unlocked storage on a fresh workspace
+    fn testStorage(ws: &Path) -> StorageState {
+        let storage: StorageState = std::sync::Arc::new(crate::storage::Storage::new());
+        storage.setCurrentWorkspace(Some(ws.to_string_lossy().to_string()));
+        storage.setDerivedKey(vec![7u8; 32]);
+        storage
+    }
+
+    /// Write a note into `folder` and move it to trash the way deleteNote does
+    fn trashNewNote(storage: &StorageState, wsPath: &str, folder: &Path) -> String {
+        let key = storage.getMasterPassword().unwrap();
+        let dir = itemsDir(folder, ItemKind::Note);
+        fs::create_dir_all(&dir).unwrap();
+        let fm = NoteFrontmatter::new(newId(), "Round trip".to_string(), 0);
+        fs::write(dir.join(uuidFilename(&fm.id)), encrypted_storage::serializeAndEncrypt(&fm, "body", &key, &fm.id).unwrap()).unwrap();
+        let note = scanNotesInFolder(&dir, Some(&key)).remove(0);
+
+        removeNoteFile(wsPath, &note, false).unwrap();
+        assert!(!note.path.exists());
+        fm.id
+    }
+
+    #[test]
+    fn test_restore_returns_note_to_nested_folder() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        let storage = testStorage(&ws);
+
+        let nested = foldersDir(&wsPath).join(newId()).join(newId());
+        let id = trashNewNote(&storage, &wsPath, &nested);
+        let restored = restoreNote(&storage, &id).unwrap();
+        assert_eq!(PathBuf::from(&restored.path).parent(), Some(itemsDir(&nested, ItemKind::Note).as_path()));
+        assert_eq!(storage.indexedPath(ItemKind::Note, &id), Some(PathBuf::from(&restored.path)));
+        assert!(readTrashOrigins(&wsPath).is_empty());
+
+        // Origin folder deleted while the note sat in trash: back to the root
+        let gone = foldersDir(&wsPath).join(newId());
+        let id = trashNewNote(&storage, &wsPath, &gone);
+        fs::remove_dir_all(&gone).unwrap();
+        let restored = restoreNote(&storage, &id).unwrap();
+        assert_eq!(PathBuf::from(&restored.path).parent(), Some(itemsDir(&foldersDir(&wsPath), ItemKind::Note).as_path()));
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_restore_all_returns_notes_to_nested_folders_and_indexes_them() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        let storage = testStorage(&ws);
+
+        let nested = foldersDir(&wsPath).join(newId()).join(newId());
+        let id = trashNewNote(&storage, &wsPath, &nested);
+        storage.rebuildIndex();
+        storage.cacheLinks(&wsPath, &id, vec!["stale".to_string()]);
+
+        restoreAllItems(&storage).unwrap();
+
+        let expected = itemsDir(&nested, ItemKind::Note).join(uuidFilename(&id));
+        assert!(expected.is_file());
+        assert_eq!(storage.indexedPath(ItemKind::Note, &id), Some(expected));
+        assert!(storage.cachedLinks(&wsPath, &id).is_none());
+        assert!(!trashDir(&wsPath).exists());
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_purge_only_removes_expired_items() {
+        const TEST_PASSWORD: &str = "correct horse battery staple";
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        let trashNotes = trashNotesDir(&wsPath);
+        fs::create_dir_all(&trashNotes).unwrap();
+
+        // One note with a recorded deletion time, one without (stamped on the first scan)
+        let mut ids = Vec::new();
+        for title in ["Recorded", "Unrecorded"] {
+            let fm = NoteFrontmatter::new(newId(), title.to_string(), 0);
+            let path = trashNotes.join(uuidFilename(&fm.id));
+            fs::write(&path, encrypted_storage::serializeAndEncrypt(&fm, "body", TEST_PASSWORD, &fileIdOf(&path)).unwrap()).unwrap();
+            ids.push(fm.id);
+        }
+        recordTrashOrigin(&wsPath, &ids[0], &itemsDir(&foldersDir(&wsPath), ItemKind::Note));
+        fs::create_dir_all(noteAssetsDir(&wsPath, &ids[0])).unwrap();
+
+        let now = chrono::Utc::now().timestamp_millis();
+        assert_eq!(purgeExpiredItems(&wsPath, Some(TEST_PASSWORD), 30, now).purged, 0);
+        // Without the key nothing can be read, so nothing is purged
+        assert_eq!(purgeExpiredItems(&wsPath, None, 30, now + 31 * 24 * 60 * 60 * 1000).purged, 0);
+
+        let report = purgeExpiredItems(&wsPath, Some(TEST_PASSWORD), 30, now + 31 * 24 * 60 * 60 * 1000);
+        assert_eq!(report.purged, 2);
+        assert!(scanTrashNotes(&trashNotes, Some(TEST_PASSWORD)).is_empty());
+        assert!(!noteAssetsDir(&wsPath, &ids[0]).exists());
+        assert!(readTrashOrigins(&wsPath).is_empty());
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_purge_stamps_unrecorded_items_instead_of_using_mtime() {
+        const TEST_PASSWORD: &str = "correct horse battery staple";
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        let trashNotes = trashNotesDir(&wsPath);
+        fs::create_dir_all(&trashNotes).unwrap();
+
+        // Trashed before deletion times were recorded: no origin, mtime is the last edit
+        let fm = NoteFrontmatter::new(newId(), "Legacy".to_string(), 0);
+        let path = trashNotes.join(uuidFilename(&fm.id));
+        fs::write(&path, encrypted_storage::serializeAndEncrypt(&fm, "body", TEST_PASSWORD, &fileIdOf(&path)).unwrap()).unwrap();
+
+        // First seen long after its mtime: stamped, not purged
+        let firstScan = chrono::Utc::now().timestamp_millis() + 90 * 24 * 60 * 60 * 1000;
+        assert_eq!(purgeExpiredItems(&wsPath, Some(TEST_PASSWORD), 30, firstScan).purged, 0);
+        assert!(path.is_file());
+        let origins = readTrashOrigins(&wsPath);
+        assert_eq!(origins[&fm.id].trashedAt, firstScan);
+        assert_eq!(origins[&fm.id].folder, "");
+
+        // Later scans keep the stamp and purge once retention has passed since it
+        assert_eq!(purgeExpiredItems(&wsPath, Some(TEST_PASSWORD), 30, firstScan + 29 * 24 * 60 * 60 * 1000).purged, 0);
+        assert_eq!(purgeExpiredItems(&wsPath, Some(TEST_PASSWORD), 30, firstScan + 31 * 24 * 60 * 60 * 1000).purged, 1);
+        assert!(!path.exists());
+
+        let _ = fs::remove_dir_all(&ws);
+    }
 }
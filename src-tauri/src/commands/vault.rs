@@ -1,11 +1,14 @@
 // Vault commands - master password and encryption management
 
 use std::fs;
+use std::path::{Path, PathBuf};
 use tauri::State;
 
+use crate::error::ClaudiaError;
 use crate::crypto;
-use crate::encrypted_storage;
-use crate::storage::StorageState;
+use crate::encrypted_storage::{self, fileIdOf};
+use crate::password_strength::{self, PolicyResult};
+use crate::storage::{StorageState, writeFileAtomic};
 
 /// Check if vault has been set up (master password created)
 #[tauri::command]
@@ -25,29 +28,51 @@ pub fn isVaultUnlocked(storage: State<'_, StorageState>) -> bool {
     result
 }
 
+/// Check a candidate master password against the masterPasswordPolicy setting
+/// For live feedback while the user types; setup and change enforce the same check
+#[tauri::command]
+pub fn checkMasterPasswordPolicy(storage: State<'_, StorageState>, password: String) -> PolicyResult {
+    password_strength::checkPolicy(&password, &storage.effectiveSettings().masterPasswordPolicy)
+}
+
+/// Reject a new master password that does not meet the policy
+fn enforceMasterPasswordPolicy(storage: &StorageState, password: &str) -> Result<(), ClaudiaError> {
+    let result = password_strength::checkPolicy(password, &storage.effectiveSettings().masterPasswordPolicy);
+    if result.ok {
+        Ok(())
+    } else {
+        Err(ClaudiaError::InvalidInput(format!("Master password is too weak: {}", result.failures.join("; "))))
+    }
+}
+
 /// Set up master password for the first time
 #[tauri::command]
-pub fn setupMasterPassword(storage: State<'_, StorageState>, password: String) -> Result<(), String> {
+pub fn setupMasterPassword(storage: State<'_, StorageState>, password: String) -> Result<(), ClaudiaError> {
     println!("[setupMasterPassword] Setting up master password");
 
     if storage.isVaultSetup() {
-        return Err("Master password already set up".to_string());
+        return Err("Master password already set up".into());
     }
 
+    enforceMasterPasswordPolicy(&storage, &password)?;
+
+    storage.getAvailableWorkspacePath()?;
     let hashPath = storage.masterPasswordHashPath()
-        .ok_or("No workspace selected")?;
+        .ok_or(ClaudiaError::NoWorkspace)?;
 
     // Hash the password
     let hash = crypto::hashMasterPassword(&password)?;
 
     // Write hash to file
-    fs::write(&hashPath, &hash).map_err(|e| {
+    writeFileAtomic(&hashPath, &hash).map_err(|e| {
         println!("[setupMasterPassword] ERROR writing hash: {}", e);
         e.to_string()
     })?;
 
-    // Derive key and unlock vault
-    let key = deriveKeyFromPassword(&password)?;
+    // Derive key from a fresh random salt and unlock vault
+    let salt = generateVaultSalt();
+    writeVaultSalt(&storage, &salt)?;
+    let key = deriveKeyFromPassword(&password, &salt)?;
     storage.setDerivedKey(key);
 
     println!("[setupMasterPassword] SUCCESS - vault set up and unlocked");
@@ -56,86 +81,297 @@ pub fn setupMasterPassword(storage: State<'_, StorageState>, password: String) -
 
 /// Unlock the vault with master password
 #[tauri::command]
-pub fn unlockVault(storage: State<'_, StorageState>, password: String) -> Result<bool, String> {
+pub fn unlockVault(storage: State<'_, StorageState>, password: String) -> Result<bool, ClaudiaError> {
     println!("[unlockVault] Attempting to unlock vault");
+    unlockWithPassword(&storage, &password)
+}
 
+/// Verify the password against the current workspace's vault and keep the derived key
+/// Returns Ok(false) on a wrong password
+pub(crate) fn unlockWithPassword(storage: &StorageState, password: &str) -> Result<bool, ClaudiaError> {
+    storage.getAvailableWorkspacePath()?;
     let hashPath = storage.masterPasswordHashPath()
-        .ok_or("No workspace selected")?;
+        .ok_or(ClaudiaError::NoWorkspace)?;
 
     if !hashPath.exists() {
-        return Err("Vault not set up - no master password".to_string());
+        return Err("Vault not set up - no master password".into());
     }
 
     // Read stored hash
     let storedHash = fs::read_to_string(&hashPath)
-        .map_err(|e| format!("Failed to read master password hash: {}", e))?;
+        .map_err(|e| ClaudiaError::Io(format!("Failed to read master password hash: {}", e)))?;
 
     // Verify password
-    if !crypto::verifyMasterPassword(&password, &storedHash) {
+    if !crypto::verifyMasterPassword(password, &storedHash) {
         println!("[unlockVault] Password verification failed");
         return Ok(false);
     }
 
     // Derive key and store it
-    let key = deriveKeyFromPassword(&password)?;
+    let salt = readVaultSalt(storage, password)?;
+    let key = deriveKeyFromPassword(password, &salt)?;
     storage.setDerivedKey(key);
 
     println!("[unlockVault] SUCCESS - vault unlocked");
     Ok(true)
 }
 
+/// Unlock like unlockVault, but hand back a short-lived session token for scripted callers
+/// Read commands then require it as `sessionToken`, so the caller doesn't have to keep the password
+#[tauri::command]
+pub fn deriveSessionKey(storage: State<'_, StorageState>, password: String) -> Result<String, ClaudiaError> {
+    println!("[deriveSessionKey] Called");
+
+    if !unlockWithPassword(&storage, &password)? {
+        return Err(ClaudiaError::InvalidInput("Invalid master password".to_string()));
+    }
+
+    println!("[deriveSessionKey] SUCCESS - session token issued");
+    Ok(storage.createSessionToken())
+}
+
 /// Lock the vault (clear derived key from memory)
 #[tauri::command]
-pub fn lockVault(storage: State<'_, StorageState>) -> Result<(), String> {
+pub fn lockVault(storage: State<'_, StorageState>) -> Result<(), ClaudiaError> {
     println!("[lockVault] Locking vault");
     storage.lock();
     Ok(())
 }
 
 /// Change master password
+/// With `verify`, every file is test-decrypted with the new key afterwards and
+/// any file that did not re-encrypt is reported as an error
 #[tauri::command]
 pub fn changeMasterPasswordVault(
     storage: State<'_, StorageState>,
     oldPassword: String,
     newPassword: String,
-) -> Result<(), String> {
+    verify: Option<bool>,
+) -> Result<(), ClaudiaError> {
     println!("[changeMasterPassword] Changing master password");
 
+    storage.getAvailableWorkspacePath()?;
     let hashPath = storage.masterPasswordHashPath()
-        .ok_or("No workspace selected")?;
+        .ok_or(ClaudiaError::NoWorkspace)?;
 
     if !hashPath.exists() {
-        return Err("Vault not set up".to_string());
+        return Err("Vault not set up".into());
     }
 
     // Verify old password
     let storedHash = fs::read_to_string(&hashPath)
-        .map_err(|e| format!("Failed to read master password hash: {}", e))?;
+        .map_err(|e| ClaudiaError::Io(format!("Failed to read master password hash: {}", e)))?;
 
     if !crypto::verifyMasterPassword(&oldPassword, &storedHash) {
-        return Err("Current password is incorrect".to_string());
+        return Err(ClaudiaError::Auth("Current password is incorrect".to_string()));
     }
 
+    enforceMasterPasswordPolicy(&storage, &newPassword)?;
+
     // Hash new password
     let newHash = crypto::hashMasterPassword(&newPassword)?;
 
-    // Write new hash
-    fs::write(&hashPath, &newHash).map_err(|e| {
-        println!("[changeMasterPassword] ERROR writing hash: {}", e);
-        e.to_string()
-    })?;
-
-    // Re-encrypt all files with new password
-    reEncryptAllFiles(&storage, &oldPassword, &newPassword)?;
+    // Re-encrypt all files with the key derived from the new password and a new salt
+    // (this is also where vaults still on the legacy salt move to a random one)
+    let oldKey = encryptionKeyFor(&oldPassword, &readVaultSalt(&storage, &oldPassword)?)?;
+    let newSalt = generateVaultSalt();
+    let newKey = encryptionKeyFor(&newPassword, &newSalt)?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+    let files = encryptedVaultFiles(&wsPath);
+    println!("[changeMasterPassword] Re-encrypting {} files", files.len());
+    let swapped = StagedReEncryption::stage(&files, &oldKey, &newKey)?.swapIn()?;
+
+    // Hash and salt only change once every file is re-encrypted
+    let saltPath = storage.vaultSaltPath().ok_or(ClaudiaError::NoWorkspace)?;
+    let oldSalt = fs::read(&saltPath).ok();
+    let saved = writeVaultSalt(&storage, &newSalt).and_then(|_| {
+        writeFileAtomic(&hashPath, &newHash).map_err(|e| ClaudiaError::Io(format!("Failed to write master password hash: {}", e)))
+    });
+    if let Err(e) = saved {
+        println!("[changeMasterPassword] ERROR: {} - restoring previous files", e);
+        swapped.rollback();
+        let _ = match oldSalt {
+            Some(salt) => writeFileAtomic(&saltPath, salt),
+            None => fs::remove_file(&saltPath),
+        };
+        return Err(e);
+    }
+    swapped.finish();
 
     // Update derived key
-    let key = deriveKeyFromPassword(&newPassword)?;
+    let key = deriveKeyFromPassword(&newPassword, &newSalt)?;
     storage.setDerivedKey(key);
 
+    if verify.unwrap_or(false) {
+        let report = verifyWithKey(&storage, &newKey)?;
+        if !report.failures.is_empty() {
+            let paths: Vec<&str> = report.failures.iter().map(|f| f.path.as_str()).collect();
+            return Err(format!(
+                "Master password changed, but {} of {} files could not be decrypted with the new password: {}",
+                report.failures.len(), report.checked, paths.join(", ")
+            ).into());
+        }
+    }
+
     println!("[changeMasterPassword] SUCCESS");
     Ok(())
 }
 
+#[derive(serde::Serialize)]
+pub struct IntegrityFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct IntegrityReport {
+    pub checked: usize,
+    pub failures: Vec<IntegrityFailure>,
+}
+
+/// Confirm every encrypted file (folders and trash) decrypts with the given password
+/// Intended to run right after changeMasterPasswordVault
+#[tauri::command]
+pub fn verifyReEncryption(storage: State<'_, StorageState>, newPassword: String) -> Result<IntegrityReport, ClaudiaError> {
+    println!("[verifyReEncryption] Verifying all files against new password");
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let key = encryptionKeyFor(&newPassword, &readVaultSalt(&storage, &newPassword)?)?;
+    let report = verifyWithKey(&storage, &key)?;
+
+    println!("[verifyReEncryption] Checked {} files, {} failures", report.checked, report.failures.len());
+    storage.updateActivity();
+    Ok(report)
+}
+
+fn verifyWithKey(storage: &StorageState, key: &str) -> Result<IntegrityReport, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    let mut report = IntegrityReport { checked: 0, failures: Vec::new() };
+    scanIntegrity(&crate::storage::foldersDir(&wsPath), key, &mut report);
+    scanIntegrity(&crate::storage::trashDir(&wsPath), key, &mut report);
+    scanIntegrity(&crate::storage::assetsDir(&wsPath), key, &mut report);
+    Ok(report)
+}
+
+/// Integrity scanner: try to decrypt every encrypted .md/.asset file below `dir`
+pub(crate) fn scanIntegrity(dir: &std::path::Path, key: &str, report: &mut IntegrityReport) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+
+        if path.is_dir() {
+            scanIntegrity(&path, key, report);
+            continue;
+        }
+        if !path.extension().map(|e| e == "md" || e == "asset").unwrap_or(false) {
+            continue;
+        }
+
+        let content = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) => {
+                report.checked += 1;
+                report.failures.push(IntegrityFailure {
+                    path: path.to_string_lossy().to_string(),
+                    error: format!("Failed to read file: {}", e),
+                });
+                continue;
+            }
+        };
+        if !encrypted_storage::isEncryptedFormat(&content) {
+            continue;
+        }
+
+        report.checked += 1;
+        let result = encrypted_storage::parseEncryptedFile(&content, &fileIdOf(&path)).and_then(|encrypted| {
+            encrypted.decryptMetadata(key)?;
+            if !encrypted.content.is_empty() {
+                encrypted.decryptContent(key)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            report.failures.push(IntegrityFailure {
+                path: path.to_string_lossy().to_string(),
+                error: e.to_string(),
+            });
+        }
+    }
+}
+
+/// Rewrite v1 files and files still using legacy per-blob Argon2 encryption as key-wrapped v2
+/// Reads already handle all of them, so this only speeds up later scans and adds the file-id binding. Returns the number of files upgraded.
+#[tauri::command]
+pub fn upgradeVaultEncryption(storage: State<'_, StorageState>) -> Result<usize, ClaudiaError> {
+    println!("[upgradeVaultEncryption] Called");
+
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let key = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    let mut upgraded = 0;
+    upgradeDirectory(&crate::storage::foldersDir(&wsPath), &key, &mut upgraded);
+    upgradeDirectory(&crate::storage::trashDir(&wsPath), &key, &mut upgraded);
+    upgradeDirectory(&crate::storage::assetsDir(&wsPath), &key, &mut upgraded);
+
+    println!("[upgradeVaultEncryption] SUCCESS - upgraded {} files", upgraded);
+    storage.updateActivity();
+    Ok(upgraded)
+}
+
+/// Re-encrypt legacy .md/.asset files below `dir` with the same key
+/// Files that fail to decrypt are left alone (verifyReEncryption reports them)
+fn upgradeDirectory(dir: &std::path::Path, key: &str, upgraded: &mut usize) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+
+        if path.is_dir() {
+            upgradeDirectory(&path, key, upgraded);
+            continue;
+        }
+        if !path.extension().map(|e| e == "md" || e == "asset").unwrap_or(false) {
+            continue;
+        }
+
+        let Ok(content) = fs::read_to_string(&path) else { continue };
+        if !encrypted_storage::isEncryptedFormat(&content) {
+            continue;
+        }
+
+        let result = encrypted_storage::parseEncryptedFile(&content, &fileIdOf(&path)).and_then(|encrypted| {
+            if !encrypted_storage::usesLegacyEncryption(&encrypted) {
+                return Ok(false);
+            }
+            let metadata = encrypted.decryptMetadata(key)?;
+            let body = if encrypted.content.is_empty() {
+                String::new()
+            } else {
+                encrypted.decryptContent(key)?
+            };
+            let newContent = encrypted_storage::createEncryptedFile(&metadata, &body, key, &fileIdOf(&path))?;
+            writeFileAtomic(&path, newContent)?;
+            Ok(true)
+        });
+
+        match result {
+            Ok(true) => *upgraded += 1,
+            Ok(false) => {}
+            Err(e) => println!("[upgradeVaultEncryption] Skipping {:?}: {}", path, e),
+        }
+    }
+}
+
 /// Update activity to reset auto-lock timer (kept for compatibility)
 #[tauri::command]
 pub fn updateVaultActivity(storage: State<'_, StorageState>) {
@@ -152,26 +388,48 @@ pub fn isPasswordsAccessUnlocked(storage: State<'_, StorageState>) -> bool {
     storage.isPasswordsAccessUnlocked()
 }
 
-/// Unlock passwords access (verify password and grant 10-minute access)
+/// Lock state and countdowns for the UI; a None countdown means it never locks on its own
+#[derive(serde::Serialize)]
+pub struct LockStatus {
+    pub vaultUnlocked: bool,
+    pub vaultSecondsUntilLock: Option<u64>,
+    pub passwordsUnlocked: bool,
+    pub passwordsSecondsUntilLock: Option<u64>,
+}
+
+/// Current lock state; polling it does not count as activity
+#[tauri::command]
+pub fn getLockStatus(storage: State<'_, StorageState>) -> LockStatus {
+    let now = std::time::Instant::now();
+    LockStatus {
+        vaultUnlocked: storage.isUnlocked(),
+        vaultSecondsUntilLock: storage.secondsUntilAutoLock(now),
+        passwordsUnlocked: storage.isPasswordsAccessUnlocked(),
+        passwordsSecondsUntilLock: storage.secondsUntilPasswordsLock(now),
+    }
+}
+
+/// Unlock passwords access (verify password; stays open for passwordsAccessMinutes of inactivity)
 #[tauri::command]
-pub fn unlockPasswordsAccess(storage: State<'_, StorageState>, password: String) -> Result<bool, String> {
+pub fn unlockPasswordsAccess(storage: State<'_, StorageState>, password: String) -> Result<bool, ClaudiaError> {
     println!("[unlockPasswordsAccess] Attempting to unlock passwords access");
 
     // Vault must be unlocked first
     if !storage.isUnlocked() {
-        return Err("Vault is not unlocked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
+    storage.getAvailableWorkspacePath()?;
     let hashPath = storage.masterPasswordHashPath()
-        .ok_or("No workspace selected")?;
+        .ok_or(ClaudiaError::NoWorkspace)?;
 
     if !hashPath.exists() {
-        return Err("Vault not set up".to_string());
+        return Err("Vault not set up".into());
     }
 
     // Read stored hash and verify password
     let storedHash = std::fs::read_to_string(&hashPath)
-        .map_err(|e| format!("Failed to read master password hash: {}", e))?;
+        .map_err(|e| ClaudiaError::Io(format!("Failed to read master password hash: {}", e)))?;
 
     if !crypto::verifyMasterPassword(&password, &storedHash) {
         println!("[unlockPasswordsAccess] Password verification failed");
@@ -198,75 +456,397 @@ pub fn updatePasswordsActivity(storage: State<'_, StorageState>) {
     storage.updatePasswordsActivity();
 }
 
+// ============================================
+// SENSITIVE ACTION RE-AUTH
+// ============================================
+
+/// Commands that need a fresh password check when the reauthForSensitive setting is on
+pub(crate) const SENSITIVE_ACTIONS: &[&str] = &["exportWorkspace", "exportVault", "exportFolderPlaintext", "exportFolderZip", "exportItemJson"];
+
+/// Whether the frontend must prompt for the master password before running `action`
+#[tauri::command]
+pub fn requireReauth(storage: State<'_, StorageState>, action: String) -> bool {
+    isReauthRequired(&storage, &action)
+}
+
+/// Verify the master password again while the vault is unlocked
+/// Returns a single-use re-auth token for the next sensitive command, or None on a wrong password
+#[tauri::command]
+pub fn verifyMasterPasswordFresh(storage: State<'_, StorageState>, password: String) -> Result<Option<String>, ClaudiaError> {
+    println!("[verifyMasterPasswordFresh] Verifying master password");
+
+    if !storage.isUnlocked() {
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    storage.getAvailableWorkspacePath()?;
+    let hashPath = storage.masterPasswordHashPath()
+        .ok_or(ClaudiaError::NoWorkspace)?;
+    let storedHash = fs::read_to_string(&hashPath)
+        .map_err(|e| ClaudiaError::Io(format!("Failed to read master password hash: {}", e)))?;
+
+    if !crypto::verifyMasterPassword(&password, &storedHash) {
+        println!("[verifyMasterPasswordFresh] Password verification failed");
+        return Ok(None);
+    }
+
+    println!("[verifyMasterPasswordFresh] SUCCESS - re-auth token issued");
+    Ok(Some(storage.createReauthToken()))
+}
+
+fn isReauthRequired(storage: &StorageState, action: &str) -> bool {
+    SENSITIVE_ACTIONS.contains(&action) && storage.effectiveSettings().reauthForSensitive
+}
+
+/// Gate for sensitive commands: spends `reauthToken` when re-auth is required
+pub(crate) fn checkReauth(storage: &StorageState, action: &str, reauthToken: Option<&str>) -> Result<(), ClaudiaError> {
+    if !isReauthRequired(storage, action) {
+        return Ok(());
+    }
+    match reauthToken {
+        Some(token) if storage.consumeReauthToken(token) => Ok(()),
+        _ => {
+            println!("[checkReauth] {} refused - no valid re-auth token", action);
+            Err(ClaudiaError::ReauthRequired(format!("Reauthentication required: {} needs the master password re-entered", action)))
+        }
+    }
+}
+
 // ============================================
 // HELPER FUNCTIONS
 // ============================================
 
-/// Derive a 32-byte key from password using Argon2
-fn deriveKeyFromPassword(password: &str) -> Result<Vec<u8>, String> {
-    use argon2::Argon2;
+/// Size of the per-install vault salt stored in .vault-salt
+const VAULT_SALT_SIZE: usize = 16;
 
-    // Use a fixed salt derived from the password for deterministic key derivation
-    // This is safe because we also use random salts in the encryption itself
-    let salt = format!("claudia-vault-{}", password.len());
-    let salt_bytes = salt.as_bytes();
+/// Random salt for a new vault key
+fn generateVaultSalt() -> Vec<u8> {
+    let mut salt = vec![0u8; VAULT_SALT_SIZE];
+    rand::Rng::fill(&mut rand::thread_rng(), salt.as_mut_slice());
+    salt
+}
+
+/// Persist the vault salt (base64) next to the master password hash
+fn writeVaultSalt(storage: &StorageState, salt: &[u8]) -> Result<(), ClaudiaError> {
+    let saltPath = storage.vaultSaltPath().ok_or(ClaudiaError::NoWorkspace)?;
+    let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, salt);
+    writeFileAtomic(&saltPath, encoded).map_err(|e| ClaudiaError::Io(format!("Failed to write vault salt: {}", e)))
+}
+
+/// Salt for the current vault's key
+/// Vaults set up before .vault-salt existed keep their old length-based salt until the
+/// master password is changed, since every file is encrypted under that key
+fn readVaultSalt(storage: &StorageState, password: &str) -> Result<Vec<u8>, ClaudiaError> {
+    let saltPath = storage.vaultSaltPath().ok_or(ClaudiaError::NoWorkspace)?;
+    if !saltPath.exists() {
+        return Ok(format!("claudia-vault-{}", password.len()).into_bytes());
+    }
+
+    let encoded = fs::read_to_string(&saltPath)
+        .map_err(|e| ClaudiaError::Io(format!("Failed to read vault salt: {}", e)))?;
+    base64::Engine::decode(&base64::engine::general_purpose::STANDARD, encoded.trim())
+        .ok()
+        .filter(|salt| salt.len() >= 8)
+        .ok_or_else(|| ClaudiaError::Decrypt("Vault salt file is corrupt".to_string()))
+}
+
+/// Derive a 32-byte key from password and vault salt using Argon2
+fn deriveKeyFromPassword(password: &str, salt: &[u8]) -> Result<Vec<u8>, String> {
+    use argon2::Argon2;
 
     let mut key = vec![0u8; 32];
     Argon2::default()
-        .hash_password_into(password.as_bytes(), salt_bytes, &mut key)
+        .hash_password_into(password.as_bytes(), salt, &mut key)
         .map_err(|e| format!("Key derivation failed: {}", e))?;
 
     Ok(key)
 }
 
-/// Re-encrypt all files with new password when master password changes
-fn reEncryptAllFiles(
-    storage: &StorageState,
-    oldPassword: &str,
-    newPassword: &str,
-) -> Result<(), String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
-    let foldersDir = crate::storage::foldersDir(&wsPath);
-
-    println!("[reEncryptAllFiles] Re-encrypting files in {:?}", foldersDir);
+/// Key string used by the crypto functions for a given master password
+/// (base64 of the derived key, same as Storage::getMasterPassword)
+fn encryptionKeyFor(password: &str, salt: &[u8]) -> Result<String, String> {
+    let key = zeroize::Zeroizing::new(deriveKeyFromPassword(password, salt)?);
+    Ok(base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &*key))
+}
 
-    // Walk through all .md files and re-encrypt them
-    reEncryptDirectory(&foldersDir, oldPassword, newPassword)?;
+// ============================================
+// STAGED RE-ENCRYPTION
+// ============================================
+// Changing the master password rewrites every encrypted file. Doing that in place would
+// leave a vault readable by neither password if it failed halfway, so the new versions
+// are first written next to the originals and only swapped in once all of them exist.
+// Originals are kept as backups until the new hash and salt are on disk.
+
+const STAGED_SUFFIX: &str = "rekey-new";
+const BACKUP_SUFFIX: &str = "rekey-old";
+
+/// `path` with `.suffix` appended to the file name
+fn siblingWithSuffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
 
-    Ok(())
+/// Every encrypted .md/.asset file of the vault (trashed items too, so they stay restorable)
+fn encryptedVaultFiles(wsPath: &str) -> Vec<PathBuf> {
+    let roots = [
+        crate::storage::foldersDir(wsPath),
+        crate::storage::trashDir(wsPath),
+        crate::storage::assetsDir(wsPath),
+    ];
+    let mut files: Vec<PathBuf> = roots.iter()
+        .flat_map(|root| walkdir::WalkDir::new(root).into_iter().filter_map(|e| e.ok()))
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|p| p.extension().map(|e| e == "md" || e == "asset").unwrap_or(false))
+        .collect();
+    files.sort();
+    files
 }
 
-fn reEncryptDirectory(dir: &std::path::Path, oldPassword: &str, newPassword: &str) -> Result<(), String> {
-    if !dir.exists() {
-        return Ok(());
+/// New content for one file under `newKey`, or None if it is not encrypted
+fn reEncryptFile(path: &Path, oldKey: &str, newKey: &str) -> Result<Option<String>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if !encrypted_storage::isEncryptedFormat(&content) {
+        return Ok(None);
     }
 
-    for entry in fs::read_dir(dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
+    let fileId = fileIdOf(path);
+    let encrypted = encrypted_storage::parseEncryptedFile(&content, &fileId)?;
+    let metadata = encrypted.decryptMetadata(oldKey)?;
+    let body = if encrypted.content.is_empty() {
+        String::new()
+    } else {
+        encrypted.decryptContent(oldKey)?
+    };
+    encrypted_storage::createEncryptedFile(&metadata, &body, newKey, &fileId).map(Some)
+}
 
-        if path.is_dir() {
-            reEncryptDirectory(&path, oldPassword, newPassword)?;
-        } else if path.extension().map(|e| e == "md").unwrap_or(false) {
-            let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+/// Re-encrypted copies written next to their originals, nothing swapped in yet
+struct StagedReEncryption {
+    files: Vec<PathBuf>,
+}
 
-            // Only re-encrypt if it's in encrypted format
-            if encrypted_storage::isEncryptedFormat(&content) {
-                println!("[reEncryptDirectory] Re-encrypting {:?}", path);
+impl StagedReEncryption {
+    /// Write a re-encrypted copy of every file; on any error the copies are removed
+    /// and the originals are untouched
+    fn stage(files: &[PathBuf], oldKey: &str, newKey: &str) -> Result<Self, String> {
+        let mut staged = Self { files: Vec::new() };
+        for path in files {
+            let result = reEncryptFile(path, oldKey, newKey).and_then(|newContent| match newContent {
+                Some(c) => fs::write(siblingWithSuffix(path, STAGED_SUFFIX), c)
+                    .map(|_| true)
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e)),
+                None => Ok(false),
+            });
+            match result {
+                Ok(true) => staged.files.push(path.clone()),
+                Ok(false) => {}
+                Err(e) => {
+                    println!("[StagedReEncryption] ERROR at {:?}: {} - discarding staged files", path, e);
+                    staged.discard();
+                    return Err(e);
+                }
+            }
+        }
+        Ok(staged)
+    }
 
-                let encrypted = encrypted_storage::parseEncryptedFile(&content)?;
+    fn discard(self) {
+        for path in &self.files {
+            let _ = fs::remove_file(siblingWithSuffix(path, STAGED_SUFFIX));
+        }
+    }
 
-                // Decrypt with old password
-                let metadata = encrypted_storage::decryptMetadata(&encrypted.metadata, oldPassword)?;
-                let body = encrypted_storage::decryptContent(&encrypted.content, oldPassword)?;
+    /// Move every staged copy into place, keeping the originals as backups
+    /// If a rename fails, files already swapped are restored before returning the error
+    fn swapIn(self) -> Result<SwappedReEncryption, String> {
+        let mut swapped = SwappedReEncryption { files: Vec::new() };
+        for (i, path) in self.files.iter().enumerate() {
+            let result = fs::rename(path, siblingWithSuffix(path, BACKUP_SUFFIX)).and_then(|_| {
+                fs::rename(siblingWithSuffix(path, STAGED_SUFFIX), path).inspect_err(|_| {
+                    let _ = fs::rename(siblingWithSuffix(path, BACKUP_SUFFIX), path);
+                })
+            });
+            if let Err(e) = result {
+                println!("[StagedReEncryption] ERROR swapping {:?}: {} - rolling back", path, e);
+                swapped.rollback();
+                Self { files: self.files[i..].to_vec() }.discard();
+                return Err(format!("Failed to replace {}: {}", path.display(), e));
+            }
+            swapped.files.push(path.clone());
+        }
+        Ok(swapped)
+    }
+}
 
-                // Re-encrypt with new password
-                let newContent = encrypted_storage::createEncryptedFile(&metadata, &body, newPassword)?;
+/// Re-encrypted files in place, originals still available as backups
+struct SwappedReEncryption {
+    files: Vec<PathBuf>,
+}
 
-                fs::write(&path, newContent).map_err(|e| e.to_string())?;
+impl SwappedReEncryption {
+    /// Put the originals back
+    fn rollback(self) {
+        for path in self.files.iter().rev() {
+            if let Err(e) = fs::rename(siblingWithSuffix(path, BACKUP_SUFFIX), path) {
+                println!("[SwappedReEncryption] ERROR restoring {:?}: {}", path, e);
             }
         }
     }
 
-    Ok(())
+    /// Drop the backups once the new password is fully in effect
+    fn finish(self) {
+        for path in &self.files {
+            let _ = fs::remove_file(siblingWithSuffix(path, BACKUP_SUFFIX));
+        }
+    }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_password_different_installs() {
+        let first = deriveKeyFromPassword("correct horse battery", &generateVaultSalt()).unwrap();
+        let second = deriveKeyFromPassword("correct horse battery", &generateVaultSalt()).unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_same_salt_same_key() {
+        let salt = generateVaultSalt();
+        assert_eq!(salt.len(), VAULT_SALT_SIZE);
+        let first = deriveKeyFromPassword("correct horse battery", &salt).unwrap();
+        let second = deriveKeyFromPassword("correct horse battery", &salt).unwrap();
+        assert_eq!(first, second);
+    }
+
+    /// Workspace with `count` encrypted notes; the note at `badIndex` uses a different key
+    fn writeEncryptedNotes(count: usize, key: &str, badIndex: Option<usize>) -> (PathBuf, Vec<PathBuf>) {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", crate::commands::common::newId()));
+        let dir = crate::storage::notesDir(&ws.to_string_lossy(), "");
+        fs::create_dir_all(&dir).unwrap();
+        let files: Vec<PathBuf> = (0..count).map(|i| {
+            let path = dir.join(format!("{}-{}.md", i, crate::commands::common::newId()));
+            let fileKey = if badIndex == Some(i) { "some other key" } else { key };
+            let content = encrypted_storage::createEncryptedFile("title: note", &format!("body {}", i), fileKey, &fileIdOf(&path)).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        }).collect();
+        (ws, files)
+    }
+
+    #[test]
+    fn test_failed_re_encryption_leaves_vault_unchanged() {
+        let (ws, files) = writeEncryptedNotes(5, "old key", Some(3));
+        let found = encryptedVaultFiles(&ws.to_string_lossy());
+        assert_eq!(found, files);
+        let before: Vec<Vec<u8>> = files.iter().map(|f| fs::read(f).unwrap()).collect();
+
+        assert!(StagedReEncryption::stage(&found, "old key", "new key").is_err());
+
+        let after: Vec<Vec<u8>> = files.iter().map(|f| fs::read(f).unwrap()).collect();
+        assert_eq!(before, after);
+        let leftovers = walkdir::WalkDir::new(&ws).into_iter().filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .count();
+        assert_eq!(leftovers, files.len());
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_re_encryption_swaps_in_all_files() {
+        let (ws, files) = writeEncryptedNotes(3, "old key", None);
+
+        StagedReEncryption::stage(&files, "old key", "new key").unwrap().swapIn().unwrap().finish();
+
+        for (i, path) in files.iter().enumerate() {
+            let encrypted = encrypted_storage::parseEncryptedFile(&fs::read_to_string(path).unwrap(), &fileIdOf(path)).unwrap();
+            assert_eq!(encrypted.decryptContent("new key").unwrap(), format!("body {}", i));
+            assert!(encrypted.decryptContent("old key").is_err());
+            assert!(!siblingWithSuffix(path, STAGED_SUFFIX).exists());
+            assert!(!siblingWithSuffix(path, BACKUP_SUFFIX).exists());
+        }
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_re_encryption_covers_history_and_trash() {
+        use crate::storage::{foldersDir, historyDir, itemsDir, trashNotesDir, uuidFilename};
+        use crate::commands::common::newId;
+        use crate::commands::item::{listVersions, snapshotVersion};
+        use crate::models::ItemKind;
+
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        let writeNote = |dir: &Path, body: &str| -> (String, PathBuf) {
+            fs::create_dir_all(dir).unwrap();
+            let id = newId();
+            let path = dir.join(uuidFilename(&id));
+            fs::write(&path, encrypted_storage::createEncryptedFile("title: note", body, "old key", &id).unwrap()).unwrap();
+            (id, path)
+        };
+
+        let notes = itemsDir(&foldersDir(&wsPath), ItemKind::Note);
+        let (id, live) = writeNote(&notes, "current");
+        snapshotVersion(&notes, &live, &id, 20).unwrap();
+        let version = historyDir(&notes, &id).join(format!("{}.md", listVersions(&notes, &id)[0]));
+        let (_, trashed) = writeNote(&trashNotesDir(&wsPath), "trashed");
+
+        let files = encryptedVaultFiles(&wsPath);
+        assert_eq!(files.len(), 3);
+        StagedReEncryption::stage(&files, "old key", "new key").unwrap().swapIn().unwrap().finish();
+
+        for (path, body) in [(&live, "current"), (&version, "current"), (&trashed, "trashed")] {
+            let encrypted = encrypted_storage::parseEncryptedFile(&fs::read_to_string(path).unwrap(), &fileIdOf(path)).unwrap();
+            assert_eq!(encrypted.decryptContent("new key").unwrap(), body);
+        }
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    /// Create a vault in a fresh workspace directory and make it current (still locked)
+    fn setUpVault(storage: &StorageState, password: &str) -> String {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", crate::commands::common::newId()));
+        fs::create_dir_all(&ws).unwrap();
+        let wsPath = ws.to_string_lossy().to_string();
+        storage.setCurrentWorkspace(Some(wsPath.clone()));
+        fs::write(storage.masterPasswordHashPath().unwrap(), crypto::hashMasterPassword(password).unwrap()).unwrap();
+        writeVaultSalt(storage, &generateVaultSalt()).unwrap();
+        wsPath
+    }
+
+    #[test]
+    fn test_unlocking_one_workspace_does_not_unlock_another() {
+        let storage: StorageState = std::sync::Arc::new(crate::storage::Storage::new());
+        let wsA = setUpVault(&storage, "password of A");
+        let wsB = setUpVault(&storage, "password of B");
+
+        storage.setCurrentWorkspace(Some(wsA.clone()));
+        assert!(unlockWithPassword(&storage, "password of A").unwrap());
+        let keyA = storage.getDerivedKey().unwrap();
+
+        // Switching workspaces locks, and A's password does not open B
+        storage.setCurrentWorkspace(Some(wsB.clone()));
+        assert!(!storage.isUnlocked());
+        assert!(!unlockWithPassword(&storage, "password of A").unwrap());
+        assert!(!storage.isUnlocked());
+
+        assert!(unlockWithPassword(&storage, "password of B").unwrap());
+        assert_ne!(storage.getDerivedKey().unwrap(), keyA);
+
+        // Re-selecting the open workspace keeps it unlocked
+        storage.setCurrentWorkspace(Some(wsB.clone()));
+        assert!(storage.isUnlocked());
+
+        for ws in [wsA, wsB] {
+            let _ = fs::remove_dir_all(ws);
+        }
+    }
+}
+
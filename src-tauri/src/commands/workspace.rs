@@ -5,9 +5,10 @@ use std::path::PathBuf;
 use tauri::State;
 use rfd::FileDialog;
 
+use crate::error::ClaudiaError;
 use crate::storage::{StorageState, saveGlobalConfig, foldersDir, notesDir, tasksDir, workspaceConfigPath, parseFrontmatter};
 use crate::models::{WorkspaceEntry, SettingsOverride};
-use super::common::now;
+use super::common::{now, sanitizeTitle};
 
 #[derive(serde::Serialize)]
 pub struct WorkspaceInfo {
@@ -64,7 +65,7 @@ pub fn getCurrentWorkspace(storage: State<'_, StorageState>) -> Option<Workspace
 }
 
 #[tauri::command]
-pub fn createWorkspace(storage: State<'_, StorageState>, path: String) -> Result<WorkspaceInfo, String> {
+pub fn createWorkspace(storage: State<'_, StorageState>, path: String) -> Result<WorkspaceInfo, ClaudiaError> {
     println!("[createWorkspace] Called with path: {}", path);
 
     let pathBuf = PathBuf::from(&path);
@@ -72,16 +73,16 @@ pub fn createWorkspace(storage: State<'_, StorageState>, path: String) -> Result
     // Create unified workspace structure: folders/ with notes/ and tasks/ inside
     let folders = foldersDir(&path);
     println!("[createWorkspace] Creating folders directory: {:?}", folders);
-    fs::create_dir_all(&folders).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&folders)?;
     
     // Create root notes and tasks directories
     let notes = notesDir(&path, "");
     println!("[createWorkspace] Creating notes directory: {:?}", notes);
-    fs::create_dir_all(&notes).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&notes)?;
     
     let tasks = tasksDir(&path, "");
     println!("[createWorkspace] Creating tasks directory: {:?}", tasks);
-    fs::create_dir_all(&tasks).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&tasks)?;
 
     let name = pathBuf.file_name()
         .and_then(|n| n.to_str())
@@ -107,11 +108,7 @@ pub fn createWorkspace(storage: State<'_, StorageState>, path: String) -> Result
     }
 
     // Set as current workspace
-    {
-        let mut settings = storage.globalSettings.write();
-        settings.currentWorkspace = Some(path.clone());
-    }
-    *storage.workspacePath.write() = Some(path.clone());
+    storage.setCurrentWorkspace(Some(path.clone()));
     println!("[createWorkspace] Set as current workspace");
 
     // Load workspace config override if exists
@@ -120,12 +117,13 @@ pub fn createWorkspace(storage: State<'_, StorageState>, path: String) -> Result
         println!("[createWorkspace] Loading workspace config override from {:?}", configPath);
         if let Ok(content) = fs::read_to_string(&configPath) {
             if let Some((over, _)) = parseFrontmatter::<SettingsOverride>(&content) {
-                *storage.workspaceOverride.write() = over;
+                storage.setWorkspaceOverride(over);
             }
         }
     }
 
     saveGlobalConfig(&storage)?;
+    crate::watcher::syncVaultWatcher(&storage);
     println!("[createWorkspace] SUCCESS");
 
     Ok(WorkspaceInfo {
@@ -137,8 +135,15 @@ pub fn createWorkspace(storage: State<'_, StorageState>, path: String) -> Result
 }
 
 #[tauri::command]
-pub fn openWorkspace(storage: State<'_, StorageState>, path: String) -> Result<WorkspaceInfo, String> {
+pub fn openWorkspace(storage: State<'_, StorageState>, path: String) -> Result<WorkspaceInfo, ClaudiaError> {
     println!("[openWorkspace] Called with path: {}", path);
+    openWorkspaceAt(&storage, &path)
+}
+
+/// Make a known workspace current and load its settings override
+/// Shared by the openWorkspace command and the MCP switch_workspace tool
+pub(crate) fn openWorkspaceAt(storage: &StorageState, path: &str) -> Result<WorkspaceInfo, ClaudiaError> {
+    let path = path.to_string();
 
     // Update lastOpened
     {
@@ -152,11 +157,7 @@ pub fn openWorkspace(storage: State<'_, StorageState>, path: String) -> Result<W
     }
 
     // Set as current
-    {
-        let mut settings = storage.globalSettings.write();
-        settings.currentWorkspace = Some(path.clone());
-    }
-    *storage.workspacePath.write() = Some(path.clone());
+    storage.setCurrentWorkspace(Some(path.clone()));
     println!("[openWorkspace] Set as current workspace");
 
     // Load workspace config override
@@ -165,18 +166,19 @@ pub fn openWorkspace(storage: State<'_, StorageState>, path: String) -> Result<W
         println!("[openWorkspace] Loading config override from {:?}", configPath);
         if let Ok(content) = fs::read_to_string(&configPath) {
             if let Some((over, _)) = parseFrontmatter::<SettingsOverride>(&content) {
-                *storage.workspaceOverride.write() = over;
+                storage.setWorkspaceOverride(over);
             }
         }
     } else {
         println!("[openWorkspace] No config override found, using defaults");
-        *storage.workspaceOverride.write() = SettingsOverride::default();
+        storage.setWorkspaceOverride(SettingsOverride::default());
     }
 
-    saveGlobalConfig(&storage)?;
+    saveGlobalConfig(storage)?;
+    crate::watcher::syncVaultWatcher(storage);
 
     let workspaces = storage.workspaces.read();
-    let ws = workspaces.iter().find(|ws| ws.path == path).ok_or("Workspace not found")?;
+    let ws = workspaces.iter().find(|ws| ws.path == path).ok_or_else(|| ClaudiaError::NotFound("Workspace not found".to_string()))?;
     println!("[openWorkspace] SUCCESS - opened workspace: {}", ws.name);
 
     Ok(WorkspaceInfo {
@@ -188,12 +190,11 @@ pub fn openWorkspace(storage: State<'_, StorageState>, path: String) -> Result<W
 }
 
 #[tauri::command]
-pub fn closeWorkspace(storage: State<'_, StorageState>) -> Result<(), String> {
+pub fn closeWorkspace(storage: State<'_, StorageState>) -> Result<(), ClaudiaError> {
     println!("[closeWorkspace] Called");
 
-    storage.globalSettings.write().currentWorkspace = None;
-    *storage.workspacePath.write() = None;
-    *storage.workspaceOverride.write() = SettingsOverride::default();
+    storage.setCurrentWorkspace(None);
+    storage.setWorkspaceOverride(SettingsOverride::default());
 
     saveGlobalConfig(&storage)?;
     println!("[closeWorkspace] SUCCESS - workspace closed");
@@ -201,7 +202,7 @@ pub fn closeWorkspace(storage: State<'_, StorageState>) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn removeWorkspace(storage: State<'_, StorageState>, path: String) -> Result<(), String> {
+pub fn removeWorkspace(storage: State<'_, StorageState>, path: String) -> Result<(), ClaudiaError> {
     println!("[removeWorkspace] Called with path: {}", path);
 
     {
@@ -220,7 +221,7 @@ pub fn removeWorkspace(storage: State<'_, StorageState>, path: String) -> Result
         }
     }
     if storage.getWorkspacePath().as_ref() == Some(&path) {
-        *storage.workspacePath.write() = None;
+        storage.setCurrentWorkspace(None);
     }
 
     saveGlobalConfig(&storage)?;
@@ -228,6 +229,104 @@ pub fn removeWorkspace(storage: State<'_, StorageState>, path: String) -> Result
     Ok(())
 }
 
+/// Move a workspace directory on disk and repoint its entry (and current workspace) at the new path
+/// Only same-device moves are supported; across drives, export and import instead
+#[tauri::command]
+pub fn relocateWorkspace(storage: State<'_, StorageState>, oldPath: String, newPath: String) -> Result<WorkspaceInfo, ClaudiaError> {
+    println!("[relocateWorkspace] Called with oldPath: {}, newPath: {}", oldPath, newPath);
+
+    if !storage.workspaces.read().iter().any(|ws| ws.path == oldPath) {
+        return Err(ClaudiaError::NotFound("Workspace not found".to_string()));
+    }
+
+    let source = PathBuf::from(&oldPath);
+    if !source.is_dir() {
+        return Err(format!("Workspace unavailable: {} no longer exists", oldPath).into());
+    }
+
+    let target = PathBuf::from(&newPath);
+    if target.starts_with(&source) {
+        return Err(ClaudiaError::InvalidInput("Invalid input: new location is inside the workspace".to_string()));
+    }
+    if target.exists() {
+        let isEmptyDir = target.is_dir()
+            && fs::read_dir(&target).map(|mut e| e.next().is_none()).unwrap_or(false);
+        if !isEmptyDir {
+            return Err(ClaudiaError::InvalidInput("Invalid input: new location must be empty".to_string()));
+        }
+        // rename() won't replace a directory on every platform
+        fs::remove_dir(&target)?;
+    } else if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::rename(&source, &target).map_err(|e| {
+        println!("[relocateWorkspace] ERROR: {}", e);
+        if e.kind() == std::io::ErrorKind::CrossesDevices {
+            "Cannot move a workspace to another drive - export it and import at the new location instead".to_string()
+        } else {
+            e.to_string()
+        }
+    })?;
+    println!("[relocateWorkspace] Moved {} -> {}", oldPath, newPath);
+
+    let entry = {
+        let mut workspaces = storage.workspaces.write();
+        let ws = workspaces.iter_mut().find(|ws| ws.path == oldPath).ok_or_else(|| ClaudiaError::NotFound("Workspace not found".to_string()))?;
+        ws.path = newPath.clone();
+        ws.clone()
+    };
+
+    let isCurrent = storage.getWorkspacePath().as_deref() == Some(oldPath.as_str());
+    // Same vault at a new location, so it stays unlocked
+    if isCurrent {
+        storage.globalSettings.write().currentWorkspace = Some(newPath.clone());
+        *storage.workspacePath.write() = Some(newPath.clone());
+    }
+
+    saveGlobalConfig(&storage)?;
+    println!("[relocateWorkspace] SUCCESS");
+
+    Ok(WorkspaceInfo {
+        path: entry.path,
+        name: entry.name,
+        lastOpened: entry.lastOpened,
+        isCurrent,
+    })
+}
+
+/// Change a workspace's display name; the directory itself is left alone
+#[tauri::command]
+pub fn renameWorkspace(storage: State<'_, StorageState>, path: String, newName: String) -> Result<WorkspaceInfo, ClaudiaError> {
+    println!("[renameWorkspace] Called with path: {}, newName: {}", path, newName);
+
+    let name = sanitizeTitle(&newName, false);
+    if name.is_empty() {
+        return Err(ClaudiaError::InvalidInput("Invalid input: workspace name cannot be empty".to_string()));
+    }
+    // Names are stored in the markdown table of the global config
+    if name.contains('|') {
+        return Err(ClaudiaError::InvalidInput("Invalid input: workspace name cannot contain '|'".to_string()));
+    }
+
+    let entry = {
+        let mut workspaces = storage.workspaces.write();
+        let ws = workspaces.iter_mut().find(|ws| ws.path == path).ok_or_else(|| ClaudiaError::NotFound("Workspace not found".to_string()))?;
+        ws.name = name;
+        ws.clone()
+    };
+
+    saveGlobalConfig(&storage)?;
+    println!("[renameWorkspace] SUCCESS - renamed to: {}", entry.name);
+
+    Ok(WorkspaceInfo {
+        isCurrent: storage.getWorkspacePath().as_deref() == Some(entry.path.as_str()),
+        path: entry.path,
+        name: entry.name,
+        lastOpened: entry.lastOpened,
+    })
+}
+
 #[tauri::command]
 pub fn openFolderDialog() -> Option<String> {
     println!("[openFolderDialog] Called");
@@ -1,7 +1,7 @@
 // Password encryption using AES-256-GCM with Argon2 key derivation
 
 use aes_gcm::{
-    aead::{Aead, KeyInit},
+    aead::{Aead, KeyInit, Payload},
     Aes256Gcm, Nonce,
 };
 use argon2::{Argon2, password_hash::SaltString};
@@ -14,6 +14,9 @@ const SALT_SIZE: usize = 16;
 /// Derive a 256-bit key from master password using Argon2
 /// Key is wrapped in Zeroizing for secure memory cleanup
 fn deriveKey(password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, String> {
+    #[cfg(test)]
+    tests::ARGON2_CALLS.with(|c| c.set(c.get() + 1));
+
     let mut key = Zeroizing::new([0u8; 32]);
     Argon2::default()
         .hash_password_into(password.as_bytes(), salt, key.as_mut())
@@ -21,37 +24,101 @@ fn deriveKey(password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, String>
     Ok(key)
 }
 
-/// Encrypt content with master password
-/// Returns: salt (16) + nonce (12) + ciphertext, base64 encoded
-pub fn encrypt(plaintext: &str, masterPassword: &str) -> Result<String, String> {
-    let mut rng = rand::thread_rng();
+// ============================================
+// KEY WRAPPING
+// ============================================
+// Every blob gets its own random data key, sealed under a vault wrapping key:
+//   "k1:" + base64(wrap nonce (12) + wrapped data key (48) + nonce (12) + ciphertext)
+// The master password handed to these functions is already Argon2 output
+// (Storage::getMasterPassword), so the wrapping key comes from a fast KDF and
+// scans no longer pay one Argon2 run per file.
+// Legacy blobs - base64(salt (16) + nonce (12) + ciphertext) with an Argon2 key per
+// blob - still decrypt; they are rewritten on the next save or by upgradeVaultEncryption.
+// `aad` is authenticated but not encrypted (encrypted_storage binds the file id with it);
+// legacy blobs predate it and only open with an empty aad.
+
+/// Prefix of key-wrapped blobs (':' is not in the base64 alphabet, so legacy blobs never match)
+const WRAPPED_PREFIX: &str = "k1:";
+const KEY_SIZE: usize = 32;
+const TAG_SIZE: usize = 16;
+const WRAPPED_KEY_SIZE: usize = NONCE_SIZE + KEY_SIZE + TAG_SIZE;
+
+/// Vault wrapping key for a master key string
+fn wrappingKey(masterPassword: &str) -> Zeroizing<[u8; 32]> {
+    Zeroizing::new(blake3::derive_key("claudia vault key wrapping v1", masterPassword.as_bytes()))
+}
 
-    // Generate random salt and nonce
-    let mut salt = [0u8; SALT_SIZE];
+/// AES-256-GCM seal with a fresh nonce: nonce (12) + ciphertext
+fn seal(key: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
     let mut nonce_bytes = [0u8; NONCE_SIZE];
-    rng.fill(&mut salt);
-    rng.fill(&mut nonce_bytes);
+    rand::thread_rng().fill(&mut nonce_bytes);
 
-    // Derive key (automatically zeroed when dropped)
-    let key = deriveKey(masterPassword, &salt)?;
-    let cipher = Aes256Gcm::new_from_slice(key.as_ref()).map_err(|e| e.to_string())?;
-    let nonce = Nonce::from_slice(&nonce_bytes);
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?;
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce_bytes), Payload { msg: plaintext, aad })
+        .map_err(|e| e.to_string())?;
+
+    let mut sealed = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
+    sealed.extend_from_slice(&nonce_bytes);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Inverse of seal
+fn open(key: &[u8], sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
+    if sealed.len() < NONCE_SIZE + TAG_SIZE {
+        return Err("Invalid encrypted data".to_string());
+    }
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?;
+    cipher.decrypt(Nonce::from_slice(&sealed[..NONCE_SIZE]), Payload { msg: &sealed[NONCE_SIZE..], aad })
+        .map_err(|_| "Decryption failed - wrong password?".to_string())
+}
+
+/// Encrypt content with master password, authenticating `aad` alongside it
+/// Returns a key-wrapped blob (see KEY WRAPPING)
+pub fn encrypt(plaintext: &str, masterPassword: &str, aad: &[u8]) -> Result<String, String> {
+    let mut dataKey = Zeroizing::new([0u8; KEY_SIZE]);
+    rand::thread_rng().fill(&mut *dataKey);
 
-    // Encrypt
-    let ciphertext = cipher.encrypt(nonce, plaintext.as_bytes())
+    let wrappedKey = seal(wrappingKey(masterPassword).as_ref(), dataKey.as_ref(), b"")?;
+    let sealed = seal(dataKey.as_ref(), plaintext.as_bytes(), aad)?;
+
+    let mut combined = Vec::with_capacity(wrappedKey.len() + sealed.len());
+    combined.extend_from_slice(&wrappedKey);
+    combined.extend_from_slice(&sealed);
+
+    Ok(format!("{}{}", WRAPPED_PREFIX, base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &combined)))
+}
+
+/// Decrypt content with master password (key-wrapped or legacy blob)
+/// Fails unless `aad` matches what the blob was encrypted with
+pub fn decrypt(encrypted: &str, masterPassword: &str, aad: &[u8]) -> Result<String, String> {
+    match encrypted.strip_prefix(WRAPPED_PREFIX) {
+        Some(wrapped) => decryptWrapped(wrapped, masterPassword, aad),
+        None if aad.is_empty() => decryptLegacy(encrypted, masterPassword),
+        None => Err("Decryption failed - legacy data where bound data was expected".to_string()),
+    }
+}
+
+/// Whether a blob still uses the legacy per-blob Argon2 scheme
+pub fn isLegacyCiphertext(encrypted: &str) -> bool {
+    !encrypted.is_empty() && !encrypted.starts_with(WRAPPED_PREFIX)
+}
+
+fn decryptWrapped(encoded: &str, masterPassword: &str, aad: &[u8]) -> Result<String, String> {
+    let combined = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, encoded)
         .map_err(|e| e.to_string())?;
 
-    // Combine: salt + nonce + ciphertext
-    let mut combined = Vec::with_capacity(SALT_SIZE + NONCE_SIZE + ciphertext.len());
-    combined.extend_from_slice(&salt);
-    combined.extend_from_slice(&nonce_bytes);
-    combined.extend_from_slice(&ciphertext);
+    if combined.len() < WRAPPED_KEY_SIZE + NONCE_SIZE + TAG_SIZE {
+        return Err("Invalid encrypted data".to_string());
+    }
+
+    let dataKey = Zeroizing::new(open(wrappingKey(masterPassword).as_ref(), &combined[..WRAPPED_KEY_SIZE], b"")?);
+    let plaintext = open(&dataKey, &combined[WRAPPED_KEY_SIZE..], aad)?;
 
-    Ok(base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &combined))
+    String::from_utf8(plaintext).map_err(|e| e.to_string())
 }
 
-/// Decrypt content with master password
-pub fn decrypt(encrypted: &str, masterPassword: &str) -> Result<String, String> {
+fn decryptLegacy(encrypted: &str, masterPassword: &str) -> Result<String, String> {
     let combined = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, encrypted)
         .map_err(|e| e.to_string())?;
 
@@ -60,17 +127,10 @@ pub fn decrypt(encrypted: &str, masterPassword: &str) -> Result<String, String>
     }
 
     let salt = &combined[..SALT_SIZE];
-    let nonce_bytes = &combined[SALT_SIZE..SALT_SIZE + NONCE_SIZE];
-    let ciphertext = &combined[SALT_SIZE + NONCE_SIZE..];
 
     // Derive key (automatically zeroed when dropped)
     let key = deriveKey(masterPassword, salt)?;
-    let cipher = Aes256Gcm::new_from_slice(key.as_ref()).map_err(|e| e.to_string())?;
-    let nonce = Nonce::from_slice(nonce_bytes);
-
-    // Decrypt
-    let plaintext = cipher.decrypt(nonce, ciphertext)
-        .map_err(|_| "Decryption failed - wrong password?".to_string())?;
+    let plaintext = open(key.as_ref(), &combined[SALT_SIZE..], b"")?;
 
     String::from_utf8(plaintext).map_err(|e| e.to_string())
 }
@@ -94,3 +154,77 @@ pub fn verifyMasterPassword(password: &str, hash: &str) -> bool {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    thread_local! {
+        /// Argon2 runs on the current test thread
+        pub static ARGON2_CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn argon2Calls() -> usize {
+        ARGON2_CALLS.with(|c| c.get())
+    }
+
+    /// The pre-wrapping format, to check old files still open
+    fn encryptLegacy(plaintext: &str, masterPassword: &str) -> String {
+        let mut salt = [0u8; SALT_SIZE];
+        rand::thread_rng().fill(&mut salt);
+        let key = deriveKey(masterPassword, &salt).unwrap();
+
+        let mut combined = salt.to_vec();
+        combined.extend(seal(key.as_ref(), plaintext.as_bytes(), b"").unwrap());
+        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &combined)
+    }
+
+    #[test]
+    fn test_wrapped_round_trip() {
+        let blob = encrypt("secret note", "vault-key", b"").unwrap();
+        assert!(blob.starts_with(WRAPPED_PREFIX));
+        assert!(!isLegacyCiphertext(&blob));
+        assert_eq!(decrypt(&blob, "vault-key", b"").unwrap(), "secret note");
+        assert!(decrypt(&blob, "other-key", b"").is_err());
+    }
+
+    #[test]
+    fn test_associated_data_must_match() {
+        let blob = encrypt("secret note", "vault-key", b"id-1:metadata").unwrap();
+        assert_eq!(decrypt(&blob, "vault-key", b"id-1:metadata").unwrap(), "secret note");
+        assert!(decrypt(&blob, "vault-key", b"id-2:metadata").is_err());
+        assert!(decrypt(&blob, "vault-key", b"").is_err());
+    }
+
+    #[test]
+    fn test_legacy_blobs_still_decrypt() {
+        let blob = encryptLegacy("old note", "vault-key");
+        assert!(isLegacyCiphertext(&blob));
+        assert_eq!(decrypt(&blob, "vault-key", b"").unwrap(), "old note");
+        assert!(decrypt(&blob, "vault-key", b"id-1:metadata").is_err());
+    }
+
+    /// Benchmark: scanning N wrapped files costs no Argon2 runs, legacy files cost one each
+    #[test]
+    fn test_argon2_invocations_per_scan() {
+        const FILES: usize = 4;
+        let wrapped: Vec<String> = (0..FILES).map(|i| encrypt(&format!("item {}", i), "vault-key", b"").unwrap()).collect();
+        let legacy: Vec<String> = (0..FILES).map(|i| encryptLegacy(&format!("item {}", i), "vault-key")).collect();
+
+        let before = argon2Calls();
+        for (i, blob) in wrapped.iter().enumerate() {
+            assert_eq!(decrypt(blob, "vault-key", b"").unwrap(), format!("item {}", i));
+        }
+        let wrappedCalls = argon2Calls() - before;
+
+        let before = argon2Calls();
+        for (i, blob) in legacy.iter().enumerate() {
+            assert_eq!(decrypt(blob, "vault-key", b"").unwrap(), format!("item {}", i));
+        }
+        let legacyCalls = argon2Calls() - before;
+
+        assert_eq!(wrappedCalls, 0);
+        assert_eq!(legacyCalls, FILES);
+    }
+}
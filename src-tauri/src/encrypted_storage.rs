@@ -1,26 +1,80 @@
 // Encrypted storage format for Claudia
-// Format: CLAUDIA-ENCRYPTED-v1 with separate encrypted metadata and content sections
+// Format: CLAUDIA-ENCRYPTED-v2 with separate encrypted metadata and content sections
+// Each section is a crypto blob: key-wrapped for new writes, legacy Argon2 for older files
+// v2 binds every section to the file's UUID (its filename) and section name as associated
+// data, so a section copied into another file no longer decrypts. v1 files still read.
+
+use std::path::Path;
 
 use crate::crypto;
+use crate::error::ClaudiaError;
+use crate::storage::HISTORY_DIR;
 
-const FORMAT_HEADER: &str = "CLAUDIA-ENCRYPTED-v1";
+const FORMAT_HEADER: &str = "CLAUDIA-ENCRYPTED-v2";
+const FORMAT_HEADER_V1: &str = "CLAUDIA-ENCRYPTED-v1";
 const METADATA_MARKER: &str = "[METADATA]";
 const CONTENT_MARKER: &str = "[CONTENT]";
 
 /// Parsed encrypted file with separate metadata and content sections
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
 pub struct EncryptedFile {
     pub metadata: String,  // Base64-encoded encrypted metadata
     pub content: String,   // Base64-encoded encrypted content
+    pub version: u8,       // Format version from the header (1 or 2)
+    pub fileId: String,    // UUID the file is stored under; v2 sections are bound to it
+}
+
+impl EncryptedFile {
+    /// Associated data for a section: empty for v1, "{fileId}:{section}" for v2
+    fn associatedData(&self, section: &str) -> Vec<u8> {
+        if self.version >= 2 {
+            sectionBinding(&self.fileId, section)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Decrypt the metadata section with master password
+    pub fn decryptMetadata(&self, masterPassword: &str) -> Result<String, ClaudiaError> {
+        crypto::decrypt(&self.metadata, masterPassword, &self.associatedData(METADATA_MARKER)).map_err(ClaudiaError::Decrypt)
+    }
+
+    /// Decrypt the content section with master password
+    pub fn decryptContent(&self, masterPassword: &str) -> Result<String, ClaudiaError> {
+        crypto::decrypt(&self.content, masterPassword, &self.associatedData(CONTENT_MARKER)).map_err(ClaudiaError::Decrypt)
+    }
+}
+
+fn sectionBinding(fileId: &str, section: &str) -> Vec<u8> {
+    format!("{}:{}", fileId, section).into_bytes()
+}
+
+/// UUID an item file is stored under: the filename stem, the folder's directory name
+/// for .folder.md, or the item's directory name for a version in .history/{uuid}/
+pub fn fileIdOf(path: &Path) -> String {
+    fn nameOf(p: Option<&Path>) -> Option<&str> {
+        p.and_then(Path::file_name).and_then(|n| n.to_str())
+    }
+    let isFolderMeta = nameOf(Some(path)) == Some(".folder.md");
+    let isHistoryVersion = nameOf(path.parent().and_then(Path::parent)) == Some(HISTORY_DIR);
+    let idPart = if isFolderMeta || isHistoryVersion {
+        path.parent().and_then(|p| p.file_name())
+    } else {
+        path.file_stem()
+    };
+    idPart.map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
 }
 
 /// Parse an encrypted file into its components
-pub fn parseEncryptedFile(raw: &str) -> Result<EncryptedFile, String> {
+/// `fileId` is the UUID the file is stored under (see fileIdOf), not the id inside its metadata
+/// A malformed file is a Decrypt error: it can't be read with any key
+pub fn parseEncryptedFile(raw: &str, fileId: &str) -> Result<EncryptedFile, ClaudiaError> {
+    let malformed = |message: &str| ClaudiaError::Decrypt(message.to_string());
     let lines: Vec<&str> = raw.lines().collect();
 
-    if lines.is_empty() || lines[0].trim() != FORMAT_HEADER {
-        return Err("Invalid file format: missing header".to_string());
-    }
+    let version = lines.first().copied()
+        .and_then(formatVersion)
+        .ok_or_else(|| malformed("Invalid file format: missing header"))?;
 
     let mut metadataStart = None;
     let mut contentStart = None;
@@ -34,11 +88,11 @@ pub fn parseEncryptedFile(raw: &str) -> Result<EncryptedFile, String> {
         }
     }
 
-    let metadataIdx = metadataStart.ok_or("Missing [METADATA] section")?;
-    let contentIdx = contentStart.ok_or("Missing [CONTENT] section")?;
+    let metadataIdx = metadataStart.ok_or_else(|| malformed("Missing [METADATA] section"))?;
+    let contentIdx = contentStart.ok_or_else(|| malformed("Missing [CONTENT] section"))?;
 
     if metadataIdx >= contentIdx {
-        return Err("Invalid format: [METADATA] must come before [CONTENT]".to_string());
+        return Err(malformed("Invalid format: [METADATA] must come before [CONTENT]"));
     }
 
     // Collect metadata lines (between [METADATA] and [CONTENT])
@@ -57,10 +111,11 @@ pub fn parseEncryptedFile(raw: &str) -> Result<EncryptedFile, String> {
         .collect::<Vec<_>>()
         .join("");
 
-    Ok(EncryptedFile { metadata, content })
+    Ok(EncryptedFile { metadata, content, version, fileId: fileId.to_string() })
 }
 
 /// Serialize encrypted metadata and content to file format
+/// Both sections must have been encrypted for the same file id (encryptMetadata/encryptContent)
 pub fn toEncryptedFile(encryptedMetadata: &str, encryptedContent: &str) -> String {
     format!(
         "{}\n{}\n{}\n{}\n{}\n",
@@ -72,39 +127,94 @@ pub fn toEncryptedFile(encryptedMetadata: &str, encryptedContent: &str) -> Strin
     )
 }
 
-/// Encrypt metadata (YAML frontmatter) with master password
-pub fn encryptMetadata(yamlContent: &str, masterPassword: &str) -> Result<String, String> {
-    crypto::encrypt(yamlContent, masterPassword)
+/// Encrypt metadata (YAML frontmatter) with master password, bound to the file id
+pub fn encryptMetadata(yamlContent: &str, masterPassword: &str, fileId: &str) -> Result<String, String> {
+    crypto::encrypt(yamlContent, masterPassword, &sectionBinding(fileId, METADATA_MARKER))
 }
 
-/// Decrypt metadata with master password
-pub fn decryptMetadata(encryptedMetadata: &str, masterPassword: &str) -> Result<String, String> {
-    crypto::decrypt(encryptedMetadata, masterPassword)
+/// Encrypt content (markdown body) with master password, bound to the file id
+pub fn encryptContent(bodyContent: &str, masterPassword: &str, fileId: &str) -> Result<String, String> {
+    crypto::encrypt(bodyContent, masterPassword, &sectionBinding(fileId, CONTENT_MARKER))
 }
 
-/// Encrypt content (markdown body) with master password
-pub fn encryptContent(bodyContent: &str, masterPassword: &str) -> Result<String, String> {
-    crypto::encrypt(bodyContent, masterPassword)
+/// Whether the file predates v2 or any section still uses legacy per-blob Argon2 encryption
+pub fn usesLegacyEncryption(encrypted: &EncryptedFile) -> bool {
+    encrypted.version < 2
+        || crypto::isLegacyCiphertext(&encrypted.metadata)
+        || crypto::isLegacyCiphertext(&encrypted.content)
 }
 
-/// Decrypt content with master password
-pub fn decryptContent(encryptedContent: &str, masterPassword: &str) -> Result<String, String> {
-    crypto::decrypt(encryptedContent, masterPassword)
+/// Format version named by a header line (1 or 2), None for anything else
+pub fn formatVersion(header: &str) -> Option<u8> {
+    match header.trim() {
+        FORMAT_HEADER => Some(2),
+        FORMAT_HEADER_V1 => Some(1),
+        _ => None,
+    }
 }
 
-/// Check if raw file content is in encrypted format
+/// Check if raw file content is in encrypted format (any version)
 pub fn isEncryptedFormat(raw: &str) -> bool {
-    raw.trim().starts_with(FORMAT_HEADER)
+    let raw = raw.trim();
+    raw.starts_with(FORMAT_HEADER) || raw.starts_with(FORMAT_HEADER_V1)
+}
+
+// Codes prefixed to item read failures ("CODE: message") and skipped-file reasons, so the
+// message itself says which advice applies (restore from backup vs re-unlock)
+pub const ERR_NOT_FOUND: &str = "NOT_FOUND";
+pub const ERR_NOT_ENCRYPTED: &str = "NOT_ENCRYPTED";
+pub const ERR_CORRUPT_FILE: &str = "CORRUPT_FILE";
+pub const ERR_METADATA_DECRYPT: &str = "METADATA_DECRYPT_FAILED";
+pub const ERR_CONTENT_DECRYPT: &str = "CONTENT_DECRYPT_FAILED";
+
+/// Format an error message with its code
+pub fn codedError(code: &str, message: impl std::fmt::Display) -> String {
+    format!("{}: {}", code, message)
+}
+
+/// Decrypt an item's content section, classifying the failure
+/// When content fails, metadata is tried too: if that also fails the key is wrong,
+/// otherwise the content section itself is damaged
+pub fn decryptContentChecked(encrypted: &EncryptedFile, masterPassword: &str) -> Result<String, ClaudiaError> {
+    encrypted.decryptContent(masterPassword).map_err(|e| {
+        ClaudiaError::Decrypt(if encrypted.decryptMetadata(masterPassword).is_ok() {
+            codedError(ERR_CONTENT_DECRYPT, format!("Metadata decrypted but content did not ({}) - the file may be corrupt", e))
+        } else {
+            codedError(ERR_METADATA_DECRYPT, format!("File could not be decrypted with the current key ({})", e))
+        })
+    })
+}
+
+/// Read an item file and decrypt its body
+/// Returns Ok(None) for legacy plaintext files
+pub fn readDecryptedBody(path: &Path, masterPassword: &str) -> Result<Option<String>, ClaudiaError> {
+    let raw = std::fs::read_to_string(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ClaudiaError::NotFound(codedError(ERR_NOT_FOUND, format!("File not found: {}", path.display())))
+        } else {
+            ClaudiaError::Io(format!("Failed to read file: {}", e))
+        }
+    })?;
+
+    if !isEncryptedFormat(&raw) {
+        return Ok(None);
+    }
+
+    let encrypted = parseEncryptedFile(&raw, &fileIdOf(path))
+        .map_err(|e| ClaudiaError::Decrypt(codedError(ERR_CORRUPT_FILE, e)))?;
+    decryptContentChecked(&encrypted, masterPassword).map(Some)
 }
 
 /// Create a new encrypted file from plaintext metadata (YAML) and content
+/// `fileId` is the UUID the file will be stored under (see fileIdOf)
 pub fn createEncryptedFile(
     yamlMetadata: &str,
     bodyContent: &str,
     masterPassword: &str,
+    fileId: &str,
 ) -> Result<String, String> {
-    let encryptedMetadata = encryptMetadata(yamlMetadata, masterPassword)?;
-    let encryptedContent = encryptContent(bodyContent, masterPassword)?;
+    let encryptedMetadata = encryptMetadata(yamlMetadata, masterPassword, fileId)?;
+    let encryptedContent = encryptContent(bodyContent, masterPassword, fileId)?;
     Ok(toEncryptedFile(&encryptedMetadata, &encryptedContent))
 }
 
@@ -113,16 +223,25 @@ pub fn serializeAndEncrypt<T: serde::Serialize>(
     frontmatter: &T,
     body: &str,
     masterPassword: &str,
+    fileId: &str,
 ) -> Result<String, String> {
     let yaml = serde_yaml::to_string(frontmatter)
         .map_err(|e| format!("YAML serialization error: {}", e))?;
-    createEncryptedFile(&yaml, body, masterPassword)
+    createEncryptedFile(&yaml, body, masterPassword, fileId)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_file_id_of_layouts() {
+        let id = "0b9f4a52-6c3e-4d0e-9a55-2f1c7d8e9f10";
+        assert_eq!(fileIdOf(&Path::new("/ws/folders/notes").join(format!("{}.md", id))), id);
+        assert_eq!(fileIdOf(&Path::new("/ws/folders").join(id).join(".folder.md")), id);
+        assert_eq!(fileIdOf(&Path::new("/ws/folders/notes/.history").join(id).join("1700000000000.md")), id);
+    }
+
     #[test]
     fn test_parse_encrypted_format() {
         let raw = r#"CLAUDIA-ENCRYPTED-v1
@@ -131,15 +250,17 @@ dGVzdG1ldGE=
 [CONTENT]
 dGVzdGNvbnRlbnQ="#;
 
-        let result = parseEncryptedFile(raw).unwrap();
+        let result = parseEncryptedFile(raw, "some-id").unwrap();
         assert_eq!(result.metadata, "dGVzdG1ldGE=");
         assert_eq!(result.content, "dGVzdGNvbnRlbnQ=");
+        assert_eq!(result.version, 1);
+        assert_eq!(result.fileId, "some-id");
     }
 
     #[test]
     fn test_to_encrypted_format() {
         let output = toEncryptedFile("encmeta", "enccontent");
-        assert!(output.contains("CLAUDIA-ENCRYPTED-v1"));
+        assert!(output.contains("CLAUDIA-ENCRYPTED-v2"));
         assert!(output.contains("[METADATA]"));
         assert!(output.contains("encmeta"));
         assert!(output.contains("[CONTENT]"));
@@ -149,6 +270,66 @@ dGVzdGNvbnRlbnQ="#;
     #[test]
     fn test_is_encrypted_format() {
         assert!(isEncryptedFormat("CLAUDIA-ENCRYPTED-v1\n[METADATA]..."));
+        assert!(isEncryptedFormat("CLAUDIA-ENCRYPTED-v2\n[METADATA]..."));
         assert!(!isEncryptedFormat("---\ntitle: test\n---\ncontent"));
+        assert_eq!(formatVersion("CLAUDIA-ENCRYPTED-v1\n"), Some(1));
+        assert_eq!(formatVersion("CLAUDIA-ENCRYPTED-v2\n"), Some(2));
+        assert_eq!(formatVersion("---\n"), None);
+    }
+
+    #[test]
+    fn test_file_id_of() {
+        assert_eq!(fileIdOf(Path::new("/ws/folders/notes/abc-123.md")), "abc-123");
+        assert_eq!(fileIdOf(Path::new("/ws/folders/f-456/.folder.md")), "f-456");
+    }
+
+    #[test]
+    fn test_v2_round_trip() {
+        let raw = createEncryptedFile("title: A", "body", "vault-key", "id-a").unwrap();
+        let encrypted = parseEncryptedFile(&raw, "id-a").unwrap();
+        assert_eq!(encrypted.version, 2);
+        assert!(!usesLegacyEncryption(&encrypted));
+        assert_eq!(encrypted.decryptMetadata("vault-key").unwrap(), "title: A");
+        assert_eq!(encrypted.decryptContent("vault-key").unwrap(), "body");
+    }
+
+    #[test]
+    fn test_v2_rejects_swapped_metadata() {
+        let a = parseEncryptedFile(&createEncryptedFile("title: A", "body A", "vault-key", "id-a").unwrap(), "id-a").unwrap();
+        let b = parseEncryptedFile(&createEncryptedFile("title: B", "body B", "vault-key", "id-b").unwrap(), "id-b").unwrap();
+
+        // File b on disk, but carrying a's metadata block
+        let swapped = parseEncryptedFile(&toEncryptedFile(&a.metadata, &b.content), "id-b").unwrap();
+        assert!(swapped.decryptMetadata("vault-key").is_err());
+        assert_eq!(swapped.decryptContent("vault-key").unwrap(), "body B");
+
+        // Same file renamed to another UUID
+        let renamed = parseEncryptedFile(&toEncryptedFile(&a.metadata, &a.content), "id-b").unwrap();
+        assert!(renamed.decryptMetadata("vault-key").is_err());
+
+        // Metadata block moved into the content slot of its own file
+        let crossed = parseEncryptedFile(&toEncryptedFile(&a.metadata, &a.metadata), "id-a").unwrap();
+        assert!(crossed.decryptContent("vault-key").is_err());
+    }
+
+    #[test]
+    fn test_read_errors_carry_their_kind() {
+        let dir = std::env::temp_dir().join(format!("claudia-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("id-a.md");
+
+        let missing = readDecryptedBody(&path, "vault-key").unwrap_err();
+        assert!(matches!(missing, ClaudiaError::NotFound(_)), "{:?}", missing);
+
+        std::fs::write(&path, "CLAUDIA-ENCRYPTED-v2\n[CONTENT]\n").unwrap();
+        let corrupt = readDecryptedBody(&path, "vault-key").unwrap_err();
+        assert!(matches!(corrupt, ClaudiaError::Decrypt(_)), "{:?}", corrupt);
+
+        std::fs::write(&path, createEncryptedFile("title: A", "body", "vault-key", "id-a").unwrap()).unwrap();
+        let wrongKey = readDecryptedBody(&path, "other-key").unwrap_err();
+        assert!(matches!(wrongKey, ClaudiaError::Decrypt(_)), "{:?}", wrongKey);
+        assert_eq!(readDecryptedBody(&path, "vault-key").unwrap().as_deref(), Some("body"));
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
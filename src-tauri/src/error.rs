@@ -0,0 +1,121 @@
+// Error type returned by Tauri commands
+// Serialized as { code, message } so the frontend can branch on `code` and show `message`
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClaudiaError {
+    VaultLocked,
+    NoWorkspace,
+    /// The workspace directory is gone (e.g. an unmounted drive)
+    WorkspaceUnavailable(String),
+    /// A sensitive action needs the master password re-entered
+    ReauthRequired(String),
+    /// A password check failed or a locked section (e.g. passwords access) was touched
+    Auth(String),
+    NotFound(String),
+    Io(String),
+    Decrypt(String),
+    InvalidInput(String),
+    /// Anything not covered above; the message is passed through unchanged
+    Other(String),
+}
+
+impl ClaudiaError {
+    /// Stable machine-readable code for the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::VaultLocked => "VAULT_LOCKED",
+            Self::NoWorkspace => "NO_WORKSPACE",
+            Self::WorkspaceUnavailable(_) => "WORKSPACE_UNAVAILABLE",
+            Self::ReauthRequired(_) => "REAUTH_REQUIRED",
+            Self::Auth(_) => "AUTH",
+            Self::NotFound(_) => "NOT_FOUND",
+            Self::Io(_) => "IO",
+            Self::Decrypt(_) => "DECRYPT",
+            Self::InvalidInput(_) => "INVALID_INPUT",
+            Self::Other(_) => "OTHER",
+        }
+    }
+}
+
+impl fmt::Display for ClaudiaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VaultLocked => write!(f, "Vault is locked"),
+            Self::NoWorkspace => write!(f, "No workspace"),
+            Self::WorkspaceUnavailable(m) | Self::ReauthRequired(m) | Self::Auth(m) | Self::NotFound(m) | Self::Io(m) | Self::Decrypt(m) | Self::InvalidInput(m) | Self::Other(m) => {
+                write!(f, "{}", m)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClaudiaError {}
+
+impl serde::Serialize for ClaudiaError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("ClaudiaError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+/// Errors from String-returning helpers that no typed variant covers
+/// Sites that know what went wrong return the matching variant instead
+impl From<String> for ClaudiaError {
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
+
+impl From<&str> for ClaudiaError {
+    fn from(message: &str) -> Self {
+        Self::Other(message.to_string())
+    }
+}
+
+impl From<std::io::Error> for ClaudiaError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => Self::NotFound(e.to_string()),
+            _ => Self::Io(e.to_string()),
+        }
+    }
+}
+
+/// Lets String-returning helpers call commands with `?`
+impl From<ClaudiaError> for String {
+    fn from(e: ClaudiaError) -> Self {
+        e.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_untyped_messages_pass_through_as_other() {
+        // No guessing from the text: only the error site decides the code
+        assert_eq!(ClaudiaError::from("Note not found"), ClaudiaError::Other("Note not found".to_string()));
+        assert_eq!(ClaudiaError::from("Vault is locked".to_string()).code(), "OTHER");
+        assert_eq!(ClaudiaError::from("disk on fire").to_string(), "disk on fire");
+    }
+
+    #[test]
+    fn test_io_errors_keep_not_found_apart() {
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        assert_eq!(ClaudiaError::from(missing).code(), "NOT_FOUND");
+        let denied = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert_eq!(ClaudiaError::from(denied).code(), "IO");
+    }
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let json = serde_json::to_value(ClaudiaError::VaultLocked).unwrap();
+        assert_eq!(json, serde_json::json!({ "code": "VAULT_LOCKED", "message": "Vault is locked" }));
+    }
+}
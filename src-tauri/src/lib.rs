@@ -4,19 +4,28 @@
 mod commands;
 mod crypto;
 mod encrypted_storage;
+mod error;
+mod links;
 mod mcp;
 mod models;
+mod password_strength;
+mod search;
 mod storage;
+mod totp;
+mod undo;
+mod watcher;
 
 use std::sync::Arc;
 use parking_lot::RwLock;
 use tauri::{
     menu::{Menu, MenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Manager, State,
+    Emitter, Manager, State,
 };
 use tokio_util::sync::CancellationToken;
 
+use error::ClaudiaError;
+
 #[cfg(target_os = "macos")]
 use tauri::ActivationPolicy;
 
@@ -24,6 +33,8 @@ use tauri::ActivationPolicy;
 pub struct MCPServerManager {
     is_running: Arc<RwLock<bool>>,
     cancel_token: Arc<RwLock<Option<CancellationToken>>>,
+    /// Address the running server is bound to
+    bound_address: Arc<RwLock<Option<String>>>,
 }
 
 impl MCPServerManager {
@@ -31,6 +42,7 @@ impl MCPServerManager {
         Self {
             is_running: Arc::new(RwLock::new(false)),
             cancel_token: Arc::new(RwLock::new(None)),
+            bound_address: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -39,24 +51,81 @@ impl MCPServerManager {
     }
 }
 
-const MCP_BIND_ADDRESS: &str = "127.0.0.1:44055";
+/// How often the scheduled backup checks whether a backup is due
+const BACKUP_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// How often trash retention runs once the vault is unlocked, and how often it checks before that
+const TRASH_RETENTION_INTERVAL: std::time::Duration = std::time::Duration::from_secs(6 * 60 * 60);
+const TRASH_RETENTION_POLL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How often the idle auto-lock (autoLockMinutes) checks for inactivity
+const AUTO_LOCK_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How long the main window may stay hidden before lockOnHide locks the vault
+const LOCK_ON_HIDE_GRACE: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Bumped on every blur and refocus; a pending lockOnHide only fires if it is still the latest
+static LOCK_ON_HIDE_GENERATION: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Lock the vault if the main window is still hidden/minimized after the grace period
+/// Scheduling again, or cancelLockOnHide, supersedes any earlier pending lock.
+fn scheduleLockOnHide(window: &tauri::Window) {
+    let generation = LOCK_ON_HIDE_GENERATION.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+    let app = window.app_handle().clone();
+    if !app.state::<storage::StorageState>().effectiveSettings().lockOnHide {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        std::thread::sleep(LOCK_ON_HIDE_GRACE);
+        if LOCK_ON_HIDE_GENERATION.load(std::sync::atomic::Ordering::SeqCst) != generation {
+            return;
+        }
+        let Some(window) = app.get_webview_window("main") else { return };
+        let hidden = !window.is_visible().unwrap_or(true) || window.is_minimized().unwrap_or(false);
+        let storage = app.state::<storage::StorageState>();
+        if hidden && storage.isUnlocked() {
+            println!("[lockOnHide] Main window hidden for {:?}, locking vault", LOCK_ON_HIDE_GRACE);
+            storage.lock();
+            let _ = app.emit("vault-locked", ());
+        }
+    });
+}
+
+/// Drop any pending lockOnHide (the main window is back in front)
+fn cancelLockOnHide() {
+    LOCK_ON_HIDE_GENERATION.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+}
 
 #[tauri::command]
 async fn start_mcp_server(
     app: tauri::AppHandle,
     mcp_manager: State<'_, MCPServerManager>,
     storage: State<'_, storage::StorageState>,
-) -> Result<(), String> {
+) -> Result<(), ClaudiaError> {
     use rmcp::transport::streamable_http_server::{
         StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
     };
     
     if *mcp_manager.is_running.read() {
-        return Err("MCP server is already running".to_string());
+        return Err("MCP server is already running".into());
     }
     
-    println!("[MCP] Starting server on {}...", MCP_BIND_ADDRESS);
-    
+    let settings = storage.effectiveSettings();
+    let address = settings.mcpSocketAddr().map_err(ClaudiaError::InvalidInput)?;
+    println!("[MCP] Starting server on {}...", address);
+
+    // Bind before reporting success so a taken port reaches the caller
+    let tcp_listener = tokio::net::TcpListener::bind(address).await.map_err(|e| {
+        eprintln!("[MCP] Failed to bind: {}", e);
+        if e.kind() == std::io::ErrorKind::AddrInUse {
+            ClaudiaError::Io(format!("Port {} is already in use - choose another MCP port in settings", settings.mcpPort))
+        } else {
+            ClaudiaError::Io(format!("Failed to start MCP server on {}: {}", address, e))
+        }
+    })?;
+    let bound = tcp_listener.local_addr().unwrap_or(address).to_string();
+
     let storage_arc = storage.inner().clone();
     let app_handle = app.clone();
     
@@ -77,19 +146,12 @@ async fn start_mcp_server(
     
     let is_running = mcp_manager.is_running.clone();
     *is_running.write() = true;
+    let bound_address = mcp_manager.bound_address.clone();
+    *bound_address.write() = Some(bound.clone());
     
     // Start server in background
     tokio::spawn(async move {
-        let tcp_listener = match tokio::net::TcpListener::bind(MCP_BIND_ADDRESS).await {
-            Ok(l) => l,
-            Err(e) => {
-                eprintln!("[MCP] Failed to bind: {}", e);
-                *is_running.write() = false;
-                return;
-            }
-        };
-        
-        println!("[MCP] Server started successfully on {}", MCP_BIND_ADDRESS);
+        println!("[MCP] Server started successfully on {}", bound);
         
         let _ = axum::serve(tcp_listener, router)
             .with_graceful_shutdown(async move {
@@ -98,6 +160,7 @@ async fn start_mcp_server(
             .await;
         
         *is_running.write() = false;
+        *bound_address.write() = None;
         println!("[MCP] Server stopped");
     });
     
@@ -105,7 +168,7 @@ async fn start_mcp_server(
 }
 
 #[tauri::command]
-async fn stop_mcp_server(mcp_manager: State<'_, MCPServerManager>) -> Result<(), String> {
+async fn stop_mcp_server(mcp_manager: State<'_, MCPServerManager>) -> Result<(), ClaudiaError> {
     println!("[MCP] Stopping server...");
     if let Some(ct) = mcp_manager.cancel_token.read().as_ref() {
         ct.cancel();
@@ -115,16 +178,23 @@ async fn stop_mcp_server(mcp_manager: State<'_, MCPServerManager>) -> Result<(),
 }
 
 #[tauri::command]
-async fn get_mcp_server_status(mcp_manager: State<'_, MCPServerManager>) -> Result<bool, String> {
+async fn get_mcp_server_status(mcp_manager: State<'_, MCPServerManager>) -> Result<bool, ClaudiaError> {
     Ok(mcp_manager.is_running())
 }
 
+/// Address the MCP server is listening on, None when it is not running
+#[tauri::command]
+async fn get_mcp_server_address(mcp_manager: State<'_, MCPServerManager>) -> Result<Option<String>, ClaudiaError> {
+    Ok(mcp_manager.bound_address.read().clone())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .setup(|app| {
             // Hide from dock on macOS (tray-only app)
             #[cfg(target_os = "macos")]
@@ -141,6 +211,35 @@ pub fn run() {
                 }
             }
 
+            // Let storage notify the frontend (e.g. "workspace-unavailable")
+            storage.setAppHandle(app.handle().clone());
+
+            // Scheduled automatic backups (backupSchedule setting)
+            let backupStorage = storage.clone();
+            std::thread::spawn(move || loop {
+                if let Err(e) = commands::backup::runScheduledBackup(&backupStorage) {
+                    eprintln!("[backup] Scheduled backup failed: {}", e);
+                }
+                std::thread::sleep(BACKUP_CHECK_INTERVAL);
+            });
+
+            // Lock the vault after autoLockMinutes without activity
+            let autoLockStorage = storage.clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(AUTO_LOCK_CHECK_INTERVAL);
+                autoLockStorage.autoLockIfIdle(std::time::Instant::now());
+            });
+
+            // Purge expired trash (trashRetentionDays) as soon as the vault is unlocked
+            let retentionStorage = storage.clone();
+            std::thread::spawn(move || loop {
+                let ran = commands::trash::runTrashRetention(&retentionStorage).is_some();
+                std::thread::sleep(if ran { TRASH_RETENTION_INTERVAL } else { TRASH_RETENTION_POLL });
+            });
+
+            // Pick up files changed outside the app (watchVault setting)
+            watcher::syncVaultWatcher(&storage);
+
             app.manage(storage);
 
             // Show the main window on app start
@@ -153,8 +252,9 @@ pub fn run() {
             app.manage(MCPServerManager::new());
 
             // Create tray menu
+            let lockAndHide = MenuItem::with_id(app, "lock-hide", "Lock and Hide", true, None::<&str>)?;
             let quit = MenuItem::with_id(app, "quit", "Exit", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&quit])?;
+            let menu = Menu::with_items(app, &[&lockAndHide, &quit])?;
 
             // Build tray icon
             let _tray = TrayIconBuilder::new()
@@ -163,7 +263,22 @@ pub fn run() {
                 .menu(&menu)
                 .show_menu_on_left_click(false)
                 .on_menu_event(|app, event| match event.id.as_ref() {
-                    "quit" => app.exit(0),
+                    "lock-hide" => {
+                        let storage = app.state::<storage::StorageState>();
+                        storage.lock();
+                        if let Some(window) = app.get_webview_window("main") {
+                            let _ = window.hide();
+                        }
+                        let _ = app.emit("vault-locked", ());
+                    }
+                    "quit" => {
+                        // Don't leave secrets in memory on the way out if the user asked for it
+                        let storage = app.state::<storage::StorageState>();
+                        if storage.effectiveSettings().lockOnQuit {
+                            storage.lock();
+                        }
+                        app.exit(0)
+                    }
                     _ => {}
                 })
                 .on_tray_icon_event(|tray, event| {
@@ -184,16 +299,32 @@ pub fn run() {
 
             Ok(())
         })
+        .on_window_event(|window, event| {
+            // The frontend hides the main window on close; both paths can leave it in the background
+            if window.label() != "main" {
+                return;
+            }
+            match event {
+                tauri::WindowEvent::CloseRequested { .. } | tauri::WindowEvent::Focused(false) => {
+                    scheduleLockOnHide(window);
+                }
+                tauri::WindowEvent::Focused(true) => cancelLockOnHide(),
+                _ => {}
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             // MCP Server
             start_mcp_server,
             stop_mcp_server,
             get_mcp_server_status,
+            get_mcp_server_address,
             // Settings
             commands::settings::getSettings,
             commands::settings::getGlobalSettings,
+            commands::settings::getSettingsWithSource,
             commands::settings::updateGlobalSettings,
             commands::settings::updateWorkspaceSettings,
+            commands::settings::clearWorkspaceSettingOverride,
             // Workspace
             commands::workspace::getWorkspaces,
             commands::workspace::getCurrentWorkspace,
@@ -201,31 +332,69 @@ pub fn run() {
             commands::workspace::openWorkspace,
             commands::workspace::closeWorkspace,
             commands::workspace::removeWorkspace,
+            commands::workspace::relocateWorkspace,
+            commands::workspace::renameWorkspace,
             commands::workspace::openFolderDialog,
             // Folder
             commands::folder::getFolders,
+            commands::folder::getChildFolders,
+            commands::folder::getFolderPaths,
             commands::folder::createFolder,
+            commands::folder::createFolders,
             commands::folder::updateFolder,
+            commands::folder::setFolderFavorite,
             commands::folder::deleteFolder,
+            commands::folder::getFolderDeletionImpact,
             commands::folder::reorderFolders,
             commands::folder::moveFolder,
+            commands::folder::duplicateFolder,
+            commands::folder::findOrphanedFolders,
+            commands::folder::repairOrphanedFolder,
             // Note
             commands::note::getNotes,
             commands::note::getNoteById,
             commands::note::getNoteContent,
+            commands::note::getNoteContentsBatch,
             commands::note::createNote,
+            commands::note::duplicateNote,
             commands::note::updateNote,
+            commands::note::getNoteHistory,
+            commands::note::restoreNoteVersion,
+            commands::note::getBacklinks,
+            commands::note::resolveLink,
+            commands::note::setNotePinned,
+            commands::note::archiveNote,
+            commands::note::unarchiveNote,
+            commands::note::getOrCreateDailyNote,
             commands::note::deleteNote,
+            commands::note::deleteNotesBatch,
             commands::note::reorderNotes,
             commands::note::moveNoteToFolder,
+            commands::note::moveNotesToFolder,
+            commands::note::mergeNotes,
             // Task
             commands::task::getTasks,
+            commands::task::getTasksWithProgress,
             commands::task::getTaskById,
             commands::task::getTaskContent,
+            commands::task::getTaskContentsBatch,
             commands::task::createTask,
+            commands::task::duplicateTask,
             commands::task::updateTask,
+            commands::task::getTaskHistory,
+            commands::task::restoreTaskVersion,
+            commands::task::setTaskPinned,
+            commands::task::archiveTask,
+            commands::task::unarchiveTask,
+            commands::task::getDueReminders,
+            commands::task::snoozeTaskReminder,
+            commands::task::dismissTaskReminder,
+            commands::task::getTaskStatuses,
+            commands::task::setTaskStatuses,
             commands::task::deleteTask,
+            commands::task::deleteTasksBatch,
             commands::task::moveTaskToFolder,
+            commands::task::moveTasksToFolder,
             commands::task::reorderTasks,
             // Password
             commands::password::getPasswords,
@@ -233,23 +402,38 @@ pub fn run() {
             commands::password::getPasswordContent,
             commands::password::getPasswordContentsBatch,
             commands::password::createPassword,
+            commands::password::importPasswordsCsv,
             commands::password::updatePassword,
             commands::password::deletePassword,
+            commands::password::deletePasswordsBatch,
+            commands::password::passwordStrength,
+            commands::password::getPasswordTotp,
+            commands::password::copyPasswordToClipboard,
+            commands::password::findReusedPasswords,
+            commands::password::getExpiringPasswords,
             commands::password::reorderPasswords,
             commands::password::movePasswordToFolder,
             // Vault
             commands::vault::isVaultSetup,
             commands::vault::isVaultUnlocked,
+            commands::vault::checkMasterPasswordPolicy,
             commands::vault::setupMasterPassword,
             commands::vault::unlockVault,
             commands::vault::lockVault,
+            commands::vault::deriveSessionKey,
             commands::vault::changeMasterPasswordVault,
+            commands::vault::verifyReEncryption,
+            commands::vault::upgradeVaultEncryption,
             commands::vault::updateVaultActivity,
             // Passwords access (auto-lock for passwords only)
             commands::vault::isPasswordsAccessUnlocked,
+            commands::vault::getLockStatus,
             commands::vault::unlockPasswordsAccess,
             commands::vault::lockPasswordsAccess,
             commands::vault::updatePasswordsActivity,
+            // Sensitive action re-auth
+            commands::vault::requireReauth,
+            commands::vault::verifyMasterPasswordFresh,
             // Floating window
             commands::floating::createFloatingWindow,
             commands::floating::showFloatingWindow,
@@ -261,9 +445,17 @@ pub fn run() {
             commands::floating::updateFloatingWindowSize,
             commands::floating::getFloatingWindowPosition,
             commands::floating::getFloatingWindowSize,
+            commands::floating::restoreFloatingWindows,
+            commands::floating::recenterFloatingWindow,
             // Templates
             commands::template::getTemplates,
             commands::template::getTemplateContent,
+            commands::template::createTemplate,
+            commands::template::updateTemplate,
+            commands::template::deleteTemplate,
+            commands::template::saveAsTemplate,
+            commands::template::exportTemplates,
+            commands::template::importTemplates,
             commands::template::initializeDefaultTemplates,
             // Trash
             commands::trash::listTrashNotes,
@@ -272,6 +464,39 @@ pub fn run() {
             commands::trash::getTrashCounts,
             commands::trash::emptyTrash,
             commands::trash::restoreAllFromTrash,
+            commands::trash::restoreNoteFromTrash,
+            commands::trash::restoreTaskFromTrash,
+            commands::trash::restorePasswordFromTrash,
+            commands::trash::purgeExpiredTrash,
+            // Assets
+            commands::asset::saveNoteImageFromBytes,
+            commands::asset::readNoteAsset,
+            // Backup
+            commands::backup::exportWorkspace,
+            commands::backup::exportVault,
+            commands::backup::importVault,
+            commands::backup::exportFolderPlaintext,
+            commands::backup::exportFolderZip,
+            commands::backup::importMarkdownDir,
+            commands::backup::getLastBackupTime,
+            // Item
+            commands::item::getItemFileInfo,
+            commands::item::undoLastOperation,
+            commands::item::getItemContentHash,
+            commands::item::togglePin,
+            commands::item::exportItemJson,
+            commands::item::getItemsByTag,
+            commands::item::listArchived,
+            commands::item::getAllTags,
+            commands::item::renameTag,
+            commands::item::deleteTag,
+            commands::item::getTimestampAnomalies,
+            commands::item::repairTimestamps,
+            commands::item::findItemByTitle,
+            commands::item::searchContent,
+            // Maintenance
+            commands::maintenance::compactWorkspace,
+            commands::maintenance::scanHealth,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,164 @@
+// Wiki-style links between notes: `[[Note title]]` or `[[uuid]]`, optionally `[[target|label]]`
+// Finding backlinks means decrypting every note body, so the raw link targets of each note are
+// cached per workspace. Targets are resolved against current titles at query time, so renames
+// need no invalidation - only body changes do (see Storage::invalidateLinks).
+
+use std::collections::HashMap;
+
+use crate::models::Note;
+
+/// Link targets found in a note body, in order of first appearance
+pub fn parseLinks(body: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    for line in body.lines() {
+        let mut rest = line;
+        while let Some(start) = rest.find("[[") {
+            rest = &rest[start + 2..];
+            let Some(end) = rest.find("]]") else {
+                break;
+            };
+            let inner = &rest[..end];
+            // "[[a [[b]]" links to b
+            if let Some(nested) = inner.rfind("[[") {
+                rest = &rest[nested..];
+                continue;
+            }
+            rest = &rest[end + 2..];
+
+            let target = inner.split('|').next().unwrap_or_default().trim();
+            if !target.is_empty() && !target.contains(['[', ']']) && !links.iter().any(|l| l == target) {
+                links.push(target.to_string());
+            }
+        }
+    }
+    links
+}
+
+/// Note a link points to: exact title first, then UUID
+pub fn resolveLink<'a>(text: &str, notes: &'a [Note]) -> Option<&'a Note> {
+    let text = text.trim();
+    notes.iter()
+        .find(|n| n.frontmatter.title == text)
+        .or_else(|| notes.iter().find(|n| n.frontmatter.id.eq_ignore_ascii_case(text)))
+}
+
+/// Notes with a link resolving to `targetId`, given each note's link targets
+/// A note linking to itself is not its own backlink
+pub fn backlinksTo<'a>(targetId: &str, notes: &'a [Note], linksOf: &HashMap<String, Vec<String>>) -> Vec<&'a Note> {
+    notes.iter()
+        .filter(|n| n.frontmatter.id != targetId)
+        .filter(|n| {
+            linksOf.get(&n.frontmatter.id).is_some_and(|links| {
+                links.iter().any(|l| resolveLink(l, notes).is_some_and(|t| t.frontmatter.id == targetId))
+            })
+        })
+        .collect()
+}
+
+/// Parsed link targets per note id, for one workspace
+#[derive(Debug, Default)]
+pub struct LinkCache {
+    workspacePath: Option<String>,
+    links: HashMap<String, Vec<String>>,
+}
+
+impl LinkCache {
+    pub fn get(&self, workspacePath: &str, id: &str) -> Option<Vec<String>> {
+        if self.workspacePath.as_deref() != Some(workspacePath) {
+            return None;
+        }
+        self.links.get(id).cloned()
+    }
+
+    /// Remember a note's links; entries from another workspace are dropped first
+    pub fn insert(&mut self, workspacePath: &str, id: &str, links: Vec<String>) {
+        if self.workspacePath.as_deref() != Some(workspacePath) {
+            self.links.clear();
+            self.workspacePath = Some(workspacePath.to_string());
+        }
+        self.links.insert(id.to_string(), links);
+    }
+
+    pub fn invalidate(&mut self, id: &str) {
+        self.links.remove(id);
+    }
+
+    pub fn clear(&mut self) {
+        self.links.clear();
+        self.workspacePath = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use crate::models::NoteFrontmatter;
+
+    fn note(id: &str, title: &str) -> Note {
+        Note {
+            path: PathBuf::from(format!("/ws/folders/notes/{}.md", id)),
+            folderPath: PathBuf::from("/ws/folders/notes"),
+            frontmatter: NoteFrontmatter::new(id.to_string(), title.to_string(), 1),
+            content: String::new(),
+        }
+    }
+
+    const TARGET_ID: &str = "7f1c2a4e-0000-4000-8000-000000000001";
+
+    #[test]
+    fn test_parse_links() {
+        let body = "See [[Groceries]] and [[Groceries|the list]].\n[[ Trip plan ]] [[]] [[a [[b]] [[unclosed";
+        assert_eq!(parseLinks(body), vec!["Groceries", "Trip plan", "b"]);
+        assert!(parseLinks("no links [here]").is_empty());
+    }
+
+    #[test]
+    fn test_title_and_uuid_links() {
+        let notes = vec![
+            note(TARGET_ID, "Groceries"),
+            note("7f1c2a4e-0000-4000-8000-000000000002", "By title"),
+            note("7f1c2a4e-0000-4000-8000-000000000003", "By uuid"),
+            note("7f1c2a4e-0000-4000-8000-000000000004", "Unrelated"),
+        ];
+        let linksOf: HashMap<String, Vec<String>> = [
+            (notes[0].frontmatter.id.clone(), vec!["Groceries".to_string()]),
+            (notes[1].frontmatter.id.clone(), vec!["Groceries".to_string()]),
+            (notes[2].frontmatter.id.clone(), vec![TARGET_ID.to_uppercase()]),
+            (notes[3].frontmatter.id.clone(), vec!["By title".to_string()]),
+        ].into_iter().collect();
+
+        assert_eq!(resolveLink("Groceries", &notes).unwrap().frontmatter.id, TARGET_ID);
+        assert_eq!(resolveLink(TARGET_ID, &notes).unwrap().frontmatter.id, TARGET_ID);
+
+        let titles: Vec<&str> = backlinksTo(TARGET_ID, &notes, &linksOf).iter().map(|n| n.frontmatter.title.as_str()).collect();
+        assert_eq!(titles, vec!["By title", "By uuid"]);
+    }
+
+    #[test]
+    fn test_broken_links_resolve_to_nothing() {
+        let notes = vec![note(TARGET_ID, "Groceries"), note("7f1c2a4e-0000-4000-8000-000000000002", "Dangling")];
+        let linksOf: HashMap<String, Vec<String>> =
+            [(notes[1].frontmatter.id.clone(), vec!["groceries".to_string(), "Missing note".to_string()])].into_iter().collect();
+
+        assert!(resolveLink("Missing note", &notes).is_none());
+        // Title matching is exact
+        assert!(resolveLink("groceries", &notes).is_none());
+        assert!(backlinksTo(TARGET_ID, &notes, &linksOf).is_empty());
+    }
+
+    #[test]
+    fn test_cache_is_per_workspace() {
+        let mut cache = LinkCache::default();
+        cache.insert("/ws1", "a", vec!["b".to_string()]);
+        assert_eq!(cache.get("/ws1", "a"), Some(vec!["b".to_string()]));
+        assert_eq!(cache.get("/ws2", "a"), None);
+
+        cache.invalidate("a");
+        assert_eq!(cache.get("/ws1", "a"), None);
+
+        cache.insert("/ws1", "a", Vec::new());
+        cache.insert("/ws2", "c", Vec::new());
+        assert_eq!(cache.get("/ws1", "a"), None);
+    }
+}
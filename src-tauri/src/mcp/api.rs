@@ -1,21 +1,30 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::storage::{StorageState, foldersDir, notesDir, tasksDir, uuidFilename, validateFolderPath};
-use crate::encrypted_storage;
+use crate::storage::{StorageState, foldersDir, notesDir, tasksDir, trashNotesDir, trashTasksDir, trashPasswordsDir, uuidFilename, validateFolderPath, noteAssetsDir, itemsDir, resolveFolderArg, validateFolderDir, validateMoveTarget, writeFileAtomic};
+use crate::encrypted_storage::{self, fileIdOf};
+use crate::error::ClaudiaError;
+use crate::search::{self, MatchRange};
+use crate::totp::{self, TotpCode};
 // Note: notesDir and tasksDir are used for root-level paths
-use crate::models::{Note, NoteFrontmatter, Task, TaskFrontmatter, TaskStatus, Folder, FolderFrontmatter, FloatWindow};
-use crate::commands::common::newId;
-use crate::commands::note::{NoteInfo, scanNotesInFolder, scanAllNotes};
-use crate::commands::task::{TaskInfo, scanTasksInFolder, scanAllTasks, scanTasksInStatus};
-use crate::commands::folder::{FolderInfo, scanFolders};
+use crate::models::{Note, NoteFrontmatter, TemplateType, Task, TaskFrontmatter, TaskStatus, Folder, FolderFrontmatter, FloatWindow, ItemKind, SortSpec, TagMatch, normalizeColor};
+use crate::commands::common::{newId, validateItemSize, sanitizeTitle};
+use crate::commands::note::{NoteInfo, findNoteById, scanNotesInFolder, scanAllNotes};
+use crate::commands::task::{TaskInfo, findTaskById, scanTasksInFolder, scanAllTasks, scanTasksInStatus, spawnRecurringTask};
+use crate::commands::folder::{FolderInfo, UpdateFolderInput, MoveFolderInput, scanFolders, createFolderLayout, loadFolder, applyFolderUpdate, moveFolderTo};
+use crate::commands::password::{PasswordInfo, PASSWORDS_LOCKED, copyPasswordField, decryptPasswordFields, findPasswordById, scanAllPasswords, scanPasswordsInFolder};
+use crate::commands::item::{TagCount, countTags};
+use crate::commands::trash::{TrashItem, clearTrash, listAllTrash, restoreNote, restorePassword, restoreTask};
+use crate::commands::template::{TemplateInfo, findTemplateByKey, listTemplates};
+use crate::commands::vault::unlockWithPassword;
+use crate::commands::workspace::{WorkspaceInfo, openWorkspaceAt};
 
 // ============================================
 // Notes API
 // ============================================
 
-pub fn get_notes(storage: &StorageState, folder_path: Option<&str>) -> Result<Vec<NoteInfo>, String> {
-    let wsPath = match storage.getWorkspacePath() {
+pub fn get_notes(storage: &StorageState, folder_path: Option<&str>, tags: Option<&[String]>, tag_match: TagMatch, include_archived: bool) -> Result<Vec<NoteInfo>, String> {
+    let wsPath = match storage.getAvailableWorkspacePath()? {
         Some(p) => p,
         None => return Ok(Vec::new()),
     };
@@ -32,7 +41,7 @@ pub fn get_notes(storage: &StorageState, folder_path: Option<&str>) -> Result<Ve
             // Validate and scan the notes subdirectory within the specified folder
             match validateFolderPath(&wsPath, fp) {
                 Ok(validatedPath) => {
-                    let notesSubdir = validatedPath.join("notes");
+                    let notesSubdir = itemsDir(&validatedPath, ItemKind::Note);
                     scanNotesInFolder(&notesSubdir, passwordRef)
                 }
                 Err(_) => return Ok(Vec::new()), // Invalid path, return empty
@@ -44,12 +53,21 @@ pub fn get_notes(storage: &StorageState, folder_path: Option<&str>) -> Result<Ve
         }
     };
 
+    // Rank, then id: a total order, so MCP pages don't shift between calls
+    let mut notes = notes;
+    SortSpec::default().sort(&mut notes, false, |n| &n.frontmatter);
+
+    let tags = tags.unwrap_or_default();
     storage.updateActivity();
-    Ok(notes.iter().map(NoteInfo::from).collect())
+    Ok(notes.iter()
+        .filter(|n| tag_match.matches(&n.frontmatter.tags, tags))
+        .filter(|n| include_archived || !n.frontmatter.archived)
+        .map(NoteInfo::from)
+        .collect())
 }
 
 pub fn get_note_by_id(storage: &StorageState, id: &str) -> Result<Option<NoteInfo>, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace")?;
 
     if !storage.isUnlocked() {
         return Err("Vault is locked".to_string());
@@ -58,13 +76,13 @@ pub fn get_note_by_id(storage: &StorageState, id: &str) -> Result<Option<NoteInf
     let masterPassword = storage.getMasterPassword();
     let passwordRef = masterPassword.as_deref();
 
-    let notes = scanAllNotes(&foldersDir(&wsPath), passwordRef);
+    let note = findNoteById(storage, &wsPath, id, passwordRef);
     storage.updateActivity();
-    Ok(notes.iter().find(|n| n.frontmatter.id == id).map(NoteInfo::from))
+    Ok(note.as_ref().map(NoteInfo::from))
 }
 
 pub fn get_note_content(storage: &StorageState, id: &str) -> Result<Option<String>, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace")?;
 
     if !storage.isUnlocked() {
         return Err("Vault is locked".to_string());
@@ -83,8 +101,8 @@ pub fn get_note_content(storage: &StorageState, id: &str) -> Result<Option<Strin
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
     let content = if encrypted_storage::isEncryptedFormat(&fileContent) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-        encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
+        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&note.path))?;
+        encrypted.decryptContent(&masterPassword)?
     } else {
         note.content.clone()
     };
@@ -93,6 +111,14 @@ pub fn get_note_content(storage: &StorageState, id: &str) -> Result<Option<Strin
     Ok(Some(content))
 }
 
+/// Created note plus the body exactly as it was stored
+#[derive(serde::Serialize)]
+pub struct CreatedNote {
+    #[serde(flatten)]
+    pub note: NoteInfo,
+    pub content: String,
+}
+
 pub fn create_note(
     storage: &StorageState,
     title: &str,
@@ -100,8 +126,8 @@ pub fn create_note(
     folder_path: Option<&str>,
     color: Option<&str>,
     tags: Option<&[String]>,
-) -> Result<NoteInfo, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace selected")?;
+) -> Result<CreatedNote, String> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace selected")?;
 
     if !storage.isUnlocked() {
         return Err("Vault is locked".to_string());
@@ -109,6 +135,10 @@ pub fn create_note(
 
     let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
 
+    if let Some(c) = content {
+        validateItemSize(c, storage.effectiveSettings().maxItemBytes)?;
+    }
+
     // If folder_path is provided, create notes in folder_path/notes/
     // Otherwise use the root workspace/folders/notes/
     // Validate path to prevent directory traversal attacks
@@ -116,7 +146,7 @@ pub fn create_note(
         Some(p) if !p.is_empty() && p != "null" => {
             // Validate the folder path is within workspace
             let validatedPath = validateFolderPath(&wsPath, p)?;
-            validatedPath.join("notes")
+            itemsDir(&validatedPath, ItemKind::Note)
         }
         _ => notesDir(&wsPath, ""),
     };
@@ -132,17 +162,18 @@ pub fn create_note(
     let filename = uuidFilename(&id);
     let notePath = notesSubdir.join(&filename);
 
-    let mut fm = NoteFrontmatter::new(id, title.to_string(), nextRank);
+    let mut fm = NoteFrontmatter::new(id, sanitizeTitle(title, storage.effectiveSettings().emojiShortcodes), nextRank);
     if let Some(c) = color {
-        fm.color = c.to_string();
+        fm.color = normalizeColor(c)?;
     }
     if let Some(t) = tags {
         fm.tags = t.to_vec();
     }
 
     let body = content.unwrap_or_default().to_string();
-    let file_content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
-    fs::write(&notePath, file_content).map_err(|e| e.to_string())?;
+    let file_content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&notePath))?;
+    writeFileAtomic(&notePath, file_content).map_err(|e| e.to_string())?;
+    storage.indexItem(ItemKind::Note, &fm.id, &notePath);
 
     let note = Note {
         path: notePath,
@@ -152,7 +183,7 @@ pub fn create_note(
     };
 
     storage.updateActivity();
-    Ok(NoteInfo::from(&note))
+    Ok(CreatedNote { note: NoteInfo::from(&note), content: note.content })
 }
 
 pub fn update_note(
@@ -164,19 +195,24 @@ pub fn update_note(
     pinned: Option<bool>,
     tags: Option<&[String]>,
     float: Option<FloatWindow>,
-) -> Result<(), String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+) -> Result<(), ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    if let Some(c) = content {
+        validateItemSize(c, storage.effectiveSettings().maxItemBytes)?;
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
     let notes = scanAllNotes(&foldersDir(&wsPath), Some(&masterPassword));
 
     let note = notes.iter()
         .find(|n| n.frontmatter.id == id)
-        .ok_or("Note not found")?;
+        .ok_or_else(|| ClaudiaError::NotFound("Note not found".to_string()))?;
 
     let mut fm = note.frontmatter.clone();
 
@@ -185,20 +221,20 @@ pub fn update_note(
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
     let mut body = if encrypted_storage::isEncryptedFormat(&fileContent) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-        encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
+        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&note.path))?;
+        encrypted.decryptContent(&masterPassword)?
     } else {
         note.content.clone()
     };
 
     if let Some(t) = title {
-        fm.title = t.to_string();
+        fm.title = sanitizeTitle(t, storage.effectiveSettings().emojiShortcodes);
     }
     if let Some(c) = content {
         body = c.to_string();
     }
     if let Some(c) = color {
-        fm.color = c.to_string();
+        fm.color = normalizeColor(c)?;
     }
     if let Some(p) = pinned {
         fm.pinned = p;
@@ -212,18 +248,19 @@ pub fn update_note(
 
     fm.updated = chrono::Utc::now().timestamp_millis();
 
-    let file_content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
-    fs::write(&note.path, file_content).map_err(|e| e.to_string())?;
+    let file_content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&note.path))?;
+    writeFileAtomic(&note.path, file_content).map_err(|e| e.to_string())?;
+    storage.invalidateLinks(&fm.id);
 
     storage.updateActivity();
     Ok(())
 }
 
-pub fn delete_note(storage: &StorageState, id: &str) -> Result<(), String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn delete_note(storage: &StorageState, id: &str) -> Result<(), ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
@@ -233,13 +270,25 @@ pub fn delete_note(storage: &StorageState, id: &str) -> Result<(), String> {
 
     let note = notes.iter()
         .find(|n| n.frontmatter.id == id)
-        .ok_or("Note not found")?;
+        .ok_or_else(|| ClaudiaError::NotFound("Note not found".to_string()))?;
 
-    fs::remove_file(&note.path).map_err(|e| e.to_string())
+    fs::remove_file(&note.path).map_err(|e| e.to_string())?;
+    storage.unindexItem(id);
+    let _ = fs::remove_dir_all(noteAssetsDir(&wsPath, id));
+    Ok(())
+}
+
+/// Search hit with byte ranges of the matches in the title and body
+#[derive(serde::Serialize)]
+pub struct NoteSearchHit {
+    #[serde(flatten)]
+    pub note: NoteInfo,
+    pub titleMatches: Vec<MatchRange>,
+    pub contentMatches: Vec<MatchRange>,
 }
 
-pub fn search_notes(storage: &StorageState, query: &str) -> Result<Vec<NoteInfo>, String> {
-    let wsPath = match storage.getWorkspacePath() {
+pub fn search_notes(storage: &StorageState, query: &str) -> Result<Vec<NoteSearchHit>, String> {
+    let wsPath = match storage.getAvailableWorkspacePath()? {
         Some(p) => p,
         None => return Ok(Vec::new()),
     };
@@ -252,15 +301,85 @@ pub fn search_notes(storage: &StorageState, query: &str) -> Result<Vec<NoteInfo>
     let passwordRef = masterPassword.as_deref();
 
     let notes = scanAllNotes(&foldersDir(&wsPath), passwordRef);
-    let query_lower = query.to_lowercase();
 
-    // Note: This only searches metadata (title) since content is not decrypted during scan
-    // For full-text search, would need to decrypt each file's content
+    // Content is not decrypted during scan, so decrypt each body for the full-text pass
     let result = notes.iter()
-        .filter(|n| {
-            n.frontmatter.title.to_lowercase().contains(&query_lower)
+        .filter_map(|n| {
+            let body = match passwordRef {
+                Some(mp) => encrypted_storage::readDecryptedBody(&n.path, mp).ok()?
+                    .unwrap_or_else(|| n.content.clone()),
+                None => n.content.clone(),
+            };
+            let titleMatches = search::findMatches(&n.frontmatter.title, query);
+            let contentMatches = search::findMatches(&body, query);
+            if titleMatches.is_empty() && contentMatches.is_empty() {
+                return None;
+            }
+            Some(NoteSearchHit { note: NoteInfo::from(n), titleMatches, contentMatches })
         })
-        .map(NoteInfo::from)
+        .collect();
+
+    storage.updateActivity();
+    Ok(result)
+}
+
+/// Default cap on full-text results; scanning stops once it is reached
+pub const MAX_CONTENT_SEARCH_RESULTS: usize = 50;
+/// Snippets returned per hit
+const MAX_SNIPPETS_PER_HIT: usize = 3;
+
+/// Full-text hit for any item kind, with excerpts around the body matches
+#[derive(serde::Serialize)]
+pub struct ContentSearchHit {
+    pub id: String,
+    pub kind: ItemKind,
+    pub title: String,
+    pub folderPath: String,
+    pub titleMatches: Vec<MatchRange>,
+    pub snippets: Vec<search::Snippet>,
+}
+
+/// Match `query` against a title and decrypted body, None when neither matches
+fn contentHit(kind: ItemKind, id: String, title: String, folderPath: String, body: &str, query: &str) -> Option<ContentSearchHit> {
+    let titleMatches = search::findMatches(&title, query);
+    let snippets: Vec<_> = search::findMatches(body, query).into_iter()
+        .take(MAX_SNIPPETS_PER_HIT)
+        .map(|m| search::snippet(body, m, search::SNIPPET_CONTEXT_CHARS))
+        .collect();
+    if titleMatches.is_empty() && snippets.is_empty() {
+        return None;
+    }
+    Some(ContentSearchHit { id, kind, title, folderPath, titleMatches, snippets })
+}
+
+/// Body of an item file, decrypted on demand (legacy plaintext files keep their parsed body)
+fn readBodyForSearch(path: &Path, masterPassword: &str, parsed: &str) -> Option<String> {
+    encrypted_storage::readDecryptedBody(path, masterPassword).ok()
+        .map(|body| body.unwrap_or_else(|| parsed.to_string()))
+}
+
+/// Full-text search over note titles and bodies, stopping after `limit` hits
+/// Files that fail to decrypt are skipped
+pub fn search_notes_fulltext(storage: &StorageState, query: &str, limit: usize) -> Result<Vec<ContentSearchHit>, String> {
+    let wsPath = match storage.getAvailableWorkspacePath()? {
+        Some(p) => p,
+        None => return Ok(Vec::new()),
+    };
+
+    if !storage.isUnlocked() {
+        return Err("Vault is locked".to_string());
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let notes = scanAllNotes(&foldersDir(&wsPath), Some(&masterPassword));
+
+    let result = notes.iter()
+        .filter_map(|n| {
+            let body = readBodyForSearch(&n.path, &masterPassword, &n.content)?;
+            let info = NoteInfo::from(n);
+            contentHit(ItemKind::Note, info.id, info.title, info.folderPath, &body, query)
+        })
+        .take(limit)
         .collect();
 
     storage.updateActivity();
@@ -271,8 +390,15 @@ pub fn search_notes(storage: &StorageState, query: &str) -> Result<Vec<NoteInfo>
 // Tasks API
 // ============================================
 
-pub fn get_tasks(storage: &StorageState, folder_path: Option<&str>, status_filter: Option<&str>) -> Result<Vec<TaskInfo>, String> {
-    let wsPath = match storage.getWorkspacePath() {
+pub fn get_tasks(
+    storage: &StorageState,
+    folder_path: Option<&str>,
+    status_filter: Option<&str>,
+    tags: Option<&[String]>,
+    tag_match: TagMatch,
+    include_archived: bool,
+) -> Result<Vec<TaskInfo>, String> {
+    let wsPath = match storage.getAvailableWorkspacePath()? {
         Some(p) => p,
         None => return Ok(Vec::new()),
     };
@@ -289,7 +415,7 @@ pub fn get_tasks(storage: &StorageState, folder_path: Option<&str>, status_filte
             // Validate and scan the tasks subdirectory within the specified folder
             match validateFolderPath(&wsPath, fp) {
                 Ok(validatedPath) => {
-                    let tasksSubdir = validatedPath.join("tasks");
+                    let tasksSubdir = itemsDir(&validatedPath, ItemKind::Task);
                     scanTasksInFolder(&tasksSubdir, passwordRef)
                 }
                 Err(_) => return Ok(Vec::new()), // Invalid path, return empty
@@ -301,19 +427,27 @@ pub fn get_tasks(storage: &StorageState, folder_path: Option<&str>, status_filte
         }
     };
 
-    let filtered: Vec<_> = if let Some(status_str) = status_filter {
+    let mut filtered: Vec<_> = if let Some(status_str) = status_filter {
         let target_status = TaskStatus::fromFolder(status_str);
-        tasks.into_iter().filter(|t| target_status.map(|s| t.status == s).unwrap_or(true)).collect()
+        tasks.into_iter().filter(|t| target_status.as_ref().map(|s| &t.status == s).unwrap_or(true)).collect()
     } else {
         tasks
     };
 
+    // Rank, then id: a total order, so MCP pages don't shift between calls
+    SortSpec::default().sort(&mut filtered, false, |t| &t.frontmatter);
+
+    let tags = tags.unwrap_or_default();
     storage.updateActivity();
-    Ok(filtered.iter().map(TaskInfo::from).collect())
+    Ok(filtered.iter()
+        .filter(|t| tag_match.matches(&t.frontmatter.tags, tags))
+        .filter(|t| include_archived || !t.frontmatter.archived)
+        .map(TaskInfo::from)
+        .collect())
 }
 
 pub fn get_task_by_id(storage: &StorageState, id: &str) -> Result<Option<TaskInfo>, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace")?;
 
     if !storage.isUnlocked() {
         return Err("Vault is locked".to_string());
@@ -322,13 +456,13 @@ pub fn get_task_by_id(storage: &StorageState, id: &str) -> Result<Option<TaskInf
     let masterPassword = storage.getMasterPassword();
     let passwordRef = masterPassword.as_deref();
 
-    let tasks = scanAllTasks(&foldersDir(&wsPath), passwordRef);
+    let task = findTaskById(storage, &wsPath, id, passwordRef);
     storage.updateActivity();
-    Ok(tasks.iter().find(|t| t.frontmatter.id == id).map(TaskInfo::from))
+    Ok(task.as_ref().map(TaskInfo::from))
 }
 
 pub fn get_task_content(storage: &StorageState, id: &str) -> Result<Option<String>, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace")?;
 
     if !storage.isUnlocked() {
         return Err("Vault is locked".to_string());
@@ -347,8 +481,8 @@ pub fn get_task_content(storage: &StorageState, id: &str) -> Result<Option<Strin
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
     let content = if encrypted_storage::isEncryptedFormat(&fileContent) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-        encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
+        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&task.path))?;
+        encrypted.decryptContent(&masterPassword)?
     } else {
         task.content.clone()
     };
@@ -357,6 +491,14 @@ pub fn get_task_content(storage: &StorageState, id: &str) -> Result<Option<Strin
     Ok(Some(content))
 }
 
+/// Created task plus the body exactly as it was stored
+#[derive(serde::Serialize)]
+pub struct CreatedTask {
+    #[serde(flatten)]
+    pub task: TaskInfo,
+    pub content: String,
+}
+
 pub fn create_task(
     storage: &StorageState,
     title: &str,
@@ -365,8 +507,8 @@ pub fn create_task(
     folder_path: Option<&str>,
     color: Option<&str>,
     due: Option<i64>,
-) -> Result<TaskInfo, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace selected")?;
+) -> Result<CreatedTask, String> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace selected")?;
 
     if !storage.isUnlocked() {
         return Err("Vault is locked".to_string());
@@ -374,6 +516,10 @@ pub fn create_task(
 
     let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
 
+    if let Some(c) = content {
+        validateItemSize(c, storage.effectiveSettings().maxItemBytes)?;
+    }
+
     // If folder_path is provided, create tasks in folder_path/tasks/
     // Otherwise use the root workspace/folders/tasks/
     // Validate path to prevent directory traversal attacks
@@ -381,20 +527,21 @@ pub fn create_task(
         Some(p) if !p.is_empty() && p != "null" => {
             // Validate the folder path is within workspace
             let validatedPath = validateFolderPath(&wsPath, p)?;
-            validatedPath.join("tasks")
+            itemsDir(&validatedPath, ItemKind::Task)
         }
         _ => tasksDir(&wsPath, ""),
     };
 
     let task_status = status
-        .and_then(|s| TaskStatus::fromFolder(s))
-        .unwrap_or(TaskStatus::Todo);
+        .and_then(TaskStatus::fromFolder)
+        .or_else(|| TaskStatus::all().into_iter().next())
+        .unwrap_or_default();
 
     let statusPath = tasksSubdir.join(task_status.folderName());
     fs::create_dir_all(&statusPath).map_err(|e| e.to_string())?;
 
     // Find next rank from existing tasks
-    let existingTasks = scanTasksInStatus(&statusPath, &tasksSubdir, task_status, Some(&masterPassword));
+    let existingTasks = scanTasksInStatus(&statusPath, &tasksSubdir, task_status.clone(), Some(&masterPassword));
     let nextRank = existingTasks.iter().map(|t| t.frontmatter.rank).max().unwrap_or(0) + 1;
 
     // UUID is the filename
@@ -402,17 +549,18 @@ pub fn create_task(
     let filename = uuidFilename(&id);
     let taskPath = statusPath.join(&filename);
 
-    let mut fm = TaskFrontmatter::new(id, title.to_string(), nextRank);
+    let mut fm = TaskFrontmatter::new(id, sanitizeTitle(title, storage.effectiveSettings().emojiShortcodes), nextRank);
     if let Some(c) = color {
-        fm.color = c.to_string();
+        fm.color = normalizeColor(c)?;
     }
     if let Some(d) = due {
         fm.due = Some(d);
     }
 
     let body = content.unwrap_or_default().to_string();
-    let file_content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
-    fs::write(&taskPath, file_content).map_err(|e| e.to_string())?;
+    let file_content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&taskPath))?;
+    writeFileAtomic(&taskPath, file_content).map_err(|e| e.to_string())?;
+    storage.indexItem(ItemKind::Task, &fm.id, &taskPath);
 
     let task = Task {
         path: taskPath,
@@ -423,7 +571,7 @@ pub fn create_task(
     };
 
     storage.updateActivity();
-    Ok(TaskInfo::from(&task))
+    Ok(CreatedTask { task: TaskInfo::from(&task), content: task.content })
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -438,19 +586,24 @@ pub fn update_task(
     tags: Option<&[String]>,
     due: Option<i64>,
     float: Option<FloatWindow>,
-) -> Result<(), String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+) -> Result<(), ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
+
+    if let Some(c) = content {
+        validateItemSize(c, storage.effectiveSettings().maxItemBytes)?;
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
     let tasks = scanAllTasks(&foldersDir(&wsPath), Some(&masterPassword));
 
     let task = tasks.iter()
         .find(|t| t.frontmatter.id == id)
-        .ok_or("Task not found")?;
+        .ok_or_else(|| ClaudiaError::NotFound("Task not found".to_string()))?;
 
     let mut fm = task.frontmatter.clone();
     let mut newPath = task.path.clone();
@@ -460,20 +613,20 @@ pub fn update_task(
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
     let mut body = if encrypted_storage::isEncryptedFormat(&fileContent) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-        encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
+        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&task.path))?;
+        encrypted.decryptContent(&masterPassword)?
     } else {
         task.content.clone()
     };
 
     if let Some(t) = title {
-        fm.title = t.to_string();
+        fm.title = sanitizeTitle(t, storage.effectiveSettings().emojiShortcodes);
     }
     if let Some(c) = content {
         body = c.to_string();
     }
     if let Some(c) = color {
-        fm.color = c.to_string();
+        fm.color = normalizeColor(c)?;
     }
     if let Some(p) = pinned {
         fm.pinned = p;
@@ -482,7 +635,7 @@ pub fn update_task(
         fm.tags = t.to_vec();
     }
     if let Some(d) = due {
-        fm.due = Some(d);
+        fm.setDue(d);
     }
     if let Some(f) = float {
         fm.float = f;
@@ -502,22 +655,30 @@ pub fn update_task(
 
     fm.updated = chrono::Utc::now().timestamp_millis();
 
-    let file_content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
+    let file_content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&newPath))?;
 
     if newPath != task.path {
         fs::remove_file(&task.path).map_err(|e| e.to_string())?;
     }
-    fs::write(&newPath, file_content).map_err(|e| e.to_string())?;
+    writeFileAtomic(&newPath, file_content).map_err(|e| e.to_string())?;
+    storage.indexItem(ItemKind::Task, &fm.id, &newPath);
+
+    if newPath != task.path
+        && TaskStatus::fromFolder(status.unwrap_or_default()) == Some(TaskStatus::Done)
+        && let Some(next) = spawnRecurringTask(&fm, &body, &task.folderPath, &masterPassword)?
+    {
+        storage.indexItem(ItemKind::Task, &next.frontmatter.id, &next.path);
+    }
 
     storage.updateActivity();
     Ok(())
 }
 
-pub fn delete_task(storage: &StorageState, id: &str) -> Result<(), String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn delete_task(storage: &StorageState, id: &str) -> Result<(), ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
     let masterPassword = storage.getMasterPassword();
@@ -527,9 +688,208 @@ pub fn delete_task(storage: &StorageState, id: &str) -> Result<(), String> {
 
     let task = tasks.iter()
         .find(|t| t.frontmatter.id == id)
-        .ok_or("Task not found")?;
+        .ok_or_else(|| ClaudiaError::NotFound("Task not found".to_string()))?;
+
+    fs::remove_file(&task.path).map_err(|e| e.to_string())?;
+    storage.unindexItem(id);
+    Ok(())
+}
+
+/// Full-text search over task titles and bodies, stopping after `limit` hits
+/// Files that fail to decrypt are skipped
+pub fn search_tasks_fulltext(storage: &StorageState, query: &str, limit: usize) -> Result<Vec<ContentSearchHit>, String> {
+    let wsPath = match storage.getAvailableWorkspacePath()? {
+        Some(p) => p,
+        None => return Ok(Vec::new()),
+    };
+
+    if !storage.isUnlocked() {
+        return Err("Vault is locked".to_string());
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let tasks = scanAllTasks(&foldersDir(&wsPath), Some(&masterPassword));
+
+    let result = tasks.iter()
+        .filter_map(|t| {
+            let body = readBodyForSearch(&t.path, &masterPassword, &t.content)?;
+            let info = TaskInfo::from(t);
+            contentHit(ItemKind::Task, info.id, info.title, info.folderPath, &body, query)
+        })
+        .take(limit)
+        .collect();
+
+    storage.updateActivity();
+    Ok(result)
+}
+
+/// Full-text search across notes then tasks, up to `limit` hits in total
+/// `kinds` ("note", "task") limits the search; passwords are never searched by content
+pub fn search_content(storage: &StorageState, query: &str, kinds: Option<&[String]>, limit: usize) -> Result<Vec<ContentSearchHit>, String> {
+    let kinds = match kinds {
+        Some(list) => Some(list.iter()
+            .map(|k| ItemKind::fromStr(k).ok_or_else(|| format!("Invalid item kind: {}", k)))
+            .collect::<Result<Vec<_>, _>>()?),
+        None => None,
+    };
+    if kinds.as_ref().is_some_and(|list| list.contains(&ItemKind::Password)) {
+        return Err("Invalid input: passwords cannot be searched by content".to_string());
+    }
+    let wants = |k: ItemKind| kinds.as_ref().is_none_or(|list| list.contains(&k));
+
+    let mut hits = Vec::new();
+    if wants(ItemKind::Note) {
+        hits.extend(search_notes_fulltext(storage, query, limit)?);
+    }
+    if wants(ItemKind::Task) && hits.len() < limit {
+        hits.extend(search_tasks_fulltext(storage, query, limit - hits.len())?);
+    }
+    Ok(hits)
+}
+
+// ============================================
+// Tags API
+// ============================================
+
+pub fn list_tags(storage: &StorageState) -> Result<Vec<TagCount>, String> {
+    Ok(countTags(storage)?)
+}
+
+// ============================================
+// Passwords API
+// ============================================
+// Listing only needs the vault; content and copying also need password access, which
+// only the user can grant in the app. None of these refresh the password access timer.
+
+/// Decrypted fields of a password for an agent; the 2FA secret stays in the vault,
+/// only its current code is handed out
+#[derive(serde::Serialize)]
+pub struct PasswordCredentials {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    pub notes: String,
+    pub totp: Option<TotpCode>,
+}
+
+pub fn get_passwords(storage: &StorageState, folder_path: Option<&str>) -> Result<Vec<PasswordInfo>, String> {
+    let wsPath = match storage.getAvailableWorkspacePath()? {
+        Some(p) => p,
+        None => return Ok(Vec::new()),
+    };
+
+    if !storage.isUnlocked() {
+        return Err("Vault is locked".to_string());
+    }
 
-    fs::remove_file(&task.path).map_err(|e| e.to_string())
+    let masterPassword = storage.getMasterPassword();
+    let passwordRef = masterPassword.as_deref();
+
+    let mut passwords = match folder_path {
+        Some(fp) if !fp.is_empty() => match validateFolderPath(&wsPath, fp) {
+            Ok(validatedPath) => scanPasswordsInFolder(&itemsDir(&validatedPath, ItemKind::Password), passwordRef),
+            Err(_) => return Ok(Vec::new()),
+        },
+        _ => scanAllPasswords(&foldersDir(&wsPath), passwordRef),
+    };
+    // Rank, then id, so MCP pages stay stable
+    passwords.sort_by(|a, b| a.frontmatter.rank.cmp(&b.frontmatter.rank).then_with(|| a.frontmatter.id.cmp(&b.frontmatter.id)));
+
+    storage.updateActivity();
+    Ok(passwords.iter().map(PasswordInfo::from).collect())
+}
+
+pub fn get_password_content(storage: &StorageState, id: &str) -> Result<Option<PasswordCredentials>, String> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace")?;
+
+    if !storage.isPasswordsAccessUnlocked() {
+        return Err(PASSWORDS_LOCKED.to_string());
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let Some(password) = findPasswordById(storage, &wsPath, id, Some(&masterPassword)) else {
+        return Ok(None);
+    };
+    let content = decryptPasswordFields(&password, &masterPassword)?;
+    let totp = (!content.totpSecret.is_empty())
+        .then(|| totp::currentCode(&content.totpSecret))
+        .transpose()?;
+
+    println!("[MCP] Password content read for {}", id);
+    storage.updateActivity();
+    Ok(Some(PasswordCredentials {
+        url: content.url,
+        username: content.username,
+        password: content.password,
+        notes: content.notes,
+        totp,
+    }))
+}
+
+/// Copy one field to the clipboard (cleared after `clear_after_secs`), returning the clear time
+pub fn copy_password(storage: &StorageState, app: &tauri::AppHandle, id: &str, field: &str, clear_after_secs: Option<u64>) -> Result<i64, String> {
+    let clearAt = copyPasswordField(app, storage, id, field, clear_after_secs)?;
+    println!("[MCP] Copied {} of password {} to the clipboard", field, id);
+    storage.updateActivity();
+    Ok(clearAt)
+}
+
+// ============================================
+// Trash API
+// ============================================
+
+/// An item brought back from trash, tagged with `kind`
+#[derive(serde::Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum RestoredItem {
+    Note(NoteInfo),
+    Task(TaskInfo),
+    Password(PasswordInfo),
+}
+
+pub fn list_trash(storage: &StorageState) -> Result<Vec<TrashItem>, String> {
+    let wsPath = match storage.getAvailableWorkspacePath()? {
+        Some(p) => p,
+        None => return Ok(Vec::new()),
+    };
+
+    if !storage.isUnlocked() {
+        return Err("Vault is locked".to_string());
+    }
+
+    let masterPassword = storage.getMasterPassword();
+    storage.updateActivity();
+    Ok(listAllTrash(&wsPath, masterPassword.as_deref()))
+}
+
+/// Restore a trashed item of any kind to the folder it was deleted from
+pub fn restore_from_trash(storage: &StorageState, id: &str) -> Result<RestoredItem, String> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace")?;
+    let filename = uuidFilename(id);
+
+    if trashNotesDir(&wsPath).join(&filename).is_file() {
+        restoreNote(storage, id).map(RestoredItem::Note).map_err(String::from)
+    } else if TaskStatus::all().iter().any(|s| trashTasksDir(&wsPath).join(s.folderName()).join(&filename).is_file()) {
+        restoreTask(storage, id).map(RestoredItem::Task).map_err(String::from)
+    } else if trashPasswordsDir(&wsPath).join(&filename).is_file() {
+        restorePassword(storage, id).map(RestoredItem::Password).map_err(String::from)
+    } else {
+        Err(format!("Not found in trash: {}", id))
+    }
+}
+
+/// Permanently delete everything in trash - there is no undo
+pub fn empty_trash(storage: &StorageState) -> Result<(), String> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace")?;
+
+    if !storage.isUnlocked() {
+        return Err("Vault is locked".to_string());
+    }
+
+    clearTrash(&wsPath)?;
+    println!("[MCP] Trash emptied");
+    storage.updateActivity();
+    Ok(())
 }
 
 // ============================================
@@ -537,7 +897,7 @@ pub fn delete_task(storage: &StorageState, id: &str) -> Result<(), String> {
 // ============================================
 
 pub fn get_folders(storage: &StorageState) -> Result<Vec<FolderInfo>, String> {
-    let wsPath = match storage.getWorkspacePath() {
+    let wsPath = match storage.getAvailableWorkspacePath()? {
         Some(p) => p,
         None => return Ok(Vec::new()),
     };
@@ -561,7 +921,7 @@ pub fn create_folder(
     name: &str,
     parent_path: Option<&str>,
 ) -> Result<FolderInfo, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace")?;
 
     if !storage.isUnlocked() {
         return Err("Vault is locked".to_string());
@@ -569,11 +929,7 @@ pub fn create_folder(
 
     let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
 
-    let baseDir = foldersDir(&wsPath);
-
-    let parentDir = parent_path
-        .map(PathBuf::from)
-        .unwrap_or(baseDir.clone());
+    let parentDir = resolveFolderArg(&wsPath, parent_path)?;
 
     // Find next rank from existing folders
     let existingFolders = scanFolders(&parentDir, None, Some(&masterPassword));
@@ -591,16 +947,11 @@ pub fn create_folder(
         &serde_yaml::to_string(&fm).map_err(|e| e.to_string())?,
         "", // Folders have no body content
         &masterPassword,
+        &id,
     )?;
-    fs::write(folderPath.join(".folder.md"), fileContent).map_err(|e| e.to_string())?;
+    writeFileAtomic(folderPath.join(".folder.md"), fileContent).map_err(|e| e.to_string())?;
 
-    // Create notes/, tasks/, and passwords/ subdirectories
-    fs::create_dir_all(folderPath.join("notes")).map_err(|e| e.to_string())?;
-    fs::create_dir_all(folderPath.join("tasks")).map_err(|e| e.to_string())?;
-    fs::create_dir_all(folderPath.join("passwords")).map_err(|e| e.to_string())?;
-    for status in ["todo", "doing", "done"] {
-        fs::create_dir_all(folderPath.join("tasks").join(status)).map_err(|e| e.to_string())?;
-    }
+    createFolderLayout(&folderPath, &storage.effectiveSettings().folderLayout)?;
 
     let folder = Folder {
         path: folderPath.clone(),
@@ -613,30 +964,104 @@ pub fn create_folder(
     Ok(FolderInfo::from(&folder))
 }
 
-pub fn delete_folder(_storage: &StorageState, path: &str) -> Result<(), String> {
-    let folderPath = PathBuf::from(path);
+/// Resolve a folder path given by an agent, refusing anything outside the workspace's folders/
+fn resolveFolderPath(wsPath: &str, path: &str) -> Result<PathBuf, String> {
+    let folderPath = validateFolderDir(wsPath, path)?;
+    if !folderPath.is_dir() {
+        return Err(format!("Folder not found: {}", path));
+    }
+    Ok(folderPath)
+}
+
+pub fn get_folder(storage: &StorageState, path: &str) -> Result<FolderInfo, String> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace")?;
+
+    if !storage.isUnlocked() {
+        return Err("Vault is locked".to_string());
+    }
+
+    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let folderPath = resolveFolderPath(&wsPath, path)?;
+    let folder = loadFolder(&folderPath, &masterPassword)?;
+
+    storage.updateActivity();
+    Ok(FolderInfo::from(&folder))
+}
+
+pub fn update_folder(
+    storage: &StorageState,
+    path: &str,
+    name: Option<&str>,
+    color: Option<&str>,
+    icon: Option<&str>,
+    pinned: Option<bool>,
+    favorite: Option<bool>,
+) -> Result<FolderInfo, String> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace")?;
+    let folderPath = resolveFolderPath(&wsPath, path)?;
+
+    let name = match name {
+        Some(n) => {
+            let sanitized = sanitizeTitle(n, storage.effectiveSettings().emojiShortcodes);
+            if sanitized.is_empty() {
+                return Err("Folder name cannot be empty".to_string());
+            }
+            Some(sanitized)
+        }
+        None => None,
+    };
+
+    applyFolderUpdate(storage, UpdateFolderInput {
+        path: folderPath.to_string_lossy().to_string(),
+        name,
+        pinned,
+        favorite,
+        color: color.map(String::from),
+        icon: icon.map(String::from),
+    })?;
+
+    get_folder(storage, path)
+}
+
+pub fn move_folder(storage: &StorageState, path: &str, new_parent_path: Option<&str>) -> Result<FolderInfo, String> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace")?;
+    let folderPath = resolveFolderPath(&wsPath, path)?;
+    let newParentPath = match new_parent_path {
+        Some(p) if !p.is_empty() => Some(resolveFolderPath(&wsPath, p)?.to_string_lossy().to_string()),
+        _ => None,
+    };
+
+    Ok(moveFolderTo(storage, MoveFolderInput {
+        folderPath: folderPath.to_string_lossy().to_string(),
+        newParentPath,
+    })?)
+}
+
+pub fn delete_folder(storage: &StorageState, path: &str) -> Result<(), String> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or("No workspace")?;
+    let folderPath = validateFolderDir(&wsPath, path)?;
     if folderPath.exists() {
         fs::remove_dir_all(&folderPath).map_err(|e| e.to_string())?;
     }
     Ok(())
 }
 
-pub fn move_note_to_folder(storage: &StorageState, id: &str, target_folder_path: &str) -> Result<NoteInfo, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn move_note_to_folder(storage: &StorageState, id: &str, target_folder_path: &str) -> Result<NoteInfo, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
     let notes = scanAllNotes(&foldersDir(&wsPath), Some(&masterPassword));
 
     let note = notes.iter()
         .find(|n| n.frontmatter.id == id)
-        .ok_or("Note not found")?;
+        .ok_or_else(|| ClaudiaError::NotFound("Note not found".to_string()))?;
 
     // Target is the notes subdirectory within the folder
-    let targetNotesDir = PathBuf::from(target_folder_path).join("notes");
+    let targetNotesDir = itemsDir(validateMoveTarget(&wsPath, target_folder_path)?, ItemKind::Note);
     fs::create_dir_all(&targetNotesDir).map_err(|e| e.to_string())?;
 
     // Find next rank in target folder
@@ -655,15 +1080,15 @@ pub fn move_note_to_folder(storage: &StorageState, id: &str, target_folder_path:
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
     let body = if encrypted_storage::isEncryptedFormat(&fileContent) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-        encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
+        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&note.path))?;
+        encrypted.decryptContent(&masterPassword)?
     } else {
         note.content.clone()
     };
 
     // Encrypt and write to new location
-    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
-    fs::write(&newPath, &content).map_err(|e| e.to_string())?;
+    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&newPath))?;
+    writeFileAtomic(&newPath, &content).map_err(|e| e.to_string())?;
 
     // Remove old file
     fs::remove_file(&note.path).map_err(|e| e.to_string())?;
@@ -674,32 +1099,33 @@ pub fn move_note_to_folder(storage: &StorageState, id: &str, target_folder_path:
         frontmatter: fm,
         content: body,
     };
+    storage.indexItem(ItemKind::Note, id, &movedNote.path);
 
     storage.updateActivity();
     Ok(NoteInfo::from(&movedNote))
 }
 
-pub fn move_task_to_folder(storage: &StorageState, id: &str, target_folder_path: &str) -> Result<TaskInfo, String> {
-    let wsPath = storage.getWorkspacePath().ok_or("No workspace")?;
+pub fn move_task_to_folder(storage: &StorageState, id: &str, target_folder_path: &str) -> Result<TaskInfo, ClaudiaError> {
+    let wsPath = storage.getAvailableWorkspacePath()?.ok_or(ClaudiaError::NoWorkspace)?;
 
     if !storage.isUnlocked() {
-        return Err("Vault is locked".to_string());
+        return Err(ClaudiaError::VaultLocked);
     }
 
-    let masterPassword = storage.getMasterPassword().ok_or("No master password")?;
+    let masterPassword = storage.getMasterPassword().ok_or(ClaudiaError::VaultLocked)?;
     let tasks = scanAllTasks(&foldersDir(&wsPath), Some(&masterPassword));
 
     let task = tasks.iter()
         .find(|t| t.frontmatter.id == id)
-        .ok_or("Task not found")?;
+        .ok_or_else(|| ClaudiaError::NotFound("Task not found".to_string()))?;
 
     // Target is the tasks subdirectory within the folder
-    let targetTasksDir = PathBuf::from(target_folder_path).join("tasks");
+    let targetTasksDir = itemsDir(validateMoveTarget(&wsPath, target_folder_path)?, ItemKind::Task);
     let statusPath = targetTasksDir.join(task.status.folderName());
     fs::create_dir_all(&statusPath).map_err(|e| e.to_string())?;
 
     // Find next rank in target folder
-    let existingTasks = scanTasksInStatus(&statusPath, &targetTasksDir, task.status, Some(&masterPassword));
+    let existingTasks = scanTasksInStatus(&statusPath, &targetTasksDir, task.status.clone(), Some(&masterPassword));
     let nextRank = existingTasks.iter().map(|t| t.frontmatter.rank).max().unwrap_or(0) + 1;
 
     // Same UUID filename, new location
@@ -714,15 +1140,15 @@ pub fn move_task_to_folder(storage: &StorageState, id: &str, target_folder_path:
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
     let body = if encrypted_storage::isEncryptedFormat(&fileContent) {
-        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent)?;
-        encrypted_storage::decryptContent(&encrypted.content, &masterPassword)?
+        let encrypted = encrypted_storage::parseEncryptedFile(&fileContent, &fileIdOf(&task.path))?;
+        encrypted.decryptContent(&masterPassword)?
     } else {
         task.content.clone()
     };
 
     // Encrypt and write to new location
-    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword)?;
-    fs::write(&newPath, &content).map_err(|e| e.to_string())?;
+    let content = encrypted_storage::serializeAndEncrypt(&fm, &body, &masterPassword, &fileIdOf(&newPath))?;
+    writeFileAtomic(&newPath, &content).map_err(|e| e.to_string())?;
 
     // Remove old file
     fs::remove_file(&task.path).map_err(|e| e.to_string())?;
@@ -730,11 +1156,90 @@ pub fn move_task_to_folder(storage: &StorageState, id: &str, target_folder_path:
     let movedTask = Task {
         path: newPath,
         folderPath: targetTasksDir,
-        status: task.status,
+        status: task.status.clone(),
         frontmatter: fm,
         content: body,
     };
+    storage.indexItem(ItemKind::Task, id, &movedTask.path);
 
     storage.updateActivity();
     Ok(TaskInfo::from(&movedTask))
 }
+
+// ============================================
+// Workspace API
+// ============================================
+
+/// Switch the app to another known workspace, matched by name or path.
+/// Requires an unlocked vault; the old key is dropped since each workspace has its own vault,
+/// and the new one is unlocked only when its master password is supplied.
+pub fn switch_workspace(storage: &StorageState, workspace: &str, password: Option<&str>) -> Result<WorkspaceInfo, String> {
+    if !storage.isUnlocked() {
+        return Err("Vault is locked".to_string());
+    }
+
+    let path = storage.workspaces.read().iter()
+        .find(|ws| ws.path == workspace || ws.name == workspace)
+        .map(|ws| ws.path.clone())
+        .ok_or_else(|| format!("Workspace not found: {}", workspace))?;
+
+    if !PathBuf::from(&path).is_dir() {
+        return Err(format!("Workspace unavailable: {} no longer exists", path));
+    }
+
+    if storage.getWorkspacePath().as_deref() != Some(path.as_str()) {
+        storage.lock();
+    }
+    let info = openWorkspaceAt(storage, &path)?;
+    storage.emitEvent("mcp-workspace-changed", info.path.clone());
+
+    if let Some(pw) = password
+        && !storage.isUnlocked()
+        && !unlockWithPassword(storage, pw)?
+    {
+        return Err("Invalid master password for workspace".to_string());
+    }
+
+    Ok(info)
+}
+
+// ============================================
+// Templates API
+// ============================================
+
+pub fn list_templates(template_type: &str) -> Result<Vec<TemplateInfo>, String> {
+    let tType = TemplateType::fromStr(template_type).ok_or("Invalid template type (expected note or task)")?;
+    Ok(listTemplates(tType))
+}
+
+/// An item created from a template, tagged with `kind`
+#[derive(serde::Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum CreatedFromTemplate {
+    Note(CreatedNote),
+    Task(CreatedTask),
+}
+
+/// Create a note or task whose body is the template's content.
+/// `template_id` is the template's id, or its directory slug (e.g. "meeting-notes").
+pub fn create_from_template(
+    storage: &StorageState,
+    template_type: &str,
+    template_id: &str,
+    title: &str,
+    folder_path: Option<&str>,
+) -> Result<CreatedFromTemplate, String> {
+    let tType = TemplateType::fromStr(template_type).ok_or("Invalid template type (expected note or task)")?;
+    let template = findTemplateByKey(tType, template_id)
+        .ok_or_else(|| format!("Template not found: {}", template_id))?;
+
+    match tType {
+        TemplateType::Note => create_note(storage, title, Some(&template.content), folder_path, None, None)
+            .map(CreatedFromTemplate::Note),
+        TemplateType::Task => {
+            let status = template.defaultTaskStatus();
+            create_task(storage, title, Some(&template.content), status.as_ref().map(|s| s.folderName()), folder_path, None, None)
+                .map(CreatedFromTemplate::Task)
+        }
+    }
+}
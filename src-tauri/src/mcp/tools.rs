@@ -11,10 +11,11 @@ use serde::Deserialize;
 use schemars::JsonSchema;
 use tauri::Emitter;
 
+use crate::error::ClaudiaError;
 use crate::storage::StorageState;
 use crate::mcp::api;
 
-/// Claudia MCP Server - provides tools for notes, tasks, and folders
+/// Claudia MCP Server - provides tools for notes, tasks, folders and passwords
 #[derive(Clone)]
 pub struct ClaudiaServer {
     pub storage: StorageState,
@@ -93,10 +94,39 @@ impl rmcp::handler::server::ServerHandler for ClaudiaServer {
 // Tool Input Types
 // ============================================
 
+/// "all" (default) keeps items carrying every tag, "any" items carrying at least one
+#[derive(Deserialize, JsonSchema, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatchInput {
+    #[default]
+    All,
+    Any,
+}
+
+impl From<TagMatchInput> for crate::models::TagMatch {
+    fn from(input: TagMatchInput) -> Self {
+        match input {
+            TagMatchInput::All => Self::All,
+            TagMatchInput::Any => Self::Any,
+        }
+    }
+}
+
 #[derive(Deserialize, JsonSchema)]
-pub struct FolderPathInput {
+pub struct NotesFilterInput {
     #[serde(rename = "folderPath")]
     pub folder_path: Option<String>,
+    /// Only notes carrying these tags
+    pub tags: Option<Vec<String>>,
+    #[serde(rename = "tagMatch")]
+    pub tag_match: Option<TagMatchInput>,
+    /// Also list archived notes
+    #[serde(rename = "includeArchived")]
+    pub include_archived: Option<bool>,
+    /// Opaque cursor from a previous page's nextCursor
+    pub cursor: Option<String>,
+    /// Page size (default 100, max 500)
+    pub limit: Option<usize>,
 }
 
 #[derive(Deserialize, JsonSchema)]
@@ -149,6 +179,17 @@ pub struct TasksFilterInput {
     #[serde(rename = "folderPath")]
     pub folder_path: Option<String>,
     pub status: Option<String>,
+    /// Only tasks carrying these tags
+    pub tags: Option<Vec<String>>,
+    #[serde(rename = "tagMatch")]
+    pub tag_match: Option<TagMatchInput>,
+    /// Also list archived tasks
+    #[serde(rename = "includeArchived")]
+    pub include_archived: Option<bool>,
+    /// Opaque cursor from a previous page's nextCursor
+    pub cursor: Option<String>,
+    /// Page size (default 100, max 500)
+    pub limit: Option<usize>,
 }
 
 #[derive(Deserialize, JsonSchema)]
@@ -156,6 +197,15 @@ pub struct SearchInput {
     pub query: String,
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub struct SearchContentInput {
+    pub query: String,
+    /// Item kinds to search ("note", "task"); both when omitted
+    pub kinds: Option<Vec<String>>,
+    /// Maximum number of hits (default 50)
+    pub limit: Option<usize>,
+}
+
 #[derive(Deserialize, JsonSchema)]
 pub struct CreateFolderInput {
     pub name: String,
@@ -163,11 +213,64 @@ pub struct CreateFolderInput {
     pub parent_path: Option<String>,
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub struct FolderPathInput {
+    pub path: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct UpdateFolderInput {
+    pub path: String,
+    pub name: Option<String>,
+    /// Hex color (e.g. #5B8DEF) or palette name
+    pub color: Option<String>,
+    /// Lucide icon name (e.g. Folder, Briefcase)
+    pub icon: Option<String>,
+    pub pinned: Option<bool>,
+    pub favorite: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct MoveFolderInput {
+    pub path: String,
+    /// Path of the new parent folder; omit to move to the root
+    #[serde(rename = "newParentPath")]
+    pub new_parent_path: Option<String>,
+}
+
 #[derive(Deserialize, JsonSchema)]
 pub struct DeleteFolderInput {
     pub path: String,
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub struct TemplateTypeInput {
+    /// "note" or "task"
+    #[serde(rename = "templateType")]
+    pub template_type: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct CreateFromTemplateInput {
+    /// "note" or "task"
+    #[serde(rename = "templateType")]
+    pub template_type: String,
+    /// Template id or slug, as returned by list_templates
+    #[serde(rename = "templateId")]
+    pub template_id: String,
+    pub title: String,
+    #[serde(rename = "folderPath")]
+    pub folder_path: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SwitchWorkspaceInput {
+    /// Workspace name or path
+    pub workspace: String,
+    /// Master password of the target workspace; without it the vault stays locked after switching
+    pub password: Option<String>,
+}
+
 #[derive(Deserialize, JsonSchema)]
 pub struct MoveInput {
     pub id: String,
@@ -175,20 +278,93 @@ pub struct MoveInput {
     pub target_folder_path: String,
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub struct PasswordsFilterInput {
+    #[serde(rename = "folderPath")]
+    pub folder_path: Option<String>,
+    /// Opaque cursor from a previous page's nextCursor
+    pub cursor: Option<String>,
+    /// Page size (default 100, max 500)
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct PageInput {
+    /// Opaque cursor from a previous page's nextCursor
+    pub cursor: Option<String>,
+    /// Page size (default 100, max 500)
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct CopyPasswordInput {
+    pub id: String,
+    /// One of password, username, url, notes
+    pub field: String,
+    /// Seconds before the clipboard is cleared (default 30, max 600)
+    #[serde(rename = "clearAfterSecs")]
+    pub clear_after_secs: Option<u64>,
+}
+
 // ============================================
 // Tool Implementations
 // ============================================
 
+/// Password access is granted by the user in the app, never through MCP, so a locked
+/// error carries a code and an instruction the agent can relay
+fn password_error(e: String) -> McpError {
+    if e == crate::commands::password::PASSWORDS_LOCKED {
+        McpError::invalid_request(
+            "Password access is locked. Ask the user to unlock passwords in the Claudia app, then try again.",
+            Some(serde_json::json!({ "code": "passwords_locked" })),
+        )
+    } else {
+        McpError::internal_error(e, None)
+    }
+}
+
+/// A missing item or bad argument is the agent's to fix; anything else is an internal failure
+fn claudia_error(e: ClaudiaError) -> McpError {
+    match e {
+        ClaudiaError::NotFound(_) | ClaudiaError::InvalidInput(_) => McpError::invalid_params(e.to_string(), None),
+        _ => McpError::internal_error(e.to_string(), None),
+    }
+}
+
+// ============================================
+// Pagination
+// ============================================
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 500;
+
+/// One page of a list tool result as `{ items, total, nextCursor }`, like list_tools' next_cursor
+/// The cursor is the offset of the next page; `total` comes from the full scan
+fn paged_result<T: serde::Serialize>(items: Vec<T>, cursor: Option<&str>, limit: Option<usize>) -> Result<CallToolResult, McpError> {
+    let offset = match cursor {
+        Some(c) => c.parse::<usize>().map_err(|_| McpError::invalid_params(format!("Invalid cursor: {}", c), None))?,
+        None => 0,
+    };
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let page = crate::commands::common::Page::of(items, offset, Some(limit));
+    let result = serde_json::json!({
+        "items": page.items,
+        "total": page.total,
+        "nextCursor": page.nextOffset.map(|o| o.to_string()),
+    });
+    Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&result).unwrap())]))
+}
+
 #[tool_router]
 impl ClaudiaServer {
     // --- Notes ---
     
-    #[tool(description = "List all notes, optionally filtered by folder")]
-    async fn list_notes(&self, input: Parameters<FolderPathInput>) -> Result<CallToolResult, McpError> {
-        let notes = api::get_notes(&self.storage, input.0.folder_path.as_deref())
+    #[tool(description = "List notes, optionally filtered by folder and tags; archived notes are left out unless includeArchived is set. Paged: pass nextCursor back as cursor")]
+    async fn list_notes(&self, input: Parameters<NotesFilterInput>) -> Result<CallToolResult, McpError> {
+        let tagMatch = input.0.tag_match.unwrap_or_default().into();
+        let notes = api::get_notes(&self.storage, input.0.folder_path.as_deref(), input.0.tags.as_deref(), tagMatch, input.0.include_archived.unwrap_or(false))
             .map_err(|e| McpError::internal_error(e, None))?;
-        let json = serde_json::to_string_pretty(&notes).unwrap_or_else(|_| "[]".to_string());
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        paged_result(notes, input.0.cursor.as_deref(), input.0.limit)
     }
 
     #[tool(description = "Get a specific note by ID, including its content")]
@@ -203,7 +379,7 @@ impl ClaudiaServer {
         Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&result).unwrap())]))
     }
 
-    #[tool(description = "Create a new note")]
+    #[tool(description = "Create a new note; returns the note info and its stored content")]
     async fn create_note(&self, input: Parameters<CreateNoteInput>) -> Result<CallToolResult, McpError> {
         let note = api::create_note(
             &self.storage,
@@ -228,7 +404,7 @@ impl ClaudiaServer {
             None,
             input.0.tags.as_deref(),
             None,
-        ).map_err(|e| McpError::internal_error(e, None))?;
+        ).map_err(claudia_error)?;
         let _ = self.app_handle.emit("mcp-notes-changed", ());
         Ok(CallToolResult::success(vec![Content::text(format!("Note {} updated successfully", input.0.id))]))
     }
@@ -236,7 +412,8 @@ impl ClaudiaServer {
     #[tool(description = "Delete a note by ID")]
     async fn delete_note(&self, input: Parameters<IdInput>) -> Result<CallToolResult, McpError> {
         api::delete_note(&self.storage, &input.0.id)
-            .map_err(|e| McpError::internal_error(e, None))?;
+            .map_err(claudia_error)?;
+        crate::commands::floating::closeItemFloatingWindow(&self.app_handle, "note", &input.0.id);
         let _ = self.app_handle.emit("mcp-notes-changed", ());
         Ok(CallToolResult::success(vec![Content::text(format!("Note {} deleted successfully", input.0.id))]))
     }
@@ -252,19 +429,19 @@ impl ClaudiaServer {
     #[tool(description = "Move a note to a different folder")]
     async fn move_note_to_folder(&self, input: Parameters<MoveInput>) -> Result<CallToolResult, McpError> {
         let moved = api::move_note_to_folder(&self.storage, &input.0.id, &input.0.target_folder_path)
-            .map_err(|e| McpError::internal_error(e, None))?;
+            .map_err(claudia_error)?;
         let _ = self.app_handle.emit("mcp-notes-changed", ());
         Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&moved).unwrap())]))
     }
 
     // --- Tasks ---
 
-    #[tool(description = "List all tasks, optionally filtered by folder or status")]
+    #[tool(description = "List tasks, optionally filtered by folder, status or tags; archived tasks are left out unless includeArchived is set. Paged: pass nextCursor back as cursor")]
     async fn list_tasks(&self, input: Parameters<TasksFilterInput>) -> Result<CallToolResult, McpError> {
-        let tasks = api::get_tasks(&self.storage, input.0.folder_path.as_deref(), input.0.status.as_deref())
+        let tagMatch = input.0.tag_match.unwrap_or_default().into();
+        let tasks = api::get_tasks(&self.storage, input.0.folder_path.as_deref(), input.0.status.as_deref(), input.0.tags.as_deref(), tagMatch, input.0.include_archived.unwrap_or(false))
             .map_err(|e| McpError::internal_error(e, None))?;
-        let json = serde_json::to_string_pretty(&tasks).unwrap_or_else(|_| "[]".to_string());
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        paged_result(tasks, input.0.cursor.as_deref(), input.0.limit)
     }
 
     #[tool(description = "Get a specific task by ID")]
@@ -278,7 +455,7 @@ impl ClaudiaServer {
         Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&result).unwrap())]))
     }
 
-    #[tool(description = "Create a new task")]
+    #[tool(description = "Create a new task; returns the task info and its stored content")]
     async fn create_task(&self, input: Parameters<CreateTaskInput>) -> Result<CallToolResult, McpError> {
         let task = api::create_task(
             &self.storage,
@@ -306,7 +483,7 @@ impl ClaudiaServer {
             None,
             input.0.due,
             None,
-        ).map_err(|e| McpError::internal_error(e, None))?;
+        ).map_err(claudia_error)?;
         let _ = self.app_handle.emit("mcp-tasks-changed", ());
         Ok(CallToolResult::success(vec![Content::text(format!("Task {} updated successfully", input.0.id))]))
     }
@@ -314,7 +491,8 @@ impl ClaudiaServer {
     #[tool(description = "Delete a task by ID")]
     async fn delete_task(&self, input: Parameters<IdInput>) -> Result<CallToolResult, McpError> {
         api::delete_task(&self.storage, &input.0.id)
-            .map_err(|e| McpError::internal_error(e, None))?;
+            .map_err(claudia_error)?;
+        crate::commands::floating::closeItemFloatingWindow(&self.app_handle, "task", &input.0.id);
         let _ = self.app_handle.emit("mcp-tasks-changed", ());
         Ok(CallToolResult::success(vec![Content::text(format!("Task {} deleted successfully", input.0.id))]))
     }
@@ -325,7 +503,7 @@ impl ClaudiaServer {
             &self.storage,
             &input.0.id,
             None, None, Some("done"), None, None, None, None, None,
-        ).map_err(|e| McpError::internal_error(e, None))?;
+        ).map_err(claudia_error)?;
         let _ = self.app_handle.emit("mcp-tasks-changed", ());
         Ok(CallToolResult::success(vec![Content::text(format!("Task {} marked as done", input.0.id))]))
     }
@@ -333,11 +511,98 @@ impl ClaudiaServer {
     #[tool(description = "Move a task to a different folder")]
     async fn move_task_to_folder(&self, input: Parameters<MoveInput>) -> Result<CallToolResult, McpError> {
         let moved = api::move_task_to_folder(&self.storage, &input.0.id, &input.0.target_folder_path)
-            .map_err(|e| McpError::internal_error(e, None))?;
+            .map_err(claudia_error)?;
         let _ = self.app_handle.emit("mcp-tasks-changed", ());
         Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&moved).unwrap())]))
     }
 
+    // --- Search ---
+
+    #[tool(description = "Full-text search across note and task titles and bodies; returns snippets around each match")]
+    async fn search_content(&self, input: Parameters<SearchContentInput>) -> Result<CallToolResult, McpError> {
+        let limit = input.0.limit.unwrap_or(api::MAX_CONTENT_SEARCH_RESULTS);
+        let hits = api::search_content(&self.storage, &input.0.query, input.0.kinds.as_deref(), limit)
+            .map_err(|e| McpError::internal_error(e, None))?;
+        let json = serde_json::to_string_pretty(&hits).unwrap_or_else(|_| "[]".to_string());
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    // --- Tags ---
+
+    #[tool(description = "List every tag in the workspace with note, task and password counts, most used first")]
+    async fn list_tags(&self) -> Result<CallToolResult, McpError> {
+        let tags = api::list_tags(&self.storage)
+            .map_err(|e| McpError::internal_error(e, None))?;
+        let json = serde_json::to_string_pretty(&tags).unwrap_or_else(|_| "[]".to_string());
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    // --- Workspaces ---
+
+    #[tool(description = "Switch to another workspace by name or path (requires an unlocked vault)")]
+    async fn switch_workspace(&self, input: Parameters<SwitchWorkspaceInput>) -> Result<CallToolResult, McpError> {
+        let workspace = api::switch_workspace(&self.storage, &input.0.workspace, input.0.password.as_deref())
+            .map_err(|e| McpError::internal_error(e, None))?;
+        Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&workspace).unwrap())]))
+    }
+
+    // --- Passwords ---
+
+    #[tool(description = "List saved passwords (titles, tags and folders only, no secrets). Paged: pass nextCursor back as cursor")]
+    async fn list_passwords(&self, input: Parameters<PasswordsFilterInput>) -> Result<CallToolResult, McpError> {
+        let passwords = api::get_passwords(&self.storage, input.0.folder_path.as_deref())
+            .map_err(|e| McpError::internal_error(e, None))?;
+        paged_result(passwords, input.0.cursor.as_deref(), input.0.limit)
+    }
+
+    #[tool(description = "Get a password's URL, username, password, notes and current 2FA code. Only works while the user has unlocked password access in the app")]
+    async fn get_password(&self, input: Parameters<IdInput>) -> Result<CallToolResult, McpError> {
+        let content = api::get_password_content(&self.storage, &input.0.id)
+            .map_err(password_error)?
+            .ok_or_else(|| McpError::invalid_params(format!("Password not found: {}", input.0.id), None))?;
+        Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&content).unwrap())]))
+    }
+
+    #[tool(description = "Copy one field of a password to the clipboard without returning it; the clipboard is cleared afterwards. Only works while the user has unlocked password access in the app")]
+    async fn copy_password(&self, input: Parameters<CopyPasswordInput>) -> Result<CallToolResult, McpError> {
+        let clearAt = api::copy_password(&self.storage, &self.app_handle, &input.0.id, &input.0.field, input.0.clear_after_secs)
+            .map_err(password_error)?;
+        let result = serde_json::json!({ "copied": input.0.field, "clearAt": clearAt });
+        Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&result).unwrap())]))
+    }
+
+    // --- Trash ---
+
+    #[tool(description = "List trashed notes, tasks and passwords; each item has a kind of note, task or password. Paged: pass nextCursor back as cursor")]
+    async fn list_trash(&self, input: Parameters<PageInput>) -> Result<CallToolResult, McpError> {
+        let items = api::list_trash(&self.storage)
+            .map_err(|e| McpError::internal_error(e, None))?;
+        paged_result(items, input.0.cursor.as_deref(), input.0.limit)
+    }
+
+    #[tool(description = "Restore a trashed note, task or password by ID to the folder it was deleted from (the root folder if that folder is gone)")]
+    async fn restore_from_trash(&self, input: Parameters<IdInput>) -> Result<CallToolResult, McpError> {
+        let item = api::restore_from_trash(&self.storage, &input.0.id)
+            .map_err(|e| McpError::internal_error(e, None))?;
+        let event = match item {
+            api::RestoredItem::Note(_) => "mcp-notes-changed",
+            api::RestoredItem::Task(_) => "mcp-tasks-changed",
+            api::RestoredItem::Password(_) => "mcp-passwords-changed",
+        };
+        let _ = self.app_handle.emit(event, ());
+        Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&item).unwrap())]))
+    }
+
+    #[tool(description = "Permanently delete everything in trash. IRREVERSIBLE: emptied items cannot be restored or undone; only call this when the user explicitly asks")]
+    async fn empty_trash(&self) -> Result<CallToolResult, McpError> {
+        api::empty_trash(&self.storage)
+            .map_err(|e| McpError::internal_error(e, None))?;
+        for event in ["mcp-notes-changed", "mcp-tasks-changed", "mcp-passwords-changed"] {
+            let _ = self.app_handle.emit(event, ());
+        }
+        Ok(CallToolResult::success(vec![Content::text("Trash emptied successfully".to_string())]))
+    }
+
     // --- Folders ---
 
     #[tool(description = "List all folders in the workspace")]
@@ -359,6 +624,36 @@ impl ClaudiaServer {
         Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&folder).unwrap())]))
     }
 
+    #[tool(description = "Get a folder and its subfolders by path")]
+    async fn get_folder(&self, input: Parameters<FolderPathInput>) -> Result<CallToolResult, McpError> {
+        let folder = api::get_folder(&self.storage, &input.0.path)
+            .map_err(|e| McpError::internal_error(e, None))?;
+        Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&folder).unwrap())]))
+    }
+
+    #[tool(description = "Rename a folder or change its color, icon, pinned or favorite state")]
+    async fn update_folder(&self, input: Parameters<UpdateFolderInput>) -> Result<CallToolResult, McpError> {
+        let folder = api::update_folder(
+            &self.storage,
+            &input.0.path,
+            input.0.name.as_deref(),
+            input.0.color.as_deref(),
+            input.0.icon.as_deref(),
+            input.0.pinned,
+            input.0.favorite,
+        ).map_err(|e| McpError::internal_error(e, None))?;
+        let _ = self.app_handle.emit("mcp-folders-changed", ());
+        Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&folder).unwrap())]))
+    }
+
+    #[tool(description = "Move a folder, with everything in it, under another folder (or to the root if newParentPath is omitted). A folder cannot be moved into itself or one of its subfolders")]
+    async fn move_folder(&self, input: Parameters<MoveFolderInput>) -> Result<CallToolResult, McpError> {
+        let folder = api::move_folder(&self.storage, &input.0.path, input.0.new_parent_path.as_deref())
+            .map_err(|e| McpError::internal_error(e, None))?;
+        let _ = self.app_handle.emit("mcp-folders-changed", ());
+        Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&folder).unwrap())]))
+    }
+
     #[tool(description = "Delete a folder and all its contents")]
     async fn delete_folder(&self, input: Parameters<DeleteFolderInput>) -> Result<CallToolResult, McpError> {
         api::delete_folder(&self.storage, &input.0.path)
@@ -367,6 +662,32 @@ impl ClaudiaServer {
         Ok(CallToolResult::success(vec![Content::text(format!("Folder {} deleted successfully", input.0.path))]))
     }
 
+    // --- Templates ---
+
+    #[tool(description = "List the available note or task templates")]
+    async fn list_templates(&self, input: Parameters<TemplateTypeInput>) -> Result<CallToolResult, McpError> {
+        let templates = api::list_templates(&input.0.template_type)
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&templates).unwrap())]))
+    }
+
+    #[tool(description = "Create a note or task from a template, using the template's content as its body")]
+    async fn create_from_template(&self, input: Parameters<CreateFromTemplateInput>) -> Result<CallToolResult, McpError> {
+        let created = api::create_from_template(
+            &self.storage,
+            &input.0.template_type,
+            &input.0.template_id,
+            &input.0.title,
+            input.0.folder_path.as_deref(),
+        ).map_err(|e| McpError::internal_error(e, None))?;
+        let event = match created {
+            api::CreatedFromTemplate::Note(_) => "mcp-notes-changed",
+            api::CreatedFromTemplate::Task(_) => "mcp-tasks-changed",
+        };
+        let _ = self.app_handle.emit(event, ());
+        Ok(CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&created).unwrap())]))
+    }
+
     // --- Floating Windows ---
 
     #[tool(description = "Show a note in a floating window")]
@@ -382,21 +703,22 @@ impl ClaudiaServer {
             color: note.color,
             x: 100.0,
             y: 100.0,
-            width: 400.0,
-            height: 500.0,
-            opacity: 1.0,
-            theme: "system".to_string(),
+            // Size, opacity and theme follow floatingDefaults / floatingOpacity
+            width: None,
+            height: None,
+            opacity: None,
+            theme: None,
         };
 
         crate::commands::floating::createFloatingWindow(self.app_handle.clone(), config)
-            .map_err(|e| McpError::internal_error(e, None))?;
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
         Ok(CallToolResult::success(vec![Content::text(format!("Showing note {}", input.0.id))]))
     }
 
     #[tool(description = "Hide a note's floating window")]
     async fn hide_note(&self, input: Parameters<IdInput>) -> Result<CallToolResult, McpError> {
         crate::commands::floating::hideFloatingWindow(self.app_handle.clone(), input.0.id.clone())
-            .map_err(|e| McpError::internal_error(e, None))?;
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
         Ok(CallToolResult::success(vec![Content::text(format!("Hiding note {}", input.0.id))]))
     }
 
@@ -413,21 +735,22 @@ impl ClaudiaServer {
             color: task.color,
             x: 100.0,
             y: 100.0,
-            width: 400.0,
-            height: 500.0,
-            opacity: 1.0,
-            theme: "system".to_string(),
+            // Size, opacity and theme follow floatingDefaults / floatingOpacity
+            width: None,
+            height: None,
+            opacity: None,
+            theme: None,
         };
 
         crate::commands::floating::createFloatingWindow(self.app_handle.clone(), config)
-            .map_err(|e| McpError::internal_error(e, None))?;
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
         Ok(CallToolResult::success(vec![Content::text(format!("Showing task {}", input.0.id))]))
     }
 
     #[tool(description = "Hide a task's floating window")]
     async fn hide_task(&self, input: Parameters<IdInput>) -> Result<CallToolResult, McpError> {
         crate::commands::floating::hideFloatingWindow(self.app_handle.clone(), input.0.id.clone())
-            .map_err(|e| McpError::internal_error(e, None))?;
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
         Ok(CallToolResult::success(vec![Content::text(format!("Hiding task {}", input.0.id))]))
     }
 }
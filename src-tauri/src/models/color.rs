@@ -0,0 +1,66 @@
+// Color helpers for item and folder colors
+// A color field holds either a hex value (#RRGGBB) or a semantic palette token
+
+use serde::Serialize;
+
+/// Semantic palette tokens with their (light, dark) hex values
+const PALETTE: &[(&str, &str, &str)] = &[
+    ("accent", "#3B82F6", "#60A5FA"),
+    ("success", "#16A34A", "#4ADE80"),
+    ("warning", "#D97706", "#FBBF24"),
+    ("danger", "#DC2626", "#F87171"),
+    ("info", "#0891B2", "#22D3EE"),
+    ("muted", "#6B7280", "#9CA3AF"),
+];
+
+/// Check whether a color value is a palette token
+pub fn isColorToken(color: &str) -> bool {
+    PALETTE.iter().any(|(name, _, _)| *name == color)
+}
+
+/// Resolve a color value to hex for the given theme ("dark" uses the dark palette, anything else light)
+/// Hex values and unknown strings are returned unchanged
+pub fn resolveColor(token: &str, theme: &str) -> String {
+    match PALETTE.iter().find(|(name, _, _)| *name == token) {
+        Some((_, light, dark)) => (if theme == "dark" { dark } else { light }).to_string(),
+        None => token.to_string(),
+    }
+}
+
+/// Validate and canonicalize a user-supplied color
+/// Tokens are lowercased; hex is expanded from #RGB and uppercased to #RRGGBB
+pub fn normalizeColor(color: &str) -> Result<String, String> {
+    let color = color.trim();
+
+    let token = color.to_lowercase();
+    if isColorToken(&token) {
+        return Ok(token);
+    }
+
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid color: {}", color));
+    }
+    match hex.len() {
+        3 => Ok(format!("#{}", hex.chars().flat_map(|c| [c, c]).collect::<String>().to_uppercase())),
+        6 => Ok(format!("#{}", hex.to_uppercase())),
+        _ => Err(format!("Invalid color: {}", color)),
+    }
+}
+
+/// Hex values for a stored color in both themes
+/// Both are returned because the "system" theme is only known to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedColor {
+    pub light: String,
+    pub dark: String,
+}
+
+impl ResolvedColor {
+    pub fn of(color: &str) -> Self {
+        Self {
+            light: resolveColor(color, "light"),
+            dark: resolveColor(color, "dark"),
+        }
+    }
+}
@@ -1,6 +1,9 @@
 // Common types for Claudia filesystem-based storage
 // All fields use camelCase for consistency across Rust, TypeScript, and Markdown
 
+use std::borrow::Cow;
+use std::sync::RwLock;
+
 use serde::{Deserialize, Serialize};
 
 /// Floating window position and visibility
@@ -13,31 +16,274 @@ pub struct FloatWindow {
     pub show: bool,
 }
 
+/// Status columns of the open workspace, in display order (empty = built-in defaults)
+/// Kept here so scanners can resolve status folders without access to storage
+static ACTIVE_TASK_STATUSES: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
 /// Task status - derived from folder name
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
-#[serde(rename_all = "lowercase")]
-pub enum TaskStatus {
-    #[default]
-    Todo,
-    Doing,
-    Done,
+/// The built-in columns are consts; workspaces may define their own (e.g. "review", "blocked")
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TaskStatus(Cow<'static, str>);
+
+impl Default for TaskStatus {
+    fn default() -> Self {
+        Self::Todo
+    }
 }
 
+#[allow(non_upper_case_globals)]
 impl TaskStatus {
+    pub const Todo: Self = Self(Cow::Borrowed("todo"));
+    pub const Done: Self = Self(Cow::Borrowed("done"));
+
+    /// Columns used when a workspace doesn't define its own
+    pub const DEFAULTS: [&'static str; 3] = ["todo", "doing", "done"];
+
+    /// Resolve a status folder name against the active status list
     pub fn fromFolder(name: &str) -> Option<Self> {
-        match name.to_lowercase().as_str() {
-            "todo" => Some(Self::Todo),
-            "doing" => Some(Self::Doing),
-            "done" => Some(Self::Done),
+        let name = name.to_lowercase();
+        Self::all().into_iter().find(|s| s.folderName() == name)
+    }
+
+    pub fn folderName(&self) -> &str {
+        &self.0
+    }
+
+    /// All active statuses in column order
+    pub fn all() -> Vec<Self> {
+        let active = ACTIVE_TASK_STATUSES.read().unwrap_or_else(|e| e.into_inner());
+        if active.is_empty() {
+            Self::DEFAULTS.iter().map(|s| Self(Cow::Borrowed(*s))).collect()
+        } else {
+            active.iter().map(|s| Self(Cow::Owned(s.clone()))).collect()
+        }
+    }
+
+    /// Replace the active status list (None restores the defaults)
+    pub fn setActive(statuses: Option<&[String]>) {
+        let mut active = ACTIVE_TASK_STATUSES.write().unwrap_or_else(|e| e.into_inner());
+        *active = statuses.map(|s| s.to_vec()).unwrap_or_default();
+    }
+
+    /// Normalize a user-supplied status name into a safe folder name
+    pub fn validateName(name: &str) -> Result<String, String> {
+        let name = name.trim().to_lowercase();
+        if name.is_empty() {
+            return Err("Invalid input: status name cannot be empty".to_string());
+        }
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(format!("Invalid input: status '{}' may only contain letters, digits, '-' and '_'", name));
+        }
+        if ["notes", "tasks", "passwords"].contains(&name.as_str()) {
+            return Err(format!("Invalid input: '{}' is a reserved folder name", name));
+        }
+        Ok(name)
+    }
+}
+
+/// Kind of item stored in the folder tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Note,
+    Task,
+    Password,
+}
+
+impl ItemKind {
+    pub fn fromStr(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "note" | "notes" => Some(Self::Note),
+            "task" | "tasks" => Some(Self::Task),
+            "password" | "passwords" => Some(Self::Password),
             _ => None,
         }
     }
 
+    /// Name of the items subdirectory inside a folder (notes/, tasks/, passwords/)
     pub fn folderName(&self) -> &'static str {
         match self {
-            Self::Todo => "todo",
-            Self::Doing => "doing",
-            Self::Done => "done",
+            Self::Note => "notes",
+            Self::Task => "tasks",
+            Self::Password => "passwords",
+        }
+    }
+}
+
+/// How a tag filter combines several tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    /// Item must carry every requested tag
+    #[default]
+    All,
+    /// Item must carry at least one requested tag
+    Any,
+}
+
+impl TagMatch {
+    /// Whether `itemTags` satisfy the filter (case-insensitive); an empty filter matches everything
+    pub fn matches(&self, itemTags: &[String], wanted: &[String]) -> bool {
+        let has = |tag: &String| {
+            let tag = tag.trim().to_lowercase();
+            itemTags.iter().any(|t| t.to_lowercase() == tag)
+        };
+        match self {
+            Self::All => wanted.iter().all(has),
+            Self::Any => wanted.is_empty() || wanted.iter().any(has),
+        }
+    }
+}
+
+/// Field a note or task list is ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortField {
+    #[default]
+    Rank,
+    Created,
+    Updated,
+    Title,
+    Due,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDir {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Requested list order; the default (rank ascending) is the scan order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct SortSpec {
+    #[serde(default)]
+    pub field: SortField,
+    #[serde(default)]
+    pub dir: SortDir,
+}
+
+/// Frontmatter fields a list can be sorted by
+pub trait SortFields {
+    fn id(&self) -> &str;
+    fn rank(&self) -> u32;
+    fn created(&self) -> i64;
+    fn updated(&self) -> i64;
+    fn title(&self) -> &str;
+    fn pinned(&self) -> bool;
+    fn due(&self) -> Option<i64> {
+        None
+    }
+}
+
+impl SortSpec {
+    /// Order two items; ties fall back to rank then id so the order is total and stable across calls
+    /// Items without a due date go last in either direction
+    pub fn compare<F: SortFields>(&self, a: &F, b: &F) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+
+        let primary = match self.field {
+            SortField::Rank => a.rank().cmp(&b.rank()),
+            SortField::Created => a.created().cmp(&b.created()),
+            SortField::Updated => a.updated().cmp(&b.updated()),
+            SortField::Title => a.title().to_lowercase().cmp(&b.title().to_lowercase()),
+            SortField::Due => match (a.due(), b.due()) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        };
+        let primary = if self.dir == SortDir::Desc { primary.reverse() } else { primary };
+        primary.then_with(|| a.rank().cmp(&b.rank())).then_with(|| a.id().cmp(b.id()))
+    }
+
+    /// Sort items in place, optionally keeping pinned items above the rest
+    pub fn sort<T, F: SortFields>(&self, items: &mut [T], pinnedFirst: bool, fields: impl Fn(&T) -> &F) {
+        items.sort_by(|a, b| {
+            let (a, b) = (fields(a), fields(b));
+            let pinned = if pinnedFirst { b.pinned().cmp(&a.pinned()) } else { std::cmp::Ordering::Equal };
+            pinned.then_with(|| self.compare(a, b))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item {
+        id: &'static str,
+        rank: u32,
+        title: &'static str,
+        pinned: bool,
+        due: Option<i64>,
+    }
+
+    impl SortFields for Item {
+        fn id(&self) -> &str {
+            self.id
+        }
+        fn rank(&self) -> u32 {
+            self.rank
+        }
+        fn created(&self) -> i64 {
+            self.rank as i64
+        }
+        fn updated(&self) -> i64 {
+            -(self.rank as i64)
         }
+        fn title(&self) -> &str {
+            self.title
+        }
+        fn pinned(&self) -> bool {
+            self.pinned
+        }
+        fn due(&self) -> Option<i64> {
+            self.due
+        }
+    }
+
+    fn items() -> Vec<Item> {
+        vec![
+            Item { id: "a", rank: 1, title: "banana", pinned: false, due: None },
+            Item { id: "b", rank: 2, title: "Apple", pinned: true, due: Some(20) },
+            Item { id: "c", rank: 3, title: "cherry", pinned: false, due: Some(10) },
+        ]
+    }
+
+    fn ids(items: &[Item]) -> Vec<&str> {
+        items.iter().map(|i| i.id).collect()
+    }
+
+    #[test]
+    fn test_sort_spec_fields_and_directions() {
+        let mut list = items();
+        SortSpec { field: SortField::Title, dir: SortDir::Asc }.sort(&mut list, false, |i| i);
+        assert_eq!(ids(&list), ["b", "a", "c"]);
+
+        SortSpec { field: SortField::Updated, dir: SortDir::Desc }.sort(&mut list, false, |i| i);
+        assert_eq!(ids(&list), ["a", "b", "c"]);
+
+        SortSpec::default().sort(&mut list, false, |i| i);
+        assert_eq!(ids(&list), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_sort_by_due_puts_undated_last() {
+        let mut list = items();
+        SortSpec { field: SortField::Due, dir: SortDir::Asc }.sort(&mut list, false, |i| i);
+        assert_eq!(ids(&list), ["c", "b", "a"]);
+        SortSpec { field: SortField::Due, dir: SortDir::Desc }.sort(&mut list, false, |i| i);
+        assert_eq!(ids(&list), ["b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_pinned_first() {
+        let mut list = items();
+        SortSpec { field: SortField::Created, dir: SortDir::Desc }.sort(&mut list, true, |i| i);
+        assert_eq!(ids(&list), ["b", "c", "a"]);
     }
 }
@@ -1,10 +1,68 @@
 // Configuration models for Claudia
 // Global config and workspace config overrides
 
+use std::net::{IpAddr, SocketAddr};
+
 use serde::{Deserialize, Serialize};
 
+/// Default maximum note/task body size (10 MB)
+pub const DEFAULT_MAX_ITEM_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Default folder layout: every item subdirectory
+pub fn defaultFolderLayout() -> Vec<String> {
+    vec!["notes".to_string(), "tasks".to_string(), "passwords".to_string()]
+}
+
+/// Defaults for new floating windows
+/// opacity and theme fall back to floatingOpacity and theme when unset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FloatingDefaults {
+    pub width: f64,
+    pub height: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+}
+
+impl Default for FloatingDefaults {
+    fn default() -> Self {
+        Self { width: 400.0, height: 500.0, opacity: None, theme: None }
+    }
+}
+
+/// Minimum requirements for a new master password
+/// Checked by setupMasterPassword and changeMasterPasswordVault; minScore uses the
+/// password strength estimator's 0-4 scale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MasterPasswordPolicy {
+    pub minLength: u32,
+    pub requireLowercase: bool,
+    pub requireUppercase: bool,
+    pub requireDigit: bool,
+    pub requireSymbol: bool,
+    pub minScore: u8,
+}
+
+impl Default for MasterPasswordPolicy {
+    fn default() -> Self {
+        Self {
+            minLength: 12,
+            requireLowercase: false,
+            requireUppercase: false,
+            requireDigit: false,
+            requireSymbol: false,
+            minScore: 3,
+        }
+    }
+}
+
 /// All settings (stored in global config.md, can be overridden by workspace)
+/// Missing fields fall back to defaults so older config files keep loading
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
     pub theme: String,
     pub defaultMode: String,
@@ -13,6 +71,50 @@ pub struct Settings {
     pub notificationSound: bool,
     pub notificationMinutesBefore: i32,
     pub floatingOpacity: f64,
+    /// Maximum note/task body size in bytes (0 = unlimited)
+    pub maxItemBytes: u64,
+    /// Lock the vault before exiting from the tray menu
+    pub lockOnQuit: bool,
+    /// Lock the vault when the main window stays hidden or minimized
+    pub lockOnHide: bool,
+    /// Automatic backup schedule: "off", "daily" or "weekly"
+    pub backupSchedule: String,
+    /// Directory for automatic backups (empty = ~/.claudia/backups)
+    pub backupDir: String,
+    /// Number of automatic backup archives to keep per workspace
+    pub backupRetention: u32,
+    /// Record lastAccessed/openCount when note and task content is opened
+    pub trackAccess: bool,
+    /// Item subdirectories pre-created in new folders (others are created on first use)
+    pub folderLayout: Vec<String>,
+    /// Convert :emoji: shortcodes in titles to unicode
+    pub emojiShortcodes: bool,
+    /// strftime format for dates in generated titles (e.g. daily notes)
+    pub dateFormat: String,
+    /// Folder path for daily notes (empty = root notes)
+    pub dailyNotesFolder: String,
+    /// Days a trashed item is kept before it is purged automatically (0 = keep forever)
+    pub trashRetentionDays: u32,
+    /// Require re-entering the master password before sensitive commands (workspace export, item JSON export)
+    pub reauthForSensitive: bool,
+    /// Size, opacity and theme for floating windows opened without explicit values
+    pub floatingDefaults: FloatingDefaults,
+    /// Passwords not updated for this many days are reported as stale by getExpiringPasswords (0 = off)
+    pub passwordMaxAgeDays: u32,
+    /// Previous versions kept per note and task by version history (0 = off)
+    pub historyVersions: u32,
+    /// Watch the vault folder for external changes (starts and stops without a restart)
+    pub watchVault: bool,
+    /// Lock the vault after this many idle minutes (0 = never)
+    pub autoLockMinutes: u32,
+    /// Minutes of inactivity before password access locks again, while the vault stays unlocked (0 = only with the vault)
+    pub passwordsAccessMinutes: u32,
+    /// Port the MCP server listens on (global-only)
+    pub mcpPort: u16,
+    /// Loopback address the MCP server binds to (global-only; the server has no authentication)
+    pub mcpBindAddress: String,
+    /// Requirements a new master password must meet
+    pub masterPasswordPolicy: MasterPasswordPolicy,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub currentWorkspace: Option<String>,
 }
@@ -27,6 +129,28 @@ impl Default for Settings {
             notificationSound: true,
             notificationMinutesBefore: 15,
             floatingOpacity: 0.95,
+            maxItemBytes: DEFAULT_MAX_ITEM_BYTES,
+            lockOnQuit: false,
+            lockOnHide: false,
+            backupSchedule: "off".to_string(),
+            backupDir: String::new(),
+            backupRetention: 7,
+            trackAccess: false,
+            folderLayout: defaultFolderLayout(),
+            emojiShortcodes: false,
+            dateFormat: "%Y-%m-%d".to_string(),
+            dailyNotesFolder: String::new(),
+            trashRetentionDays: 30,
+            reauthForSensitive: false,
+            floatingDefaults: FloatingDefaults::default(),
+            passwordMaxAgeDays: 365,
+            historyVersions: 20,
+            watchVault: false,
+            autoLockMinutes: 0,
+            passwordsAccessMinutes: 10,
+            mcpPort: 44055,
+            mcpBindAddress: "127.0.0.1".to_string(),
+            masterPasswordPolicy: MasterPasswordPolicy::default(),
             currentWorkspace: None,
         }
     }
@@ -57,6 +181,49 @@ pub struct SettingsOverride {
     pub notificationMinutesBefore: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub floatingOpacity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maxItemBytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lockOnQuit: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lockOnHide: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backupSchedule: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backupDir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backupRetention: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trackAccess: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folderLayout: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emojiShortcodes: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dateFormat: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dailyNotesFolder: Option<String>,
+    /// Workspace-only: task status columns (subfolder names), in display order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub taskStatuses: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trashRetentionDays: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reauthForSensitive: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub floatingDefaults: Option<FloatingDefaults>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passwordMaxAgeDays: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub historyVersions: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watchVault: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autoLockMinutes: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passwordsAccessMinutes: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub masterPasswordPolicy: Option<MasterPasswordPolicy>,
 }
 
 impl Settings {
@@ -70,7 +237,68 @@ impl Settings {
             notificationSound: over.notificationSound.unwrap_or(self.notificationSound),
             notificationMinutesBefore: over.notificationMinutesBefore.unwrap_or(self.notificationMinutesBefore),
             floatingOpacity: over.floatingOpacity.unwrap_or(self.floatingOpacity),
+            maxItemBytes: over.maxItemBytes.unwrap_or(self.maxItemBytes),
+            lockOnQuit: over.lockOnQuit.unwrap_or(self.lockOnQuit),
+            lockOnHide: over.lockOnHide.unwrap_or(self.lockOnHide),
+            backupSchedule: over.backupSchedule.clone().unwrap_or_else(|| self.backupSchedule.clone()),
+            backupDir: over.backupDir.clone().unwrap_or_else(|| self.backupDir.clone()),
+            backupRetention: over.backupRetention.unwrap_or(self.backupRetention),
+            trackAccess: over.trackAccess.unwrap_or(self.trackAccess),
+            folderLayout: over.folderLayout.clone().unwrap_or_else(|| self.folderLayout.clone()),
+            emojiShortcodes: over.emojiShortcodes.unwrap_or(self.emojiShortcodes),
+            dateFormat: over.dateFormat.clone().unwrap_or_else(|| self.dateFormat.clone()),
+            dailyNotesFolder: over.dailyNotesFolder.clone().unwrap_or_else(|| self.dailyNotesFolder.clone()),
+            trashRetentionDays: over.trashRetentionDays.unwrap_or(self.trashRetentionDays),
+            reauthForSensitive: over.reauthForSensitive.unwrap_or(self.reauthForSensitive),
+            floatingDefaults: over.floatingDefaults.clone().unwrap_or_else(|| self.floatingDefaults.clone()),
+            passwordMaxAgeDays: over.passwordMaxAgeDays.unwrap_or(self.passwordMaxAgeDays),
+            historyVersions: over.historyVersions.unwrap_or(self.historyVersions),
+            watchVault: over.watchVault.unwrap_or(self.watchVault),
+            autoLockMinutes: over.autoLockMinutes.unwrap_or(self.autoLockMinutes),
+            passwordsAccessMinutes: over.passwordsAccessMinutes.unwrap_or(self.passwordsAccessMinutes),
+            // Workspace configs travel with the vault, so they can't move the MCP server
+            mcpPort: self.mcpPort,
+            mcpBindAddress: self.mcpBindAddress.clone(),
+            masterPasswordPolicy: over.masterPasswordPolicy.clone().unwrap_or_else(|| self.masterPasswordPolicy.clone()),
             currentWorkspace: self.currentWorkspace.clone(),
         }
     }
+
+    /// Address the MCP server listens on, refusing non-loopback binds
+    pub fn mcpSocketAddr(&self) -> Result<SocketAddr, String> {
+        Ok(SocketAddr::new(parseMcpBindAddress(&self.mcpBindAddress)?, self.mcpPort))
+    }
+}
+
+/// Parse an MCP bind address. The MCP server has no authentication and serves passwords,
+/// so anything reachable from another machine is rejected.
+pub fn parseMcpBindAddress(address: &str) -> Result<IpAddr, String> {
+    let ip: IpAddr = address.trim().parse()
+        .map_err(|_| format!("Invalid input: MCP bind address '{}' is not an IP address", address))?;
+    if !ip.is_loopback() {
+        return Err(format!("Invalid input: MCP server can only bind to a loopback address, not {}", ip));
+    }
+    Ok(ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mcp_bind_address_must_be_loopback() {
+        assert_eq!(parseMcpBindAddress("127.0.0.1").unwrap().to_string(), "127.0.0.1");
+        assert_eq!(parseMcpBindAddress(" ::1 ").unwrap().to_string(), "::1");
+        assert!(parseMcpBindAddress("0.0.0.0").is_err());
+        assert!(parseMcpBindAddress("192.168.1.10").is_err());
+        assert!(parseMcpBindAddress("localhost").is_err());
+    }
+
+    #[test]
+    fn test_workspace_override_cannot_move_mcp_server() {
+        // Fields a workspace config might still carry from before they became global-only
+        let over: SettingsOverride = serde_yaml::from_str("mcpPort: 1\nmcpBindAddress: 0.0.0.0\n").unwrap();
+        let effective = Settings::default().withOverride(&over);
+        assert_eq!(effective.mcpSocketAddr().unwrap().to_string(), "127.0.0.1:44055");
+    }
 }
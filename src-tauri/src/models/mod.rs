@@ -1,6 +1,7 @@
 // Models module for Claudia filesystem-based storage
 // All fields use camelCase for consistency
 
+pub mod color;
 pub mod common;
 pub mod config;
 pub mod folder;
@@ -9,11 +10,12 @@ pub mod password;
 pub mod task;
 pub mod template;
 
-pub use common::{FloatWindow, TaskStatus};
-pub use config::{Settings, SettingsOverride, WorkspaceEntry};
+pub use color::{ResolvedColor, normalizeColor};
+pub use common::{FloatWindow, ItemKind, SortSpec, TagMatch, TaskStatus};
+pub use config::{FloatingDefaults, MasterPasswordPolicy, Settings, SettingsOverride, WorkspaceEntry};
 pub use folder::{Folder, FolderFrontmatter};
 pub use note::{Note, NoteFrontmatter};
 pub use password::{Password, PasswordFrontmatter, PasswordContent};
-pub use task::{Task, TaskFrontmatter};
+pub use task::{Recurrence, Task, TaskFrontmatter};
 pub use template::{Template, TemplateFrontmatter, TemplateType};
 
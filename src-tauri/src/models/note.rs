@@ -3,7 +3,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use super::common::FloatWindow;
+use super::common::{FloatWindow, SortFields};
 
 /// Note frontmatter (YAML header in .md file)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,11 +17,17 @@ pub struct NoteFrontmatter {
     #[serde(default)]
     pub pinned: bool,
     #[serde(default)]
+    pub archived: bool,  // Hidden from listings without being trashed
+    #[serde(default)]
     pub tags: Vec<String>,
     pub created: i64,
     pub updated: i64,
     #[serde(default)]
     pub float: FloatWindow,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lastAccessed: Option<i64>,  // Only recorded when trackAccess is enabled
+    #[serde(default)]
+    pub openCount: u32,
 }
 
 impl NoteFrontmatter {
@@ -33,19 +39,43 @@ impl NoteFrontmatter {
             rank,
             color: "#6B9F78".to_string(),
             pinned: false,
+            archived: false,
             tags: Vec::new(),
             created: now,
             updated: now,
             float: FloatWindow::default(),
+            lastAccessed: None,
+            openCount: 0,
         }
     }
 }
 
+impl SortFields for NoteFrontmatter {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn rank(&self) -> u32 {
+        self.rank
+    }
+    fn created(&self) -> i64 {
+        self.created
+    }
+    fn updated(&self) -> i64 {
+        self.updated
+    }
+    fn title(&self) -> &str {
+        &self.title
+    }
+    fn pinned(&self) -> bool {
+        self.pinned
+    }
+}
+
 /// Full note with parsed data and filesystem info
 #[derive(Debug, Clone)]
 pub struct Note {
     pub path: PathBuf,       // Full path to .md file
-    pub folderPath: PathBuf, // Parent folder path
+    pub folderPath: PathBuf, // Items dir ({folder}/notes) - see storage::itemsDir
     pub frontmatter: NoteFrontmatter,
     pub content: String,     // Body content (after frontmatter)
 }
@@ -4,6 +4,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use crate::encrypted_storage::EncryptedFile;
 
 /// Password frontmatter (YAML header in .md file)
 /// Only non-sensitive metadata - all credentials are encrypted in body
@@ -19,6 +20,12 @@ pub struct PasswordFrontmatter {
     pub pinned: bool,
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Strength score (0-4) of the stored password, computed at write time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strength: Option<u8>,
+    /// When the credential should be rotated (ms), if the user set a date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiresAt: Option<i64>,
     pub created: i64,
     pub updated: i64,
 }
@@ -33,6 +40,8 @@ impl PasswordFrontmatter {
             color: "#DA7756".to_string(),
             pinned: false,
             tags: Vec::new(),
+            strength: None,
+            expiresAt: None,
             created: now,
             updated: now,
         }
@@ -50,15 +59,17 @@ pub struct PasswordContent {
     pub password: String,
     #[serde(default)]
     pub notes: String,
+    #[serde(default)]
+    pub totpSecret: String, // Base32 2FA secret, as sensitive as `password`
 }
 
 /// Full password with parsed data and filesystem info
 #[derive(Debug, Clone)]
 pub struct Password {
     pub path: PathBuf,
-    pub folderPath: PathBuf,
+    pub folderPath: PathBuf, // Items dir ({folder}/passwords)
     pub frontmatter: PasswordFrontmatter,
-    pub encryptedContent: String,
+    pub encrypted: EncryptedFile, // Content section is decrypted on demand
 }
 
 #[allow(dead_code)] // Public API methods for model consistency
@@ -3,7 +3,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use super::common::{FloatWindow, TaskStatus};
+use super::common::{FloatWindow, SortFields, TaskStatus};
 
 /// Task frontmatter (YAML header in .md file)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,13 +17,25 @@ pub struct TaskFrontmatter {
     #[serde(default)]
     pub pinned: bool,
     #[serde(default)]
+    pub archived: bool,  // Hidden from listings without being trashed
+    #[serde(default)]
     pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub due: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,  // Completing the task creates the next occurrence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remindAt: Option<i64>,  // Snoozed reminder time, overrides the due-based one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminderDismissedFor: Option<i64>,  // Due date whose reminder was dismissed
     pub created: i64,
     pub updated: i64,
     #[serde(default)]
     pub float: FloatWindow,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lastAccessed: Option<i64>,  // Only recorded when trackAccess is enabled
+    #[serde(default)]
+    pub openCount: u32,
 }
 
 impl TaskFrontmatter {
@@ -35,20 +47,121 @@ impl TaskFrontmatter {
             rank,
             color: "#3B82F6".to_string(),
             pinned: false,
+            archived: false,
             tags: Vec::new(),
             due: None,
+            recurrence: None,
+            remindAt: None,
+            reminderDismissedFor: None,
             created: now,
             updated: now,
             float: FloatWindow::default(),
+            lastAccessed: None,
+            openCount: 0,
         }
     }
+
+    /// When the reminder for this task should fire, `minutesBefore` its due date unless snoozed
+    /// None if the task has no due date or its reminder was dismissed for the current one
+    pub fn reminderTime(&self, minutesBefore: u32) -> Option<i64> {
+        let due = self.due?;
+        if self.reminderDismissedFor == Some(due) {
+            return None;
+        }
+        Some(self.remindAt.unwrap_or(due - minutesBefore as i64 * 60_000))
+    }
+
+    pub fn isReminderDue(&self, now: i64, minutesBefore: u32) -> bool {
+        self.reminderTime(minutesBefore).is_some_and(|at| at <= now)
+    }
+
+    /// Postpone the reminder until `until`
+    pub fn snoozeReminder(&mut self, until: i64) {
+        self.remindAt = Some(until);
+    }
+
+    /// Silence the reminder for the current due date; a new due date re-arms it
+    pub fn dismissReminder(&mut self) {
+        self.reminderDismissedFor = self.due;
+        self.remindAt = None;
+    }
+
+    /// Set the due date, dropping a snooze that was meant for the old one
+    pub fn setDue(&mut self, due: i64) {
+        if self.due != Some(due) {
+            self.remindAt = None;
+        }
+        self.due = Some(due);
+    }
+}
+
+impl SortFields for TaskFrontmatter {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn rank(&self) -> u32 {
+        self.rank
+    }
+    fn created(&self) -> i64 {
+        self.created
+    }
+    fn updated(&self) -> i64 {
+        self.updated
+    }
+    fn title(&self) -> &str {
+        &self.title
+    }
+    fn pinned(&self) -> bool {
+        self.pinned
+    }
+    fn due(&self) -> Option<i64> {
+        self.due
+    }
+}
+
+/// Unit of a task recurrence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecurrenceInterval {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// Repeat a task every `every` intervals (e.g. every 2 weeks)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recurrence {
+    pub interval: RecurrenceInterval,
+    #[serde(default = "Recurrence::defaultEvery")]
+    pub every: u32,
+}
+
+impl Recurrence {
+    fn defaultEvery() -> u32 {
+        1
+    }
+
+    /// Due date (ms) of the occurrence after one due at `due`
+    /// Monthly steps keep the day of month, clamped to the end of shorter months
+    pub fn advance(&self, due: i64) -> i64 {
+        let every = self.every.max(1);
+        let Some(date) = chrono::DateTime::from_timestamp_millis(due) else {
+            return due;
+        };
+        let next = match self.interval {
+            RecurrenceInterval::Daily => date.checked_add_signed(chrono::Duration::days(every as i64)),
+            RecurrenceInterval::Weekly => date.checked_add_signed(chrono::Duration::weeks(every as i64)),
+            RecurrenceInterval::Monthly => date.checked_add_months(chrono::Months::new(every)),
+        };
+        next.map(|d| d.timestamp_millis()).unwrap_or(due)
+    }
 }
 
 /// Full task with parsed data and filesystem info
 #[derive(Debug, Clone)]
 pub struct Task {
     pub path: PathBuf,       // Full path to .md file
-    pub folderPath: PathBuf, // Parent folder (project folder, not status)
+    pub folderPath: PathBuf, // Items dir ({folder}/tasks), not the status subfolder
     pub status: TaskStatus,  // Derived from parent folder name
     pub frontmatter: TaskFrontmatter,
     pub content: String,     // Body content (after frontmatter)
@@ -66,3 +179,61 @@ impl Task {
         &self.frontmatter.title
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(date: &str) -> i64 {
+        chrono::DateTime::parse_from_rfc3339(date).unwrap().timestamp_millis()
+    }
+
+    #[test]
+    fn test_recurrence_advance() {
+        let weekly = Recurrence { interval: RecurrenceInterval::Weekly, every: 1 };
+        assert_eq!(weekly.advance(ms("2024-03-01T09:00:00Z")), ms("2024-03-08T09:00:00Z"));
+
+        let everyThreeDays = Recurrence { interval: RecurrenceInterval::Daily, every: 3 };
+        assert_eq!(everyThreeDays.advance(ms("2024-02-28T09:00:00Z")), ms("2024-03-02T09:00:00Z"));
+
+        let monthly = Recurrence { interval: RecurrenceInterval::Monthly, every: 1 };
+        assert_eq!(monthly.advance(ms("2024-01-31T09:00:00Z")), ms("2024-02-29T09:00:00Z"));
+    }
+
+    #[test]
+    fn test_recurrence_every_defaults_to_one() {
+        let r: Recurrence = serde_yaml::from_str("interval: monthly").unwrap();
+        assert_eq!(r, Recurrence { interval: RecurrenceInterval::Monthly, every: 1 });
+    }
+
+    #[test]
+    fn test_snoozed_reminder_waits_for_snooze_time() {
+        let due = ms("2024-03-10T09:00:00Z");
+        let mut fm = TaskFrontmatter::new("t".to_string(), "Pay rent".to_string(), 1);
+        fm.due = Some(due);
+        assert!(fm.isReminderDue(due - 15 * 60_000, 15));
+
+        fm.snoozeReminder(due + 60 * 60_000);
+        assert!(!fm.isReminderDue(due - 15 * 60_000, 15));
+        assert!(!fm.isReminderDue(due + 59 * 60_000, 15));
+        assert!(fm.isReminderDue(due + 60 * 60_000, 15));
+
+        // Moving the due date drops the snooze
+        fm.setDue(due + 24 * 60 * 60_000);
+        assert_eq!(fm.remindAt, None);
+    }
+
+    #[test]
+    fn test_dismissed_reminder_never_fires_for_same_due() {
+        let due = ms("2024-03-10T09:00:00Z");
+        let mut fm = TaskFrontmatter::new("t".to_string(), "Pay rent".to_string(), 1);
+        fm.due = Some(due);
+        fm.snoozeReminder(due + 60_000);
+        fm.dismissReminder();
+        assert!(!fm.isReminderDue(due + 365 * 24 * 60 * 60_000, 15));
+
+        let nextDue = due + 7 * 24 * 60 * 60_000;
+        fm.setDue(nextDue);
+        assert!(fm.isReminderDue(nextDue, 15));
+    }
+}
@@ -5,6 +5,8 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use super::TaskStatus;
+
 /// Template type - note or task
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -44,9 +46,10 @@ pub struct TemplateFrontmatter {
     pub color: String,        // Accent color
     #[serde(default)]
     pub order: u32,           // Display order (lower = first)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defaultStatus: Option<String>, // Task templates only: status folder new tasks start in
 }
 
-#[allow(dead_code)] // Constructor for programmatic template creation
 impl TemplateFrontmatter {
     pub fn new(id: String, name: String, description: String) -> Self {
         Self {
@@ -57,6 +60,7 @@ impl TemplateFrontmatter {
             icon: "FileText".to_string(),
             color: "#B5AFA6".to_string(),
             order: 100,
+            defaultStatus: None,
         }
     }
 }
@@ -65,9 +69,7 @@ impl TemplateFrontmatter {
 #[derive(Debug, Clone)]
 pub struct Template {
     pub slug: String,              // Folder name (e.g., "meeting-notes")
-    #[allow(dead_code)] // Stored for potential future use (template management)
     pub path: PathBuf,             // Full path to template folder
-    #[allow(dead_code)] // Stored for potential future use (template management)
     pub templatePath: PathBuf,     // Path to template.md
     #[allow(dead_code)] // Stored for potential future use (asset copying)
     pub assetsPath: PathBuf,       // Path to assets folder
@@ -85,4 +87,12 @@ impl Template {
     pub fn name(&self) -> &str {
         &self.frontmatter.name
     }
+    /// Status for tasks created from this template; an unknown status falls back to Todo
+    pub fn defaultTaskStatus(&self) -> Option<TaskStatus> {
+        if self.templateType != TemplateType::Task {
+            return None;
+        }
+        self.frontmatter.defaultStatus.as_deref()
+            .map(|s| TaskStatus::fromFolder(s).unwrap_or(TaskStatus::Todo))
+    }
 }
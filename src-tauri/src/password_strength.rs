@@ -0,0 +1,353 @@
+// Password strength estimation - a zxcvbn-style heuristic without external word lists
+// Entropy comes from length and character classes; characters covered by a guessable
+// pattern (common password, repeat, sequence, keyboard run, year) are discounted first
+
+use serde::Serialize;
+
+use crate::models::MasterPasswordPolicy;
+
+/// Strength estimate for one password
+#[derive(Debug, Clone, Serialize)]
+pub struct StrengthReport {
+    pub score: u8, // 0 (very weak) to 4 (very strong)
+    pub entropyBits: f64,
+    pub warnings: Vec<String>,
+}
+
+/// Most common leaked passwords, most frequent first
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456", "password", "12345678", "qwerty", "123456789", "12345", "111111", "1234567",
+    "iloveyou", "admin", "welcome", "monkey", "dragon", "letmein", "abc123", "football",
+    "baseball", "sunshine", "princess", "master", "shadow", "superman", "trustno1", "hello",
+    "freedom", "whatever", "qazwsx", "michael", "secret", "login", "starwars", "changeme",
+];
+
+const KEYBOARD_ROWS: &[&str] = &["qwertyuiop", "asdfghjkl", "zxcvbnm", "1234567890"];
+
+/// Minimum entropy in bits for scores 1 to 4
+const SCORE_THRESHOLDS: [f64; 4] = [28.0, 36.0, 60.0, 80.0];
+
+/// Below this many characters a password is flagged as short
+const MIN_LENGTH: usize = 8;
+
+/// Estimate how hard a password is to guess
+pub fn estimate(password: &str) -> StrengthReport {
+    let chars: Vec<char> = password.chars().collect();
+    if chars.is_empty() {
+        return StrengthReport { score: 0, entropyBits: 0.0, warnings: vec!["Password is empty".to_string()] };
+    }
+
+    let lower: Vec<char> = chars.iter().flat_map(|c| c.to_lowercase()).collect();
+    let lower = if lower.len() == chars.len() { lower } else { chars.clone() };
+    let unleeted: Vec<char> = lower.iter().map(|&c| unleet(c)).collect();
+
+    let mut guessable = vec![false; chars.len()];
+    let mut patternBits = 0.0;
+    let mut warnings = Vec::new();
+
+    // Common passwords, inside longer ones too and with leet substitutions (p@ssw0rd)
+    let common = [findCommonWord(&lower), findCommonWord(&unleeted)]
+        .into_iter()
+        .flatten()
+        .max_by_key(|&(_, len, _)| len);
+    if let Some((start, len, rank)) = common {
+        mark(&mut guessable, start, len);
+        patternBits += ((rank + 1) as f64).log2() + 1.0;
+        warnings.push(if len == chars.len() {
+            "This is a very common password".to_string()
+        } else {
+            "Contains a common password".to_string()
+        });
+    }
+
+    let repeats = findRepeats(&lower);
+    for &(start, len) in &repeats {
+        mark(&mut guessable, start + 1, len - 1);
+        patternBits += (len as f64).log2();
+    }
+    if !repeats.is_empty() {
+        warnings.push("Repeated characters like \"aaa\" are easy to guess".to_string());
+    }
+
+    let sequences: Vec<_> = findSequences(&lower).into_iter().chain(findKeyboardRuns(&lower)).collect();
+    for &(start, len) in &sequences {
+        mark(&mut guessable, start + 1, len - 1);
+        patternBits += (len as f64).log2() + 1.0;
+    }
+    if !sequences.is_empty() {
+        warnings.push("Sequences like \"abc\", \"123\" or \"qwerty\" are easy to guess".to_string());
+    }
+
+    let years = findYears(&chars);
+    for &start in &years {
+        mark(&mut guessable, start, 4);
+        patternBits += 200f64.log2();
+    }
+    if !years.is_empty() {
+        warnings.push("Years and dates are easy to guess".to_string());
+    }
+
+    let bitsPerChar = (charsetSize(&chars) as f64).log2();
+    let freeChars = guessable.iter().filter(|g| !**g).count();
+    let entropyBits = freeChars as f64 * bitsPerChar + patternBits;
+
+    if chars.len() < MIN_LENGTH {
+        warnings.push(format!("Short passwords are easy to guess; use at least {} characters", MIN_LENGTH));
+    }
+
+    let score = SCORE_THRESHOLDS.iter().filter(|t| entropyBits >= **t).count() as u8;
+    StrengthReport { score, entropyBits: (entropyBits * 10.0).round() / 10.0, warnings }
+}
+
+/// Outcome of checking a password against a MasterPasswordPolicy
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyResult {
+    pub ok: bool,
+    /// Unmet requirements, one sentence each
+    pub failures: Vec<String>,
+    pub strength: StrengthReport,
+}
+
+/// Check a candidate master password against a policy
+pub fn checkPolicy(password: &str, policy: &MasterPasswordPolicy) -> PolicyResult {
+    let strength = estimate(password);
+    let mut failures = Vec::new();
+
+    if password.chars().count() < policy.minLength as usize {
+        failures.push(format!("Use at least {} characters", policy.minLength));
+    }
+    let classes = [
+        (policy.requireLowercase, "a lowercase letter", password.chars().any(|c| c.is_lowercase())),
+        (policy.requireUppercase, "an uppercase letter", password.chars().any(|c| c.is_uppercase())),
+        (policy.requireDigit, "a digit", password.chars().any(|c| c.is_ascii_digit())),
+        (policy.requireSymbol, "a symbol", password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace())),
+    ];
+    for (required, name, present) in classes {
+        if required && !present {
+            failures.push(format!("Include {}", name));
+        }
+    }
+    if strength.score < policy.minScore {
+        failures.push("Password is too easy to guess".to_string());
+    }
+
+    PolicyResult { ok: failures.is_empty(), failures, strength }
+}
+
+/// Size of the alphabet an attacker must cover, from the character classes present
+fn charsetSize(chars: &[char]) -> u32 {
+    let mut size = 0;
+    if chars.iter().any(|c| c.is_ascii_lowercase()) {
+        size += 26;
+    }
+    if chars.iter().any(|c| c.is_ascii_uppercase()) {
+        size += 26;
+    }
+    if chars.iter().any(|c| c.is_ascii_digit()) {
+        size += 10;
+    }
+    if chars.iter().any(|c| c.is_ascii_punctuation() || *c == ' ') {
+        size += 33;
+    }
+    if chars.iter().any(|c| !c.is_ascii()) {
+        size += 100;
+    }
+    size.max(1)
+}
+
+fn unleet(c: char) -> char {
+    match c {
+        '0' => 'o',
+        '1' | '!' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' => 't',
+        _ => c,
+    }
+}
+
+fn mark(guessable: &mut [bool], start: usize, len: usize) {
+    for g in guessable.iter_mut().skip(start).take(len) {
+        *g = true;
+    }
+}
+
+/// Longest common password found in `text` as (start, len, rank)
+fn findCommonWord(text: &[char]) -> Option<(usize, usize, usize)> {
+    let mut best: Option<(usize, usize, usize)> = None;
+    for (rank, word) in COMMON_PASSWORDS.iter().enumerate() {
+        let word: Vec<char> = word.chars().collect();
+        if word.len() > text.len() || best.is_some_and(|(_, len, _)| len >= word.len()) {
+            continue;
+        }
+        if let Some(start) = text.windows(word.len()).position(|w| w == word.as_slice()) {
+            best = Some((start, word.len(), rank));
+        }
+    }
+    best
+}
+
+/// Runs of 3+ identical characters as (start, len)
+fn findRepeats(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for i in 1..=chars.len() {
+        if i == chars.len() || chars[i] != chars[start] {
+            if i - start >= 3 {
+                runs.push((start, i - start));
+            }
+            start = i;
+        }
+    }
+    runs
+}
+
+/// Runs of 3+ alphanumerics stepping by +1 or -1 (abc, 987) as (start, len)
+fn findSequences(chars: &[char]) -> Vec<(usize, usize)> {
+    let step = |i: usize| -> Option<i32> {
+        let (a, b) = (chars[i], chars[i + 1]);
+        if !a.is_ascii_alphanumeric() || !b.is_ascii_alphanumeric() {
+            return None;
+        }
+        let delta = b as i32 - a as i32;
+        (delta == 1 || delta == -1).then_some(delta)
+    };
+
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i + 1 < chars.len() {
+        let Some(delta) = step(i) else {
+            i += 1;
+            continue;
+        };
+        let mut end = i + 1;
+        while end + 1 < chars.len() && step(end) == Some(delta) {
+            end += 1;
+        }
+        if end - i + 1 >= 3 {
+            runs.push((i, end - i + 1));
+        }
+        i = end;
+    }
+    runs
+}
+
+/// Runs of 4+ adjacent keys on one keyboard row, either direction, as (start, len)
+fn findKeyboardRuns(chars: &[char]) -> Vec<(usize, usize)> {
+    let rows: Vec<String> = KEYBOARD_ROWS.iter()
+        .flat_map(|r| [r.to_string(), r.chars().rev().collect()])
+        .collect();
+    let onRow = |s: &[char]| {
+        let s: String = s.iter().collect();
+        rows.iter().any(|r| r.contains(&s))
+    };
+
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let mut end = i + 1;
+        while end < chars.len() && onRow(&chars[i..=end]) {
+            end += 1;
+        }
+        if end - i >= 4 {
+            runs.push((i, end - i));
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+    runs
+}
+
+/// Start positions of 4-digit years 1900-2099 not embedded in longer numbers
+fn findYears(chars: &[char]) -> Vec<usize> {
+    let isDigit = |i: usize| chars.get(i).is_some_and(|c| c.is_ascii_digit());
+    (0..chars.len().saturating_sub(3))
+        .filter(|&i| (i..i + 4).all(isDigit) && (i == 0 || !isDigit(i - 1)) && !isDigit(i + 4))
+        .filter(|&i| matches!((chars[i], chars[i + 1]), ('1', '9') | ('2', '0')))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_password() {
+        let report = estimate("");
+        assert_eq!(report.score, 0);
+        assert_eq!(report.entropyBits, 0.0);
+        assert!(!report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_common_passwords_are_weak() {
+        for pw in ["password", "123456", "qwerty", "letmein", "P@ssw0rd", "iloveyou"] {
+            let report = estimate(pw);
+            assert_eq!(report.score, 0, "{} scored {}", pw, report.score);
+            assert!(report.warnings.iter().any(|w| w.contains("common")), "{}: {:?}", pw, report.warnings);
+        }
+    }
+
+    #[test]
+    fn test_patterns_are_weak() {
+        assert!(estimate("aaaaaaaaaa").score <= 1);
+        assert!(estimate("abcdefghij").score <= 1);
+        assert!(estimate("qwertyuiop").score <= 1);
+        assert!(estimate("9876543210").score <= 1);
+        assert!(estimate("summer1990").warnings.iter().any(|w| w.contains("Years")));
+    }
+
+    #[test]
+    fn test_strong_passwords() {
+        for pw in ["xK9#mQ2$vL7!pR4w", "correct horse battery staple", "Gx7&pLq2!zR9wM"] {
+            let report = estimate(pw);
+            assert_eq!(report.score, 4, "{} scored {} ({} bits)", pw, report.score, report.entropyBits);
+        }
+    }
+
+    #[test]
+    fn test_longer_is_stronger() {
+        assert!(estimate("Tr0ub4dor&3").score >= 2);
+        assert!(estimate("k7#Rq").score < estimate("k7#Rq2!mZp9x").score);
+        assert!(estimate("k7#Rq").warnings.iter().any(|w| w.contains("Short")));
+    }
+
+    #[test]
+    fn test_common_word_inside_longer_password() {
+        let report = estimate("mypassword");
+        assert!(report.warnings.iter().any(|w| w == "Contains a common password"));
+        assert!(report.entropyBits < estimate("mzqxjwvkbt").entropyBits);
+    }
+
+    #[test]
+    fn test_policy_rejects_empty_password() {
+        let result = checkPolicy("", &MasterPasswordPolicy::default());
+        assert!(!result.ok);
+        assert!(result.failures.iter().any(|f| f.contains("at least 12")));
+        assert!(result.failures.iter().any(|f| f.contains("easy to guess")));
+    }
+
+    #[test]
+    fn test_policy_accepts_strong_password() {
+        let policy = MasterPasswordPolicy {
+            requireLowercase: true,
+            requireUppercase: true,
+            requireDigit: true,
+            requireSymbol: true,
+            ..MasterPasswordPolicy::default()
+        };
+        let result = checkPolicy("xK9#mQ2$vL7!pR4w", &policy);
+        assert!(result.ok, "{:?}", result.failures);
+
+        let result = checkPolicy("correct horse battery staple", &policy);
+        assert_eq!(result.failures, vec!["Include an uppercase letter", "Include a digit", "Include a symbol"]);
+    }
+
+    #[test]
+    fn test_find_years() {
+        let chars: Vec<char> = "born1987in20245".chars().collect();
+        assert_eq!(findYears(&chars), vec![4]);
+    }
+}
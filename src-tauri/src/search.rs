@@ -0,0 +1,172 @@
+// Search helpers shared by the search commands
+// Matching is case-insensitive and accent-insensitive ("cafe" finds "Café")
+
+use serde::Serialize;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+/// Byte range of a match within the original (un-normalized) text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MatchRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Normalize text for matching: NFKD, strip combining marks (diacritics), lowercase
+pub fn normalize(s: &str) -> String {
+    s.nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Find all non-overlapping matches of `query` in `haystack`
+/// Ranges are byte offsets into the original haystack, always on char boundaries
+pub fn findMatches(haystack: &str, query: &str) -> Vec<MatchRange> {
+    let needle = normalize(query);
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    // Normalize char by char, remembering which original char each normalized byte came from
+    let mut normalized = String::with_capacity(haystack.len());
+    let mut origins: Vec<(usize, usize)> = Vec::with_capacity(haystack.len());
+    for (start, c) in haystack.char_indices() {
+        let end = start + c.len_utf8();
+        let before = normalized.len();
+        normalized.push_str(&normalize(c.encode_utf8(&mut [0; 4])));
+        if normalized.len() == before {
+            // Stripped mark (e.g. a combining accent): attach it to the preceding char
+            for origin in origins.iter_mut().rev().take_while(|o| o.1 == start) {
+                origin.1 = end;
+            }
+        }
+        origins.extend(std::iter::repeat_n((start, end), normalized.len() - before));
+    }
+
+    normalized
+        .match_indices(&needle)
+        .map(|(pos, m)| MatchRange {
+            start: origins[pos].0,
+            end: origins[pos + m.len() - 1].1,
+        })
+        .collect()
+}
+
+/// Characters of context kept on each side of a match in a snippet
+pub const SNIPPET_CONTEXT_CHARS: usize = 40;
+
+/// Excerpt of a body around one match; `matchStart`/`matchEnd` are byte offsets into `text`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Snippet {
+    pub text: String,
+    pub matchStart: usize,
+    pub matchEnd: usize,
+}
+
+/// Cut a snippet around `range` with up to `context` chars on each side
+/// Newlines are flattened to spaces so the snippet renders on one line
+pub fn snippet(haystack: &str, range: MatchRange, context: usize) -> Snippet {
+    let start = match context {
+        0 => range.start,
+        n => haystack[..range.start].char_indices().rev().nth(n - 1).map(|(i, _)| i).unwrap_or(0),
+    };
+    let end = haystack[range.end..].char_indices().nth(context)
+        .map(|(i, _)| range.end + i)
+        .unwrap_or(haystack.len());
+
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < haystack.len() { "…" } else { "" };
+    let text = format!("{}{}{}", prefix, &haystack[start..end], suffix).replace(['\n', '\r'], " ");
+    let matchStart = prefix.len() + range.start - start;
+    Snippet { text, matchStart, matchEnd: matchStart + range.end - range.start }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(haystack: &str, query: &str) -> bool {
+        normalize(haystack).contains(&normalize(query))
+    }
+
+    #[test]
+    fn test_normalize_strips_accents() {
+        assert_eq!(normalize("Café"), "cafe");
+        assert_eq!(normalize("Ångström"), "angstrom");
+        assert_eq!(normalize("naïve résumé"), "naive resume");
+    }
+
+    #[test]
+    fn test_normalize_compatibility_forms() {
+        // Ligatures and full-width letters fold to plain ASCII under NFKD
+        assert_eq!(normalize("ﬁle"), "file");
+        assert_eq!(normalize("ＡＢＣ"), "abc");
+    }
+
+    #[test]
+    fn test_matches_accent_insensitive() {
+        assert!(matches("Meeting at the Café", "cafe"));
+        assert!(matches("Meeting at the cafe", "CAFÉ"));
+        assert!(matches("Crème brûlée recipe", "creme brulee"));
+        assert!(!matches("Coffee shop", "cafe"));
+    }
+
+    #[test]
+    fn test_find_matches_ascii() {
+        let ranges = findMatches("Todo: buy milk, buy eggs", "BUY");
+        assert_eq!(ranges, vec![MatchRange { start: 6, end: 9 }, MatchRange { start: 16, end: 19 }]);
+    }
+
+    #[test]
+    fn test_find_matches_maps_back_to_original_bytes() {
+        let text = "Un café crème";
+        let ranges = findMatches(text, "cafe");
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(&text[ranges[0].start..ranges[0].end], "café");
+
+        let ranges = findMatches(text, "CREME");
+        assert_eq!(&text[ranges[0].start..ranges[0].end], "crème");
+    }
+
+    #[test]
+    fn test_find_matches_decomposed_haystack() {
+        // Combining accent belongs to the preceding letter's range
+        let text = "cafe\u{0301} noir";
+        let ranges = findMatches(text, "café");
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(&text[ranges[0].start..ranges[0].end], "cafe\u{0301}");
+        assert!(findMatches(text, "").is_empty());
+        assert!(findMatches(text, "the").is_empty());
+    }
+
+    #[test]
+    fn test_matches_precomposed_and_decomposed() {
+        // "é" as one code point vs "e" + combining acute accent
+        assert!(matches("caf\u{00e9}", "cafe\u{0301}"));
+        assert!(matches("cafe\u{0301}", "café"));
+    }
+
+    #[test]
+    fn test_snippet_context() {
+        let text = "alpha beta gamma delta";
+        let range = findMatches(text, "gamma")[0];
+        let s = snippet(text, range, 5);
+        assert_eq!(s.text, "…beta gamma delt…");
+        assert_eq!(&s.text[s.matchStart..s.matchEnd], "gamma");
+
+        // Whole text fits: no ellipses
+        let s = snippet(text, range, 100);
+        assert_eq!(s.text, text);
+        assert_eq!(&s.text[s.matchStart..s.matchEnd], "gamma");
+    }
+
+    #[test]
+    fn test_snippet_multibyte_and_newlines() {
+        let text = "première ligne\nun café crème";
+        let range = findMatches(text, "cafe")[0];
+        let s = snippet(text, range, 4);
+        assert_eq!(s.text, "… un café crè…");
+        assert_eq!(&s.text[s.matchStart..s.matchEnd], "café");
+    }
+}
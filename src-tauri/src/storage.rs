@@ -2,18 +2,20 @@
 // Replaces JSON-based storage with Markdown files + YAML frontmatter
 
 use parking_lot::RwLock;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use zeroize::Zeroizing;
 
 use crate::models::{
     Settings, SettingsOverride, WorkspaceEntry,
-    Folder,
-    Note,
-    Task,
+    ItemKind, TaskStatus,
 };
+use crate::links::LinkCache;
+use crate::error::ClaudiaError;
+use crate::undo::{UndoAction, UndoEntry, UndoStack};
 
 // ============================================
 // PATH HELPERS
@@ -68,8 +70,59 @@ pub fn validatePathWithinWorkspace(workspacePath: &str, userPath: &str) -> Resul
     Ok(canonicalPath)
 }
 
+/// Validate an absolute folder path used as a move target
+/// It must be the folders root or an existing folder (with .folder.md) inside it
+pub fn validateMoveTarget<'a>(workspacePath: &str, folderPath: &'a str) -> Result<&'a Path, ClaudiaError> {
+    let target = Path::new(folderPath);
+    if hasParentComponent(target) {
+        return Err(ClaudiaError::InvalidInput("Path traversal detected: folder path is outside workspace".to_string()));
+    }
+    let relative = target.strip_prefix(foldersDir(workspacePath))
+        .map_err(|_| ClaudiaError::InvalidInput("Invalid input: target folder is outside the workspace".to_string()))?;
+    if !relative.as_os_str().is_empty() && !target.join(".folder.md").is_file() {
+        return Err(ClaudiaError::InvalidInput(format!("Invalid input: {} is not a folder", folderPath)));
+    }
+    validateFolderPath(workspacePath, &relative.to_string_lossy()).map_err(ClaudiaError::InvalidInput)?;
+    Ok(target)
+}
+
+/// Validate an absolute path to a folder's own directory (not the folders root)
+/// Unlike validateMoveTarget, .folder.md may be missing so orphaned or already deleted
+/// folders pass; symlinks are resolved when the directory exists.
+pub fn validateFolderDir(workspacePath: &str, folderPath: &str) -> Result<PathBuf, ClaudiaError> {
+    let target = Path::new(folderPath);
+    if hasParentComponent(target) {
+        return Err(ClaudiaError::InvalidInput("Path traversal detected: folder path is outside workspace".to_string()));
+    }
+    let base = foldersDir(workspacePath);
+    let relative = target.strip_prefix(&base)
+        .map_err(|_| ClaudiaError::InvalidInput("Invalid input: folder is outside the workspace".to_string()))?;
+    let isUuidDir = target.file_name().and_then(|n| n.to_str()).is_some_and(isValidUuidDir);
+    if relative.as_os_str().is_empty() || !isUuidDir {
+        return Err(ClaudiaError::InvalidInput(format!("Invalid input: {} is not a folder", folderPath)));
+    }
+    if target.exists() {
+        let canonical = target.canonicalize().map_err(|e| ClaudiaError::InvalidInput(format!("Invalid folder path: {}", e)))?;
+        let baseCanonical = base.canonicalize().map_err(|e| ClaudiaError::Io(format!("Invalid folders directory: {}", e)))?;
+        if !canonical.starts_with(&baseCanonical) {
+            return Err(ClaudiaError::InvalidInput("Path traversal detected: folder path is outside workspace".to_string()));
+        }
+    }
+    Ok(target.to_path_buf())
+}
+
+/// Whether a path contains a ".." component
+fn hasParentComponent(path: &Path) -> bool {
+    path.components().any(|c| c == std::path::Component::ParentDir)
+}
+
 /// Validate a folder path within the workspace's folders directory
 pub fn validateFolderPath(workspacePath: &str, folderPath: &str) -> Result<PathBuf, String> {
+    // Reject ".." before anything is created on disk
+    if hasParentComponent(Path::new(folderPath)) {
+        return Err("Path traversal detected: folder path is outside workspace".to_string());
+    }
+
     let foldersBase = foldersDir(workspacePath);
 
     // Ensure folders directory exists
@@ -100,37 +153,74 @@ pub fn validateFolderPath(workspacePath: &str, folderPath: &str) -> Result<PathB
     Ok(canonicalPath)
 }
 
+// ============================================
+// FOLDER PATH CONVENTION
+// ============================================
+// A `folderPath` accepted by commands and reported in NoteInfo/TaskInfo/PasswordInfo
+// is always the folder directory itself: a UUID dir, or folders/ for the root.
+// Items live one level below it, in the items dir for their kind:
+//   {folderPath}/notes/{uuid}.md, {folderPath}/tasks/{status}/{uuid}.md, {folderPath}/passwords/{uuid}.md
+// Always go through itemsDir/folderOfItemsDir instead of joining "notes"/"tasks" by hand.
+
+/// Directory name for item version history inside an items dir
+pub const HISTORY_DIR: &str = ".history";
+
+/// Items directory of a kind inside a folder (tasks still have status subfolders below it)
+pub fn itemsDir(folderPath: &Path, kind: ItemKind) -> PathBuf {
+    folderPath.join(kind.folderName())
+}
+
+/// Inverse of itemsDir: the folder that owns an items directory
+pub fn folderOfItemsDir(itemsDirPath: &Path) -> PathBuf {
+    itemsDirPath.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// Version history of one item: {itemsDir}/.history/{uuid}/{timestamp}.md
+pub fn historyDir(itemsDirPath: &Path, id: &str) -> PathBuf {
+    itemsDirPath.join(HISTORY_DIR).join(id)
+}
+
+/// Whether a path lies inside a version history directory
+pub fn isHistoryPath(path: &Path) -> bool {
+    path.components().any(|c| c.as_os_str() == HISTORY_DIR)
+}
+
+/// Interpret a command's optional folderPath argument
+/// None, empty, "null" (sent by some frontend paths) and relative paths all mean "no folder"
+pub fn folderArg(folderPath: Option<&str>) -> Option<&Path> {
+    folderPath
+        .filter(|p| !p.is_empty() && *p != "null")
+        .map(Path::new)
+        .filter(|p| p.is_absolute())
+}
+
+/// Folder a write command targets: the given folder after validateMoveTarget, or the root folder
+/// Unlike folderArg, a relative path is an error rather than "no folder".
+pub fn resolveFolderArg(workspacePath: &str, folderPath: Option<&str>) -> Result<PathBuf, ClaudiaError> {
+    let Some(folder) = folderPath.filter(|p| !p.is_empty() && *p != "null") else {
+        return Ok(foldersDir(workspacePath));
+    };
+    if !Path::new(folder).is_absolute() {
+        return Err(ClaudiaError::InvalidInput(format!("Invalid input: folder path must be absolute: {}", folder)));
+    }
+    validateMoveTarget(workspacePath, folder).map(Path::to_path_buf)
+}
+
+/// Items dir where create commands put a new item: the given folder, or the root folder
+pub fn itemsDirFor(workspacePath: &str, folderPath: Option<&str>, kind: ItemKind) -> Result<PathBuf, ClaudiaError> {
+    resolveFolderArg(workspacePath, folderPath).map(|folder| itemsDir(&folder, kind))
+}
+
 /// Notes directory inside a specific folder
 /// folderPath is relative path within folders/ (empty string for root)
 pub fn notesDir(workspacePath: &str, folderPath: &str) -> PathBuf {
-    let base = foldersDir(workspacePath);
-    if folderPath.is_empty() {
-        base.join("notes")
-    } else {
-        base.join(folderPath).join("notes")
-    }
+    itemsDir(&foldersDir(workspacePath).join(folderPath), ItemKind::Note)
 }
 
 /// Tasks directory inside a specific folder
 /// folderPath is relative path within folders/ (empty string for root)
 pub fn tasksDir(workspacePath: &str, folderPath: &str) -> PathBuf {
-    let base = foldersDir(workspacePath);
-    if folderPath.is_empty() {
-        base.join("tasks")
-    } else {
-        base.join(folderPath).join("tasks")
-    }
-}
-
-/// Passwords directory inside a specific folder
-/// folderPath is relative path within folders/ (empty string for root)
-pub fn passwordsDir(workspacePath: &str, folderPath: &str) -> PathBuf {
-    let base = foldersDir(workspacePath);
-    if folderPath.is_empty() {
-        base.join("passwords")
-    } else {
-        base.join(folderPath).join("passwords")
-    }
+    itemsDir(&foldersDir(workspacePath).join(folderPath), ItemKind::Task)
 }
 
 /// Workspace config override file
@@ -149,17 +239,213 @@ pub fn trashDir(workspacePath: &str) -> PathBuf {
 
 /// Trash notes directory
 pub fn trashNotesDir(workspacePath: &str) -> PathBuf {
-    trashDir(workspacePath).join("notes")
+    itemsDir(&trashDir(workspacePath), ItemKind::Note)
 }
 
 /// Trash tasks directory (contains todo/doing/done subfolders)
 pub fn trashTasksDir(workspacePath: &str) -> PathBuf {
-    trashDir(workspacePath).join("tasks")
+    itemsDir(&trashDir(workspacePath), ItemKind::Task)
 }
 
 /// Trash passwords directory
 pub fn trashPasswordsDir(workspacePath: &str) -> PathBuf {
-    trashDir(workspacePath).join("passwords")
+    itemsDir(&trashDir(workspacePath), ItemKind::Password)
+}
+
+// Trashed files keep their UUID filename but not their location or deletion time, so both
+// are kept in a sidecar: {id: {folder: path relative to folders/ ("" for the root), trashedAt}}
+
+/// Where and when an item was moved to trash
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TrashOrigin {
+    pub folder: String,
+    pub trashedAt: i64,
+}
+
+/// Sidecar recording the folder each trashed item was deleted from
+pub fn trashOriginsPath(workspacePath: &str) -> PathBuf {
+    trashDir(workspacePath).join(".origins.json")
+}
+
+/// Recorded origins of trashed items (empty when the sidecar is missing or unreadable)
+pub fn readTrashOrigins(workspacePath: &str) -> HashMap<String, TrashOrigin> {
+    fs::read_to_string(trashOriginsPath(workspacePath))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn writeTrashOrigins(workspacePath: &str, origins: &HashMap<String, TrashOrigin>) -> Result<(), String> {
+    let path = trashOriginsPath(workspacePath);
+    if origins.is_empty() {
+        let _ = fs::remove_file(&path);
+        return Ok(());
+    }
+    fs::create_dir_all(trashDir(workspacePath)).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(origins).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Remember the folder owning `itemsDirPath` and the current time for a trashed item
+/// Best effort: without a record the item is restored to the root folder and ages from the
+/// first retention scan that sees it
+pub fn recordTrashOrigin(workspacePath: &str, id: &str, itemsDirPath: &Path) {
+    let folder = folderOfItemsDir(itemsDirPath);
+    let Ok(relative) = folder.strip_prefix(foldersDir(workspacePath)) else {
+        return;
+    };
+    let mut origins = readTrashOrigins(workspacePath);
+    origins.insert(id.to_string(), TrashOrigin {
+        folder: relative.to_string_lossy().to_string(),
+        trashedAt: chrono::Utc::now().timestamp_millis(),
+    });
+    if let Err(e) = writeTrashOrigins(workspacePath, &origins) {
+        println!("[recordTrashOrigin] Failed to record origin of {}: {}", id, e);
+    }
+}
+
+/// Record `trashedAt` for trashed items that have no origin record (trashed before origins
+/// were recorded, or when recording failed), so their age starts now rather than at their
+/// last edit; they keep restoring to the root folder
+pub fn stampTrashOrigins(workspacePath: &str, ids: &[String], trashedAt: i64) {
+    let mut origins = readTrashOrigins(workspacePath);
+    let before = origins.len();
+    for id in ids {
+        origins.entry(id.clone()).or_insert_with(|| TrashOrigin { folder: String::new(), trashedAt });
+    }
+    if origins.len() != before
+        && let Err(e) = writeTrashOrigins(workspacePath, &origins)
+    {
+        println!("[stampTrashOrigins] Failed to record deletion times: {}", e);
+    }
+}
+
+/// Drop the origin records of items that left the trash
+pub fn forgetTrashOrigins(workspacePath: &str, ids: &[String]) {
+    let mut origins = readTrashOrigins(workspacePath);
+    let before = origins.len();
+    origins.retain(|id, _| !ids.contains(id));
+    if origins.len() != before {
+        let _ = writeTrashOrigins(workspacePath, &origins);
+    }
+}
+
+/// Drop the origin record of an item that left the trash
+pub fn forgetTrashOrigin(workspacePath: &str, id: &str) {
+    forgetTrashOrigins(workspacePath, &[id.to_string()]);
+}
+
+/// Items dir a trashed item goes back to: its original folder when that still exists,
+/// otherwise the root folder (tasks still need their status subfolder joined)
+pub fn trashRestoreDir(workspacePath: &str, origins: &HashMap<String, TrashOrigin>, id: &str, kind: ItemKind) -> PathBuf {
+    let root = foldersDir(workspacePath);
+    let folder = origins.get(id)
+        .map(|origin| Path::new(&origin.folder))
+        .filter(|rel| rel.components().all(|c| matches!(c, std::path::Component::Normal(_))))
+        .map(|rel| root.join(rel))
+        .filter(|folder| folder.is_dir())
+        .unwrap_or(root);
+    itemsDir(&folder, kind)
+}
+
+// ============================================
+// ASSET DIRECTORY HELPERS
+// ============================================
+
+/// Encrypted note assets (hidden folder in workspace root, keyed by note id
+/// so assets survive moving the note between folders or to the trash)
+pub fn assetsDir(workspacePath: &str) -> PathBuf {
+    PathBuf::from(workspacePath).join(".assets")
+}
+
+/// Assets belonging to a single note
+pub fn noteAssetsDir(workspacePath: &str, noteId: &str) -> PathBuf {
+    assetsDir(workspacePath).join(noteId)
+}
+
+// ============================================
+// ATOMIC WRITES
+// ============================================
+// Item files are written to a `.tmp` sibling, flushed, then renamed over the target, so a
+// crash mid-write leaves the previous version instead of a truncated encrypted file.
+// On Unix the rename replaces the target atomically. On Windows std uses MoveFileExW with
+// MOVEFILE_REPLACE_EXISTING, which is atomic on NTFS in practice but not guaranteed; the
+// rename is retried briefly because virus scanners and indexers hold files open. Scanners
+// only read .md/.asset files, so a leftover `.tmp` is never mistaken for an item.
+
+/// Suffix of the temp file writeFileAtomic writes before renaming
+pub const ATOMIC_TEMP_SUFFIX: &str = "tmp";
+
+/// How long after a writeFileAtomic the vault watcher treats events for that file as the app's own
+const OWN_WRITE_WINDOW: Duration = Duration::from_secs(2);
+
+/// Files recently saved by writeFileAtomic, so the watcher doesn't rebuild the index for them
+static RECENT_OWN_WRITES: std::sync::Mutex<Option<HashMap<PathBuf, Instant>>> = std::sync::Mutex::new(None);
+
+fn recordOwnWrite(path: &Path) {
+    let mut recent = RECENT_OWN_WRITES.lock().unwrap_or_else(|e| e.into_inner());
+    let writes = recent.get_or_insert_with(HashMap::new);
+    writes.retain(|_, at| at.elapsed() < OWN_WRITE_WINDOW);
+    writes.insert(path.to_path_buf(), Instant::now());
+}
+
+/// Whether `path` was saved by writeFileAtomic within the last OWN_WRITE_WINDOW
+/// An external edit of the same file inside that window is only picked up by the next change.
+pub fn isRecentOwnWrite(path: &Path) -> bool {
+    let recent = RECENT_OWN_WRITES.lock().unwrap_or_else(|e| e.into_inner());
+    recent.as_ref()
+        .and_then(|writes| writes.get(path))
+        .is_some_and(|at| at.elapsed() < OWN_WRITE_WINDOW)
+}
+
+/// Temp sibling used while writing `path`: {name}.tmp
+pub fn atomicTempPath(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(ATOMIC_TEMP_SUFFIX);
+    path.with_file_name(name)
+}
+
+/// Drop-in replacement for fs::write that never leaves a partially written target
+/// On failure the temp file is removed and the target keeps its previous content.
+pub fn writeFileAtomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let path = path.as_ref();
+    let tempPath = atomicTempPath(path);
+    let result = fs::File::create(&tempPath)
+        .and_then(|mut file| {
+            file.write_all(contents.as_ref())?;
+            file.sync_all()
+        })
+        .and_then(|_| {
+            recordOwnWrite(path);
+            renameReplacing(&tempPath, path)
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&tempPath);
+    }
+    result
+}
+
+#[cfg(not(windows))]
+fn renameReplacing(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::rename(from, to)
+}
+
+#[cfg(windows)]
+fn renameReplacing(from: &Path, to: &Path) -> std::io::Result<()> {
+    const ATTEMPTS: u32 = 5;
+    let mut attempt = 1;
+    loop {
+        match fs::rename(from, to) {
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied && attempt < ATTEMPTS => {
+                std::thread::sleep(Duration::from_millis(20 * attempt as u64));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
 }
 
 // ============================================
@@ -214,8 +500,60 @@ pub fn isValidUuidDir(dirname: &str) -> bool {
     uuid::Uuid::parse_str(dirname).is_ok()
 }
 
-/// Generate slug from title (kept for potential display use)
-#[allow(dead_code)]
+/// Locate an item file by UUID without decrypting anything
+/// Searches the folder tree first, then the trash; hidden directories are skipped
+pub fn findItemFile(workspacePath: &str, kind: ItemKind, id: &str) -> Option<PathBuf> {
+    findItemFileIn([foldersDir(workspacePath), trashDir(workspacePath)], kind, id)
+}
+
+/// Like findItemFile, but only in the folder tree: a trashed item is not found
+pub fn findLiveItemFile(workspacePath: &str, kind: ItemKind, id: &str) -> Option<PathBuf> {
+    findItemFileIn([foldersDir(workspacePath)], kind, id)
+}
+
+/// Like findItemFile, but only in the trash
+pub fn findTrashedItemFile(workspacePath: &str, kind: ItemKind, id: &str) -> Option<PathBuf> {
+    findItemFileIn([trashDir(workspacePath)], kind, id)
+}
+
+fn findItemFileIn<const N: usize>(roots: [PathBuf; N], kind: ItemKind, id: &str) -> Option<PathBuf> {
+    if uuid::Uuid::parse_str(id).is_err() {
+        return None;
+    }
+    let filename = uuidFilename(id);
+
+    for root in roots {
+        let found = walkdir::WalkDir::new(&root)
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+            .filter_map(|e| e.ok())
+            .find(|e| {
+                e.file_type().is_file()
+                    && e.file_name().to_str() == Some(filename.as_str())
+                    && isItemPathOfKind(e.path(), kind)
+            });
+        if let Some(entry) = found {
+            return Some(entry.into_path());
+        }
+    }
+
+    None
+}
+
+/// Check that an item file sits in the directory layout for its kind
+/// (notes/{uuid}.md, passwords/{uuid}.md, tasks/{status}/{uuid}.md)
+pub(crate) fn isItemPathOfKind(path: &std::path::Path, kind: ItemKind) -> bool {
+    let dirName = |p: Option<&std::path::Path>| {
+        p.and_then(|d| d.file_name()).map(|n| n.to_string_lossy().to_string())
+    };
+    let parent = path.parent();
+    match kind {
+        ItemKind::Task => dirName(parent.and_then(|p| p.parent())).as_deref() == Some(kind.folderName()),
+        _ => dirName(parent).as_deref() == Some(kind.folderName()),
+    }
+}
+
+/// Generate slug from title (used for human-readable export filenames)
 pub fn slugify(title: &str) -> String {
     slug::slugify(title)
 }
@@ -224,17 +562,64 @@ pub fn slugify(title: &str) -> String {
 // STORAGE STATE
 // ============================================
 
-/// In-memory cache of workspace data (for future caching optimization)
-#[allow(dead_code)]
+/// Where each live (non-trash) item of the open workspace is stored, by UUID
+/// Built from filenames on unlock, so nothing is decrypted, and updated by create/move/delete.
+/// Lookups re-check the file, and callers fall back to a scan on a miss or a stale path.
+/// Frontmatter is deliberately not cached: it is decrypted data, and every metadata write
+/// would have to refresh it, so readers decrypt the one file the index points at instead.
 #[derive(Debug, Default)]
 pub struct WorkspaceData {
-    pub folders: Vec<Folder>,
-    pub notes: Vec<Note>,
-    pub tasks: Vec<Task>,
+    pub workspacePath: Option<String>,
+    pub index: HashMap<String, (ItemKind, PathBuf)>,
 }
 
-/// Passwords auto-lock timeout in seconds (10 minutes)
-const PASSWORDS_AUTO_LOCK_TIMEOUT_SECS: u64 = 600;
+impl WorkspaceData {
+    /// Index every item file under folders/ of a workspace
+    pub fn build(workspacePath: &str) -> Self {
+        let index = walkdir::WalkDir::new(foldersDir(workspacePath))
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| {
+                let id = e.file_name().to_str().and_then(parseUuidFilename)?;
+                let kind = [ItemKind::Note, ItemKind::Task, ItemKind::Password]
+                    .into_iter()
+                    .find(|k| isItemPathOfKind(e.path(), *k))?;
+                Some((id, (kind, e.into_path())))
+            })
+            .collect();
+        Self { workspacePath: Some(workspacePath.to_string()), index }
+    }
+
+    /// Indexed path of an item, if it is still on disk
+    pub fn lookup(&self, workspacePath: &str, kind: ItemKind, id: &str) -> Option<PathBuf> {
+        if self.workspacePath.as_deref() != Some(workspacePath) {
+            return None;
+        }
+        self.index.get(id)
+            .filter(|(k, path)| *k == kind && path.is_file())
+            .map(|(_, path)| path.clone())
+    }
+}
+
+/// Whether more than `minutes` have passed between `last` and `now` (0 minutes = never)
+/// `now` is passed in so the idle checks can be tested without waiting
+fn idleLongerThan(last: Option<Instant>, now: Instant, minutes: u32) -> bool {
+    minutes > 0 && last.is_some_and(|last| now.saturating_duration_since(last) > Duration::from_secs(u64::from(minutes) * 60))
+}
+
+/// Seconds left before idleLongerThan turns true, None when it never will
+fn secondsUntilIdle(last: Option<Instant>, now: Instant, minutes: u32) -> Option<u64> {
+    let last = last.filter(|_| minutes > 0)?;
+    Some((u64::from(minutes) * 60).saturating_sub(now.saturating_duration_since(last).as_secs()))
+}
+
+/// Lifetime of session tokens issued by deriveSessionKey (15 minutes)
+const SESSION_TOKEN_TTL_SECS: u64 = 900;
+
+/// Lifetime of re-auth tokens issued by verifyMasterPasswordFresh (2 minutes, single use)
+const REAUTH_TOKEN_TTL_SECS: u64 = 120;
 
 /// Main storage manager
 pub struct Storage {
@@ -242,16 +627,26 @@ pub struct Storage {
     pub globalSettings: RwLock<Settings>,
     pub workspaceOverride: RwLock<SettingsOverride>,
     pub workspaces: RwLock<Vec<WorkspaceEntry>>,
-    #[allow(dead_code)] // Reserved for future caching optimization
-    pub data: RwLock<WorkspaceData>,
     /// Cached derived key from master password (32 bytes, zeroized on drop)
     derivedKey: RwLock<Option<Zeroizing<Vec<u8>>>>,
-    /// Last activity timestamp (kept for compatibility but not used for auto-lock)
+    /// Last activity timestamp, for the autoLockMinutes idle lock
     lastActivity: RwLock<Option<Instant>>,
     /// Whether passwords access is currently unlocked (separate from main vault)
     passwordsAccessUnlocked: RwLock<bool>,
     /// Last passwords activity timestamp for passwords-only auto-lock
     lastPasswordsActivity: RwLock<Option<Instant>>,
+    /// App handle used to notify the frontend (set once the Tauri app is built)
+    appHandle: RwLock<Option<tauri::AppHandle>>,
+    /// Short-lived session tokens for scripted callers, with their issue time
+    sessionTokens: RwLock<Vec<(Zeroizing<String>, Instant)>>,
+    /// Single-use tokens proving a fresh master password check, for sensitive commands
+    reauthTokens: RwLock<Vec<(Zeroizing<String>, Instant)>>,
+    /// Inverses of recent delete/move/reorder operations (in memory, reset on restart)
+    undoStack: RwLock<UndoStack>,
+    /// UUID -> path index of the open workspace (see WorkspaceData)
+    pub data: RwLock<WorkspaceData>,
+    /// Wiki-link targets parsed from decrypted note bodies, by note id (cleared on lock)
+    linkCache: RwLock<LinkCache>,
 }
 
 impl Storage {
@@ -288,6 +683,8 @@ impl Storage {
             })
             .unwrap_or_default();
 
+        TaskStatus::setActive(workspaceOverride.taskStatuses.as_deref());
+
         println!("[Storage::new] Storage initialized successfully");
         Self {
             workspacePath: RwLock::new(currentWsPath),
@@ -299,9 +696,33 @@ impl Storage {
             lastActivity: RwLock::new(None),
             passwordsAccessUnlocked: RwLock::new(false),
             lastPasswordsActivity: RwLock::new(None),
+            appHandle: RwLock::new(None),
+            sessionTokens: RwLock::new(Vec::new()),
+            reauthTokens: RwLock::new(Vec::new()),
+            undoStack: RwLock::new(UndoStack::default()),
+            linkCache: RwLock::new(LinkCache::default()),
+        }
+    }
+
+    /// Attach the app handle so storage can emit events to the frontend
+    pub fn setAppHandle(&self, handle: tauri::AppHandle) {
+        *self.appHandle.write() = Some(handle);
+    }
+
+    /// Emit an event to the frontend (no-op until the app handle is attached)
+    pub fn emitEvent<S: serde::Serialize + Clone>(&self, event: &str, payload: S) {
+        use tauri::Emitter;
+        if let Some(handle) = self.appHandle.read().as_ref() {
+            let _ = handle.emit(event, payload);
         }
     }
 
+    /// Replace the workspace override, activating its task status columns
+    pub fn setWorkspaceOverride(&self, over: SettingsOverride) {
+        TaskStatus::setActive(over.taskStatuses.as_deref());
+        *self.workspaceOverride.write() = over;
+    }
+
     /// Get effective settings (global + workspace override)
     pub fn effectiveSettings(&self) -> Settings {
         let global = self.globalSettings.read();
@@ -316,6 +737,36 @@ impl Storage {
         path
     }
 
+    /// Make `path` the current workspace (None closes it)
+    /// Every workspace is its own vault, so leaving the current one locks the vault
+    pub fn setCurrentWorkspace(&self, path: Option<String>) {
+        let switching = *self.workspacePath.read() != path;
+        if switching && self.isUnlocked() {
+            self.lock();
+            self.emitEvent("vault-locked", ());
+        }
+        self.globalSettings.write().currentWorkspace = path.clone();
+        *self.workspacePath.write() = path;
+    }
+
+    /// Get current workspace path, verifying the directory still exists on disk
+    /// Returns Ok(None) when no workspace is selected, and a WorkspaceUnavailable error
+    /// (plus a "workspace-unavailable" event) when the directory has disappeared
+    pub fn getAvailableWorkspacePath(&self) -> Result<Option<String>, ClaudiaError> {
+        let path = match self.getWorkspacePath() {
+            Some(p) => p,
+            None => return Ok(None),
+        };
+
+        if PathBuf::from(&path).is_dir() {
+            return Ok(Some(path));
+        }
+
+        println!("[Storage::getAvailableWorkspacePath] Workspace directory missing: {}", path);
+        self.emitEvent("workspace-unavailable", path.clone());
+        Err(ClaudiaError::WorkspaceUnavailable(format!("Workspace unavailable: {} no longer exists", path)))
+    }
+
     // ============================================
     // VAULT MANAGEMENT
     // ============================================
@@ -324,13 +775,14 @@ impl Storage {
     pub fn setDerivedKey(&self, key: Vec<u8>) {
         let mut derivedKey = self.derivedKey.write();
         *derivedKey = Some(Zeroizing::new(key));
+        drop(derivedKey);
+        self.rebuildIndex();
         self.updateActivity();
         // Also unlock passwords access when vault is unlocked
         self.unlockPasswordsAccess();
     }
 
     /// Get the derived key if vault is unlocked
-    /// Note: Main vault no longer auto-locks - only passwords have auto-lock
     pub fn getDerivedKey(&self) -> Option<Vec<u8>> {
         let key = self.derivedKey.read();
         key.as_ref().map(|k| k.to_vec())
@@ -345,7 +797,7 @@ impl Storage {
     }
 
     /// Check if vault is unlocked
-    /// Note: Main vault no longer auto-locks - only passwords have auto-lock
+    /// The key is dropped by lock(), including the autoLockMinutes idle lock in autoLockIfIdle
     pub fn isUnlocked(&self) -> bool {
         self.derivedKey.read().is_some()
     }
@@ -356,22 +808,46 @@ impl Storage {
         *derivedKey = None;
         let mut lastActivity = self.lastActivity.write();
         *lastActivity = None;
-        // Also lock passwords access
+        // Also lock passwords access and drop session and re-auth tokens
         self.lockPasswordsAccess();
+        self.sessionTokens.write().clear();
+        self.reauthTokens.write().clear();
+        self.linkCache.write().clear();
+        *self.data.write() = WorkspaceData::default();
         println!("[Storage::lock] Vault locked");
     }
 
-    /// Update last activity timestamp (kept for compatibility)
+    /// Update last activity timestamp (resets the autoLockMinutes timer)
     pub fn updateActivity(&self) {
         let mut lastActivity = self.lastActivity.write();
         *lastActivity = Some(Instant::now());
     }
 
+    /// Lock the vault if it has been idle longer than autoLockMinutes
+    /// Returns true when it locked; the frontend is told with a "vault-locked" event
+    pub fn autoLockIfIdle(&self, now: Instant) -> bool {
+        if !self.isUnlocked() || !idleLongerThan(*self.lastActivity.read(), now, self.effectiveSettings().autoLockMinutes) {
+            return false;
+        }
+        println!("[Storage::autoLockIfIdle] Vault idle for over {} minutes, locking", self.effectiveSettings().autoLockMinutes);
+        self.lock();
+        self.emitEvent("vault-locked", ());
+        true
+    }
+
     // ============================================
     // PASSWORDS-ONLY AUTO-LOCK
     // ============================================
 
-    /// Unlock passwords access (grants access for 10 minutes)
+    /// Seconds until autoLockIfIdle locks the vault (None when locked or autoLockMinutes is 0)
+    pub fn secondsUntilAutoLock(&self, now: Instant) -> Option<u64> {
+        if !self.isUnlocked() {
+            return None;
+        }
+        secondsUntilIdle(*self.lastActivity.read(), now, self.effectiveSettings().autoLockMinutes)
+    }
+
+    /// Unlock passwords access (until passwordsAccessMinutes without password activity)
     pub fn unlockPasswordsAccess(&self) {
         let mut unlocked = self.passwordsAccessUnlocked.write();
         *unlocked = true;
@@ -396,24 +872,31 @@ impl Storage {
     }
 
     /// Check if passwords access should auto-lock due to inactivity
-    fn shouldPasswordsAutoLock(&self) -> bool {
-        let lastActivity = self.lastPasswordsActivity.read();
-        if let Some(last) = *lastActivity {
-            last.elapsed().as_secs() > PASSWORDS_AUTO_LOCK_TIMEOUT_SECS
-        } else {
-            false
-        }
+    fn shouldPasswordsAutoLock(&self, now: Instant) -> bool {
+        idleLongerThan(*self.lastPasswordsActivity.read(), now, self.effectiveSettings().passwordsAccessMinutes)
     }
 
     /// Check if passwords access is unlocked (with auto-lock check)
     pub fn isPasswordsAccessUnlocked(&self) -> bool {
+        self.isPasswordsAccessUnlockedAt(Instant::now())
+    }
+
+    /// Seconds until password access locks again (None when already locked or it only locks with the vault)
+    pub fn secondsUntilPasswordsLock(&self, now: Instant) -> Option<u64> {
+        if !self.isPasswordsAccessUnlockedAt(now) {
+            return None;
+        }
+        secondsUntilIdle(*self.lastPasswordsActivity.read(), now, self.effectiveSettings().passwordsAccessMinutes)
+    }
+
+    fn isPasswordsAccessUnlockedAt(&self, now: Instant) -> bool {
         // First check if vault is unlocked at all
         if !self.isUnlocked() {
             return false;
         }
 
         // Check passwords auto-lock
-        if self.shouldPasswordsAutoLock() {
+        if self.shouldPasswordsAutoLock(now) {
             self.lockPasswordsAccess();
             return false;
         }
@@ -421,6 +904,69 @@ impl Storage {
         *self.passwordsAccessUnlocked.read()
     }
 
+    // ============================================
+    // SESSION TOKENS
+    // ============================================
+
+    /// Issue a random session token valid for SESSION_TOKEN_TTL_SECS
+    pub fn createSessionToken(&self) -> String {
+        let token = randomToken();
+
+        let mut tokens = self.sessionTokens.write();
+        tokens.retain(|(_, issued)| issued.elapsed().as_secs() <= SESSION_TOKEN_TTL_SECS);
+        tokens.push((Zeroizing::new(token.clone()), Instant::now()));
+        token
+    }
+
+    /// Check a session token (vault must still be unlocked and the token unexpired)
+    pub fn isSessionTokenValid(&self, token: &str) -> bool {
+        self.isUnlocked() && self.sessionTokens.read().iter().any(|(t, issued)| {
+            t.as_str() == token && issued.elapsed().as_secs() <= SESSION_TOKEN_TTL_SECS
+        })
+    }
+
+    /// Reject a read without a valid session token once a session has been derived
+    /// A vault unlocked in the app issues no tokens, so the app's own reads pass without one
+    pub fn checkSessionToken(&self, token: Option<&str>) -> Result<(), ClaudiaError> {
+        match token {
+            Some(t) if self.isSessionTokenValid(t) => Ok(()),
+            Some(_) => Err(ClaudiaError::Auth("Invalid or expired session token".to_string())),
+            None if self.hasLiveSession() => Err(ClaudiaError::Auth("Session token required".to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether an unexpired session token has been issued since the last unlock
+    fn hasLiveSession(&self) -> bool {
+        self.sessionTokens.read().iter().any(|(_, issued)| issued.elapsed().as_secs() <= SESSION_TOKEN_TTL_SECS)
+    }
+
+    /// Issue a single-use re-auth token valid for REAUTH_TOKEN_TTL_SECS
+    pub fn createReauthToken(&self) -> String {
+        let token = randomToken();
+
+        let mut tokens = self.reauthTokens.write();
+        tokens.retain(|(_, issued)| issued.elapsed().as_secs() <= REAUTH_TOKEN_TTL_SECS);
+        tokens.push((Zeroizing::new(token.clone()), Instant::now()));
+        token
+    }
+
+    /// Check and spend a re-auth token; each token authorizes one sensitive command
+    pub fn consumeReauthToken(&self, token: &str) -> bool {
+        if !self.isUnlocked() {
+            return false;
+        }
+        let mut tokens = self.reauthTokens.write();
+        tokens.retain(|(_, issued)| issued.elapsed().as_secs() <= REAUTH_TOKEN_TTL_SECS);
+        match tokens.iter().position(|(t, _)| t.as_str() == token) {
+            Some(i) => {
+                tokens.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Get master password hash file path
     pub fn masterPasswordHashPath(&self) -> Option<PathBuf> {
         self.getWorkspacePath().map(|ws| {
@@ -428,6 +974,100 @@ impl Storage {
         })
     }
 
+    /// Get vault key salt file path (absent for vaults created before per-install salts)
+    pub fn vaultSaltPath(&self) -> Option<PathBuf> {
+        self.getWorkspacePath().map(|ws| {
+            PathBuf::from(&ws).join(".vault-salt")
+        })
+    }
+
+    /// Remember how to undo an operation just done in the current workspace
+    pub fn recordUndo(&self, label: &str, actions: Vec<UndoAction>) {
+        if let Some(workspacePath) = self.getWorkspacePath() {
+            self.undoStack.write().push(UndoEntry { label: label.to_string(), workspacePath, actions });
+        }
+    }
+
+    /// Take the most recent undo entry of the current workspace
+    pub fn takeUndo(&self) -> Option<UndoEntry> {
+        let workspacePath = self.getWorkspacePath()?;
+        self.undoStack.write().pop(&workspacePath)
+    }
+
+    /// Put back an entry that could only be partly undone
+    pub fn returnUndo(&self, entry: UndoEntry) {
+        self.undoStack.write().push(entry);
+    }
+
+    /// Re-index the current workspace from its files
+    pub fn rebuildIndex(&self) {
+        let data = match self.getWorkspacePath() {
+            Some(ws) => WorkspaceData::build(&ws),
+            None => WorkspaceData::default(),
+        };
+        println!("[Storage::rebuildIndex] Indexed {} items", data.index.len());
+        *self.data.write() = data;
+    }
+
+    /// Indexed path of a live item in the current workspace
+    pub fn indexedPath(&self, kind: ItemKind, id: &str) -> Option<PathBuf> {
+        let workspacePath = self.getWorkspacePath()?;
+        self.data.read().lookup(&workspacePath, kind, id)
+    }
+
+    /// Path of a live item: the index first, then a walk of the folder tree (never the trash)
+    /// A path found by the walk is indexed for the next lookup
+    pub fn liveItemPath(&self, kind: ItemKind, id: &str) -> Option<PathBuf> {
+        if let Some(path) = self.indexedPath(kind, id) {
+            return Some(path);
+        }
+        let path = findLiveItemFile(&self.getWorkspacePath()?, kind, id)?;
+        self.indexItem(kind, id, &path);
+        Some(path)
+    }
+
+    /// Path of an item, live or trashed: liveItemPath, then a walk of the trash
+    pub fn itemPath(&self, kind: ItemKind, id: &str) -> Option<PathBuf> {
+        self.liveItemPath(kind, id).or_else(|| findTrashedItemFile(&self.getWorkspacePath()?, kind, id))
+    }
+
+    /// Record where an item now lives (after create, move or restore)
+    /// An index built for another workspace is rebuilt first
+    pub fn indexItem(&self, kind: ItemKind, id: &str, path: &Path) {
+        let Some(workspacePath) = self.getWorkspacePath() else {
+            return;
+        };
+        let mut data = self.data.write();
+        if data.workspacePath.as_deref() != Some(workspacePath.as_str()) {
+            *data = WorkspaceData::build(&workspacePath);
+        }
+        data.index.insert(id.to_string(), (kind, path.to_path_buf()));
+    }
+
+    /// Forget an item that left the folder tree (trashed or deleted)
+    pub fn unindexItem(&self, id: &str) {
+        self.data.write().index.remove(id);
+    }
+
+    /// Cached link targets of a note in `workspacePath`
+    pub fn cachedLinks(&self, workspacePath: &str, id: &str) -> Option<Vec<String>> {
+        self.linkCache.read().get(workspacePath, id)
+    }
+
+    pub fn cacheLinks(&self, workspacePath: &str, id: &str, links: Vec<String>) {
+        self.linkCache.write().insert(workspacePath, id, links);
+    }
+
+    /// Forget a note's cached links after its body was written
+    pub fn invalidateLinks(&self, id: &str) {
+        self.linkCache.write().invalidate(id);
+    }
+
+    /// Forget all cached links, e.g. after the workspace content was replaced wholesale
+    pub fn clearLinks(&self) {
+        self.linkCache.write().clear();
+    }
+
     /// Check if master password has been set up
     pub fn isVaultSetup(&self) -> bool {
         self.masterPasswordHashPath()
@@ -438,6 +1078,13 @@ impl Storage {
 
 pub type StorageState = Arc<Storage>;
 
+/// 32 random bytes as hex, for session and re-auth tokens
+fn randomToken() -> String {
+    let mut bytes = [0u8; 32];
+    rand::Rng::fill(&mut rand::thread_rng(), &mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 /// Initialize storage
 pub fn initStorage() -> Result<StorageState, String> {
     Ok(Arc::new(Storage::new()))
@@ -527,3 +1174,240 @@ pub fn saveGlobalConfig(storage: &Storage) -> Result<(), String> {
     let content = toMarkdown(&*settings, &body)?;
     fs::write(&path, content).map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::common::newId;
+
+    #[test]
+    fn test_items_dir_round_trip() {
+        let folder = Path::new("/ws/folders/abc");
+        for kind in [ItemKind::Note, ItemKind::Task, ItemKind::Password] {
+            let dir = itemsDir(folder, kind);
+            assert_eq!(dir, folder.join(kind.folderName()));
+            assert_eq!(folderOfItemsDir(&dir), folder);
+        }
+    }
+
+    #[test]
+    fn test_folder_arg() {
+        assert_eq!(folderArg(None), None);
+        assert_eq!(folderArg(Some("")), None);
+        assert_eq!(folderArg(Some("null")), None);
+        assert_eq!(folderArg(Some("relative/dir")), None);
+        assert_eq!(folderArg(Some("/ws/folders/abc")), Some(Path::new("/ws/folders/abc")));
+    }
+
+    #[test]
+    fn test_root_items_dir() {
+        assert_eq!(itemsDirFor("/ws", None, ItemKind::Note).unwrap(), notesDir("/ws", ""));
+        assert_eq!(itemsDirFor("/ws", Some(""), ItemKind::Task).unwrap(), tasksDir("/ws", ""));
+        assert_eq!(folderOfItemsDir(&notesDir("/ws", "")), foldersDir("/ws"));
+    }
+
+    #[test]
+    fn test_atomic_write_survives_interrupted_write() {
+        let dir = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("item.md");
+        writeFileAtomic(&path, "version 1").unwrap();
+
+        // A crash while writing leaves a partial temp file; the item itself is untouched
+        fs::write(atomicTempPath(&path), "versi").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "version 1");
+
+        // The next save replaces both the stale temp file and the item
+        writeFileAtomic(&path, "version 2").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "version 2");
+        assert!(!atomicTempPath(&path).exists());
+        assert!(isRecentOwnWrite(&path));
+        assert!(!isRecentOwnWrite(&dir.join("other.md")));
+
+        // A failed rename (target is a non-empty directory) keeps the target and cleans up
+        let blocked = dir.join("blocked.md");
+        fs::create_dir_all(blocked.join("child")).unwrap();
+        assert!(writeFileAtomic(&blocked, "new").is_err());
+        assert!(blocked.join("child").is_dir());
+        assert!(!atomicTempPath(&blocked).exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_folder_paths_cannot_escape_workspace() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        let folder = foldersDir(&wsPath).join("abc");
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join(".folder.md"), "").unwrap();
+        let folderStr = folder.to_string_lossy().to_string();
+
+        assert!(validateFolderPath(&wsPath, "../escaped").is_err());
+        assert!(!ws.join("escaped").exists());
+
+        let traversal = format!("{}/../../../etc", folderStr);
+        assert!(validateMoveTarget(&wsPath, &traversal).is_err());
+        assert!(validateMoveTarget(&wsPath, "/foo/../../etc").is_err());
+        assert!(itemsDirFor(&wsPath, Some(&traversal), ItemKind::Note).is_err());
+        assert!(itemsDirFor(&wsPath, Some("/foo/../../etc"), ItemKind::Task).is_err());
+        assert!(resolveFolderArg(&wsPath, Some("/tmp")).is_err());
+        // Relative paths used to fall back to the root folder silently
+        assert!(resolveFolderArg(&wsPath, Some("../../etc")).is_err());
+        assert!(resolveFolderArg(&wsPath, Some("relative/dir")).is_err());
+        assert_eq!(resolveFolderArg(&wsPath, Some("null")).unwrap(), foldersDir(&wsPath));
+
+        assert_eq!(itemsDirFor(&wsPath, Some(&folderStr), ItemKind::Note).unwrap(), itemsDir(&folder, ItemKind::Note));
+        assert_eq!(resolveFolderArg(&wsPath, None).unwrap(), foldersDir(&wsPath));
+
+        fs::remove_dir_all(&ws).unwrap();
+    }
+
+    #[test]
+    fn test_folder_dir_must_be_a_folder_in_the_workspace() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        let base = foldersDir(&wsPath);
+        let folder = base.join(newId());
+        fs::create_dir_all(&folder).unwrap();
+        let outside = ws.join(newId());
+        fs::create_dir_all(&outside).unwrap();
+
+        assert_eq!(validateFolderDir(&wsPath, &folder.to_string_lossy()).unwrap(), folder);
+        // Deleted and orphaned folders still validate; only the location matters
+        assert!(validateFolderDir(&wsPath, &base.join(newId()).to_string_lossy()).is_ok());
+
+        assert!(validateFolderDir(&wsPath, &base.to_string_lossy()).is_err());
+        assert!(validateFolderDir(&wsPath, &outside.to_string_lossy()).is_err());
+        assert!(validateFolderDir(&wsPath, &base.join("notes").to_string_lossy()).is_err());
+        let traversal = base.join("..").join(outside.file_name().unwrap());
+        assert!(validateFolderDir(&wsPath, &traversal.to_string_lossy()).is_err());
+        #[cfg(unix)]
+        {
+            let link = base.join(newId());
+            std::os::unix::fs::symlink(&outside, &link).unwrap();
+            assert!(validateFolderDir(&wsPath, &link.to_string_lossy()).is_err());
+        }
+
+        fs::remove_dir_all(&ws).unwrap();
+    }
+
+    #[test]
+    fn test_session_token_required_once_a_session_exists() {
+        let storage = Storage::new();
+        storage.setDerivedKey(vec![7u8; 32]);
+        // Unlocked in the app: no session, so reads carry no token
+        assert!(storage.checkSessionToken(None).is_ok());
+
+        let token = storage.createSessionToken();
+        assert_eq!(storage.checkSessionToken(None).unwrap_err().code(), "AUTH");
+        assert_eq!(storage.checkSessionToken(Some("forged")).unwrap_err().code(), "AUTH");
+        assert!(storage.checkSessionToken(Some(&token)).is_ok());
+
+        // Locking drops the session along with its tokens
+        storage.lock();
+        assert!(storage.checkSessionToken(Some(&token)).is_err());
+    }
+
+    #[test]
+    fn test_idle_auto_lock() {
+        let storage = Storage::new();
+        storage.globalSettings.write().autoLockMinutes = 5;
+        storage.setDerivedKey(vec![7u8; 32]);
+        let start = (*storage.lastActivity.read()).unwrap();
+
+        assert!(!storage.autoLockIfIdle(start + Duration::from_secs(4 * 60)));
+        assert!(storage.isUnlocked());
+        assert_eq!(storage.secondsUntilAutoLock(start + Duration::from_secs(4 * 60)), Some(60));
+        assert!(storage.autoLockIfIdle(start + Duration::from_secs(6 * 60)));
+        assert!(!storage.isUnlocked());
+
+        // 0 = never
+        storage.globalSettings.write().autoLockMinutes = 0;
+        storage.setDerivedKey(vec![7u8; 32]);
+        assert!(!storage.autoLockIfIdle(start + Duration::from_secs(24 * 60 * 60)));
+        assert_eq!(storage.secondsUntilAutoLock(start), None);
+    }
+
+    #[test]
+    fn test_passwords_access_locks_before_vault() {
+        let storage = Storage::new();
+        storage.globalSettings.write().passwordsAccessMinutes = 2;
+        storage.setDerivedKey(vec![7u8; 32]);
+        let start = (*storage.lastPasswordsActivity.read()).unwrap();
+
+        assert!(storage.isPasswordsAccessUnlockedAt(start + Duration::from_secs(60)));
+        assert_eq!(storage.secondsUntilPasswordsLock(start + Duration::from_secs(90)), Some(30));
+        assert!(!storage.isPasswordsAccessUnlockedAt(start + Duration::from_secs(3 * 60)));
+        assert_eq!(storage.secondsUntilPasswordsLock(start + Duration::from_secs(3 * 60)), None);
+        assert!(storage.isUnlocked());
+    }
+
+    #[test]
+    fn test_index_stays_consistent_across_a_move() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        let id = newId().to_string();
+        let taskId = newId().to_string();
+
+        let source = notesDir(&wsPath, "");
+        let target = notesDir(&wsPath, "Projects");
+        let todo = tasksDir(&wsPath, "Projects").join("todo");
+        for dir in [&source, &target, &todo, &trashNotesDir(&wsPath)] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(source.join(uuidFilename(&id)), "x").unwrap();
+        fs::write(todo.join(uuidFilename(&taskId)), "x").unwrap();
+        fs::write(trashNotesDir(&wsPath).join(uuidFilename(&newId().to_string())), "x").unwrap();
+
+        let mut data = WorkspaceData::build(&wsPath);
+        assert_eq!(data.index.len(), 2, "trash is not indexed");
+        assert_eq!(data.lookup(&wsPath, ItemKind::Note, &id), Some(source.join(uuidFilename(&id))));
+        assert_eq!(data.lookup(&wsPath, ItemKind::Task, &taskId), Some(todo.join(uuidFilename(&taskId))));
+        assert_eq!(data.lookup(&wsPath, ItemKind::Task, &id), None, "kind must match");
+        assert_eq!(data.lookup("/elsewhere", ItemKind::Note, &id), None);
+
+        // Moving the file leaves a stale entry, which lookup refuses
+        let moved = target.join(uuidFilename(&id));
+        fs::rename(source.join(uuidFilename(&id)), &moved).unwrap();
+        assert_eq!(data.lookup(&wsPath, ItemKind::Note, &id), None);
+
+        // The move hook records the new path; a rebuild agrees with it
+        data.index.insert(id.clone(), (ItemKind::Note, moved.clone()));
+        assert_eq!(data.lookup(&wsPath, ItemKind::Note, &id), Some(moved.clone()));
+        assert_eq!(WorkspaceData::build(&wsPath).lookup(&wsPath, ItemKind::Note, &id), Some(moved));
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn test_item_path_prefers_the_index_and_keeps_trash_separate() {
+        let ws = std::env::temp_dir().join(format!("claudia-test-{}", newId()));
+        let wsPath = ws.to_string_lossy().to_string();
+        let storage = Storage::new();
+        storage.setCurrentWorkspace(Some(wsPath.clone()));
+
+        let live = newId().to_string();
+        let trashed = newId().to_string();
+        let source = notesDir(&wsPath, "");
+        let target = notesDir(&wsPath, "Projects");
+        for dir in [&source, &target, &trashNotesDir(&wsPath)] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(source.join(uuidFilename(&live)), "x").unwrap();
+        fs::write(trashNotesDir(&wsPath).join(uuidFilename(&trashed)), "x").unwrap();
+
+        assert_eq!(storage.liveItemPath(ItemKind::Note, &live), Some(source.join(uuidFilename(&live))));
+        assert_eq!(storage.indexedPath(ItemKind::Note, &live), Some(source.join(uuidFilename(&live))));
+        assert_eq!(storage.liveItemPath(ItemKind::Note, &trashed), None);
+        assert_eq!(storage.itemPath(ItemKind::Note, &trashed), Some(trashNotesDir(&wsPath).join(uuidFilename(&trashed))));
+
+        // Moved behind the index's back: the stale entry is skipped and the walk re-indexes
+        let moved = target.join(uuidFilename(&live));
+        fs::rename(source.join(uuidFilename(&live)), &moved).unwrap();
+        assert_eq!(storage.itemPath(ItemKind::Note, &live), Some(moved.clone()));
+        assert_eq!(storage.indexedPath(ItemKind::Note, &live), Some(moved));
+
+        let _ = fs::remove_dir_all(&ws);
+    }
+}
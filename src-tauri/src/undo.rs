@@ -0,0 +1,110 @@
+// Undo for the most recent file-moving operations: delete to trash, move between folders, reorder
+// The stack lives in memory only, so it starts empty on every launch. Entries remember the
+// workspace they were recorded in and are dropped instead of replayed in another workspace.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use crate::models::ItemKind;
+
+/// Operations remembered for undo; older ones are forgotten
+pub const UNDO_STACK_SIZE: usize = 20;
+
+/// Inverse of one recorded change
+#[derive(Debug, Clone, PartialEq)]
+pub enum UndoAction {
+    /// Move an item file from `current` back to `original` and restore its rank
+    MoveBack {
+        kind: ItemKind,
+        id: String,
+        current: PathBuf,
+        original: PathBuf,
+        rank: u32,
+    },
+    /// Put back the ranks items had before a reorder
+    RestoreRanks { ranks: Vec<(PathBuf, u32)> },
+}
+
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    /// Command that recorded the entry, e.g. "moveNoteToFolder"
+    pub label: String,
+    pub workspacePath: String,
+    pub actions: Vec<UndoAction>,
+}
+
+#[derive(Debug)]
+pub struct UndoStack {
+    entries: VecDeque<UndoEntry>,
+    capacity: usize,
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::new(UNDO_STACK_SIZE)
+    }
+}
+
+impl UndoStack {
+    pub fn new(capacity: usize) -> Self {
+        Self { entries: VecDeque::new(), capacity: capacity.max(1) }
+    }
+
+    pub fn push(&mut self, entry: UndoEntry) {
+        if entry.actions.is_empty() {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Most recent entry recorded in `workspacePath`
+    /// Entries from another workspace can never be replayed, so they are discarded
+    pub fn pop(&mut self, workspacePath: &str) -> Option<UndoEntry> {
+        if self.entries.back().is_some_and(|e| e.workspacePath != workspacePath) {
+            self.entries.clear();
+        }
+        self.entries.pop_back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(label: &str, ws: &str) -> UndoEntry {
+        UndoEntry {
+            label: label.to_string(),
+            workspacePath: ws.to_string(),
+            actions: vec![UndoAction::RestoreRanks { ranks: vec![(PathBuf::from("/a.md"), 1)] }],
+        }
+    }
+
+    #[test]
+    fn test_stack_is_bounded_and_lifo() {
+        let mut stack = UndoStack::new(2);
+        stack.push(entry("first", "/ws"));
+        stack.push(entry("second", "/ws"));
+        stack.push(entry("third", "/ws"));
+        assert_eq!(stack.pop("/ws").unwrap().label, "third");
+        assert_eq!(stack.pop("/ws").unwrap().label, "second");
+        assert!(stack.pop("/ws").is_none());
+    }
+
+    #[test]
+    fn test_other_workspace_entries_are_dropped() {
+        let mut stack = UndoStack::default();
+        stack.push(entry("old", "/ws1"));
+        assert!(stack.pop("/ws2").is_none());
+        assert!(stack.pop("/ws1").is_none());
+    }
+
+    #[test]
+    fn test_empty_entries_are_ignored() {
+        let mut stack = UndoStack::default();
+        stack.push(UndoEntry { label: "noop".to_string(), workspacePath: "/ws".to_string(), actions: Vec::new() });
+        assert!(stack.pop("/ws").is_none());
+    }
+}
@@ -0,0 +1,178 @@
+// Watches the open workspace's folders/ directory for .md files changed outside the app
+// (another editor, a sync client, the MCP server). Bursts of events are debounced, then the
+// item index and link cache are refreshed and the frontend gets a "vault-changed" event.
+// The app's own saves (writeFileAtomic) are skipped, so editing doesn't rebuild the index.
+// Opt-in with the watchVault setting: the thread runs only while the effective setting is on,
+// started by syncVaultWatcher when settings or the workspace change and stopping by itself.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::storage::{StorageState, foldersDir, isRecentOwnWrite};
+
+/// Quiet time after the last event before changes are reported
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// How often the loop wakes up without events (debounce flush, workspace switches)
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Whether the watcher thread is running; at most one runs at a time
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Start the watcher thread if the watchVault setting is on and it isn't running yet
+/// Call after anything that can turn the effective setting on (launch, settings, workspace
+/// switch). Turning it off needs no call: the thread notices within one poll interval.
+pub fn syncVaultWatcher(storage: &StorageState) {
+    if !storage.effectiveSettings().watchVault || RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let storage = storage.clone();
+    std::thread::spawn(move || loop {
+        let result = watchLoop(&storage);
+        RUNNING.store(false, Ordering::SeqCst);
+        if let Err(e) = result {
+            eprintln!("[watcher] Vault watcher stopped: {}", e);
+            return;
+        }
+        // Turned back on while this thread was stopping: keep watching instead of leaving it off
+        if !storage.effectiveSettings().watchVault || RUNNING.swap(true, Ordering::SeqCst) {
+            return;
+        }
+    });
+}
+
+fn watchLoop(storage: &StorageState) -> notify::Result<()> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    let mut watched: Option<PathBuf> = None;
+    let mut pending: Vec<PathBuf> = Vec::new();
+    let mut lastEvent = Instant::now();
+
+    loop {
+        if !storage.effectiveSettings().watchVault {
+            println!("[watcher] watchVault is off, stopping");
+            return Ok(());
+        }
+
+        // Follow workspace switches
+        let current = storage.workspacePath.read().clone().map(|ws| foldersDir(&ws)).filter(|p| p.is_dir());
+        if current != watched {
+            if let Some(old) = &watched {
+                let _ = watcher.unwatch(old);
+            }
+            if let Some(new) = &current {
+                match watcher.watch(new, RecursiveMode::Recursive) {
+                    Ok(()) => println!("[watcher] Watching {}", new.display()),
+                    Err(e) => eprintln!("[watcher] Failed to watch {}: {}", new.display(), e),
+                }
+            }
+            watched = current;
+            pending.clear();
+        }
+
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(Ok(event)) if isContentChange(&event.kind) => {
+                let Some(root) = &watched else { continue };
+                for path in event.paths {
+                    if isVaultFile(root, &path) && !isRecentOwnWrite(&path) && !pending.contains(&path) {
+                        pending.push(path);
+                    }
+                }
+                lastEvent = Instant::now();
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => eprintln!("[watcher] Watch error: {}", e),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+
+        if !pending.is_empty() && lastEvent.elapsed() >= DEBOUNCE {
+            vaultChanged(storage, std::mem::take(&mut pending));
+        }
+    }
+}
+
+/// Refresh derived state and tell the frontend which files changed
+fn vaultChanged(storage: &StorageState, paths: Vec<PathBuf>) {
+    println!("[watcher] {} files changed", paths.len());
+    if storage.isUnlocked() {
+        storage.rebuildIndex();
+    }
+    storage.clearLinks();
+    let paths: Vec<String> = paths.iter().map(|p| p.to_string_lossy().to_string()).collect();
+    storage.emitEvent("vault-changed", paths);
+}
+
+fn isContentChange(kind: &EventKind) -> bool {
+    matches!(kind, EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(_))
+}
+
+/// A markdown file under `root` outside hidden directories (.history, editor temp dirs)
+/// Folder metadata (.folder.md) counts; other hidden files don't.
+fn isVaultFile(root: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    let Some(name) = relative.file_name().map(|n| n.to_string_lossy()) else {
+        return false;
+    };
+    let isMarkdown = name == ".folder.md" || (!name.starts_with('.') && name.ends_with(".md"));
+    let inHiddenDir = relative.parent()
+        .is_some_and(|dir| dir.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.')));
+    isMarkdown && !inHiddenDir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+
+    #[test]
+    fn test_is_vault_file() {
+        let root = Path::new("/home/me/.vault/folders");
+        assert!(isVaultFile(root, &root.join("notes/a.md")));
+        assert!(isVaultFile(root, &root.join("Work/tasks/todo/b.md")));
+        assert!(!isVaultFile(root, &root.join("notes/.history/a/1.md")));
+        assert!(!isVaultFile(root, &root.join("notes/.a.md.swp")));
+        assert!(!isVaultFile(root, &root.join("notes/.hidden.md")));
+        assert!(!isVaultFile(root, &root.join("notes/a.md.tmp")));
+        assert!(isVaultFile(root, &root.join("Work/.folder.md")));
+        assert!(!isVaultFile(root, &root.join("notes/.history/a/.folder.md")));
+        assert!(!isVaultFile(root, &root.join("notes/image.png")));
+        assert!(!isVaultFile(root, Path::new("/elsewhere/notes/a.md")));
+    }
+
+    #[test]
+    fn test_thread_follows_the_setting() {
+        let storage: StorageState = std::sync::Arc::new(crate::storage::Storage::new());
+        let waitFor = |running: bool| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while RUNNING.load(Ordering::SeqCst) != running && Instant::now() < deadline {
+                std::thread::sleep(POLL_INTERVAL / 5);
+            }
+            RUNNING.load(Ordering::SeqCst) == running
+        };
+
+        storage.globalSettings.write().watchVault = false;
+        syncVaultWatcher(&storage);
+        assert!(waitFor(false));
+
+        storage.globalSettings.write().watchVault = true;
+        syncVaultWatcher(&storage);
+        assert!(waitFor(true));
+
+        // Turning it off needs no call
+        storage.globalSettings.write().watchVault = false;
+        assert!(waitFor(false));
+    }
+
+    #[test]
+    fn test_only_content_changes_count() {
+        assert!(isContentChange(&EventKind::Create(CreateKind::File)));
+        assert!(isContentChange(&EventKind::Modify(ModifyKind::Any)));
+        assert!(!isContentChange(&EventKind::Access(AccessKind::Any)));
+    }
+}
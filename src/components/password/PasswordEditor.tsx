@@ -9,6 +9,7 @@ import { useFolderStore } from '../../stores/folderStore';
 import { useUIStore } from '../../stores/uiStore';
 import { useVaultStore } from '../../stores/vaultStore';
 import toast from 'react-hot-toast';
+import { errorMessage } from '../../lib/tauri';
 
 export function PasswordEditor() {
     const { isPasswordEditorOpen, editingPasswordId, closePasswordEditor } = useUIStore();
@@ -109,7 +110,7 @@ export function PasswordEditor() {
             }
             closePasswordEditor();
         } catch (e) {
-            toast.error(String(e));
+            toast.error(errorMessage(e));
         } finally {
             setLoading(false);
         }
@@ -6,6 +6,7 @@ import { useVaultStore } from '../../stores/vaultStore';
 import { Sun, Moon, Monitor, Bell, Layers, Home, FileText, ListTodo, Lock, Eye, EyeOff } from 'lucide-react';
 import type { Settings } from '../../types';
 import toast from 'react-hot-toast';
+import { errorMessage } from '../../lib/tauri';
 
 interface SettingsSectionProps {
   title: string;
@@ -148,7 +149,7 @@ export function SettingsModal() {
       // Lock the vault after changing password - requires re-unlock
       await lock();
     } catch (error) {
-      toast.error(String(error));
+      toast.error(errorMessage(error));
       setIsChangingPassword(false);
     }
   };
@@ -65,7 +65,7 @@ export function TaskEditor({ taskId: propTaskId, embedded = false }: { taskId?:
                 setTitle(pendingTaskTemplate.title === 'Blank Task' ? '' : pendingTaskTemplate.title);
                 setColor(pendingTaskTemplate.color || '#3B82F6');
                 setDue(null);
-                setStatus('todo');
+                setStatus(pendingTaskTemplate.status || 'todo');
                 setFolderPath(currentFolderPath);
                 setTags([]);
                 setDescription(pendingTaskTemplate.content);
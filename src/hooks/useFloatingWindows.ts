@@ -3,15 +3,18 @@ import { listen } from '@tauri-apps/api/event';
 import { useTaskStore } from '../stores/taskStore';
 import { useNoteStore } from '../stores/noteStore';
 import { useUIStore } from '../stores/uiStore';
+import { useVaultStore } from '../stores/vaultStore';
 import {
   closeAllFloatingWindows,
   ensureFloatingWindowsOnTop,
+  restoreFloatingWindows,
 } from '../lib/tauri';
 
 export function useFloatingWindows() {
   const { updateTask, updateTaskPositionLocal, getTaskById } = useTaskStore();
   const { updateNote, updateNotePositionLocal, getNoteById } = useNoteStore();
   const { areFloatingTasksVisible, setFloatingTasksVisible } = useUIStore();
+  const { isUnlocked } = useVaultStore();
   const openWindowsRef = useRef<Set<string>>(new Set());
 
   // NOTE: Window creation is now handled directly by KanbanCard.toggleHidden()
@@ -20,6 +23,15 @@ export function useFloatingWindows() {
   // 2. Closing all windows when areFloatingTasksVisible is toggled off
   // 3. Position updates from floating windows
   // 4. Ensuring windows stay on top
+  // 5. Reopening windows left open in the last session once the vault is unlocked
+
+  // Reopen floating windows saved as open, at their last position and size
+  useEffect(() => {
+    if (!isUnlocked) return;
+    restoreFloatingWindows()
+      .then((count) => console.log('[useFloatingWindows] Restored floating windows:', count))
+      .catch((error) => console.error('[useFloatingWindows] Failed to restore floating windows:', error));
+  }, [isUnlocked]);
 
   // Handle task-hidden event from floating windows
   useEffect(() => {
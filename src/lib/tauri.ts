@@ -1,5 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { Settings, Workspace } from '../types';
+import type { PolicyResult, Settings, Workspace } from '../types';
 
 // ============================================
 // WORKSPACE API
@@ -70,6 +70,67 @@ export async function updateSettings(input: Partial<Settings>): Promise<Settings
   return parseSettings(settings);
 }
 
+// ============================================
+// VAULT API
+// ============================================
+
+/** Live feedback for a master password being chosen; setup rejects passwords that fail it */
+export async function checkMasterPasswordPolicy(password: string): Promise<PolicyResult> {
+  return await invoke<PolicyResult>('checkMasterPasswordPolicy', { password });
+}
+
+// ============================================
+// ITEM API
+// ============================================
+
+export type ItemKind = 'note' | 'task' | 'password';
+
+/** Flip an item's pinned flag without re-saving its body; resolves to the new state */
+export async function togglePin(kind: ItemKind, id: string): Promise<boolean> {
+  return await invoke<boolean>('togglePin', { kind, id });
+}
+
+// ============================================
+// ITEM CONTENT API
+// ============================================
+
+export interface ItemContent {
+  id: string;
+  content: string;
+}
+
+/** Decrypted note bodies in one round-trip; unknown ids are left out */
+export async function getNoteContentsBatch(ids: string[]): Promise<ItemContent[]> {
+  return await invoke<ItemContent[]>('getNoteContentsBatch', { ids });
+}
+
+/** Decrypted task bodies in one round-trip; unknown ids are left out */
+export async function getTaskContentsBatch(ids: string[]): Promise<ItemContent[]> {
+  return await invoke<ItemContent[]>('getTaskContentsBatch', { ids });
+}
+
+// ============================================
+// TAG API
+// ============================================
+
+/** Emitted after each item renameTag/deleteTag rewrites */
+export const TAG_PROGRESS_EVENT = 'tag-update-progress';
+
+export interface TagUpdateProgress {
+  done: number;
+  total: number;
+}
+
+/** Rename a tag on every note, task and password; resolves to the number of items changed */
+export async function renameTag(oldTag: string, newTag: string): Promise<number> {
+  return await invoke<number>('renameTag', { oldTag, newTag });
+}
+
+/** Remove a tag from every note, task and password; resolves to the number of items changed */
+export async function deleteTag(tag: string): Promise<number> {
+  return await invoke<number>('deleteTag', { tag });
+}
+
 // ============================================
 // FLOATING WINDOW API (for Tasks)
 // ============================================
@@ -131,6 +192,16 @@ export async function closeAllFloatingWindows(): Promise<void> {
   await invoke('closeAllFloatingWindows');
 }
 
+export async function recenterFloatingWindow(taskId: string): Promise<void> {
+  console.log('[tauri] recenterFloatingWindow:', taskId);
+  await invoke('recenterFloatingWindow', { note_id: taskId });
+}
+
+export async function restoreFloatingWindows(): Promise<number> {
+  console.log('[tauri] restoreFloatingWindows');
+  return await invoke('restoreFloatingWindows');
+}
+
 export async function toggleAllFloatingWindows(): Promise<boolean> {
   console.log('[tauri] toggleAllFloatingWindows');
   return await invoke('toggleAllFloatingWindows');
@@ -161,6 +232,42 @@ export async function getFloatingWindowSize(taskId: string): Promise<{ width: nu
   return result ? { width: result[0], height: result[1] } : null;
 }
 
+// ============================================
+// ERRORS
+// ============================================
+
+export type CommandErrorCode =
+  | 'VAULT_LOCKED'
+  | 'NO_WORKSPACE'
+  | 'WORKSPACE_UNAVAILABLE'
+  | 'REAUTH_REQUIRED'
+  | 'AUTH'
+  | 'NOT_FOUND'
+  | 'IO'
+  | 'DECRYPT'
+  | 'INVALID_INPUT'
+  | 'OTHER';
+
+/** What a failed command rejects with */
+export interface CommandError {
+  code: CommandErrorCode;
+  message: string;
+}
+
+export function isCommandError(error: unknown): error is CommandError {
+  return typeof error === 'object' && error !== null && 'code' in error && 'message' in error;
+}
+
+/** Human-readable text for any caught error */
+export function errorMessage(error: unknown): string {
+  return isCommandError(error) ? error.message : String(error);
+}
+
+/** Machine-readable code of a command error, null for anything else */
+export function errorCode(error: unknown): CommandErrorCode | null {
+  return isCommandError(error) ? error.code : null;
+}
+
 // ============================================
 // PARSE FUNCTIONS
 // ============================================
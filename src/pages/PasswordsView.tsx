@@ -28,6 +28,7 @@ import {
     verticalListSortingStrategy,
 } from '@dnd-kit/sortable';
 import { CSS } from '@dnd-kit/utilities';
+import { errorMessage } from '../lib/tauri';
 
 // Helper function to copy text to clipboard with fallback
 async function copyTextToClipboard(text: string): Promise<void> {
@@ -229,7 +230,7 @@ export function PasswordsView() {
                 copyTextToClipboard('').catch(() => { });
             }, 30000);
         } catch (e) {
-            toast.error(`Failed to copy: ${String(e)}`);
+            toast.error(`Failed to copy: ${errorMessage(e)}`);
         }
     }, [isUnlocked, getDecryptedContent]);
 
@@ -41,7 +41,7 @@ export function TasksView() {
     }, []);
 
     const handleTemplateSelect = useCallback((content: string, templateInfo: TemplateInfo) => {
-        openTaskEditorWithTemplate(content, templateInfo.color, templateInfo.name);
+        openTaskEditorWithTemplate(content, templateInfo.color, templateInfo.name, templateInfo.defaultStatus);
     }, [openTaskEditorWithTemplate]);
 
     const sensors = useSensors(
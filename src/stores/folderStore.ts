@@ -2,6 +2,7 @@ import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { FolderInfo, UpdateFolderInput, Folder } from '../types';
 import { useTrashStore } from './trashStore';
+import { errorMessage } from '../lib/tauri';
 
 // Unified folder structure - no more separate notes/tasks folders
 interface FolderState {
@@ -86,7 +87,7 @@ export const useFolderStore = create<FolderState>((set, get) => ({
             const folders = await invoke<FolderInfo[]>('getFolders');
             set({ folders, loading: false });
         } catch (error) {
-            set({ error: String(error), loading: false });
+            set({ error: errorMessage(error), loading: false });
         }
     },
 
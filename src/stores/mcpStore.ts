@@ -1,5 +1,6 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
+import { errorMessage } from '../lib/tauri';
 
 const MCP_PORT = 44055;
 const MCP_URL = `http://127.0.0.1:${MCP_PORT}/sse`;
@@ -35,7 +36,7 @@ export const useMCPStore = create<MCPState>((set) => ({
             }
             set({ isRunning: true, isLoading: false });
         } catch (error) {
-            set({ error: String(error), isLoading: false });
+            set({ error: errorMessage(error), isLoading: false });
         }
     },
 
@@ -51,7 +52,7 @@ export const useMCPStore = create<MCPState>((set) => ({
             }
             set({ isRunning: false, isLoading: false });
         } catch (error) {
-            set({ error: String(error), isLoading: false });
+            set({ error: errorMessage(error), isLoading: false });
         }
     },
 
@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import type { NoteInfo, CreateNoteInput, UpdateNoteInput, Note, FloatWindow, TrashNoteInfo } from '../types';
 import { toNote } from '../types';
 import { useTrashStore } from './trashStore';
+import { errorMessage } from '../lib/tauri';
 
 // Content cache for notes with LRU eviction to prevent memory leaks
 const MAX_CONTENT_CACHE_SIZE = 100;
@@ -64,7 +65,7 @@ export const useNoteStore = create<NoteState>((set, get) => ({
             });
             set({ notes, loading: false });
         } catch (error) {
-            set({ error: String(error), loading: false });
+            set({ error: errorMessage(error), loading: false });
         }
     },
 
@@ -79,7 +80,7 @@ export const useNoteStore = create<NoteState>((set, get) => ({
             });
             set({ notes, loading: false });
         } catch (error) {
-            set({ error: String(error), loading: false });
+            set({ error: errorMessage(error), loading: false });
         }
     },
 
@@ -108,7 +109,7 @@ export const useNoteStore = create<NoteState>((set, get) => ({
             }));
             set({ notes, loading: false });
         } catch (error) {
-            set({ error: String(error), loading: false });
+            set({ error: errorMessage(error), loading: false });
         }
     },
 
@@ -2,6 +2,7 @@ import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { PasswordInfo, CreatePasswordInput, UpdatePasswordInput, DecryptedPasswordContent, TrashPasswordInfo } from '../types';
 import { useTrashStore } from './trashStore';
+import { errorMessage } from '../lib/tauri';
 
 // Cache for decrypted content - avoids re-decryption
 interface CachedContent {
@@ -56,7 +57,7 @@ export const usePasswordStore = create<PasswordState>((set, get) => ({
             const passwords = await invoke<PasswordInfo[]>('getPasswords', { folderPath: folderPath ?? null });
             set({ passwords, loading: false });
         } catch (e) {
-            set({ error: String(e), loading: false });
+            set({ error: errorMessage(e), loading: false });
         }
     },
 
@@ -79,7 +80,7 @@ export const usePasswordStore = create<PasswordState>((set, get) => ({
             }));
             set({ passwords, loading: false });
         } catch (e) {
-            set({ error: String(e), loading: false });
+            set({ error: errorMessage(e), loading: false });
         }
     },
 
@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { Settings, SettingsOverride } from '../types';
+import { errorMessage } from '../lib/tauri';
 
 // Default settings
 const defaultSettings: Settings = {
@@ -69,7 +70,7 @@ export const useSettingsStore = create<SettingsState>((set) => ({
             set({ settings: normalizedSettings, globalSettings, loading: false });
         } catch (error) {
             console.error('[SettingsStore] Failed to fetch settings:', error);
-            set({ error: String(error), loading: false });
+            set({ error: errorMessage(error), loading: false });
         }
     },
 
@@ -115,7 +116,7 @@ export const useSettingsStore = create<SettingsState>((set) => ({
             const globalSettings = await invoke<Settings>('getGlobalSettings');
             set({ globalSettings, loading: false });
         } catch (error) {
-            set({ error: String(error), loading: false });
+            set({ error: errorMessage(error), loading: false });
         }
     },
 
@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import type { TaskInfo, TaskStatus, CreateTaskInput, UpdateTaskInput, Task, FloatWindow, TrashTaskInfo } from '../types';
 import { toTask } from '../types';
 import { useTrashStore } from './trashStore';
+import { errorMessage } from '../lib/tauri';
 
 // Content cache for task descriptions with LRU eviction to prevent memory leaks
 const MAX_CONTENT_CACHE_SIZE = 100;
@@ -70,7 +71,7 @@ export const useTaskStore = create<TaskState>((set, get) => ({
             });
             set({ tasks, loading: false });
         } catch (error) {
-            set({ error: String(error), loading: false });
+            set({ error: errorMessage(error), loading: false });
         }
     },
 
@@ -88,7 +89,7 @@ export const useTaskStore = create<TaskState>((set, get) => ({
             });
             set({ tasks, loading: false });
         } catch (error) {
-            set({ error: String(error), loading: false });
+            set({ error: errorMessage(error), loading: false });
         }
     },
 
@@ -119,7 +120,7 @@ export const useTaskStore = create<TaskState>((set, get) => ({
             }));
             set({ tasks, loading: false });
         } catch (error) {
-            set({ error: String(error), loading: false });
+            set({ error: errorMessage(error), loading: false });
         }
     },
 
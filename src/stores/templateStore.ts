@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { TemplateInfo, TemplateType } from '../types';
+import { errorMessage } from '../lib/tauri';
 
 interface TemplateState {
   noteTemplates: TemplateInfo[];
@@ -37,7 +38,7 @@ export const useTemplateStore = create<TemplateState>((set, get) => ({
         set({ taskTemplates: templates, loading: false });
       }
     } catch (error) {
-      set({ error: String(error), loading: false });
+      set({ error: errorMessage(error), loading: false });
     }
   },
 
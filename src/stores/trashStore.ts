@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { TrashNoteInfo, TrashTaskInfo, TrashPasswordInfo, TrashCounts } from '../types';
+import { errorMessage } from '../lib/tauri';
 
 export type TrashTab = 'notes' | 'tasks' | 'passwords';
 
@@ -80,7 +81,7 @@ export const useTrashStore = create<TrashState>((set, get) => ({
             ]);
             set({ loading: false });
         } catch (error) {
-            set({ error: String(error), loading: false });
+            set({ error: errorMessage(error), loading: false });
         }
     },
 
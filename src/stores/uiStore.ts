@@ -1,4 +1,5 @@
 import { create } from 'zustand';
+import type { TaskStatus } from '../types';
 
 export type ViewType = 'notes' | 'tasks' | 'passwords';
 type DeleteItemType = 'note' | 'task' | 'folder' | 'password';
@@ -31,9 +32,9 @@ interface UIState {
     // Task Editor Modal
     isTaskEditorOpen: boolean;
     editingTaskId: string | null;
-    pendingTaskTemplate: { content: string; color: string; title: string } | null;
+    pendingTaskTemplate: { content: string; color: string; title: string; status?: TaskStatus | null } | null;
     openTaskEditor: (taskId?: string) => void;
-    openTaskEditorWithTemplate: (content: string, color: string, title: string) => void;
+    openTaskEditorWithTemplate: (content: string, color: string, title: string, status?: TaskStatus | null) => void;
     closeTaskEditor: () => void;
 
     // Note Editor Modal
@@ -91,10 +92,10 @@ export const useUIStore = create<UIState>((set) => ({
     editingTaskId: null,
     pendingTaskTemplate: null,
     openTaskEditor: (taskId) => set({ isTaskEditorOpen: true, editingTaskId: taskId ?? null, pendingTaskTemplate: null }),
-    openTaskEditorWithTemplate: (content, color, title) => set({
+    openTaskEditorWithTemplate: (content, color, title, status) => set({
         isTaskEditorOpen: true,
         editingTaskId: null,
-        pendingTaskTemplate: { content, color, title }
+        pendingTaskTemplate: { content, color, title, status }
     }),
     closeTaskEditor: () => set({ isTaskEditorOpen: false, editingTaskId: null, pendingTaskTemplate: null }),
 
@@ -1,5 +1,6 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
+import { errorMessage } from '../lib/tauri';
 
 interface VaultState {
     isUnlocked: boolean;
@@ -44,7 +45,7 @@ export const useVaultStore = create<VaultState>((set) => ({
             ]);
             set({ isSetup, isUnlocked, isPasswordsAccessUnlocked, isLoading: false });
         } catch (error) {
-            set({ error: String(error), isLoading: false });
+            set({ error: errorMessage(error), isLoading: false });
         }
     },
 
@@ -60,7 +61,7 @@ export const useVaultStore = create<VaultState>((set) => ({
             }
             return success;
         } catch (error) {
-            set({ error: String(error), isLoading: false });
+            set({ error: errorMessage(error), isLoading: false });
             return false;
         }
     },
@@ -80,7 +81,7 @@ export const useVaultStore = create<VaultState>((set) => ({
             await invoke('setupMasterPassword', { password });
             set({ isSetup: true, isUnlocked: true, isPasswordsAccessUnlocked: true, isLoading: false });
         } catch (error) {
-            set({ error: String(error), isLoading: false });
+            set({ error: errorMessage(error), isLoading: false });
             throw error;
         }
     },
@@ -91,7 +92,7 @@ export const useVaultStore = create<VaultState>((set) => ({
             await invoke('changeMasterPasswordVault', { oldPassword, newPassword });
             set({ isLoading: false });
         } catch (error) {
-            set({ error: String(error), isLoading: false });
+            set({ error: errorMessage(error), isLoading: false });
             throw error;
         }
     },
@@ -101,7 +102,7 @@ export const useVaultStore = create<VaultState>((set) => ({
     },
 
     updateActivity: () => {
-        // Call backend (kept for compatibility, no longer auto-locks vault)
+        // Resets the backend's autoLockMinutes idle timer
         invoke('updateVaultActivity').catch(console.error);
     },
 
@@ -126,7 +127,7 @@ export const useVaultStore = create<VaultState>((set) => ({
             }
             return success;
         } catch (error) {
-            set({ passwordsError: String(error) });
+            set({ passwordsError: errorMessage(error) });
             return false;
         }
     },
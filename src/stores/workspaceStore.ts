@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { WorkspaceInfo } from '../types';
+import { errorMessage } from '../lib/tauri';
 
 interface WorkspaceState {
     workspaces: WorkspaceInfo[];
@@ -31,7 +32,7 @@ export const useWorkspaceStore = create<WorkspaceState>((set, get) => ({
             const current = workspaces.find(w => w.isCurrent) || null;
             set({ workspaces, currentWorkspace: current, loading: false });
         } catch (error) {
-            set({ error: String(error), loading: false });
+            set({ error: errorMessage(error), loading: false });
         }
     },
 
@@ -225,6 +225,7 @@ export interface TemplateInfo {
   order: number;
   slug: string;
   templateType: string;
+  defaultStatus?: TaskStatus | null;
 }
 
 // ============================================
@@ -254,6 +255,24 @@ export interface SettingsOverride {
   floatingOpacity?: number;
 }
 
+// ============================================
+// VAULT TYPES
+// ============================================
+
+/** Password strength estimate - matches Rust password_strength::StrengthReport */
+export interface StrengthReport {
+  score: number; // 0 (very weak) to 4 (very strong)
+  entropyBits: number;
+  warnings: string[];
+}
+
+/** Master password policy check - matches Rust password_strength::PolicyResult */
+export interface PolicyResult {
+  ok: boolean;
+  failures: string[];
+  strength: StrengthReport;
+}
+
 // ============================================
 // UI CONSTANTS
 // ============================================